@@ -0,0 +1,115 @@
+//! Clipboard history
+//!
+//! Keeps the last few log copies in memory so a previous copy can be put
+//! back on the clipboard after a newer one replaced it. Not persisted.
+
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// Characters of the first line shown per entry
+const PREVIEW_CHARS: usize = 60;
+
+/// One copied text with its copy time
+pub struct ClipboardEntry {
+    pub text: String,
+    pub copied_at: DateTime<Local>,
+}
+
+/// Most recent copies first, bounded by `capacity`
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardEntry>,
+    capacity: usize,
+}
+
+impl ClipboardHistory {
+    /// Create an empty history (`capacity` 0 disables it)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Record a copy, dropping the oldest entry when full
+    pub fn push(&mut self, text: String) {
+        self.push_at(text, Local::now());
+    }
+
+    fn push_at(&mut self, text: String, copied_at: DateTime<Local>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.truncate(self.capacity - 1);
+        self.entries.push_front(ClipboardEntry { text, copied_at });
+    }
+
+    pub fn get(&self, index: usize) -> Option<&ClipboardEntry> {
+        self.entries.get(index)
+    }
+
+    /// One line per entry: `[N] HH:MM:SS - <first line>`
+    pub fn labels(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let first_line: String = entry
+                    .text
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .chars()
+                    .take(PREVIEW_CHARS)
+                    .collect();
+                format!(
+                    "[{}] {} - {}",
+                    i + 1,
+                    entry.copied_at.format("%H:%M:%S"),
+                    first_line
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_push_keeps_most_recent_first_and_bounded() {
+        let mut history = ClipboardHistory::new(2);
+        history.push("a".into());
+        history.push("b".into());
+        history.push("c".into());
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(0).unwrap().text, "c");
+        assert_eq!(history.get(1).unwrap().text, "b");
+
+        let mut disabled = ClipboardHistory::new(0);
+        disabled.push("a".into());
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_labels_show_time_and_truncated_first_line() {
+        let at = Local.with_ymd_and_hms(2024, 1, 1, 12, 34, 56).unwrap();
+        let mut history = ClipboardHistory::new(5);
+        history.push_at(format!("{}\nsecond line", "x".repeat(80)), at);
+
+        assert_eq!(
+            history.labels(),
+            vec![format!("[1] 12:34:56 - {}", "x".repeat(60))]
+        );
+    }
+}
@@ -19,6 +19,10 @@ impl App {
                 self.toggle_bridge_pause();
                 false
             }
+            AppCommand::CycleHostTransport => {
+                self.cycle_host_transport();
+                false
+            }
             AppCommand::ScrollUp => {
                 self.logs.scroll_up();
                 false
@@ -59,6 +63,10 @@ impl App {
                 self.logs.set_filter(FilterMode::All);
                 false
             }
+            AppCommand::FilterEvents => {
+                self.logs.set_filter(FilterMode::Events);
+                false
+            }
             AppCommand::FilterDebugLevel(level) => {
                 self.logs.set_debug_level(level);
                 self.set_status(debug_level_status(level));
@@ -84,10 +92,196 @@ impl App {
                 self.export_logs();
                 false
             }
+            AppCommand::ToggleWordWrap => {
+                self.toggle_word_wrap();
+                false
+            }
+            AppCommand::ToggleJitter => {
+                self.toggle_jitter();
+                false
+            }
+            AppCommand::ToggleMessageStats => {
+                self.show_message_stats = !self.show_message_stats;
+                false
+            }
+            AppCommand::ToggleBookmark => {
+                self.toggle_bookmark();
+                false
+            }
+            AppCommand::NextBookmark => {
+                self.jump_to_bookmark(true);
+                false
+            }
+            AppCommand::PrevBookmark => {
+                self.jump_to_bookmark(false);
+                false
+            }
+            AppCommand::AnnotateEntry => {
+                self.open_note_input();
+                false
+            }
+            AppCommand::NoteInput(c) => {
+                if let Some((_, text)) = &mut self.note_input {
+                    text.push(c);
+                }
+                false
+            }
+            AppCommand::NoteBackspace => {
+                if let Some((_, text)) = &mut self.note_input {
+                    text.pop();
+                }
+                false
+            }
+            AppCommand::NoteSubmit => {
+                self.submit_note();
+                false
+            }
+            AppCommand::NoteCancel => {
+                self.note_input = None;
+                false
+            }
+            AppCommand::NextAnnotation => {
+                self.jump_to_annotation(true);
+                false
+            }
+            AppCommand::PrevAnnotation => {
+                self.jump_to_annotation(false);
+                false
+            }
+            AppCommand::OpenSearch => {
+                self.open_search();
+                false
+            }
+            AppCommand::SearchInput(c) => {
+                self.edit_search(|query| query.push(c));
+                false
+            }
+            AppCommand::SearchBackspace => {
+                self.edit_search(|query| {
+                    query.pop();
+                });
+                false
+            }
+            AppCommand::SearchSubmit => {
+                self.submit_search();
+                false
+            }
+            AppCommand::SearchCancel => {
+                self.search_input = None;
+                self.logs.set_search(None);
+                false
+            }
+            AppCommand::SearchNext => {
+                self.jump_to_match(true);
+                false
+            }
+            AppCommand::SearchPrev => {
+                self.jump_to_match(false);
+                false
+            }
             AppCommand::OpenConfig => {
                 self.open_config();
                 false
             }
+            AppCommand::ApplyBridgeConfig => {
+                self.apply_bridge_config();
+                false
+            }
+            AppCommand::ToggleRecording => {
+                self.toggle_recording();
+                false
+            }
+            AppCommand::ExportFlamegraph => {
+                self.export_flamegraph();
+                false
+            }
+            AppCommand::Redraw => {
+                self.force_redraw = true;
+                false
+            }
+            AppCommand::ToggleClipboardHistory => {
+                self.toggle_clipboard_history();
+                false
+            }
+            AppCommand::ClipboardHistoryUp => {
+                self.move_clipboard_selection(false);
+                false
+            }
+            AppCommand::ClipboardHistoryDown => {
+                self.move_clipboard_selection(true);
+                false
+            }
+            AppCommand::ClipboardHistoryCopy => {
+                self.recopy_clipboard_entry();
+                false
+            }
+            AppCommand::NetworkScan => {
+                self.show_network_scan();
+                false
+            }
+            AppCommand::PeersUp => {
+                self.move_peer_selection(false);
+                false
+            }
+            AppCommand::PeersDown => {
+                self.move_peer_selection(true);
+                false
+            }
+            AppCommand::ClosePeers => {
+                self.peers_selected = None;
+                false
+            }
+            AppCommand::ListPorts => {
+                self.show_serial_ports();
+                false
+            }
+            AppCommand::PortsUp => {
+                self.move_port_selection(false);
+                false
+            }
+            AppCommand::PortsDown => {
+                self.move_port_selection(true);
+                false
+            }
+            AppCommand::PickPort => {
+                self.pick_port();
+                false
+            }
+            AppCommand::CopyPort => {
+                self.copy_port();
+                false
+            }
+            AppCommand::ClosePorts => {
+                self.ports_selected = None;
+                false
+            }
+            AppCommand::ShowStatistics => {
+                self.show_statistics();
+                false
+            }
+            AppCommand::CloseStatistics => {
+                self.stats_popup = None;
+                false
+            }
+            AppCommand::OpenCommandPalette => {
+                self.open_command_palette();
+                false
+            }
+            AppCommand::PaletteInput(c) => {
+                self.palette_input(c);
+                false
+            }
+            AppCommand::PaletteBackspace => {
+                self.palette_backspace();
+                false
+            }
+            AppCommand::PaletteSubmit => self.submit_command_palette(),
+            AppCommand::PaletteCancel => {
+                self.close_command_palette();
+                false
+            }
+            // Only meaningful inside a pipeline (see execute_pipeline)
+            AppCommand::Sleep(_) => false,
             AppCommand::None => false,
         }
     }
@@ -0,0 +1,74 @@
+//! Daemon status stream
+//!
+//! A background thread holds a control-plane `watch` connection and
+//! forwards each status update. When the daemon is not running (or the
+//! stream ends) it reports `None` and reconnects after a short delay.
+//!
+//! Dropping the watch (on a control port change, or with the `App`) stops
+//! the thread and closes its connection by the next update.
+
+use super::App;
+use crate::constants::{DAEMON_WATCH_INTERVAL_MS, DAEMON_WATCH_RETRY_MS};
+use crate::control::{self, Response};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// Updates from the watch thread for one control port
+pub(super) struct DaemonWatch {
+    port: u16,
+    rx: mpsc::Receiver<Option<Response>>,
+    /// Set on drop; the thread checks it on each update and before reconnecting
+    stop: Arc<AtomicBool>,
+}
+
+impl DaemonWatch {
+    /// Start watching the daemon on `port`
+    fn spawn(port: u16) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let timeout = Duration::from_millis(DAEMON_WATCH_INTERVAL_MS * 4);
+        std::thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                let _ =
+                    control::send_watch_command(port, DAEMON_WATCH_INTERVAL_MS, timeout, |resp| {
+                        if stopped.load(Ordering::Relaxed) || tx.send(Some(resp)).is_err() {
+                            return ControlFlow::Break(());
+                        }
+                        ControlFlow::Continue(())
+                    });
+                // Not running, or the stream ended
+                if tx.send(None).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(DAEMON_WATCH_RETRY_MS));
+            }
+        });
+        Self { port, rx, stop }
+    }
+}
+
+impl Drop for DaemonWatch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl App {
+    /// Apply the latest streamed daemon status (restarts on a port change)
+    pub(super) fn update_daemon_status(&mut self) {
+        let port = self.config.bridge.control_port;
+        if self.daemon_watch.as_ref().is_none_or(|w| w.port != port) {
+            self.daemon_watch = Some(DaemonWatch::spawn(port));
+        }
+
+        let Some(watch) = &self.daemon_watch else {
+            return;
+        };
+        if let Some(status) = watch.rx.try_iter().last() {
+            self.apply_daemon_status(status);
+        }
+    }
+}
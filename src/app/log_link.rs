@@ -0,0 +1,123 @@
+//! Log receiver link quality
+//!
+//! Samples the receiver counters every `LOG_LINK_SAMPLE_SECS` and turns the
+//! drop rate of the last window into signal bars for the status bar. Loss
+//! above `LOG_LINK_LOSS_WARN_PERCENT` is logged once until it recovers.
+
+use super::App;
+use crate::constants::{LOG_LINK_LOSS_WARN_PERCENT, LOG_LINK_SAMPLE_SECS};
+use crate::logging::receiver::{LogReceiverStats, ReceiverStatsHandle};
+use crate::logging::LogEntry;
+use std::time::{Duration, Instant};
+
+/// Windowed view of the receiver counters
+pub(super) struct LogLink {
+    stats: ReceiverStatsHandle,
+    /// Counters at the start of the current window
+    sample: LogReceiverStats,
+    sampled_at: Instant,
+    /// Drop rate over the last complete window (percent)
+    loss_percent: f64,
+    /// Loss above the warning threshold was already logged
+    warned: bool,
+}
+
+impl LogLink {
+    pub(super) fn new(stats: ReceiverStatsHandle) -> Self {
+        let sample = stats.snapshot();
+        Self {
+            stats,
+            sample,
+            sampled_at: Instant::now(),
+            loss_percent: 0.0,
+            warned: false,
+        }
+    }
+
+    pub(super) fn reconnect_count(&self) -> u32 {
+        self.sample.reconnect_count
+    }
+
+    pub(super) fn signal_bars(&self, connected: bool) -> u8 {
+        signal_bars(self.loss_percent, connected)
+    }
+}
+
+/// Drop rate between two snapshots (percent of packets sent)
+fn loss_percent(prev: &LogReceiverStats, now: &LogReceiverStats) -> f64 {
+    let received = now.packets_received.saturating_sub(prev.packets_received);
+    let dropped = now.packets_dropped.saturating_sub(prev.packets_dropped);
+    let total = received + dropped;
+    if total == 0 {
+        return 0.0;
+    }
+    dropped as f64 * 100.0 / total as f64
+}
+
+/// Signal strength (0-4 bars) for a drop rate
+fn signal_bars(loss_percent: f64, connected: bool) -> u8 {
+    if !connected {
+        0
+    } else if loss_percent > 20.0 {
+        1
+    } else if loss_percent > 5.0 {
+        2
+    } else if loss_percent > 1.0 {
+        3
+    } else {
+        4
+    }
+}
+
+impl App {
+    /// Close the sampling window when due, logging packet loss
+    pub(super) fn update_log_link(&mut self) {
+        let Some(link) = &mut self.log_link else {
+            return;
+        };
+        if link.sampled_at.elapsed() < Duration::from_secs(LOG_LINK_SAMPLE_SECS) {
+            return;
+        }
+
+        let now = link.stats.snapshot();
+        link.loss_percent = loss_percent(&link.sample, &now);
+        link.sample = now;
+        link.sampled_at = Instant::now();
+
+        let lossy = link.loss_percent > LOG_LINK_LOSS_WARN_PERCENT;
+        if lossy && !link.warned {
+            let message = format!("Log receiver: {:.1}% packet loss", link.loss_percent);
+            self.logs.add(LogEntry::system(message));
+        }
+        link.warned = lossy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(received: u64, dropped: u64) -> LogReceiverStats {
+        LogReceiverStats {
+            packets_received: received,
+            packets_dropped: dropped,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_loss_percent_uses_window_deltas() {
+        assert_eq!(loss_percent(&stats(0, 0), &stats(0, 0)), 0.0);
+        assert_eq!(loss_percent(&stats(100, 50), &stats(190, 60)), 10.0);
+    }
+
+    #[test]
+    fn test_signal_bars_thresholds() {
+        assert_eq!(signal_bars(0.0, true), 4);
+        assert_eq!(signal_bars(1.0, true), 4);
+        assert_eq!(signal_bars(1.5, true), 3);
+        assert_eq!(signal_bars(10.0, true), 2);
+        assert_eq!(signal_bars(50.0, true), 1);
+        assert_eq!(signal_bars(0.0, false), 0);
+    }
+}
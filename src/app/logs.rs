@@ -1,10 +1,14 @@
 //! Log operations
 //!
-//! Copy, cut, clear, export, and pause operations on the log store.
+//! Copy, cut, clear, export, pause, word-wrap, bookmark, annotation and
+//! statistics operations on the log store, and opening URLs clicked in the
+//! log view.
 
 use super::operations::{self, ClipboardResult, ExportResult};
 use super::App;
 use crate::config;
+use crate::platform;
+use std::path::Path;
 
 impl App {
     /// Toggle pause state
@@ -17,10 +21,23 @@ impl App {
         });
     }
 
+    /// Toggle word-wrap of long log messages
+    pub fn toggle_word_wrap(&mut self) {
+        self.word_wrap = !self.word_wrap;
+        self.set_status(if self.word_wrap {
+            "Word wrap on"
+        } else {
+            "Word wrap off"
+        });
+    }
+
     /// Copy filtered logs to clipboard
     pub fn copy_logs(&mut self) {
         match operations::copy_logs(&self.logs) {
-            ClipboardResult::Success(n) => self.set_status(format!("Copied {} logs", n)),
+            ClipboardResult::Success { count, text } => {
+                self.clipboard_history.push(text);
+                self.set_status(format!("Copied {} logs", count));
+            }
             ClipboardResult::Error(e) => self.set_status(e),
         }
     }
@@ -34,17 +51,174 @@ impl App {
     /// Copy logs to clipboard and clear
     pub fn cut_logs(&mut self) {
         match operations::copy_logs(&self.logs) {
-            ClipboardResult::Success(n) => {
+            ClipboardResult::Success { count, text } => {
+                self.clipboard_history.push(text);
                 self.logs.clear();
-                self.set_status(format!("Cut {} logs", n));
+                self.set_status(format!("Cut {} logs", count));
             }
             ClipboardResult::Error(e) => self.set_status(e),
         }
     }
 
+    /// Bookmark (or unmark) the entry at the scroll position
+    pub fn toggle_bookmark(&mut self) {
+        let Some(index) = self.logs.index_at(self.logs.scroll_position()) else {
+            return;
+        };
+        let added = self.logs.toggle_bookmark(index);
+        let count = self.logs.bookmark_count();
+        self.set_status(if added {
+            format!("Bookmark added ({})", count)
+        } else {
+            format!("Bookmark removed ({})", count)
+        });
+    }
+
+    /// Scroll to the next (or previous) bookmark
+    pub fn jump_to_bookmark(&mut self, forward: bool) {
+        if self.logs.bookmark_count() == 0 {
+            self.set_status("No bookmarks (Ctrl+B to add)");
+        } else if !self.logs.jump_to_bookmark(forward) {
+            self.set_status(if forward {
+                "No later bookmark"
+            } else {
+                "No earlier bookmark"
+            });
+        }
+    }
+
+    /// Open the search input, filled with the current query
+    pub fn open_search(&mut self) {
+        let query = self.logs.search_query().unwrap_or_default().to_string();
+        self.search_input = Some(query);
+    }
+
+    /// Change the typed query; matches follow as it is typed
+    pub fn edit_search(&mut self, edit: impl FnOnce(&mut String)) {
+        if let Some(query) = &mut self.search_input {
+            edit(query);
+            self.logs.set_search(Some(query.clone()));
+        }
+    }
+
+    /// Close the search input, keeping the matches highlighted for `n` / `N`
+    pub fn submit_search(&mut self) {
+        if self.search_input.take().is_none() || self.logs.search_query().is_none() {
+            return;
+        }
+        let count = self.logs.search_matches().len();
+        self.set_status(match count {
+            0 => "No matches".to_string(),
+            1 => "1 match".to_string(),
+            n => format!("{} matches (n / N to cycle, Esc to clear)", n),
+        });
+    }
+
+    /// Scroll to the next (or previous) search match
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let total = self.logs.search_matches().len();
+        match self.logs.jump_to_match(forward) {
+            Some(index) => self.set_status(format!("Match {}/{}", index + 1, total)),
+            None => self.set_status("No matches"),
+        }
+    }
+
+    /// Open the note input for the entry at the scroll position, filled
+    /// with its current note
+    pub fn open_note_input(&mut self) {
+        let Some(index) = self.logs.index_at(self.logs.scroll_position()) else {
+            self.set_status("No log entry to annotate");
+            return;
+        };
+        let note = self
+            .logs
+            .get(index)
+            .and_then(|e| e.user_note.clone())
+            .unwrap_or_default();
+        self.note_input = Some((index, note));
+    }
+
+    /// Close the note input and save its text (empty = remove the note)
+    pub fn submit_note(&mut self) {
+        let Some((index, note)) = self.note_input.take() else {
+            return;
+        };
+        let removed = note.trim().is_empty();
+        if !self.logs.annotate(index, note) {
+            self.set_status("Entry no longer in the log buffer");
+            return;
+        }
+        let count = self.logs.annotated_entries().len();
+        self.set_status(if removed {
+            format!("Note removed ({})", count)
+        } else {
+            format!("Note saved ({})", count)
+        });
+    }
+
+    /// Scroll to the next (or previous) annotated entry and show its note
+    pub fn jump_to_annotation(&mut self, forward: bool) {
+        if self.logs.annotated_entries().is_empty() {
+            self.set_status("No notes (Shift+N to add)");
+            return;
+        }
+        let note = self
+            .logs
+            .jump_to_annotation(forward)
+            .and_then(|index| self.logs.get(index))
+            .and_then(|e| e.user_note.clone());
+        match note {
+            Some(note) => self.set_status(format!("✎ {}", note)),
+            None => self.set_status(if forward {
+                "No later note"
+            } else {
+                "No earlier note"
+            }),
+        }
+    }
+
+    /// Open or close the clipboard history popup
+    pub fn toggle_clipboard_history(&mut self) {
+        if self.clipboard_selected.take().is_some() {
+            return;
+        }
+        if self.clipboard_history.is_empty() {
+            self.set_status("Clipboard history is empty");
+        } else {
+            self.clipboard_selected = Some(0);
+        }
+    }
+
+    /// Move the popup selection one entry down (or up)
+    pub fn move_clipboard_selection(&mut self, down: bool) {
+        let last = self.clipboard_history.len().saturating_sub(1);
+        if let Some(selected) = &mut self.clipboard_selected {
+            *selected = if down {
+                (*selected + 1).min(last)
+            } else {
+                selected.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Put the selected history entry back on the clipboard and close the popup
+    pub fn recopy_clipboard_entry(&mut self) {
+        let Some(selected) = self.clipboard_selected.take() else {
+            return;
+        };
+        let Some(entry) = self.clipboard_history.get(selected) else {
+            return;
+        };
+        match operations::set_clipboard(&entry.text) {
+            Ok(()) => self.set_status(format!("Re-copied clipboard entry [{}]", selected + 1)),
+            Err(e) => self.set_status(e),
+        }
+    }
+
     /// Export logs to file and open
     pub fn export_logs(&mut self) {
-        match operations::export_logs(&self.logs, self.config.logs.export_max) {
+        let logs = &self.config.logs;
+        match operations::export_logs(&self.logs, logs.export_max, logs.export_format) {
             ExportResult::Success { line_count, opened } => {
                 if opened {
                     self.set_status(format!("Exported {} logs", line_count));
@@ -56,6 +230,23 @@ impl App {
         }
     }
 
+    /// Open the log statistics popup (only while no bridge is running)
+    pub fn show_statistics(&mut self) {
+        if self.daemon_running && !self.read_only {
+            self.set_status("Statistics: available while the bridge is stopped");
+            return;
+        }
+        self.stats_popup = Some(self.logs.statistics().table_lines());
+    }
+
+    /// Open a URL clicked in the log view with the default application
+    pub fn handle_link_click(&mut self, url: &str) {
+        match platform::open_file(Path::new(url)) {
+            Ok(()) => self.set_status(format!("Opened {}", url)),
+            Err(e) => self.set_status(format!("Cannot open: {}", e)),
+        }
+    }
+
     /// Open config file in default editor
     pub fn open_config(&mut self) {
         match config::open_in_editor() {
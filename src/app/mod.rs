@@ -2,118 +2,585 @@
 //!
 //! The TUI is a *client* for the background `oc-bridge --daemon`.
 //! It does not run the bridge locally.
+//!
+//! In analyze mode it only browses an imported log file: no daemon polling,
+//! no log receiver, and bridge control is disabled.
 
+mod clipboard;
 mod commands;
+mod daemon_watch;
+mod log_link;
 mod logs;
-mod operations;
+pub mod operations;
+mod peers;
+mod pipeline;
+mod ports;
 pub mod state;
 
-pub use state::{AppState, ControllerTransportState, HostTransportState};
+pub use state::{
+    AppState, ClipboardPopupState, ControllerTransportState, HostTransportState, PeersPopupState,
+    PortsPopupState,
+};
 
-use crate::config::{self, Config, ControllerTransport, HostTransport};
-use crate::constants::{LOG_CONNECTION_TIMEOUT_SECS, STATUS_MESSAGE_TIMEOUT_SECS};
+use crate::bridge::stats::SpikeDetector;
+use crate::config::{
+    self, BridgeConfig, Config, ConfigWatcher, ControllerTransport, HostTransport, KeyBindings,
+    SerialCodec,
+};
+use crate::constants::{LOG_CONNECTION_TIMEOUT_SECS, STARTUP_QUERY_TIMEOUT_MS, TOP_MESSAGES};
 use crate::control;
+use crate::discovery::BridgeInstance;
+use crate::error::Result;
+use crate::input::AppCommand;
+use crate::logging::file::FileLogger;
+use crate::logging::ws_stream::LogStreamServer;
 use crate::logging::{Direction, FilterMode, LogEntry, LogKind, LogStore};
+use crate::transport::ports::PortListing;
+use clipboard::ClipboardHistory;
+use daemon_watch::DaemonWatch;
+use log_link::LogLink;
+use operations::{FlamegraphCapture, LogRecording};
+use std::borrow::Cow;
 use std::collections::VecDeque;
+use std::path::Path;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 /// Main application
 pub struct App {
-    // Config snapshot (reloaded periodically)
+    // Config snapshot (follows the file, see `apply_config`)
     config: Config,
+    /// Reloads `config` when the file changes (polled every second without it)
+    config_watcher: Option<ConfigWatcher>,
+    /// Edited `[bridge]` settings that need a daemon restart (applied on Shift+R)
+    pending_bridge: Option<BridgeConfig>,
+    /// `config.keybindings` after validation
+    keys: KeyBindings,
+    /// Controller codec label for `config` (see `codec_label`)
+    active_codec: String,
 
     // Daemon status
     daemon_running: bool,
     bridge_paused: bool,
     serial_open: bool,
+    /// Daemon runs with `--trace-cobs`
+    cobs_trace: bool,
+    /// Host transport reported by the daemon (may differ from config after a switch)
+    daemon_host_transport: Option<HostTransport>,
+    /// Open WebSocket host connections reported by the daemon
+    host_ws_clients: Option<usize>,
+    /// Last UDP host sender reported by the daemon
+    host_udp_peer: Option<String>,
+    /// Controller jitter reported by the daemon (ms)
+    daemon_jitter_ms: Option<f64>,
+    /// Jitter shown in the status widget (Shift+J)
+    show_jitter: bool,
+    /// Per-message-type table shown over the logs (Tab)
+    show_message_stats: bool,
+    /// Controller write channel fill reported by the daemon (0.0 to 1.0)
+    daemon_write_fill_ratio: Option<f64>,
+    /// Control plane request count and average latency (ms) reported by the daemon
+    daemon_control_requests: Option<(u64, f64)>,
     controller_state: ControllerTransportState,
+    /// Serial port set by an `OC_BRIDGE_SERIAL_PORT` / `OC_BRIDGE_PORT` override
+    serial_port_from_env: bool,
 
     // Logs + stats
     logs: LogStore,
     log_rx: Option<mpsc::Receiver<LogEntry>>,
     log_connected: bool,
     last_log_time: Option<Instant>,
+    /// Receiver packet loss and reconnects (live mode only)
+    log_link: Option<LogLink>,
     stats: crate::bridge::stats::Stats,
+    /// Flags spikes in `stats` for the status bar (the daemon logs them)
+    spike_detector: Option<SpikeDetector>,
+    /// Live capture of received log entries (Alt+R)
+    recording: Option<LogRecording>,
+    /// Session log of received entries (`[logs] file_logging`)
+    file_logger: Option<FileLogger>,
+    /// WebSocket feed of received entries (`[logs] ws_stream_port`)
+    log_stream: Option<LogStreamServer>,
+    /// Daemon CPU profile in progress (Alt+P)
+    flamegraph: Option<FlamegraphCapture>,
+    /// Network scan in progress (N), answered with the bridges found
+    network_scan: Option<std::sync::mpsc::Receiver<Vec<BridgeInstance>>>,
 
-    // Polling
-    last_status_poll: Instant,
+    // Polling (daemon status is streamed)
+    daemon_watch: Option<DaemonWatch>,
     last_config_reload: Instant,
 
+    // Clipboard history (popup open while `clipboard_selected` is set)
+    clipboard_history: ClipboardHistory,
+    clipboard_selected: Option<usize>,
+
+    // Last network scan (popup open while `peers_selected` is set)
+    peers: Vec<BridgeInstance>,
+    peers_selected: Option<usize>,
+
+    // Last serial port listing (picker open while `ports_selected` is set)
+    ports: Vec<PortListing>,
+    ports_selected: Option<usize>,
+
+    /// Log statistics popup rows (open while set)
+    stats_popup: Option<Vec<String>>,
+
+    /// Note being typed for the entry with this absolute index (open while set)
+    note_input: Option<(usize, String)>,
+
+    /// Search query being typed (input open while set)
+    search_input: Option<String>,
+
+    // Command palette (open while set) and the rest of a sleeping pipeline
+    command_palette: Option<String>,
+    pipeline: VecDeque<AppCommand>,
+    pipeline_resume_at: Option<Instant>,
+
     // UI
+    /// Wrap long log messages (starts from `config.ui.word_wrap`)
+    word_wrap: bool,
     status_message: Option<(String, Instant)>,
+    /// Last mouse position (column, row), for hover tooltips
+    mouse: Option<(u16, u16)>,
     should_quit: bool,
+    /// Clear the terminal before the next draw (Ctrl+L)
+    force_redraw: bool,
+
+    // Offline analysis (imported log file, bridge stopped)
+    read_only: bool,
+
+    /// HTTP state API (`--http-port`)
+    #[cfg(feature = "http-api")]
+    http: Option<crate::bridge::http_state::StatePublisher>,
 }
 
 impl App {
-    pub fn new() -> Self {
-        let cfg = config::load();
+    /// Build the app without blocking the async runtime
+    ///
+    /// Config loading and the daemon status query run on the blocking pool.
+    /// The status query is bounded by `STARTUP_QUERY_TIMEOUT_MS`; on timeout
+    /// the daemon is assumed not running and a warning is logged.
+    pub async fn new_async() -> Self {
+        let cfg = tokio::task::spawn_blocking(config::load)
+            .await
+            .unwrap_or_default();
         let max_entries = cfg.logs.max_entries;
+        let watcher = config::config_dir()
+            .and_then(|dir| ConfigWatcher::new(&dir, cfg.clone(), config::reload));
+
+        let (log_rx, log_link) = spawn_log_receiver(cfg.bridge.log_broadcast_port);
+
+        let port = cfg.bridge.control_port;
+        let mut app = Self::with_logs(cfg, LogStore::new(max_entries), log_rx);
+        app.log_link = log_link;
+        app.open_file_logger();
+        app.start_log_stream();
+        match watcher {
+            Ok(watcher) => app.config_watcher = Some(watcher),
+            Err(e) => app.logs.add(LogEntry::system(format!(
+                "Config watcher unavailable ({}), checking the file every second",
+                e
+            ))),
+        }
+
+        let timeout = Duration::from_millis(STARTUP_QUERY_TIMEOUT_MS);
+        let query = tokio::task::spawn_blocking(move || {
+            control::send_command_blocking(port, "status", timeout)
+        });
+        let timed_out = match tokio::time::timeout(timeout, query).await {
+            Ok(Ok(result)) => {
+                app.apply_daemon_status(result.ok());
+                false
+            }
+            Ok(Err(_)) => {
+                app.apply_daemon_status(None);
+                false
+            }
+            Err(_) => {
+                app.apply_daemon_status(None);
+                true
+            }
+        };
+        app.last_config_reload = Instant::now();
+
+        app.log_welcome_message();
+        if timed_out {
+            app.logs.add(LogEntry::system(format!(
+                "Warning: daemon status query timed out after {}ms, assuming not running",
+                STARTUP_QUERY_TIMEOUT_MS
+            )));
+        }
+        app
+    }
+
+    /// Open a log file for offline analysis (read-only, bridge stopped)
+    pub fn analyze(path: &Path) -> Result<Self> {
+        let cfg = config::load();
+        let logs = LogStore::from_file(path, cfg.logs.max_entries)?;
 
-        let shutdown = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
-        let log_rx = crate::logging::receiver::spawn_log_receiver_with_port(
-            shutdown,
-            cfg.bridge.log_broadcast_port,
-        )
-        .ok();
+        let mut app = Self::with_logs(cfg, logs, None);
+        app.read_only = true;
+        app.set_status(format!("Analyzing {} (read-only)", path.display()));
+        Ok(app)
+    }
 
+    fn with_logs(config: Config, logs: LogStore, log_rx: Option<mpsc::Receiver<LogEntry>>) -> Self {
+        let clipboard_history = ClipboardHistory::new(config.logs.clipboard_history_size);
+        let word_wrap = config.ui.word_wrap;
+        let spike_detector = spike_detector(&config);
+        let active_codec = codec_label(&config);
         let mut app = Self {
-            config: cfg,
+            config,
+            config_watcher: None,
+            pending_bridge: None,
+            keys: KeyBindings::default(),
+            active_codec,
             daemon_running: false,
             bridge_paused: false,
             serial_open: false,
+            cobs_trace: false,
+            daemon_host_transport: None,
+            host_ws_clients: None,
+            host_udp_peer: None,
+            daemon_jitter_ms: None,
+            show_jitter: false,
+            show_message_stats: false,
+            daemon_write_fill_ratio: None,
+            daemon_control_requests: None,
             controller_state: ControllerTransportState::Disconnected,
-            logs: LogStore::new(max_entries),
+            serial_port_from_env: false,
+            logs,
             log_rx,
             log_connected: false,
             last_log_time: None,
+            log_link: None,
             stats: crate::bridge::stats::Stats::new(),
-            last_status_poll: Instant::now() - Duration::from_secs(60),
+            spike_detector,
+            recording: None,
+            file_logger: None,
+            log_stream: None,
+            flamegraph: None,
+            network_scan: None,
+            daemon_watch: None,
             last_config_reload: Instant::now() - Duration::from_secs(60),
+            clipboard_history,
+            clipboard_selected: None,
+            peers: Vec::new(),
+            peers_selected: None,
+            ports: Vec::new(),
+            ports_selected: None,
+            stats_popup: None,
+            note_input: None,
+            search_input: None,
+            command_palette: None,
+            pipeline: VecDeque::new(),
+            pipeline_resume_at: None,
+            word_wrap,
             status_message: None,
+            mouse: None,
             should_quit: false,
+            force_redraw: false,
+            read_only: false,
+            #[cfg(feature = "http-api")]
+            http: None,
         };
-
-        app.refresh_daemon_status();
-        app.log_welcome_message();
+        app.logs
+            .set_dedup_threshold(app.config.logs.dedup_threshold);
+        app.apply_keybindings();
         app
     }
 
+    /// Apply a config reloaded from disk
+    ///
+    /// Hot-reloadable settings take effect now. Other `[bridge]` changes are
+    /// kept in `pending_bridge`: the daemon only reads them when it starts,
+    /// so the TUI keeps showing (and talking to) the running setup until
+    /// Shift+R.
+    fn apply_config(&mut self, mut cfg: Config) {
+        let keys_changed = cfg.keybindings != self.config.keybindings;
+        let (old, new) = (&self.config.logs, &cfg.logs);
+        let file_logging_changed = new.file_logging != old.file_logging
+            || new.log_dir != old.log_dir
+            || new.max_file_size_mb != old.max_file_size_mb
+            || new.max_files != old.max_files;
+        let log_stream_changed = new.ws_stream_port != old.ws_stream_port;
+        if cfg.ui.word_wrap != self.config.ui.word_wrap {
+            self.word_wrap = cfg.ui.word_wrap;
+        }
+        if cfg.bridge.spike_detector != self.config.bridge.spike_detector {
+            self.spike_detector = spike_detector(&cfg);
+        }
+        if cfg.logs.max_entries != self.config.logs.max_entries {
+            self.logs.set_max_entries(cfg.logs.max_entries);
+        }
+        if cfg.logs.dedup_threshold != self.config.logs.dedup_threshold {
+            self.logs.set_dedup_threshold(cfg.logs.dedup_threshold);
+        }
+        if cfg.ui.default_filter != self.config.ui.default_filter {
+            if let Some(mode) = FilterMode::from_name(&cfg.ui.default_filter) {
+                self.logs.set_filter(mode);
+            }
+        }
+        if cfg.bridge.log_broadcast_port != self.config.bridge.log_broadcast_port {
+            // The old receiver thread stops once its channel is dropped
+            let (log_rx, log_link) = spawn_log_receiver(cfg.bridge.log_broadcast_port);
+            self.log_rx = log_rx;
+            self.log_link = log_link;
+            self.log_connected = false;
+        }
+
+        let mut running = self.config.bridge.clone();
+        running.log_broadcast_port = cfg.bridge.log_broadcast_port;
+        running.spike_detector = cfg.bridge.spike_detector;
+        let edited = std::mem::replace(&mut cfg.bridge, running);
+        let pending = (edited != cfg.bridge).then_some(edited);
+        if pending.is_some() && pending != self.pending_bridge {
+            self.set_status("Config changed - restart the daemon, then press Shift+R");
+        }
+        self.pending_bridge = pending;
+
+        self.config = cfg;
+        self.active_codec = codec_label(&self.config);
+        if keys_changed {
+            self.apply_keybindings();
+        }
+        if file_logging_changed {
+            self.open_file_logger();
+        }
+        if log_stream_changed {
+            self.start_log_stream();
+        }
+    }
+
+    /// Start, restart or stop the WebSocket log feed to match `config.logs`
+    fn start_log_stream(&mut self) {
+        // Dropping the server stops it and frees the port
+        self.log_stream = None;
+        let Some(port) = self.config.logs.ws_stream_port else {
+            return;
+        };
+        match LogStreamServer::start(port) {
+            Ok(server) => {
+                self.logs.add(LogEntry::system(format!(
+                    "Log stream on ws://127.0.0.1:{}",
+                    port
+                )));
+                self.log_stream = Some(server);
+            }
+            Err(e) => self
+                .logs
+                .add(LogEntry::system(format!("Log stream unavailable: {}", e))),
+        }
+    }
+
+    /// Start, restart or stop the session log to match `config.logs`
+    fn open_file_logger(&mut self) {
+        // Dropping the logger flushes it
+        self.file_logger = None;
+        let logs = &self.config.logs;
+        if !logs.file_logging {
+            return;
+        }
+        let opened = config::session_log_dir(logs)
+            .map_err(|e| e.to_string())
+            .and_then(|dir| {
+                FileLogger::new(
+                    dir,
+                    logs.max_file_size_mb.saturating_mul(1024 * 1024),
+                    logs.max_files,
+                )
+                .map_err(|e| e.to_string())
+            });
+        match opened {
+            Ok(logger) => {
+                self.logs.add(LogEntry::system(format!(
+                    "Logging to {}",
+                    logger.path().display()
+                )));
+                self.file_logger = Some(logger);
+            }
+            Err(e) => self
+                .logs
+                .add(LogEntry::system(format!("File logging unavailable: {}", e))),
+        }
+    }
+
+    /// Show the `[bridge]` settings edited since the TUI started (Shift+R)
+    pub(super) fn apply_bridge_config(&mut self) {
+        let Some(bridge) = self.pending_bridge.take() else {
+            self.set_status("Bridge settings unchanged");
+            return;
+        };
+        self.config.bridge = bridge;
+        self.active_codec = codec_label(&self.config);
+        self.controller_state =
+            determine_controller_state(&self.config, self.daemon_running, self.serial_open);
+        self.serial_port_from_env = config::serial_port_from_env(&self.config);
+        self.set_status("Bridge settings applied");
+    }
+
+    /// Validate `config.keybindings`, reporting fallbacks in the log view
+    fn apply_keybindings(&mut self) {
+        let (keys, warnings) = self.config.keybindings.validated();
+        self.keys = keys;
+        for warning in warnings {
+            self.logs
+                .add(LogEntry::system(format!("Config: {}", warning)));
+        }
+    }
+
     pub fn state(&self) -> AppState<'_> {
-        let (tx_rate, rx_rate) = self.stats.update_rates();
-        let host_state = determine_host_state(&self.config);
+        let (tx_rate, rx_rate) = self.stats.windowed_rates(1);
+        let (tx_rate_avg, rx_rate_avg) = self.stats.windowed_rates(60);
+        let host_transport = self
+            .daemon_host_transport
+            .unwrap_or(self.config.bridge.host_transport);
+        let host_state = determine_host_state(&self.config, host_transport);
+        let active_host_info = host_info(
+            &host_state,
+            self.daemon_running,
+            self.host_ws_clients,
+            self.host_udp_peer.as_deref(),
+        );
 
         AppState {
             daemon_running: self.daemon_running,
+            profile: config::active_profile(),
+            keys: Cow::Borrowed(&self.keys),
             controller_transport_config: self.config.bridge.controller_transport,
             host_transport_config: self.config.bridge.host_transport,
-            controller_state: &self.controller_state,
+            controller_state: Cow::Borrowed(&self.controller_state),
+            config_changed: self.pending_bridge.is_some(),
+            serial_port_from_env: self.serial_port_from_env,
             host_state,
+            active_codec: self.active_codec.clone(),
+            active_host_info,
+            jitter_ms: self.daemon_jitter_ms.filter(|_| self.show_jitter),
+            write_fill_ratio: self.daemon_write_fill_ratio,
+            control_requests_total: self.daemon_control_requests.map(|(total, _)| total),
+            control_latency_ms_avg: self.daemon_control_requests.map(|(_, avg)| avg),
             bridge_paused: self.bridge_paused,
+            cobs_trace: self.cobs_trace,
             control_port: self.config.bridge.control_port,
             log_port: self.config.bridge.log_broadcast_port,
             log_available: self.log_rx.is_some(),
             log_connected: self.log_connected,
+            log_signal: self
+                .log_link
+                .as_ref()
+                .map_or(0, |l| l.signal_bars(self.log_connected)),
+            log_reconnects: self.log_link.as_ref().map_or(0, LogLink::reconnect_count),
             rx_rate,
             tx_rate,
+            rx_rate_avg,
+            tx_rate_avg,
             paused: self.logs.is_paused(),
-            status_message: self.status_text(),
+            status_message: self.status_text().map(Cow::Borrowed),
+            log_revision: self.logs.revision(),
+            word_wrap: self.word_wrap,
+            clipboard_popup: self.clipboard_selected.map(|selected| ClipboardPopupState {
+                entries: self.clipboard_history.labels(),
+                selected,
+            }),
+            peers_popup: self.peers_selected.map(|selected| PeersPopupState {
+                entries: self.peer_labels(),
+                selected,
+            }),
+            ports_popup: self.ports_selected.map(|selected| {
+                let mut rows = crate::transport::ports::table(&self.ports);
+                PortsPopupState {
+                    header: rows.remove(0),
+                    entries: rows,
+                    selected,
+                }
+            }),
+            command_palette: self.command_palette.as_deref().map(Cow::Borrowed),
+            stats_popup: self.stats_popup.as_deref().map(Cow::Borrowed),
+            note_input: self
+                .note_input
+                .as_ref()
+                .map(|(_, t)| Cow::Borrowed(t.as_str())),
+            search_input: self.search_input.as_deref().map(Cow::Borrowed),
+            top_messages: self.show_message_stats.then(|| {
+                let mut top = self.stats.top_messages(TOP_MESSAGES);
+                // Whole seconds, as shown: the diff skips redraws in between
+                for (_, stats) in &mut top {
+                    stats.idle_ms -= stats.idle_ms % 1000;
+                }
+                top
+            }),
+            // Blink at 1 Hz
+            spike_flash: self.stats.in_spike()
+                && chrono::Local::now().timestamp_subsec_millis() < 500,
+            recording_bytes: self.recording.as_ref().map(LogRecording::shown_bytes),
+            log_file: self
+                .file_logger
+                .as_ref()
+                .map(|l| l.path().to_string_lossy()),
+            export_format: self.config.logs.export_format,
+            mouse: self.mouse,
+        }
+    }
+
+    /// Serve the HTTP state API on 127.0.0.1:`port` (outcome shown in the log)
+    #[cfg(feature = "http-api")]
+    pub async fn with_http_api(mut self, port: u16) -> Self {
+        match crate::bridge::http_state::serve(port).await {
+            Ok(publisher) => {
+                self.http = Some(publisher);
+                self.logs.add(LogEntry::system(format!(
+                    "HTTP API on http://127.0.0.1:{}",
+                    port
+                )));
+            }
+            Err(e) => self
+                .logs
+                .add(LogEntry::system(format!("HTTP API unavailable: {}", e))),
+        }
+        self
+    }
+
+    /// Publish a rendered snapshot to HTTP state API clients
+    #[cfg(feature = "http-api")]
+    pub fn publish_state(&self, state: &AppState<'static>) {
+        if let Some(http) = &self.http {
+            http.publish_state(state.clone());
         }
     }
 
     pub fn poll(&mut self) {
+        self.resume_pipeline();
+        self.update_network_scan();
+
+        if self.read_only {
+            return;
+        }
+
         self.drain_logs();
+        self.logs.flush_idle_dedup(Instant::now());
+        self.update_log_link();
+        if let Some(recording) = &mut self.recording {
+            recording.refresh_shown_bytes();
+        }
+        self.update_flamegraph();
+        if let Some(detector) = &mut self.spike_detector {
+            detector.update(&self.stats);
+        }
 
         // Keep a fresh config view so the TUI reflects manual edits.
-        if self.last_config_reload.elapsed() >= Duration::from_secs(1) {
-            self.last_config_reload = Instant::now();
-            self.config = config::load();
+        let reloaded = match &mut self.config_watcher {
+            Some(watcher) => watcher.changed(),
+            None if self.last_config_reload.elapsed() >= Duration::from_secs(1) => {
+                self.last_config_reload = Instant::now();
+                Some(config::load())
+            }
+            None => None,
+        };
+        if let Some(cfg) = reloaded {
+            self.apply_config(cfg);
         }
 
-        if self.last_status_poll.elapsed() >= Duration::from_millis(600) {
-            self.last_status_poll = Instant::now();
-            self.refresh_daemon_status();
-        }
+        self.update_daemon_status();
 
         // (Autostart is managed by ms-manager.)
     }
@@ -122,12 +589,13 @@ impl App {
         self.should_quit
     }
 
-    pub fn logs(&self) -> &VecDeque<LogEntry> {
-        self.logs.entries()
+    /// Whether a full redraw was requested; resets the request
+    pub fn take_force_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.force_redraw)
     }
 
-    pub fn filter(&self) -> &crate::logging::LogFilter {
-        self.logs.filter()
+    pub fn log_store(&self) -> &LogStore {
+        &self.logs
     }
 
     pub fn filter_mode(&self) -> FilterMode {
@@ -146,23 +614,53 @@ impl App {
         }
     }
 
+    /// Track the pointer for hover tooltips
+    pub fn handle_mouse_move(&mut self, column: u16, row: u16) {
+        self.mouse = Some((column, row));
+    }
+
     pub fn handle_key(&mut self, key: crossterm::event::KeyEvent) -> bool {
-        self.execute_command(crate::input::translate_key(key, self.logs.filter_mode()))
+        let cmd = if self.command_palette.is_some() {
+            crate::input::translate_palette_key(key)
+        } else if self.note_input.is_some() {
+            crate::input::translate_note_key(key)
+        } else if self.search_input.is_some() {
+            crate::input::translate_search_key(key)
+        } else if self.clipboard_selected.is_some() {
+            crate::input::translate_popup_key(key)
+        } else if self.peers_selected.is_some() {
+            crate::input::translate_peers_key(key)
+        } else if self.ports_selected.is_some() {
+            crate::input::translate_ports_key(key)
+        } else if self.stats_popup.is_some() {
+            crate::input::translate_stats_key(key)
+        } else if self.logs.search_query().is_some() {
+            crate::input::translate_search_results_key(key, self.logs.filter_mode(), &self.keys)
+        } else {
+            crate::input::translate_key(key, self.logs.filter_mode(), &self.keys)
+        };
+        self.execute_command(cmd)
     }
 
     pub fn quit(&mut self) {
+        self.stop_recording();
         self.should_quit = true;
     }
 
     // Daemon lifecycle (start/stop/restart/autostart) is handled by ms-manager.
 
     pub(super) fn toggle_bridge_pause(&mut self) {
+        if self.read_only {
+            self.set_status("Read-only: analyzing a log file");
+            return;
+        }
+
         if !self.daemon_running {
             self.set_status("Daemon not running");
             return;
         }
 
-        if self.config.bridge.controller_transport != ControllerTransport::Serial {
+        if !self.config.bridge.controller_transport.is_serial() {
             self.set_status("Serial control is only available in Serial controller mode");
             return;
         }
@@ -189,6 +687,47 @@ impl App {
         }
     }
 
+    /// Show / hide controller jitter in the status widget
+    pub(super) fn toggle_jitter(&mut self) {
+        self.show_jitter = !self.show_jitter;
+        self.set_status(if self.show_jitter {
+            "Jitter shown"
+        } else {
+            "Jitter hidden"
+        });
+    }
+
+    pub(super) fn cycle_host_transport(&mut self) {
+        if self.read_only {
+            self.set_status("Read-only: analyzing a log file");
+            return;
+        }
+
+        if !self.daemon_running {
+            self.set_status("Daemon not running");
+            return;
+        }
+
+        let port = self.config.bridge.control_port;
+        match control::send_command_blocking(port, "switch-host", Duration::from_millis(3000)) {
+            Ok(resp) => {
+                self.daemon_host_transport = resp.host_transport;
+                match (resp.ok, resp.host_transport) {
+                    (true, Some(transport)) => {
+                        self.set_status(format!("Host transport: {:?}", transport))
+                    }
+                    _ => self.set_status(format!(
+                        "Host switch failed: {}",
+                        resp.message.unwrap_or_default()
+                    )),
+                }
+            }
+            Err(e) => {
+                self.set_status(format!("Host switch failed: {}", e));
+            }
+        }
+    }
+
     pub(super) fn set_status(&mut self, msg: impl Into<String>) {
         self.status_message = Some((msg.into(), Instant::now()));
     }
@@ -196,7 +735,7 @@ impl App {
     fn status_text(&self) -> Option<&str> {
         self.status_message
             .as_ref()
-            .filter(|(_, t)| t.elapsed().as_secs() < STATUS_MESSAGE_TIMEOUT_SECS)
+            .filter(|(_, t)| t.elapsed().as_secs() < self.config.ui.status_message_timeout_secs)
             .map(|(s, _)| s.as_str())
     }
 
@@ -216,19 +755,50 @@ impl App {
         };
 
         let before = self.logs.entries().len();
+        let mut recording_error = None;
+        let mut file_log_error = None;
 
         while let Ok(entry) = rx.try_recv() {
             if let LogKind::Protocol {
-                direction, size, ..
+                direction,
+                message_name,
+                size,
             } = &entry.kind
             {
                 match direction {
                     Direction::In => self.stats.add_rx(*size),
                     Direction::Out => self.stats.add_tx(*size),
                 }
+                self.stats.record_message(message_name, *direction, *size);
+            }
+            #[cfg(feature = "http-api")]
+            if let Some(http) = &self.http {
+                http.push_log(entry.clone());
+            }
+            if let Some(stream) = &self.log_stream {
+                stream.send(&entry);
+            }
+            // A write error (e.g. disk full) ends the recording
+            if let Some(Err(e)) = self.recording.as_mut().map(|r| r.write(&entry)) {
+                self.recording = None;
+                recording_error = Some(e);
+            }
+            if let Some(Err(e)) = self.file_logger.as_mut().map(|l| l.append(&entry)) {
+                self.file_logger = None;
+                file_log_error = Some(e);
             }
             self.logs.add(entry);
         }
+        if let Some(Err(e)) = self.file_logger.as_mut().map(FileLogger::flush) {
+            self.file_logger = None;
+            file_log_error = Some(e);
+        }
+        if let Some(e) = recording_error {
+            self.set_status(format!("Recording stopped: {}", e));
+        }
+        if let Some(e) = file_log_error {
+            self.set_status(format!("File logging stopped: {}", e));
+        }
 
         let after = self.logs.entries().len();
         if after > before {
@@ -241,31 +811,97 @@ impl App {
         }
     }
 
-    fn refresh_daemon_status(&mut self) {
-        let port = self.config.bridge.control_port;
-        let timeout = Duration::from_millis(180);
-        match control::send_command_blocking(port, "status", timeout) {
-            Ok(resp) => {
+    fn apply_daemon_status(&mut self, status: Option<control::Response>) {
+        match status {
+            Some(resp) => {
                 self.daemon_running = true;
                 self.bridge_paused = resp.paused;
                 self.serial_open = resp.serial_open;
+                self.cobs_trace = resp.cobs_trace.unwrap_or(false);
+                self.daemon_host_transport = resp.host_transport;
+                self.host_ws_clients = resp.host_ws_clients;
+                self.host_udp_peer = resp.host_udp_peer;
+                self.daemon_jitter_ms = resp.jitter_ms;
+                self.daemon_write_fill_ratio = resp.write_fill_ratio;
+                self.daemon_control_requests =
+                    resp.control_requests_total.zip(resp.control_latency_ms_avg);
             }
-            Err(_) => {
+            None => {
                 self.daemon_running = false;
                 self.bridge_paused = false;
                 self.serial_open = false;
+                self.cobs_trace = false;
+                self.daemon_host_transport = None;
+                self.host_ws_clients = None;
+                self.host_udp_peer = None;
+                self.daemon_jitter_ms = None;
+                self.daemon_write_fill_ratio = None;
+                self.daemon_control_requests = None;
             }
         }
 
         self.controller_state =
             determine_controller_state(&self.config, self.daemon_running, self.serial_open);
+        self.serial_port_from_env = config::serial_port_from_env(&self.config);
     }
 
     // (Autostart is managed by ms-manager.)
+
+    // =========================================================================
+    // Test hooks (drive the controller state machine without hardware)
+    // =========================================================================
+
+    /// Status as the running daemon would report it, with `serial_open` overridden
+    #[cfg(test)]
+    fn simulated_status(&self, serial_open: bool) -> control::Response {
+        let mut resp: control::Response = serde_json::from_value(serde_json::json!({
+            "ok": true,
+            "paused": self.bridge_paused,
+            "serial_open": serial_open,
+        }))
+        .expect("valid status response");
+        resp.cobs_trace = Some(self.cobs_trace);
+        resp.host_transport = self.daemon_host_transport;
+        resp
+    }
+
+    /// Daemon keeps running but lost its serial port (cable pulled)
+    #[cfg(test)]
+    pub(crate) fn simulate_disconnect(&mut self) {
+        let status = self.simulated_status(false);
+        self.apply_daemon_status(Some(status));
+    }
+
+    /// Daemon reopened the serial port at `port`
+    #[cfg(test)]
+    pub(crate) fn simulate_reconnect(&mut self, port: String) {
+        self.logs.add(LogEntry::controller_event(
+            crate::logging::ControllerEventKind::Connected {
+                port: port.clone(),
+                transport: "Serial".to_string(),
+            },
+        ));
+        self.config.bridge.serial_port = port;
+        let status = self.simulated_status(true);
+        self.apply_daemon_status(Some(status));
+    }
+}
+
+/// Listen for daemon log broadcasts on `port` (`None` if the port is taken)
+fn spawn_log_receiver(port: u16) -> (Option<mpsc::Receiver<LogEntry>>, Option<LogLink>) {
+    let shutdown = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let receiver = crate::logging::receiver::spawn_log_receiver_with_port(shutdown, port).ok();
+    let (log_rx, log_stats) = receiver.unzip();
+    (log_rx, log_stats.map(LogLink::new))
 }
 
-fn determine_host_state(cfg: &Config) -> HostTransportState {
-    match cfg.bridge.host_transport {
+fn spike_detector(cfg: &Config) -> Option<SpikeDetector> {
+    let spike = &cfg.bridge.spike_detector;
+    spike.enabled.then(|| SpikeDetector::new(spike))
+}
+
+fn determine_host_state(cfg: &Config, transport: HostTransport) -> HostTransportState {
+    match transport {
         HostTransport::Udp => HostTransportState::Udp {
             port: cfg.bridge.host_udp_port,
         },
@@ -276,6 +912,71 @@ fn determine_host_state(cfg: &Config) -> HostTransportState {
             udp_port: cfg.bridge.host_udp_port,
             ws_port: cfg.bridge.host_websocket_port,
         },
+        #[cfg(windows)]
+        HostTransport::NamedPipe => HostTransportState::NamedPipe {
+            name: crate::constants::HOST_PIPE_NAME,
+        },
+    }
+}
+
+/// Codec the daemon uses for the controller, with the baud rate on serial
+///
+/// `COBS+Debug @ 115200 baud` (a device preset's `baud_rate` wins, as in
+/// the daemon)
+fn codec_label(cfg: &Config) -> String {
+    let bridge = &cfg.bridge;
+    let codec = match bridge.controller_transport {
+        ControllerTransport::Serial if !bridge.codec_pipeline.is_empty() => {
+            bridge.codec_pipeline.join("+")
+        }
+        ControllerTransport::Serial => match bridge.codec {
+            SerialCodec::Cobs => "COBS+Debug".to_string(),
+            SerialCodec::CobsCrc => "COBS+CRC".to_string(),
+        },
+        ControllerTransport::FramedLength2 => "Length16".to_string(),
+        ControllerTransport::FramedLength4 => "Length32".to_string(),
+        ControllerTransport::HexSerial => "Hex".to_string(),
+        ControllerTransport::Tcp => "Length16".to_string(),
+        #[cfg(unix)]
+        ControllerTransport::Unix => "Length16".to_string(),
+        ControllerTransport::Udp | ControllerTransport::WebSocket => "Raw".to_string(),
+    };
+    if !bridge.controller_transport.is_serial() {
+        return codec;
+    }
+    match bridge.serial_baud_rate(config::detection_preset(cfg).as_ref()) {
+        Ok(baud) => format!("{} @ {} baud", codec, baud),
+        Err(_) => format!("{} @ invalid baud rate", codec),
+    }
+}
+
+/// Host transport ports, with their clients while the daemon runs
+///
+/// `UDP:9000 (127.0.0.1:5000) + WS:9002 (1 client)`
+fn host_info(
+    state: &HostTransportState,
+    daemon_running: bool,
+    ws_clients: Option<usize>,
+    udp_peer: Option<&str>,
+) -> String {
+    let udp = |port: u16| match (daemon_running, udp_peer) {
+        (false, _) => format!("UDP:{}", port),
+        (true, Some(peer)) => format!("UDP:{} ({})", port, peer),
+        (true, None) => format!("UDP:{} (no client)", port),
+    };
+    let ws = |port: u16| match (daemon_running, ws_clients) {
+        (true, Some(1)) => format!("WS:{} (1 client)", port),
+        (true, Some(n)) => format!("WS:{} ({} clients)", port, n),
+        _ => format!("WS:{}", port),
+    };
+    match state {
+        HostTransportState::Udp { port } => udp(*port),
+        HostTransportState::WebSocket { port } => ws(*port),
+        HostTransportState::Both { udp_port, ws_port } => {
+            format!("{} + {}", udp(*udp_port), ws(*ws_port))
+        }
+        #[cfg(windows)]
+        HostTransportState::NamedPipe { name } => format!("Pipe:{}", name),
     }
 }
 
@@ -289,9 +990,14 @@ fn determine_controller_state(
     }
 
     match cfg.bridge.controller_transport {
-        ControllerTransport::Serial => {
+        ControllerTransport::Serial
+        | ControllerTransport::FramedLength2
+        | ControllerTransport::FramedLength4
+        | ControllerTransport::HexSerial => {
             if serial_open {
-                let port = config::detect_serial(cfg).unwrap_or_else(|| "(waiting)".to_string());
+                let port = config::configured_serial_port(cfg)
+                    .or_else(|| config::detect_serial(cfg))
+                    .unwrap_or_else(|| "(waiting)".to_string());
                 ControllerTransportState::Serial { port }
             } else {
                 ControllerTransportState::Waiting
@@ -303,7 +1009,256 @@ fn determine_controller_state(
         ControllerTransport::WebSocket => ControllerTransportState::WebSocket {
             port: cfg.bridge.controller_websocket_port,
         },
+        ControllerTransport::Tcp => ControllerTransportState::Tcp {
+            addr: cfg.bridge.controller_tcp_addr.to_string(),
+        },
+        #[cfg(unix)]
+        ControllerTransport::Unix => ControllerTransportState::Unix {
+            path: cfg.bridge.unix_socket_path().display().to_string(),
+        },
     }
 }
 
 // (Daemon lifecycle is handled by ms-manager.)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::ControllerEventKind;
+
+    fn app(transport: ControllerTransport) -> App {
+        let mut config = Config::default();
+        config.bridge.controller_transport = transport;
+        App::with_logs(config, LogStore::new(100), None)
+    }
+
+    fn serial_app(port: &str) -> App {
+        let mut app = app(ControllerTransport::Serial);
+        app.simulate_reconnect(port.to_string());
+        app
+    }
+
+    fn serial(port: &str) -> ControllerTransportState {
+        ControllerTransportState::Serial {
+            port: port.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_starts_disconnected() {
+        let app = app(ControllerTransport::Serial);
+        assert!(!app.daemon_running);
+        assert_eq!(app.controller_state, ControllerTransportState::Disconnected);
+    }
+
+    #[test]
+    fn test_reconnect_reports_serial_port_and_logs_it() {
+        let app = serial_app("/dev/ttyACM0");
+        assert!(app.daemon_running && app.serial_open);
+        assert_eq!(app.controller_state, serial("/dev/ttyACM0"));
+
+        let last = app.logs.entries().back().unwrap();
+        assert!(matches!(
+            &last.kind,
+            LogKind::ControllerEvent {
+                event: ControllerEventKind::Connected { port, transport },
+            } if port == "/dev/ttyACM0" && transport == "Serial"
+        ));
+    }
+
+    #[test]
+    fn test_disconnect_while_running_waits_for_device() {
+        let mut app = serial_app("/dev/ttyACM0");
+        app.simulate_disconnect();
+        assert!(app.daemon_running && !app.serial_open);
+        assert_eq!(app.controller_state, ControllerTransportState::Waiting);
+    }
+
+    #[test]
+    fn test_reconnect_after_disconnect_picks_up_new_port() {
+        let mut app = serial_app("/dev/ttyACM0");
+        app.simulate_disconnect();
+        app.simulate_reconnect("/dev/ttyACM1".to_string());
+        assert_eq!(app.controller_state, serial("/dev/ttyACM1"));
+    }
+
+    #[test]
+    fn test_repeated_disconnect_is_stable() {
+        let mut app = serial_app("/dev/ttyACM0");
+        app.simulate_disconnect();
+        app.simulate_disconnect();
+        assert_eq!(app.controller_state, ControllerTransportState::Waiting);
+    }
+
+    #[test]
+    fn test_daemon_exit_resets_to_disconnected() {
+        let mut app = serial_app("/dev/ttyACM0");
+        app.cobs_trace = true;
+        app.daemon_host_transport = Some(HostTransport::WebSocket);
+
+        app.apply_daemon_status(None);
+        assert_eq!(app.controller_state, ControllerTransportState::Disconnected);
+        assert!(!app.daemon_running && !app.serial_open && !app.cobs_trace);
+        assert_eq!(app.daemon_host_transport, None);
+    }
+
+    #[test]
+    fn test_disconnect_keeps_released_serial_and_daemon_flags() {
+        let mut app = serial_app("/dev/ttyACM0");
+        app.bridge_paused = true;
+        app.cobs_trace = true;
+        app.daemon_host_transport = Some(HostTransport::Both);
+
+        app.simulate_disconnect();
+        assert!(app.bridge_paused && app.cobs_trace);
+        assert_eq!(app.daemon_host_transport, Some(HostTransport::Both));
+        assert_eq!(app.controller_state, ControllerTransportState::Waiting);
+    }
+
+    #[test]
+    fn test_framed_transports_follow_serial_state() {
+        for transport in [
+            ControllerTransport::FramedLength2,
+            ControllerTransport::FramedLength4,
+            ControllerTransport::HexSerial,
+        ] {
+            let mut app = app(transport);
+            app.simulate_reconnect("COM3".to_string());
+            assert_eq!(app.controller_state, serial("COM3"));
+            app.simulate_disconnect();
+            assert_eq!(app.controller_state, ControllerTransportState::Waiting);
+        }
+    }
+
+    #[test]
+    fn test_network_controllers_ignore_serial_events() {
+        let mut udp = app(ControllerTransport::Udp);
+        udp.simulate_disconnect();
+        let udp_port = udp.config.bridge.controller_udp_port;
+        assert_eq!(
+            udp.controller_state,
+            ControllerTransportState::Udp { port: udp_port }
+        );
+
+        let mut ws = app(ControllerTransport::WebSocket);
+        ws.simulate_reconnect("/dev/ttyACM0".to_string());
+        let ws_port = ws.config.bridge.controller_websocket_port;
+        assert_eq!(
+            ws.controller_state,
+            ControllerTransportState::WebSocket { port: ws_port }
+        );
+    }
+
+    #[test]
+    fn test_read_only_poll_keeps_state() {
+        let mut app = serial_app("/dev/ttyACM0");
+        app.read_only = true;
+        app.poll();
+        assert_eq!(app.controller_state, serial("/dev/ttyACM0"));
+        assert!(app.daemon_running);
+    }
+
+    #[test]
+    fn test_host_info_shows_clients_while_running() {
+        let both = HostTransportState::Both {
+            udp_port: 9000,
+            ws_port: 9002,
+        };
+        assert_eq!(
+            host_info(&both, true, Some(2), Some("127.0.0.1:5000")),
+            "UDP:9000 (127.0.0.1:5000) + WS:9002 (2 clients)"
+        );
+        assert_eq!(
+            host_info(&both, true, Some(1), None),
+            "UDP:9000 (no client) + WS:9002 (1 client)"
+        );
+        assert_eq!(host_info(&both, false, None, None), "UDP:9000 + WS:9002");
+    }
+
+    #[test]
+    fn test_codec_label_uses_the_device_preset_baud_rate() {
+        let mut cfg = Config::default();
+        assert_eq!(codec_label(&cfg), "COBS+Debug @ 115200 baud");
+
+        let device =
+            toml::from_str("name = \"Lab\"\nvid = 1\npid_list = [2]\nbaud_rate = 9600").unwrap();
+        cfg.device_presets.insert("lab".to_string(), device);
+        cfg.bridge.device_preset = Some("lab".to_string());
+        assert_eq!(codec_label(&cfg), "COBS+Debug @ 9600 baud");
+
+        cfg.bridge.controller_transport = ControllerTransport::Udp;
+        assert_eq!(codec_label(&cfg), "Raw");
+    }
+
+    #[test]
+    fn test_config_reload_applies_tui_settings_and_holds_bridge_changes() {
+        let mut app = app(ControllerTransport::Serial);
+        let mut cfg = app.config.clone();
+        cfg.logs.max_entries = 50;
+        cfg.ui.default_filter = "Protocol".to_string();
+        cfg.ui.status_message_timeout_secs = 7;
+        app.apply_config(cfg.clone());
+        assert_eq!(app.logs.filter_mode(), FilterMode::Protocol);
+        assert_eq!(app.config.ui.status_message_timeout_secs, 7);
+        assert!(!app.state().config_changed);
+
+        // A transport change waits for Shift+R
+        cfg.bridge.controller_transport = ControllerTransport::Udp;
+        app.apply_config(cfg);
+        assert!(app.state().config_changed);
+        assert_eq!(
+            app.config.bridge.controller_transport,
+            ControllerTransport::Serial
+        );
+        assert!(app.status_text().unwrap().starts_with("Config changed"));
+
+        app.execute_command(AppCommand::ApplyBridgeConfig);
+        assert!(!app.state().config_changed);
+        assert_eq!(
+            app.config.bridge.controller_transport,
+            ControllerTransport::Udp
+        );
+    }
+
+    #[test]
+    fn test_message_stats_panel_does_not_redraw_every_frame() {
+        let mut app = app(ControllerTransport::Serial);
+        app.show_message_stats = true;
+        app.stats.record_message("Ping", Direction::In, 4);
+
+        let first = app.state().into_owned();
+        std::thread::sleep(Duration::from_millis(20));
+        let second = app.state();
+        assert!(!second.diff(&first).logs_changed);
+    }
+
+    #[test]
+    fn test_search_types_cycles_and_clears() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        let mut app = app(ControllerTransport::Serial);
+        for message in ["ping a", "other", "PING b"] {
+            app.logs.add(LogEntry::system(message));
+        }
+
+        press(&mut app, KeyCode::Char('/'));
+        for c in "ping".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(app.state().search_input.as_deref(), Some("ping"));
+        assert_eq!(app.logs.search_matches().len(), 2);
+
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state().search_input.is_none());
+        // Typing scrolled to the first match; `n` cycles instead of scanning
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.status_text(), Some("Match 2/2"));
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.status_text(), Some("Match 1/2"));
+
+        // Esc clears the search instead of quitting
+        assert!(!press(&mut app, KeyCode::Esc));
+        assert!(app.logs.search_query().is_none());
+    }
+}
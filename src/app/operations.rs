@@ -1,10 +1,18 @@
-//! Log operations - clipboard and file export
+//! Log operations - clipboard, file export and live recording - network
+//! scan, and daemon CPU flamegraphs (both on background threads)
 
-use crate::logging::LogStore;
+use super::App;
+use crate::config::{self, ExportFormat};
+use crate::constants::{DISCOVERY_SCAN_TIMEOUT_MS, RECORDING_SIZE_REFRESH_SECS};
+use crate::control::{self, PROFILE_DURATION};
+use crate::discovery;
+use crate::logging::{LogEntry, LogStore};
 use crate::platform;
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
 // =============================================================================
 // Clipboard
@@ -12,7 +20,11 @@ use std::path::PathBuf;
 
 /// Result of a clipboard operation
 pub enum ClipboardResult {
-    Success(usize),
+    /// `count` log entries copied as `text`
+    Success {
+        count: usize,
+        text: String,
+    },
     Error(String),
 }
 
@@ -20,18 +32,22 @@ pub enum ClipboardResult {
 pub fn copy_logs(logs: &LogStore) -> ClipboardResult {
     let text = logs.to_text();
 
-    match arboard::Clipboard::new() {
-        Ok(mut clipboard) => {
-            if let Err(e) = clipboard.set_text(&text) {
-                ClipboardResult::Error(format!("Clipboard error: {}", e))
-            } else {
-                ClipboardResult::Success(logs.filtered_count())
-            }
-        }
-        Err(e) => ClipboardResult::Error(format!("Clipboard error: {}", e)),
+    match set_clipboard(&text) {
+        Ok(()) => ClipboardResult::Success {
+            count: logs.filtered_count(),
+            text,
+        },
+        Err(e) => ClipboardResult::Error(e),
     }
 }
 
+/// Put `text` on the system clipboard
+pub fn set_clipboard(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| format!("Clipboard error: {}", e))
+}
+
 // =============================================================================
 // File Export
 // =============================================================================
@@ -43,16 +59,19 @@ pub enum ExportResult {
 }
 
 /// Export logs to file and open with default application
-pub fn export_logs(logs: &LogStore, max_export: usize) -> ExportResult {
+pub fn export_logs(logs: &LogStore, max_export: usize, format: ExportFormat) -> ExportResult {
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("oc-bridge-log-{}.txt", timestamp);
+    let (text, extension) = match format {
+        ExportFormat::Text => (logs.to_text_limited(max_export), "txt"),
+        ExportFormat::JsonLines => (logs.to_jsonl_limited(max_export), "jsonl"),
+    };
+    let filename = format!("oc-bridge-log-{}.{}", timestamp, extension);
 
     let path = match get_export_path(&filename) {
         Some(p) => p,
         None => return ExportResult::Error("Cannot determine export path".to_string()),
     };
 
-    let text = logs.to_text_limited(max_export);
     let line_count = text.lines().count();
 
     match fs::File::create(&path) {
@@ -72,3 +91,308 @@ fn get_export_path(filename: &str) -> Option<PathBuf> {
         .ok()
         .and_then(|exe| exe.parent().map(|p| p.join(filename)))
 }
+
+// =============================================================================
+// Live Recording
+// =============================================================================
+
+/// Log entries received from the daemon, appended to a JSON Lines file
+pub struct LogRecording {
+    path: PathBuf,
+    writer: BufWriter<fs::File>,
+    /// Bytes written so far
+    bytes: u64,
+    /// Size shown in the status bar (refreshed every few seconds)
+    shown_bytes: u64,
+    shown_at: Instant,
+}
+
+impl LogRecording {
+    /// Create `path`, with its parent directory
+    ///
+    /// Never truncates: when `path` exists (another recording started in the
+    /// same second), `_1`, `_2`... is added to the file name.
+    fn create(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut attempt = 0;
+        let (path, file) = loop {
+            let candidate = match attempt {
+                0 => path.clone(),
+                n => suffixed_path(&path, n),
+            };
+            let opened = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&candidate);
+            match opened {
+                Ok(file) => break (candidate, file),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => {
+                    attempt += 1
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            bytes: 0,
+            shown_bytes: 0,
+            shown_at: Instant::now(),
+        })
+    }
+
+    /// Append `entry` as one JSON line
+    pub fn write(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        self.bytes += line.len() as u64;
+        Ok(())
+    }
+
+    /// Size for the status bar
+    pub fn shown_bytes(&self) -> u64 {
+        self.shown_bytes
+    }
+
+    /// Update the shown size every `RECORDING_SIZE_REFRESH_SECS`
+    pub fn refresh_shown_bytes(&mut self) {
+        if self.shown_at.elapsed() >= Duration::from_secs(RECORDING_SIZE_REFRESH_SECS) {
+            self.shown_bytes = self.bytes;
+            self.shown_at = Instant::now();
+        }
+    }
+}
+
+/// `path` with `_<n>` added before its extension
+fn suffixed_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, n, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, n),
+    };
+    path.with_file_name(name)
+}
+
+/// `{config_dir}/recordings/session-<timestamp>.jsonl`
+fn default_recording_path() -> Option<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    config::config_dir().ok().map(|dir| {
+        dir.join("recordings")
+            .join(format!("session-{}.jsonl", timestamp))
+    })
+}
+
+/// `1.5 MB`, `12.0 KB`, `512 B`
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b >= KB * KB {
+        format!("{:.1} MB", b / (KB * KB))
+    } else if b >= KB {
+        format!("{:.1} KB", b / KB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+impl App {
+    /// Start or stop recording to the default path (Alt+R)
+    pub fn toggle_recording(&mut self) {
+        if self.recording.is_some() {
+            self.stop_recording();
+            return;
+        }
+        match default_recording_path() {
+            Some(path) => self.start_recording(path),
+            None => self.set_status("Cannot determine recording path"),
+        }
+    }
+
+    /// Append every log entry received from now on to `path` (JSON Lines)
+    pub fn start_recording(&mut self, path: PathBuf) {
+        if self.read_only {
+            self.set_status("Read-only: analyzing a log file");
+            return;
+        }
+        self.stop_recording();
+        match LogRecording::create(path) {
+            Ok(recording) => {
+                self.set_status(format!("Recording to {}", recording.path.display()));
+                self.recording = Some(recording);
+            }
+            Err(e) => self.set_status(format!("Recording failed: {}", e)),
+        }
+    }
+
+    /// Flush and close the recording file
+    pub fn stop_recording(&mut self) {
+        let Some(mut recording) = self.recording.take() else {
+            return;
+        };
+        match recording.writer.flush() {
+            Ok(()) => self.set_status(format!(
+                "Recorded {} to {}",
+                format_size(recording.bytes),
+                recording.path.display()
+            )),
+            Err(e) => self.set_status(format!("Recording failed: {}", e)),
+        }
+    }
+}
+
+// =============================================================================
+// Network Scan
+// =============================================================================
+
+impl App {
+    /// Broadcast a discovery request from a background thread
+    ///
+    /// The answers are shown by `update_network_scan` once the scan window
+    /// has passed, so the UI keeps drawing meanwhile.
+    pub fn start_network_scan(&mut self) {
+        if self.network_scan.is_some() {
+            return;
+        }
+        let port = self.config.bridge.discovery_port;
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(discovery::scan(
+                port,
+                Duration::from_millis(DISCOVERY_SCAN_TIMEOUT_MS),
+            ));
+        });
+        self.network_scan = Some(rx);
+        self.set_status("Scanning the network...");
+    }
+
+    /// Open the peers popup when the running scan has finished
+    pub(super) fn update_network_scan(&mut self) {
+        let Some(scan) = &self.network_scan else {
+            return;
+        };
+        let peers = match scan.try_recv() {
+            Ok(peers) => peers,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => Vec::new(),
+        };
+        self.network_scan = None;
+        self.show_peers(peers);
+    }
+}
+
+// =============================================================================
+// Flamegraph
+// =============================================================================
+
+/// `profile` request running on a background thread
+pub struct FlamegraphCapture {
+    started: Instant,
+    /// SVG path, or why the capture failed
+    result: mpsc::Receiver<std::result::Result<String, String>>,
+}
+
+impl App {
+    /// Ask the daemon for a CPU flamegraph (Alt+P); opened when written
+    ///
+    /// Needs a daemon built with the `profiling` feature.
+    pub fn export_flamegraph(&mut self) {
+        if self.flamegraph.is_some() {
+            return;
+        }
+        if !self.daemon_running {
+            self.set_status("Profiling: bridge not running");
+            return;
+        }
+
+        let port = self.config.bridge.control_port;
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            // Capture, then report and SVG rendering
+            let timeout = PROFILE_DURATION + Duration::from_secs(10);
+            let result = match control::send_command_blocking(port, "profile", timeout) {
+                Ok(resp) if resp.ok => resp.message.ok_or_else(|| "no SVG path".to_string()),
+                Ok(resp) => Err(resp.message.unwrap_or_else(|| "refused".to_string())),
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = tx.send(result);
+        });
+        self.flamegraph = Some(FlamegraphCapture {
+            started: Instant::now(),
+            result: rx,
+        });
+    }
+
+    /// Count the capture down in the status bar, then open the SVG
+    pub(super) fn update_flamegraph(&mut self) {
+        let Some(capture) = &self.flamegraph else {
+            return;
+        };
+        let result = match capture.result.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => {
+                let left = PROFILE_DURATION.saturating_sub(capture.started.elapsed());
+                self.set_status(format!("Profiling... {}s", left.as_secs_f64().ceil()));
+                return;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => Err("profiling thread exited".to_string()),
+        };
+        self.flamegraph = None;
+
+        match result {
+            Ok(path) => match platform::open_file(Path::new(&path)) {
+                Ok(()) => self.set_status(format!("Flamegraph: {}", path)),
+                Err(e) => self.set_status(format!("Flamegraph: {} (cannot open: {})", path, e)),
+            },
+            Err(e) => self.set_status(format!("Profiling failed: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_recording_writes_json_lines_until_stopped() {
+        let dir = std::env::temp_dir().join(format!("oc-bridge-rec-{}", std::process::id()));
+        let path = dir.join("recordings").join("session.jsonl");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut app = App::with_logs(Config::default(), LogStore::new(100), None);
+        app.start_recording(path.clone());
+        assert!(app.recording.is_some());
+
+        let recording = app.recording.as_mut().unwrap();
+        recording.write(&LogEntry::system("first")).unwrap();
+        recording.write(&LogEntry::system("second")).unwrap();
+        app.stop_recording();
+        assert!(app.recording.is_none());
+
+        let content = fs::read_to_string(&path).unwrap();
+        let entries: Vec<LogEntry> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+
+        // Same name again: a new file next to it, the first one is kept
+        app.start_recording(path.clone());
+        let second = app.recording.as_ref().unwrap().path.clone();
+        app.stop_recording();
+        assert_eq!(second, dir.join("recordings").join("session_1.jsonl"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
@@ -0,0 +1,148 @@
+//! Discovered bridges popup
+//!
+//! Results of a network scan (`N`), listed until the popup is closed.
+
+use super::App;
+use crate::discovery::BridgeInstance;
+
+impl App {
+    /// Scan the network; the popup opens with the bridges found
+    pub fn show_network_scan(&mut self) {
+        if self.config.bridge.discovery_port == 0 {
+            self.set_status("Network scan disabled (discovery_port = 0)");
+            return;
+        }
+        self.start_network_scan();
+    }
+
+    pub(super) fn show_peers(&mut self, peers: Vec<BridgeInstance>) {
+        self.peers = peers;
+        if self.peers.is_empty() {
+            self.peers_selected = None;
+            self.set_status("No bridges found on the network");
+        } else {
+            self.peers_selected = Some(0);
+            self.set_status(format!("Found {} bridge(s)", self.peers.len()));
+        }
+    }
+
+    /// Move the popup selection one entry down (or up)
+    pub fn move_peer_selection(&mut self, down: bool) {
+        let last = self.peers.len().saturating_sub(1);
+        if let Some(selected) = &mut self.peers_selected {
+            *selected = if down {
+                (*selected + 1).min(last)
+            } else {
+                selected.saturating_sub(1)
+            };
+        }
+    }
+
+    /// One line per bridge: `<instance> @ <ip>  v<version>  host UDP <port>  serial <sn>`
+    pub(super) fn peer_labels(&self) -> Vec<String> {
+        self.peers
+            .iter()
+            .map(|peer| {
+                format!(
+                    "{} @ {}  v{}  host UDP {}  serial {}",
+                    peer.info.instance_id,
+                    peer.addr.ip(),
+                    peer.info.version,
+                    peer.info.host_udp_port,
+                    peer.info.controller_serial.as_deref().unwrap_or("any"),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::App;
+    use crate::config::{Config, HostTransport};
+    use crate::control::ControlInfo;
+    use crate::discovery::BridgeInstance;
+    use crate::logging::LogStore;
+
+    fn peer(instance_id: &str, ip: &str) -> BridgeInstance {
+        BridgeInstance {
+            addr: format!("{}:9099", ip).parse().unwrap(),
+            info: ControlInfo {
+                pid: 1,
+                version: "0.3.0".to_string(),
+                config_path: String::new(),
+                instance_id: instance_id.to_string(),
+                controller_serial: None,
+                host_udp_port: 9000,
+                host_pipe_name: None,
+                log_broadcast_port: 9999,
+                control_port: 7999,
+                serial_supported: true,
+                cobs_trace: false,
+                host_transport: HostTransport::Udp,
+                metrics_port: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_peers_popup_lists_and_scrolls() {
+        let mut app = App::with_logs(Config::default(), LogStore::new(10), None);
+        app.show_peers(vec![
+            peer("rack-a", "192.168.1.20"),
+            peer("rack-b", "192.168.1.21"),
+        ]);
+
+        assert_eq!(
+            app.peer_labels()[0],
+            "rack-a @ 192.168.1.20  v0.3.0  host UDP 9000  serial any"
+        );
+        assert_eq!(app.state().peers_popup.unwrap().selected, 0);
+        app.move_peer_selection(true);
+        app.move_peer_selection(true);
+        assert_eq!(app.peers_selected, Some(1));
+
+        app.show_peers(Vec::new());
+        assert!(app.state().peers_popup.is_none());
+        assert_eq!(app.status_text(), Some("No bridges found on the network"));
+    }
+
+    #[test]
+    fn test_network_scan_does_not_block_the_ui() {
+        use crate::constants::DISCOVERY_SCAN_TIMEOUT_MS;
+        use std::time::{Duration, Instant};
+
+        let mut config = Config::default();
+        // Nobody answers on a port that was just free
+        config.bridge.discovery_port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut app = App::with_logs(config, LogStore::new(10), None);
+
+        let started = Instant::now();
+        app.show_network_scan();
+        assert!(started.elapsed() < Duration::from_millis(DISCOVERY_SCAN_TIMEOUT_MS));
+        assert_eq!(app.status_text(), Some("Scanning the network..."));
+
+        while app.network_scan.is_some() && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(20));
+            app.poll();
+        }
+        assert_eq!(app.status_text(), Some("No bridges found on the network"));
+    }
+
+    #[test]
+    fn test_scan_disabled_without_discovery_port() {
+        let mut config = Config::default();
+        config.bridge.discovery_port = 0;
+        let mut app = App::with_logs(config, LogStore::new(10), None);
+        app.show_network_scan();
+        assert!(app.peers_selected.is_none());
+        assert_eq!(
+            app.status_text(),
+            Some("Network scan disabled (discovery_port = 0)")
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Command pipelines and the command palette
+//!
+//! A pipeline runs several commands in order (`FilterAll ClearLogs
+//! Sleep(500) ExportLogs`). `Sleep` does not block the UI: the remaining
+//! commands are queued and resumed from `poll` once the delay has passed.
+
+use super::App;
+use crate::input::{self, AppCommand};
+use std::time::Instant;
+
+impl App {
+    /// Run `cmds` in order. Returns true if any of them caused a quit.
+    ///
+    /// Replaces any pipeline still waiting on a `Sleep`. A `Sleep` past the
+    /// range of `Instant` stops the pipeline.
+    pub fn execute_pipeline(&mut self, cmds: &[AppCommand]) -> bool {
+        self.pipeline.clear();
+        self.pipeline_resume_at = None;
+
+        for (i, cmd) in cmds.iter().enumerate() {
+            if let AppCommand::Sleep(delay) = cmd {
+                match Instant::now().checked_add(*delay) {
+                    Some(at) => {
+                        self.pipeline.extend(cmds[i + 1..].iter().cloned());
+                        self.pipeline_resume_at = Some(at);
+                    }
+                    None => self.set_status("Sleep too long, pipeline stopped"),
+                }
+                return false;
+            }
+            if self.execute_command(cmd.clone()) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Continue a pipeline paused on `Sleep` once its delay has passed
+    pub(super) fn resume_pipeline(&mut self) {
+        if self.pipeline_resume_at.is_none_or(|at| Instant::now() < at) {
+            return;
+        }
+        let rest: Vec<AppCommand> = self.pipeline.drain(..).collect();
+        self.execute_pipeline(&rest);
+    }
+
+    pub fn open_command_palette(&mut self) {
+        self.command_palette = Some(String::new());
+    }
+
+    pub fn close_command_palette(&mut self) {
+        self.command_palette = None;
+    }
+
+    pub fn palette_input(&mut self, c: char) {
+        if let Some(text) = &mut self.command_palette {
+            text.push(c);
+        }
+    }
+
+    pub fn palette_backspace(&mut self) {
+        if let Some(text) = &mut self.command_palette {
+            text.pop();
+        }
+    }
+
+    /// Close the palette and run its text as a pipeline
+    pub fn submit_command_palette(&mut self) -> bool {
+        let Some(text) = self.command_palette.take() else {
+            return false;
+        };
+        match input::parse_pipeline(&text) {
+            Ok(cmds) => self.execute_pipeline(&cmds),
+            Err(word) => {
+                self.set_status(format!("Unknown command: {}", word));
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::App;
+    use crate::config::Config;
+    use crate::input::AppCommand;
+    use crate::logging::{FilterMode, LogEntry, LogStore};
+    use std::time::Duration;
+
+    fn app() -> App {
+        let mut app = App::with_logs(Config::default(), LogStore::new(100), None);
+        app.read_only = true;
+        app.logs.add(LogEntry::system("hello"));
+        app
+    }
+
+    #[test]
+    fn test_pipeline_runs_in_order_and_stops_on_quit() {
+        let mut app = app();
+        let quit = app.execute_pipeline(&[
+            AppCommand::FilterDebug,
+            AppCommand::ClearLogs,
+            AppCommand::Quit,
+            AppCommand::FilterAll,
+        ]);
+
+        assert!(quit);
+        assert!(app.logs.entries().is_empty());
+        assert_eq!(app.filter_mode(), FilterMode::Debug);
+    }
+
+    #[test]
+    fn test_pipeline_sleep_defers_remaining_commands() {
+        let mut app = app();
+        let quit = app.execute_pipeline(&[
+            AppCommand::FilterDebug,
+            AppCommand::Sleep(Duration::from_millis(20)),
+            AppCommand::ClearLogs,
+        ]);
+
+        assert!(!quit);
+        assert_eq!(app.filter_mode(), FilterMode::Debug);
+        app.poll();
+        assert_eq!(app.logs.entries().len(), 1);
+
+        std::thread::sleep(Duration::from_millis(30));
+        app.poll();
+        assert!(app.logs.entries().is_empty());
+        assert!(app.pipeline.is_empty());
+    }
+
+    #[test]
+    fn test_pipeline_sleep_too_long_stops_pipeline() {
+        let mut app = app();
+        let quit = app.execute_pipeline(&[AppCommand::Sleep(Duration::MAX), AppCommand::ClearLogs]);
+
+        assert!(!quit);
+        assert!(app.pipeline.is_empty());
+        assert_eq!(app.status_text(), Some("Sleep too long, pipeline stopped"));
+        app.poll();
+        assert_eq!(app.logs.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_palette_submit_parses_pipeline() {
+        let mut app = app();
+        app.open_command_palette();
+        for c in "filter_debug clearlogsx".chars() {
+            app.palette_input(c);
+        }
+        assert!(!app.submit_command_palette());
+        assert!(app.command_palette.is_none());
+        assert_eq!(app.status_text(), Some("Unknown command: clearlogsx"));
+        assert_eq!(app.filter_mode(), FilterMode::All);
+
+        app.open_command_palette();
+        for c in "filter_debug clearlogsx".chars() {
+            app.palette_input(c);
+        }
+        app.palette_backspace();
+        assert!(!app.submit_command_palette());
+        assert_eq!(app.filter_mode(), FilterMode::Debug);
+        assert!(app.logs.entries().is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Serial port picker popup
+//!
+//! Ports listed by `Shift+L` (same table as `oc-bridge list-ports`).
+//! `Enter` saves the selected port as `serial_port` in the config, which the
+//! config watcher then holds for the next daemon start (Shift+R); `c` only
+//! copies the port name.
+
+use super::operations;
+use super::App;
+use crate::config;
+use crate::transport::ports::{self, PortListing};
+
+impl App {
+    /// Enumerate the serial ports and open the picker
+    pub fn show_serial_ports(&mut self) {
+        let preset = config::detection_preset(&self.config);
+        match ports::list_ports(preset.as_ref()) {
+            Ok(listings) => self.show_ports(listings),
+            Err(e) => self.set_status(e),
+        }
+    }
+
+    pub(super) fn show_ports(&mut self, listings: Vec<PortListing>) {
+        self.ports = listings;
+        if self.ports.is_empty() {
+            self.ports_selected = None;
+            self.set_status("No serial ports found");
+        } else {
+            // Start on the port auto-detection would pick
+            self.ports_selected = Some(
+                self.ports
+                    .iter()
+                    .position(|p| p.matches_preset)
+                    .unwrap_or(0),
+            );
+        }
+    }
+
+    /// Move the picker selection one entry down (or up)
+    pub fn move_port_selection(&mut self, down: bool) {
+        let last = self.ports.len().saturating_sub(1);
+        if let Some(selected) = &mut self.ports_selected {
+            *selected = if down {
+                (*selected + 1).min(last)
+            } else {
+                selected.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Close the picker, returning the selected port name
+    fn take_selected_port(&mut self) -> Option<String> {
+        self.ports_selected
+            .take()
+            .and_then(|i| self.ports.get(i))
+            .map(|p| p.port.clone())
+    }
+
+    /// Save the selected port as `serial_port` and close the picker
+    pub fn pick_port(&mut self) {
+        let Some(port) = self.take_selected_port() else {
+            return;
+        };
+        if self.read_only {
+            self.set_status("Read-only: analyzing a log file");
+            return;
+        }
+        let saved = config::config_path().and_then(|path| config::set_serial_port(&path, &port));
+        match saved {
+            Ok(()) if self.serial_port_from_env => self.set_status(format!(
+                "serial_port = {} saved, but OC_BRIDGE_SERIAL_PORT overrides it",
+                port
+            )),
+            Ok(()) => self.set_status(format!(
+                "serial_port = {} saved: restart the daemon, then press Shift+R",
+                port
+            )),
+            Err(e) => self.set_status(format!("Cannot save serial_port: {}", e)),
+        }
+    }
+
+    /// Copy the selected port name and close the picker
+    pub fn copy_port(&mut self) {
+        let Some(port) = self.take_selected_port() else {
+            return;
+        };
+        match operations::set_clipboard(&port) {
+            Ok(()) => {
+                self.set_status(format!(
+                    "Copied {}: paste it as serial_port in the config",
+                    port
+                ));
+                self.clipboard_history.push(port);
+            }
+            Err(e) => self.set_status(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::App;
+    use crate::config::Config;
+    use crate::logging::LogStore;
+    use crate::transport::ports::PortListing;
+
+    fn listing(port: &str, matches_preset: bool) -> PortListing {
+        PortListing {
+            port: port.to_string(),
+            kind: "USB",
+            vid: Some(0x16C0),
+            pid: Some(0x0489),
+            manufacturer: None,
+            product: None,
+            serial_number: None,
+            matches_preset,
+            attributes: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_ports_popup_starts_on_preset_match() {
+        let mut app = App::with_logs(Config::default(), LogStore::new(10), None);
+        app.show_ports(vec![
+            listing("/dev/ttyS0", false),
+            listing("/dev/ttyACM0", true),
+        ]);
+
+        let popup = app.state().ports_popup.unwrap();
+        assert_eq!(popup.selected, 1);
+        assert!(popup.header.trim_start().starts_with("PORT"));
+        assert!(popup.entries[1].starts_with("* /dev/ttyACM0"));
+        app.move_port_selection(true);
+        assert_eq!(app.ports_selected, Some(1));
+        app.move_port_selection(false);
+        assert_eq!(app.ports_selected, Some(0));
+
+        app.show_ports(Vec::new());
+        assert!(app.state().ports_popup.is_none());
+        assert_eq!(app.status_text(), Some("No serial ports found"));
+    }
+}
@@ -1,13 +1,18 @@
 //! Application state types
 //!
-//! Contains the state snapshot used for rendering.
+//! Contains the state snapshot used for rendering, and the diff between
+//! two snapshots used to skip redrawing frames where nothing changed.
 
+use crate::bridge::stats::MessageStats;
 use crate::config::{
-    ControllerTransport as ControllerTransportConfig, HostTransport as HostTransportConfig,
+    ControllerTransport as ControllerTransportConfig, ExportFormat,
+    HostTransport as HostTransportConfig, KeyBindings,
 };
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// Controller transport runtime state
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ControllerTransportState {
     /// Connected via serial port
     Serial { port: String },
@@ -15,6 +20,11 @@ pub enum ControllerTransportState {
     Udp { port: u16 },
     /// WebSocket server (controller simulation)
     WebSocket { port: u16 },
+    /// TCP stream (listened on or connected to)
+    Tcp { addr: String },
+    /// UNIX domain socket (bound or connected to)
+    #[cfg(unix)]
+    Unix { path: String },
     /// Waiting for connection (e.g., serial device not plugged in)
     Waiting,
     /// Disconnected (daemon not running)
@@ -22,7 +32,7 @@ pub enum ControllerTransportState {
 }
 
 /// Host transport runtime state
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum HostTransportState {
     /// UDP only
     Udp { port: u16 },
@@ -30,36 +40,316 @@ pub enum HostTransportState {
     WebSocket { port: u16 },
     /// Both UDP and WebSocket
     Both { udp_port: u16, ws_port: u16 },
+    /// Named pipe (Windows only)
+    #[cfg(windows)]
+    #[serde(skip_deserializing)]
+    NamedPipe { name: &'static str },
 }
 
-/// Application state snapshot for rendering (zero-copy)
-#[derive(Clone)]
+/// Clipboard history popup contents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardPopupState {
+    /// One label per entry, most recent first
+    pub entries: Vec<String>,
+    pub selected: usize,
+}
+
+/// Discovered bridges popup contents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeersPopupState {
+    /// One label per bridge, in answer order
+    pub entries: Vec<String>,
+    pub selected: usize,
+}
+
+/// Serial port picker contents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortsPopupState {
+    /// Column titles of `entries`
+    pub header: String,
+    /// One table row per port (`*` = matches the device preset)
+    pub entries: Vec<String>,
+    pub selected: usize,
+}
+
+/// Application state snapshot for rendering
+///
+/// Borrows from `App` for the current frame; `into_owned` detaches it so
+/// the previous frame's state can be kept for `diff`. Serializable for the
+/// HTTP state API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppState<'a> {
     // Daemon
     pub daemon_running: bool,
 
+    // Configuration
+    /// Profile selected with `--profile` (not restored when deserializing)
+    #[serde(skip_deserializing)]
+    pub profile: Option<&'static str>,
+    /// Validated key bindings (shown in the actions bar)
+    pub keys: Cow<'a, KeyBindings>,
+
     // Transport configuration
     pub controller_transport_config: ControllerTransportConfig,
     pub host_transport_config: HostTransportConfig,
 
     // Transport runtime state
-    pub controller_state: &'a ControllerTransportState,
+    pub controller_state: Cow<'a, ControllerTransportState>,
+    /// `[bridge]` settings edited on disk, not yet applied (Shift+R)
+    pub config_changed: bool,
+    /// Serial port set by an `OC_BRIDGE_SERIAL_PORT` / `OC_BRIDGE_PORT` override
+    pub serial_port_from_env: bool,
     pub host_state: HostTransportState,
+    /// Controller codec (`COBS+Debug`, `Raw`, ...)
+    pub active_codec: String,
+    /// Host ports with their clients (`UDP:9000 (127.0.0.1:5000) + WS:9002 (1 client)`)
+    pub active_host_info: String,
+    /// Controller jitter in ms, while shown (Shift+J)
+    pub jitter_ms: Option<f64>,
+    /// Controller write channel fill (0.0 to 1.0; near 1.0 serial is the bottleneck)
+    pub write_fill_ratio: Option<f64>,
+    /// Control plane requests the daemon has handled
+    pub control_requests_total: Option<u64>,
+    /// Average control plane request latency in ms
+    pub control_latency_ms_avg: Option<f64>,
 
     // Bridge control plane
     pub bridge_paused: bool,
+    pub cobs_trace: bool,
     pub control_port: u16,
 
     // Logs
     pub log_port: u16,
     pub log_available: bool,
     pub log_connected: bool,
+    /// Log link signal strength, 0 (disconnected) to 4 bars
+    pub log_signal: u8,
+    /// Daemon restarts seen by the log receiver
+    pub log_reconnects: u32,
 
-    // Traffic stats
+    // Traffic stats (KB/s over the last second / last minute)
     pub rx_rate: f64,
     pub tx_rate: f64,
+    pub rx_rate_avg: f64,
+    pub tx_rate_avg: f64,
+    /// Traffic spike under way, in the "on" half of its blink
+    pub spike_flash: bool,
+    /// Recording file size while a recording is active (Alt+R)
+    pub recording_bytes: Option<u64>,
+    /// Session log file being written (`[logs] file_logging`)
+    pub log_file: Option<Cow<'a, str>>,
+    /// Format of `E` exports
+    pub export_format: ExportFormat,
 
     // UI
     pub paused: bool,
-    pub status_message: Option<&'a str>,
+    pub status_message: Option<Cow<'a, str>>,
+    /// `LogStore::revision` at snapshot time
+    pub log_revision: u64,
+    /// Long log messages wrap onto continuation rows
+    pub word_wrap: bool,
+    /// Open clipboard history popup
+    pub clipboard_popup: Option<ClipboardPopupState>,
+    /// Open discovered bridges popup
+    pub peers_popup: Option<PeersPopupState>,
+    /// Open serial port picker
+    pub ports_popup: Option<PortsPopupState>,
+    /// Text typed in the open command palette
+    pub command_palette: Option<Cow<'a, str>>,
+    /// Open log statistics popup (`LogStatistics::table_lines`)
+    pub stats_popup: Option<Cow<'a, [String]>>,
+    /// Text typed in the open note input
+    pub note_input: Option<Cow<'a, str>>,
+    /// Search query being typed (`/`)
+    pub search_input: Option<Cow<'a, str>>,
+    /// Message types with the most traffic (shown while set, Tab)
+    pub top_messages: Option<Vec<(String, MessageStats)>>,
+    /// Mouse position (column, row), for hover tooltips
+    #[serde(skip)]
+    pub mouse: Option<(u16, u16)>,
+}
+
+/// Which widgets need redrawing between two snapshots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AppStateDiff {
+    pub status_changed: bool,
+    pub logs_changed: bool,
+    pub actions_changed: bool,
+    pub popup_changed: bool,
+}
+
+impl AppStateDiff {
+    /// True if any widget needs redrawing
+    pub fn any(&self) -> bool {
+        self.status_changed || self.logs_changed || self.actions_changed || self.popup_changed
+    }
+}
+
+impl AppState<'_> {
+    /// Compare against the previously rendered snapshot
+    pub fn diff(&self, prev: &AppState) -> AppStateDiff {
+        let shared = self.daemon_running != prev.daemon_running
+            || self.controller_transport_config != prev.controller_transport_config
+            || self.bridge_paused != prev.bridge_paused;
+
+        AppStateDiff {
+            status_changed: shared
+                || self.host_transport_config != prev.host_transport_config
+                || self.controller_state != prev.controller_state
+                || self.serial_port_from_env != prev.serial_port_from_env
+                || self.host_state != prev.host_state
+                || self.active_codec != prev.active_codec
+                || self.active_host_info != prev.active_host_info
+                || self.jitter_ms != prev.jitter_ms
+                || self.write_fill_ratio != prev.write_fill_ratio
+                || self.control_requests_total != prev.control_requests_total
+                || self.control_latency_ms_avg != prev.control_latency_ms_avg
+                || self.cobs_trace != prev.cobs_trace
+                || self.profile != prev.profile
+                || self.control_port != prev.control_port
+                || self.log_port != prev.log_port
+                || self.log_available != prev.log_available
+                || self.log_connected != prev.log_connected
+                || self.log_signal != prev.log_signal
+                || self.log_reconnects != prev.log_reconnects
+                || self.mouse != prev.mouse
+                || self.rx_rate != prev.rx_rate
+                || self.tx_rate != prev.tx_rate
+                || self.rx_rate_avg != prev.rx_rate_avg
+                || self.tx_rate_avg != prev.tx_rate_avg
+                || self.spike_flash != prev.spike_flash
+                || self.recording_bytes != prev.recording_bytes
+                || self.status_message != prev.status_message,
+            logs_changed: self.log_revision != prev.log_revision
+                || self.search_input != prev.search_input
+                || self.top_messages != prev.top_messages
+                || self.paused != prev.paused
+                || self.word_wrap != prev.word_wrap,
+            actions_changed: shared
+                || self.config_changed != prev.config_changed
+                || self.log_file != prev.log_file
+                || self.export_format != prev.export_format
+                || self.paused != prev.paused
+                || self.log_available != prev.log_available
+                || self.log_connected != prev.log_connected
+                || self.keys != prev.keys,
+            popup_changed: self.clipboard_popup != prev.clipboard_popup
+                || self.peers_popup != prev.peers_popup
+                || self.ports_popup != prev.ports_popup
+                || self.command_palette != prev.command_palette
+                || self.stats_popup != prev.stats_popup
+                || self.note_input != prev.note_input,
+        }
+    }
+
+    /// Detach from the `App` borrow
+    pub fn into_owned(self) -> AppState<'static> {
+        AppState {
+            keys: Cow::Owned(self.keys.into_owned()),
+            controller_state: Cow::Owned(self.controller_state.into_owned()),
+            status_message: self.status_message.map(|m| Cow::Owned(m.into_owned())),
+            log_file: self.log_file.map(|p| Cow::Owned(p.into_owned())),
+            command_palette: self.command_palette.map(|t| Cow::Owned(t.into_owned())),
+            stats_popup: self.stats_popup.map(|l| Cow::Owned(l.into_owned())),
+            note_input: self.note_input.map(|t| Cow::Owned(t.into_owned())),
+            search_input: self.search_input.map(|t| Cow::Owned(t.into_owned())),
+            top_messages: self.top_messages,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> AppState<'static> {
+        AppState {
+            daemon_running: true,
+            profile: None,
+            keys: Cow::Owned(KeyBindings::default()),
+            controller_transport_config: ControllerTransportConfig::Serial,
+            host_transport_config: HostTransportConfig::Udp,
+            controller_state: Cow::Owned(ControllerTransportState::Waiting),
+            config_changed: false,
+            serial_port_from_env: false,
+            host_state: HostTransportState::Udp { port: 9000 },
+            active_codec: "COBS+Debug".to_string(),
+            active_host_info: "UDP:9000".to_string(),
+            jitter_ms: None,
+            write_fill_ratio: None,
+            control_requests_total: None,
+            control_latency_ms_avg: None,
+            bridge_paused: false,
+            cobs_trace: false,
+            control_port: 7999,
+            log_port: 9999,
+            log_available: true,
+            log_connected: true,
+            log_signal: 4,
+            log_reconnects: 0,
+            rx_rate: 0.0,
+            tx_rate: 0.0,
+            rx_rate_avg: 0.0,
+            tx_rate_avg: 0.0,
+            spike_flash: false,
+            recording_bytes: None,
+            log_file: None,
+            export_format: ExportFormat::Text,
+            paused: false,
+            status_message: None,
+            log_revision: 0,
+            word_wrap: false,
+            clipboard_popup: None,
+            peers_popup: None,
+            ports_popup: None,
+            command_palette: None,
+            stats_popup: None,
+            note_input: None,
+            search_input: None,
+            top_messages: None,
+            mouse: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_identical_is_empty() {
+        let prev = state();
+        assert!(!state().diff(&prev).any());
+    }
+
+    #[test]
+    fn test_diff_flags_only_affected_widgets() {
+        let prev = state();
+
+        let mut next = state();
+        next.log_revision = 1;
+        let diff = next.diff(&prev);
+        assert!(diff.logs_changed && !diff.status_changed && !diff.actions_changed);
+
+        let mut next = state();
+        next.status_message = Some(Cow::Borrowed("Config saved"));
+        let diff = next.diff(&prev);
+        assert!(diff.status_changed && !diff.logs_changed && !diff.actions_changed);
+
+        let mut next = state();
+        next.bridge_paused = true;
+        let diff = next.diff(&prev);
+        assert!(diff.status_changed && diff.actions_changed && !diff.logs_changed);
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut prev = state();
+        prev.status_message = Some(Cow::Borrowed("Config saved"));
+        prev.peers_popup = Some(PeersPopupState {
+            entries: vec!["bridge".to_string()],
+            selected: 0,
+        });
+
+        let json = serde_json::to_string(&prev).unwrap();
+        let back: AppState = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, prev);
+        assert!(!back.diff(&prev).any());
+    }
 }
@@ -0,0 +1,118 @@
+//! Backpressure for a slow host
+//!
+//! Counts controller -> host messages dropped because the host channel was
+//! full. Once drops exceed `max_drops_per_sec` within one second, the
+//! controller reader is paused (via a shared flag) until the host channel
+//! drains below the low-water mark. Pausing the serial reader lets the OS
+//! and USB flow control hold data instead of silently losing it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const WINDOW_MS: u64 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackpressureChange {
+    Applied,
+    Released,
+}
+
+#[derive(Default)]
+pub struct Backpressure {
+    /// 0 disables backpressure
+    max_drops_per_sec: u32,
+    /// Resume reading once the host queue holds at most this many messages
+    low_water: usize,
+    pause_reader: Arc<AtomicBool>,
+    window_start_ms: u64,
+    drops_in_window: u32,
+}
+
+impl Backpressure {
+    pub fn new(max_drops_per_sec: u32, low_water: usize, pause_reader: Arc<AtomicBool>) -> Self {
+        Self {
+            max_drops_per_sec,
+            low_water,
+            pause_reader,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_applied(&self) -> bool {
+        self.pause_reader.load(Ordering::Relaxed)
+    }
+
+    /// Record one dropped message; returns `Applied` when the threshold is crossed
+    pub fn on_drop(&mut self, now_ms: u64) -> Option<BackpressureChange> {
+        if self.max_drops_per_sec == 0 {
+            return None;
+        }
+
+        if now_ms.saturating_sub(self.window_start_ms) >= WINDOW_MS {
+            self.window_start_ms = now_ms;
+            self.drops_in_window = 0;
+        }
+        self.drops_in_window += 1;
+
+        if self.drops_in_window > self.max_drops_per_sec && !self.is_applied() {
+            self.pause_reader.store(true, Ordering::Relaxed);
+            return Some(BackpressureChange::Applied);
+        }
+        None
+    }
+
+    /// Check the host queue depth; returns `Released` once it has drained
+    pub fn on_queue_depth(&mut self, queued: usize) -> Option<BackpressureChange> {
+        if !self.is_applied() || queued > self.low_water {
+            return None;
+        }
+        self.pause_reader.store(false, Ordering::Relaxed);
+        self.drops_in_window = 0;
+        Some(BackpressureChange::Released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_applies_after_threshold_and_releases_at_low_water() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut bp = Backpressure::new(3, 8, flag.clone());
+
+        for _ in 0..3 {
+            assert_eq!(bp.on_drop(10), None);
+        }
+        assert_eq!(bp.on_drop(20), Some(BackpressureChange::Applied));
+        assert!(flag.load(Ordering::Relaxed));
+        // Already applied: no repeated notification
+        assert_eq!(bp.on_drop(30), None);
+
+        assert_eq!(bp.on_queue_depth(100), None);
+        assert_eq!(bp.on_queue_depth(8), Some(BackpressureChange::Released));
+        assert!(!flag.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_window_resets_each_second() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut bp = Backpressure::new(2, 0, flag.clone());
+
+        assert_eq!(bp.on_drop(0), None);
+        assert_eq!(bp.on_drop(500), None);
+        assert_eq!(bp.on_drop(1500), None);
+        assert_eq!(bp.on_drop(1600), None);
+        assert!(!flag.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_disabled_never_pauses() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut bp = Backpressure::new(0, 0, flag.clone());
+        for i in 0..1000 {
+            assert_eq!(bp.on_drop(i), None);
+        }
+        assert!(!flag.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,197 @@
+//! HTTP state API for remote monitoring dashboards (feature `http-api`)
+//!
+//! Started by the TUI with `--http-port`; serves what the TUI renders:
+//! - `GET /state`: current `AppState` as JSON
+//! - `GET /state/stream`: Server-Sent Events, one `AppState` per change
+//! - `GET /logs?since=<timestamp>`: JSON Lines of recent log entries
+//!
+//! The TUI pushes through `StatePublisher`; handlers only read. Bound to
+//! 127.0.0.1 like the control plane.
+
+use crate::app::AppState;
+use crate::error::{BridgeError, Result};
+use crate::logging::{LogEntry, Timestamp};
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use axum::Router;
+use futures_util::stream::{self, Stream};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::warn;
+
+/// Log entries kept for `GET /logs`
+const HTTP_LOG_CAPACITY: usize = 1000;
+
+type SharedLogs = Arc<Mutex<VecDeque<LogEntry>>>;
+
+/// TUI side of the API: publishes snapshots and log entries
+pub struct StatePublisher {
+    state_tx: watch::Sender<Option<AppState<'static>>>,
+    logs: SharedLogs,
+}
+
+impl StatePublisher {
+    fn new() -> (Self, ApiState) {
+        let (state_tx, state_rx) = watch::channel(None);
+        let logs = SharedLogs::default();
+        let api = ApiState {
+            state_rx,
+            logs: Arc::clone(&logs),
+        };
+        (Self { state_tx, logs }, api)
+    }
+
+    /// Replace the served snapshot (wakes `/state/stream` clients)
+    pub fn publish_state(&self, state: AppState<'static>) {
+        self.state_tx.send_replace(Some(state));
+    }
+
+    /// Keep a log entry for `GET /logs`, dropping the oldest at capacity
+    pub fn push_log(&self, entry: LogEntry) {
+        let mut logs = self.logs.lock();
+        if logs.len() >= HTTP_LOG_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(entry);
+    }
+}
+
+/// Handler side of the API
+#[derive(Clone)]
+struct ApiState {
+    state_rx: watch::Receiver<Option<AppState<'static>>>,
+    logs: SharedLogs,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsQuery {
+    /// Log timestamp (epoch microseconds or HH:MM:SS.mmm today); only later
+    /// entries are returned
+    since: Option<String>,
+}
+
+/// Bind 127.0.0.1:`port` and serve the API in the background
+pub async fn serve(port: u16) -> Result<StatePublisher> {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| BridgeError::HttpBind { port, source: e })?;
+
+    let (publisher, api) = StatePublisher::new();
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(api)).await {
+            warn!("HTTP API stopped: {}", e);
+        }
+    });
+    Ok(publisher)
+}
+
+fn router(api: ApiState) -> Router {
+    Router::new()
+        .route("/state", get(get_state))
+        .route("/state/stream", get(stream_state))
+        .route("/logs", get(get_logs))
+        .with_state(api)
+}
+
+async fn get_state(State(api): State<ApiState>) -> Response {
+    match api.state_rx.borrow().as_ref() {
+        Some(state) => Json(state).into_response(),
+        // The TUI has not drawn its first frame yet
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+async fn stream_state(
+    State(api): State<ApiState>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, axum::Error>>> {
+    let mut rx = api.state_rx;
+    // Send the current snapshot first
+    rx.mark_changed();
+    let events = stream::unfold(rx, |mut rx| async move {
+        loop {
+            // Ends the stream when the TUI exits
+            rx.changed().await.ok()?;
+            let event = rx
+                .borrow_and_update()
+                .as_ref()
+                .map(|state| Event::default().json_data(state));
+            if let Some(event) = event {
+                return Some((event, rx));
+            }
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn get_logs(State(api): State<ApiState>, Query(query): Query<LogsQuery>) -> Response {
+    let body = logs_since(&api.logs.lock(), query.since.as_deref());
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response()
+}
+
+/// JSON Lines of the entries stamped after `since` (all when `None`)
+///
+/// An unreadable `since` returns everything.
+fn logs_since(logs: &VecDeque<LogEntry>, since: Option<&str>) -> String {
+    let since = since.and_then(Timestamp::parse);
+    let mut out = String::new();
+    for entry in logs
+        .iter()
+        .filter(|e| since.is_none_or(|s| e.timestamp > s))
+    {
+        if let Ok(line) = serde_json::to_string(entry) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, message: &str) -> LogEntry {
+        let mut entry = LogEntry::system(message);
+        entry.timestamp = Timestamp::parse(timestamp).unwrap();
+        entry
+    }
+
+    #[test]
+    fn test_logs_since_filters_by_timestamp() {
+        let logs: VecDeque<_> = [
+            entry("10:00:00.000", "a"),
+            entry("10:00:01.500", "b"),
+            entry("10:00:02.000", "c"),
+        ]
+        .into();
+
+        assert_eq!(logs_since(&logs, None).lines().count(), 3);
+
+        let body = logs_since(&logs, Some("10:00:01.500"));
+        let lines: Vec<LogEntry> = body
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].timestamp.to_string(), "10:00:02.000");
+    }
+
+    #[test]
+    fn test_push_log_keeps_capacity() {
+        let (publisher, api) = StatePublisher::new();
+        for i in 0..HTTP_LOG_CAPACITY + 5 {
+            publisher.push_log(entry("10:00:00.000", &i.to_string()));
+        }
+        assert_eq!(api.logs.lock().len(), HTTP_LOG_CAPACITY);
+        assert!(api.state_rx.borrow().is_none());
+    }
+}
@@ -0,0 +1,163 @@
+//! Serial loopback self-test (`oc-bridge test-loopback`)
+//!
+//! Verifies the serial path after installation without a controller: with
+//! a loopback connector (TX wired to RX) on the port, every COBS frame
+//! written must come back unchanged. Frames are sent one at a time and each
+//! round trip is timed. Runs on `SerialTransport` directly, without a
+//! bridge session.
+
+use crate::codec::cobs;
+use crate::error::Result;
+use crate::transport::{SerialTransport, Transport};
+use bytes::{Bytes, BytesMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time allowed for one frame to come back
+pub const LOOPBACK_FRAME_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Pattern bytes after the sequence number (includes zeros, so COBS
+/// stuffing is exercised)
+const PATTERN_LEN: usize = 48;
+
+/// Outcome of a loopback run
+#[derive(Debug, Default)]
+pub struct LoopbackReport {
+    pub sent: usize,
+    /// Frames that came back unchanged
+    pub received: usize,
+    /// Frames that came back altered or undecodable
+    pub corrupted: usize,
+    /// Round trip of each frame received unchanged
+    pub latencies: Vec<Duration>,
+}
+
+impl LoopbackReport {
+    pub fn passed(&self) -> bool {
+        self.sent > 0 && self.received == self.sent
+    }
+
+    /// Human-readable summary, one line per item
+    pub fn summary_lines(&self) -> Vec<String> {
+        let lost = self.sent - self.received - self.corrupted;
+        let mut lines = vec![format!(
+            "frames: {} sent, {} ok, {} corrupted, {} lost",
+            self.sent, self.received, self.corrupted, lost
+        )];
+        if let (Some(min), Some(max)) = (self.latencies.iter().min(), self.latencies.iter().max()) {
+            let avg = self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32;
+            lines.push(format!(
+                "latency: min {:.2} ms, avg {:.2} ms, max {:.2} ms",
+                ms(*min),
+                ms(avg),
+                ms(*max)
+            ));
+        }
+        lines.push(if self.passed() { "PASS" } else { "FAIL" }.to_string());
+        lines
+    }
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Test frame `seq`: big-endian sequence number, then a pattern
+fn test_frame(seq: u32) -> Vec<u8> {
+    let mut frame = seq.to_be_bytes().to_vec();
+    frame.extend((0..PATTERN_LEN).map(|i| (i as u32 * 7 + seq) as u8));
+    frame
+}
+
+/// Send `count` frames through `port_name` and check they come back
+pub async fn run_loopback(port_name: &str, count: usize) -> Result<LoopbackReport> {
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let mut channels = SerialTransport::new(port_name).spawn(shutdown.clone())?;
+
+    let mut report = LoopbackReport::default();
+    let mut pending = Vec::new();
+    let mut decoded = BytesMut::new();
+    let mut encoded = Vec::new();
+
+    for seq in 0..count as u32 {
+        let frame = test_frame(seq);
+        // Test frames are far below the COBS size limit
+        let _ = cobs::encode_into(&frame, &mut encoded);
+        let started = Instant::now();
+        if channels
+            .tx
+            .send(Bytes::copy_from_slice(&encoded))
+            .await
+            .is_err()
+        {
+            break;
+        }
+        report.sent += 1;
+
+        // Wait for the next complete frame (bytes before it are line noise)
+        let deadline = tokio::time::Instant::now() + LOOPBACK_FRAME_TIMEOUT;
+        let echoed = loop {
+            if let Some(end) = pending.iter().position(|b| *b == 0) {
+                let raw: Vec<u8> = pending.drain(..=end).collect();
+                if raw.len() == 1 {
+                    continue;
+                }
+                break Some(cobs::decode_into(&raw[..raw.len() - 1], &mut decoded).is_ok());
+            }
+            match tokio::time::timeout_at(deadline, channels.rx.recv()).await {
+                Ok(Some(data)) => pending.extend_from_slice(&data),
+                Ok(None) | Err(_) => break None,
+            }
+        };
+
+        match echoed {
+            Some(true) if decoded[..] == frame[..] => {
+                report.received += 1;
+                report.latencies.push(started.elapsed());
+            }
+            Some(_) => report.corrupted += 1,
+            // Lost: drop partial bytes so the next frame starts clean
+            None => pending.clear(),
+        }
+    }
+
+    shutdown.store(true, Ordering::Relaxed);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_summary() {
+        let report = LoopbackReport {
+            sent: 4,
+            received: 2,
+            corrupted: 1,
+            latencies: vec![Duration::from_millis(1), Duration::from_millis(3)],
+        };
+        assert!(!report.passed());
+        assert_eq!(
+            report.summary_lines(),
+            [
+                "frames: 4 sent, 2 ok, 1 corrupted, 1 lost",
+                "latency: min 1.00 ms, avg 2.00 ms, max 3.00 ms",
+                "FAIL",
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_software_loopback_passes() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let port = crate::platform::spawn_serial_echo(shutdown.clone()).unwrap();
+
+        let report = run_loopback(&port, 20).await.unwrap();
+        shutdown.store(true, Ordering::Relaxed);
+        assert!(report.passed(), "{:?}", report.summary_lines());
+        assert_eq!(report.latencies.len(), 20);
+    }
+}
@@ -23,16 +23,28 @@
 //! ## Modules
 //! - `session` - Relay logic with codec application
 //! - `stats` - Lock-free traffic counters
+//! - `backpressure` - Pauses the controller reader when the host is slow
 //! - `protocol` - Message name parsing
+//! - `http_state` - HTTP state API for dashboards (feature `http-api`)
+//! - `webhook` - Connection event notifications
+//! - `loopback` - Serial loopback self-test (`test-loopback`)
+//! - `profiling` - CPU flamegraphs of the daemon (`profiling` feature)
 
+pub mod backpressure;
 pub mod guard;
+#[cfg(feature = "http-api")]
+pub mod http_state;
+pub mod loopback;
+#[cfg(all(feature = "profiling", unix))]
+pub mod profiling;
 pub mod protocol;
 pub mod session;
 pub mod stats;
 
 mod runner;
+mod webhook;
 
-use crate::config::BridgeConfig;
+use crate::config::{BridgeConfig, DeviceConfig};
 use crate::error::Result;
 use crate::logging::LogEntry;
 use crate::platform;
@@ -44,13 +56,16 @@ use tokio::sync::mpsc;
 ///
 /// This function blocks until shutdown is signaled. It handles
 /// auto-reconnection for serial mode.
+///
+/// `device` is the resolved device preset used for serial auto-detection.
 pub async fn run_with_shutdown(
     config: &BridgeConfig,
+    device: Option<DeviceConfig>,
     shutdown: Arc<AtomicBool>,
     stats: Arc<stats::Stats>,
     log_tx: Option<mpsc::Sender<LogEntry>>,
 ) -> Result<()> {
     platform::init_perf();
 
-    runner::run(config, shutdown, stats, log_tx).await
+    runner::run(config, device, shutdown, stats, log_tx).await
 }
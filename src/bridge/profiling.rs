@@ -0,0 +1,44 @@
+//! CPU flamegraphs of the running daemon (`profiling` feature, Unix)
+//!
+//! `pprof` samples every thread of the process (tokio workers, serial
+//! reader and writer threads) at `PROFILE_FREQUENCY_HZ`. The report is
+//! written as a flamegraph SVG next to the config file.
+
+use crate::config;
+use crate::error::{BridgeError, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Sampling rate of a capture
+const PROFILE_FREQUENCY_HZ: i32 = 100;
+
+/// Sample the process for `duration` and write `flamegraph-<timestamp>.svg`
+///
+/// Blocks for the whole capture; call from a blocking thread.
+pub fn capture_flamegraph(duration: Duration) -> Result<PathBuf> {
+    let failed = |e: pprof::Error| BridgeError::Profiling {
+        reason: e.to_string(),
+    };
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(PROFILE_FREQUENCY_HZ)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .map_err(failed)?;
+    std::thread::sleep(duration);
+    let report = guard.report().build().map_err(failed)?;
+    if report.data.is_empty() {
+        return Err(BridgeError::Profiling {
+            reason: "no samples collected".to_string(),
+        });
+    }
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let path = config::config_dir()?.join(format!("flamegraph-{}.svg", timestamp));
+    let file = std::fs::File::create(&path).map_err(|source| BridgeError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    report.flamegraph(file).map_err(failed)?;
+    Ok(path)
+}
@@ -1,26 +1,47 @@
 //! Bridge runner (internal implementation)
 //!
 //! Unified bridge execution for all controller/host transport combinations.
-//! Handles auto-reconnection for Serial controller transport.
+//! Handles auto-reconnection for Serial and TCP controller transports, and swaps the
+//! host transport at runtime (failover, or `switch-host` on the control plane)
+//! without touching the controller side.
+//!
+//! `pre_start_hook` runs before anything is bound and must succeed;
+//! `post_stop_hook` runs once the bridge has stopped.
 
 use super::session::BridgeSession;
-use super::stats::Stats;
-use crate::codec::{CobsDebugCodec, RawCodec};
-use crate::config::{BridgeConfig, ControllerTransport, HostTransport};
+use super::stats::{SpikeDetector, Stats, SPIKE_CHECK_INTERVAL};
+use super::webhook::{ConnectionEvent, Webhooks};
+use crate::codec::{
+    CobsCrcCodec, CobsDebugCodec, Codec, FramedLength2Codec, FramedLength4Codec, HexCodec,
+    LengthPrefixCodec, PipelineCodec, PrefixSize, RawCodec,
+};
+use crate::config::{BridgeConfig, ControllerTransport, DeviceConfig, HostTransport, SerialCodec};
+#[cfg(windows)]
+use crate::constants::HOST_PIPE_NAME;
 use crate::constants::{
-    CHANNEL_CAPACITY, POST_DISCONNECT_DELAY_SECS, RECONNECT_DELAY_SECS, UDP_BUFFER_SIZE,
+    BACKPRESSURE_LOW_WATER, CHANNEL_CAPACITY, HOOK_TIMEOUT_SECS, HOST_FAILOVER_RETRY_SECS,
+    HOST_SWITCH_TIMEOUT_MS, POST_DISCONNECT_DELAY_SECS, RECONNECT_DELAY_SECS, UDP_BUFFER_SIZE,
+};
+use crate::control::{ControlEvent, ControlRuntime, ControlState, SerialRunState};
+use crate::error::{BridgeError, Result};
+use crate::logging::{self, ControllerEventKind, DisconnectReason, LogEntry};
+use crate::platform::{self, HotplugEvent};
+#[cfg(windows)]
+use crate::transport::NamedPipeTransport;
+use crate::transport::{
+    tcp, websocket, HostClients, SerialMatchRequest, SerialTransport, TcpTransport, Transport,
+    TransportChannels, UdpTransport, WebSocketTransport,
 };
-use crate::control::{ControlRuntime, ControlState, SerialRunState};
-use crate::error::Result;
-use crate::logging::{self, LogEntry};
+#[cfg(unix)]
 use crate::transport::{
-    SerialMatchRequest, SerialTransport, Transport, TransportChannels, UdpTransport,
-    WebSocketTransport,
+    unix_socket::{self, Role},
+    UnixTransport,
 };
 use bytes::Bytes;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use tokio::sync::watch;
@@ -29,25 +50,48 @@ use tokio::sync::watch;
 // Main entry point
 // =============================================================================
 
-/// Run the bridge with configured transports
+/// Run the bridge with configured transports, between the lifecycle hooks
+pub(super) async fn run(
+    config: &BridgeConfig,
+    device: Option<DeviceConfig>,
+    shutdown: Arc<AtomicBool>,
+    stats: Arc<Stats>,
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+) -> Result<()> {
+    if let Some(hook) = &config.pre_start_hook {
+        run_hook("pre_start_hook", hook, &log_tx).await?;
+    }
+    let result = run_transports(config, device, shutdown, stats, log_tx.clone()).await;
+    if let Some(hook) = &config.post_stop_hook {
+        // Best effort: the outcome is only logged
+        if let Err(e) = run_hook("post_stop_hook", hook, &log_tx).await {
+            logging::try_log(&log_tx, LogEntry::system(e.to_string()), "post_stop_hook");
+        }
+    }
+    result
+}
+
+/// Dispatch to the transport combination selected by config
 ///
-/// Dispatches to the appropriate transport combination based on config.
 /// Serial controller transport has auto-reconnection support.
-pub(super) async fn run(
+async fn run_transports(
     config: &BridgeConfig,
+    device: Option<DeviceConfig>,
     shutdown: Arc<AtomicBool>,
     stats: Arc<Stats>,
     log_tx: Option<mpsc::Sender<LogEntry>>,
 ) -> Result<()> {
     // Control plane (local IPC): always available in daemon mode when control_port != 0.
     // Serial pause/resume is only supported when controller transport is Serial.
-    let serial_supported = matches!(config.controller_transport, ControllerTransport::Serial);
+    let serial_supported = config.controller_transport.is_serial();
     let (
         control_state,
         ControlRuntime {
             desired_rx,
             serial_open_tx,
             resolved_serial_port_tx,
+            desired_host_rx,
+            active_host_tx,
         },
     ) = ControlState::new(
         shutdown.clone(),
@@ -62,11 +106,35 @@ pub(super) async fn run(
                 config.serial_number.as_deref(),
             ),
             host_udp_port: config.host_udp_port,
+            host_pipe_name: host_pipe_name(config),
             log_broadcast_port: config.log_broadcast_port,
             control_port: config.control_port,
             serial_supported,
+            cobs_trace: config.trace_cobs,
+            host_transport: config.host_transport,
+            metrics_port: config.metrics_port,
         },
     );
+    let host_clients = Arc::new(HostClients::default());
+    let control_state = control_state
+        .with_stats(stats.clone())
+        .with_host_clients(host_clients.clone());
+    // `subscribe` clients of the control plane see port changes and log entries
+    let log_tx = if config.control_port != 0 {
+        control_state.spawn_serial_events();
+        tee_logs_to_control(log_tx, control_state.clone())
+    } else {
+        log_tx
+    };
+    let host_switch = HostSwitch {
+        desired_rx: desired_host_rx,
+        active_tx: active_host_tx,
+        clients: host_clients,
+    };
+
+    if let Some(warning) = config.remote_access_warning() {
+        logging::try_log(&log_tx, LogEntry::system(warning), "remote_access_warning");
+    }
 
     // Keep the control watch sender alive for Serial mode even when the server
     // is disabled (e.g., control_port = 0 in headless/dev configs). If the
@@ -78,6 +146,54 @@ pub(super) async fn run(
         None
     };
 
+    if config.spike_detector.enabled {
+        spawn_spike_detector(
+            SpikeDetector::new(&config.spike_detector),
+            stats.clone(),
+            shutdown.clone(),
+            log_tx.clone(),
+        );
+    }
+
+    if config.discovery_port != 0 {
+        match crate::discovery::bind_responder(config.discovery_port) {
+            Ok(socket) => {
+                tokio::spawn(crate::discovery::run_responder(
+                    socket,
+                    control_state.info().clone(),
+                    config.allowed_remote_cidrs.clone(),
+                    shutdown.clone(),
+                ));
+            }
+            // Not fatal: the bridge works, it is just not discoverable
+            Err(e) => logging::try_log(
+                &log_tx,
+                LogEntry::system(format!("Discovery disabled: {}", e)),
+                "discovery_bind_error",
+            ),
+        }
+    }
+
+    if let Some(port) = config.metrics_port {
+        match crate::metrics::bind_listener(port).await {
+            Ok(listener) => {
+                tokio::spawn(crate::metrics::run_server(
+                    listener,
+                    control_state.clone(),
+                    stats.clone(),
+                    config.allowed_remote_cidrs.clone(),
+                    shutdown.clone(),
+                ));
+            }
+            // Not fatal, as for discovery
+            Err(e) => logging::try_log(
+                &log_tx,
+                LogEntry::system(format!("Metrics disabled: {}", e)),
+                "metrics_bind_error",
+            ),
+        }
+    }
+
     if config.control_port != 0 {
         let control_port = config.control_port;
         let listener = crate::control::bind_listener(control_port).await?;
@@ -99,15 +215,116 @@ pub(super) async fn run(
 
     match config.controller_transport {
         ControllerTransport::Serial => {
+            let _keepalive = control_keepalive;
+            let trace_tx = log_tx.clone().filter(|_| config.trace_cobs);
+            let frame_timeout = Duration::from_millis(config.partial_frame_timeout_ms);
+            let make_cobs = move || {
+                let mut codec = CobsDebugCodec::new(UDP_BUFFER_SIZE);
+                codec.set_frame_timeout(frame_timeout);
+                if let Some(tx) = &trace_tx {
+                    codec.set_trace(true, tx.clone());
+                }
+                codec
+            };
+            if !config.codec_pipeline.is_empty() {
+                // Reject unknown names now rather than at each connection
+                PipelineCodec::from_names(&config.codec_pipeline, &make_cobs)?;
+                let names = config.codec_pipeline.clone();
+                return run_with_serial_controller(
+                    config,
+                    device,
+                    shutdown,
+                    stats,
+                    log_tx,
+                    host_switch,
+                    desired_rx,
+                    serial_open_tx,
+                    resolved_serial_port_tx,
+                    move || {
+                        PipelineCodec::from_names(&names, &make_cobs)
+                            .expect("validated before the first connection")
+                    },
+                )
+                .await;
+            }
+            match config.codec {
+                SerialCodec::Cobs => {
+                    run_with_serial_controller(
+                        config,
+                        device,
+                        shutdown,
+                        stats,
+                        log_tx,
+                        host_switch,
+                        desired_rx,
+                        serial_open_tx,
+                        resolved_serial_port_tx,
+                        make_cobs,
+                    )
+                    .await
+                }
+                SerialCodec::CobsCrc => {
+                    run_with_serial_controller(
+                        config,
+                        device,
+                        shutdown,
+                        stats,
+                        log_tx,
+                        host_switch,
+                        desired_rx,
+                        serial_open_tx,
+                        resolved_serial_port_tx,
+                        move || CobsCrcCodec::new(make_cobs()),
+                    )
+                    .await
+                }
+            }
+        }
+        ControllerTransport::FramedLength2 => {
+            let _keepalive = control_keepalive;
+            run_with_serial_controller(
+                config,
+                device,
+                shutdown,
+                stats,
+                log_tx,
+                host_switch,
+                desired_rx,
+                serial_open_tx,
+                resolved_serial_port_tx,
+                || FramedLength2Codec::new(UDP_BUFFER_SIZE),
+            )
+            .await
+        }
+        ControllerTransport::FramedLength4 => {
+            let _keepalive = control_keepalive;
+            run_with_serial_controller(
+                config,
+                device,
+                shutdown,
+                stats,
+                log_tx,
+                host_switch,
+                desired_rx,
+                serial_open_tx,
+                resolved_serial_port_tx,
+                || FramedLength4Codec::new(UDP_BUFFER_SIZE),
+            )
+            .await
+        }
+        ControllerTransport::HexSerial => {
             let _keepalive = control_keepalive;
             run_with_serial_controller(
                 config,
+                device,
                 shutdown,
                 stats,
                 log_tx,
+                host_switch,
                 desired_rx,
                 serial_open_tx,
                 resolved_serial_port_tx,
+                || HexCodec::new(UDP_BUFFER_SIZE),
             )
             .await
         }
@@ -116,14 +333,29 @@ pub(super) async fn run(
             drop(desired_rx);
             drop(serial_open_tx);
             drop(resolved_serial_port_tx);
-            run_with_udp_controller(config, shutdown, stats, log_tx).await
+            run_with_udp_controller(config, shutdown, stats, log_tx, host_switch).await
         }
         ControllerTransport::WebSocket => {
             drop(control_keepalive);
             drop(desired_rx);
             drop(serial_open_tx);
             drop(resolved_serial_port_tx);
-            run_with_websocket_controller(config, shutdown, stats, log_tx).await
+            run_with_websocket_controller(config, shutdown, stats, log_tx, host_switch).await
+        }
+        ControllerTransport::Tcp => {
+            drop(control_keepalive);
+            drop(desired_rx);
+            drop(serial_open_tx);
+            drop(resolved_serial_port_tx);
+            run_with_tcp_controller(config, shutdown, stats, log_tx, host_switch).await
+        }
+        #[cfg(unix)]
+        ControllerTransport::Unix => {
+            drop(control_keepalive);
+            drop(desired_rx);
+            drop(serial_open_tx);
+            drop(resolved_serial_port_tx);
+            run_with_unix_controller(config, shutdown, stats, log_tx, host_switch).await
         }
     }
 }
@@ -135,39 +367,53 @@ pub(super) async fn run(
 /// Run with Serial controller transport
 ///
 /// Supports auto-reconnection when device is unplugged/replugged.
-/// Uses COBS encoding for serial communication.
-async fn run_with_serial_controller(
+/// `make_codec` builds a fresh serial framing codec (COBS or length prefix)
+/// for each session so no partial frame survives a reconnect.
+#[allow(clippy::too_many_arguments)]
+async fn run_with_serial_controller<C: Codec + 'static>(
     config: &BridgeConfig,
+    device_config: Option<DeviceConfig>,
     shutdown: Arc<AtomicBool>,
     stats: Arc<Stats>,
     log_tx: Option<mpsc::Sender<LogEntry>>,
+    host_switch: HostSwitch,
     mut pause_rx: watch::Receiver<SerialRunState>,
     serial_open_tx: watch::Sender<bool>,
     resolved_serial_port_tx: watch::Sender<Option<String>>,
+    make_codec: impl Fn() -> C,
 ) -> Result<()> {
-    // Load device preset if configured
-    let device_config = config
-        .device_preset
-        .as_ref()
-        .and_then(|name| crate::config::load_device_preset(name).ok());
-
+    let baud_rate = config.serial_baud_rate(device_config.as_ref())?;
     let _ = serial_open_tx.send_replace(false);
     let _ = resolved_serial_port_tx.send_replace(None);
 
     // Create host transport once and keep it alive across serial reconnects/pause.
-    let host_transport = create_host_transport(config, shutdown.clone(), &log_tx).await?;
+    let host_transport =
+        create_host_transport(config, shutdown.clone(), &log_tx, host_switch, &stats).await?;
     let host_tx = host_transport.tx;
 
-    let (host_bcast_tx, _) = broadcast::channel::<Bytes>(CHANNEL_CAPACITY);
-    {
-        let mut host_rx = host_transport.rx;
-        let host_bcast_tx = host_bcast_tx.clone();
-        tokio::spawn(async move {
-            while let Some(data) = host_rx.recv().await {
-                let _ = host_bcast_tx.send(data);
+    let host_bcast_tx = broadcast_host_rx(host_transport.rx);
+
+    // Consecutive reconnect attempts since the connection was lost (0 = not reconnecting)
+    let mut reconnect_attempt: u32 = 0;
+    let webhooks = Webhooks::new(config, log_tx.clone());
+
+    // Auto-detection rescans as soon as a port appears, not only every few seconds
+    let mut hotplug = if config.serial_port.is_empty() {
+        match platform::watch_serial_hotplug(shutdown.clone()) {
+            Ok(rx) => Some(rx),
+            Err(BridgeError::PlatformNotSupported { .. }) => None,
+            Err(e) => {
+                logging::try_log(
+                    &log_tx,
+                    LogEntry::system(format!("{}, polling for the device instead", e)),
+                    "hotplug_unavailable",
+                );
+                None
             }
-        });
-    }
+        }
+    } else {
+        None
+    };
 
     // Main reconnection loop
     while !shutdown.load(Ordering::Relaxed) {
@@ -221,7 +467,7 @@ async fn run_with_serial_controller(
                 }
                 Err(_) => {
                     // Device not found, wait and retry (passive waiting)
-                    tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+                    wait_for_serial_device(&mut hotplug, &log_tx).await;
                     continue;
                 }
             }
@@ -229,18 +475,39 @@ async fn run_with_serial_controller(
             config.serial_port.clone()
         };
 
+        if reconnect_attempt > 0 {
+            log_controller_event(
+                &log_tx,
+                ControllerEventKind::Reconnecting {
+                    attempt: reconnect_attempt,
+                },
+            );
+        }
+
         // Create controller transport
         // Per-session shutdown: set on global shutdown OR pause.
         let session_shutdown = Arc::new(AtomicBool::new(false));
+        let pause_reader = Arc::new(AtomicBool::new(false));
 
-        let controller = match SerialTransport::new(&port_name).spawn(session_shutdown.clone()) {
+        let mut transport = SerialTransport::new(&port_name)
+            .with_baud_rate(baud_rate)
+            .with_pause_reader(pause_reader.clone());
+        if let Some(device) = &device_config {
+            transport = transport.with_signals(device);
+        }
+        let controller = match transport.spawn_with_priority(session_shutdown.clone()) {
             Ok(c) => c,
             Err(e) => {
-                logging::try_log(
-                    &log_tx,
-                    LogEntry::system(format!("Serial open failed: {}", e)),
-                    "serial_open_failed",
-                );
+                if reconnect_attempt > 0 {
+                    log_controller_event(&log_tx, ControllerEventKind::ReconnectFailed);
+                    reconnect_attempt += 1;
+                } else {
+                    logging::try_log(
+                        &log_tx,
+                        LogEntry::system(format!("Serial open failed: {}", e)),
+                        "serial_open_failed",
+                    );
+                }
                 tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
                 continue;
             }
@@ -250,52 +517,47 @@ async fn run_with_serial_controller(
         let _ = resolved_serial_port_tx.send_replace(Some(port_name.clone()));
 
         // Create per-session host receiver (subscribe to persistent host transport).
-        let mut host_sub = host_bcast_tx.subscribe();
-        let (host_in_tx, host_in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
-        {
-            let session_shutdown = session_shutdown.clone();
-            tokio::spawn(async move {
-                while !session_shutdown.load(Ordering::Relaxed) {
-                    let data = match host_sub.recv().await {
-                        Ok(d) => d,
-                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
-                        Err(broadcast::error::RecvError::Closed) => break,
-                    };
-
-                    match host_in_tx.try_send(data) {
-                        Ok(()) => {}
-                        Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {}
-                        Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => break,
-                    }
-                }
-            });
-        }
-
         let host = TransportChannels {
-            rx: host_in_rx,
+            rx: subscribe_host_rx(&host_bcast_tx, session_shutdown.clone(), stats.clone()),
             tx: host_tx.clone(),
         };
 
         // Log connection info
-        let host_info = format_host_transport_info(config);
-        logging::try_log(
+        if reconnect_attempt > 0 {
+            stats.add_reconnection();
+        }
+        reconnect_attempt = 0;
+        log_controller_event(
             &log_tx,
-            LogEntry::system(format!("Connected: Serial:{} <-> {}", port_name, host_info)),
-            "connected",
+            ControllerEventKind::Connected {
+                port: port_name.clone(),
+                transport: "Serial".to_string(),
+            },
         );
+        webhooks.notify(ConnectionEvent::Connect, &port_name);
 
-        // Run session with COBS codec (Serial uses COBS encoding)
+        // Run session with the serial framing codec
         let session = BridgeSession::new(
-            controller,
+            controller.channels,
             host,
-            CobsDebugCodec::new(UDP_BUFFER_SIZE),
+            make_codec(),
             stats.clone(),
             log_tx.clone(),
         )
         .with_duplicate_guard(
             config.duplicate_guard_enabled,
             config.duplicate_guard_window_ms,
-        );
+        )
+        .with_backpressure(
+            config.max_drops_per_sec,
+            BACKPRESSURE_LOW_WATER,
+            pause_reader,
+        )
+        .with_batching(config.batch)
+        .with_drain_timeout(drain_timeout(config))
+        .with_payload_warning(config.max_payload_warn_bytes)
+        .with_max_message_size(config.max_message_size)
+        .with_priority_tx(controller.priority_tx);
 
         // Run the session until:
         // - transport disconnect
@@ -326,29 +588,310 @@ async fn run_with_serial_controller(
         // Session dropped: serial port should be released.
         let _ = serial_open_tx.send_replace(false);
         let _ = resolved_serial_port_tx.send_replace(None);
+        webhooks.notify(ConnectionEvent::Disconnect, &port_name);
 
         // Check if this was a clean shutdown
         if shutdown.load(Ordering::Relaxed) {
+            log_controller_event(
+                &log_tx,
+                ControllerEventKind::Disconnected {
+                    reason: DisconnectReason::Shutdown,
+                },
+            );
             break;
         }
 
         // If paused, loop will now wait at the top until resumed.
         if pause_rx.borrow().is_paused() {
+            log_controller_event(
+                &log_tx,
+                ControllerEventKind::Disconnected {
+                    reason: DisconnectReason::Paused,
+                },
+            );
             continue;
         }
 
         // Connection lost, wait before retry
-        logging::try_log(
+        log_controller_event(
             &log_tx,
-            LogEntry::system("Connection lost, reconnecting..."),
-            "connection_lost",
+            ControllerEventKind::Disconnected {
+                reason: DisconnectReason::Lost,
+            },
         );
+        reconnect_attempt = 1;
         tokio::time::sleep(Duration::from_secs(POST_DISCONNECT_DELAY_SECS)).await;
     }
 
     Ok(())
 }
 
+/// Wait `RECONNECT_DELAY_SECS` before the next device scan, or until a
+/// serial port is plugged in
+///
+/// Falls back to the plain delay if the hotplug watcher has stopped.
+async fn wait_for_serial_device(
+    hotplug: &mut Option<mpsc::Receiver<HotplugEvent>>,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+) {
+    let delay = tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS));
+    tokio::pin!(delay);
+    while let Some(rx) = hotplug.as_mut() {
+        tokio::select! {
+            _ = &mut delay => return,
+            event = rx.recv() => match event {
+                Some(HotplugEvent::Added(port)) => {
+                    logging::try_log(
+                        log_tx,
+                        LogEntry::system(format!("Serial device plugged in: {}", port)),
+                        "hotplug_added",
+                    );
+                    return;
+                }
+                Some(HotplugEvent::Removed(_)) => {}
+                None => *hotplug = None,
+            },
+        }
+    }
+    delay.await;
+}
+
+/// Forward log entries to `log_tx`, publishing them to the control plane's
+/// `subscribe` clients on the way
+fn tee_logs_to_control(
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    control: ControlState,
+) -> Option<mpsc::Sender<LogEntry>> {
+    let log_tx = log_tx?;
+    let (tee_tx, mut tee_rx) = mpsc::channel::<LogEntry>(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        while let Some(entry) = tee_rx.recv().await {
+            if control.has_subscribers() {
+                control.publish(ControlEvent::Log {
+                    entry: entry.clone(),
+                });
+            }
+            if log_tx.send(entry).await.is_err() {
+                break;
+            }
+        }
+    });
+    Some(tee_tx)
+}
+
+/// Fan the persistent host transport's input out to per-session subscribers
+fn broadcast_host_rx(mut host_rx: mpsc::Receiver<Bytes>) -> broadcast::Sender<Bytes> {
+    let (host_bcast_tx, _) = broadcast::channel::<Bytes>(CHANNEL_CAPACITY);
+    let tx = host_bcast_tx.clone();
+    tokio::spawn(async move {
+        while let Some(data) = host_rx.recv().await {
+            let _ = tx.send(data);
+        }
+    });
+    host_bcast_tx
+}
+
+/// Host input for one controller session, until `session_shutdown` is set
+fn subscribe_host_rx(
+    host_bcast_tx: &broadcast::Sender<Bytes>,
+    session_shutdown: Arc<AtomicBool>,
+    stats: Arc<Stats>,
+) -> mpsc::Receiver<Bytes> {
+    let mut host_sub = host_bcast_tx.subscribe();
+    let (host_in_tx, host_in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        while !session_shutdown.load(Ordering::Relaxed) {
+            let data = match host_sub.recv().await {
+                Ok(d) => d,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            match host_in_tx.try_send(data) {
+                Ok(()) => {}
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    stats.add_channel_drop();
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => break,
+            }
+        }
+    });
+    host_in_rx
+}
+
+// =============================================================================
+// TCP / UNIX socket Controller (with reconnection)
+// =============================================================================
+
+/// Run with TCP controller transport
+async fn run_with_tcp_controller(
+    config: &BridgeConfig,
+    shutdown: Arc<AtomicBool>,
+    stats: Arc<Stats>,
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    host_switch: HostSwitch,
+) -> Result<()> {
+    let addr = config.controller_tcp_addr;
+    // Bound once: the port stays open while sessions come and go
+    let listener = if config.controller_tcp_connect {
+        None
+    } else {
+        Some(Arc::new(tcp::bind_listener(addr)?))
+    };
+    let make_transport = || {
+        let transport = match &listener {
+            Some(listener) => TcpTransport::listen(addr).with_listener(listener.clone()),
+            None => TcpTransport::connect(addr),
+        };
+        transport.with_log(log_tx.clone())
+    };
+    let label = format!("TCP:{}", addr);
+    run_with_stream_controller(
+        config,
+        &label,
+        make_transport,
+        shutdown,
+        stats,
+        log_tx.clone(),
+        host_switch,
+    )
+    .await
+}
+
+/// Run with UNIX socket controller transport
+#[cfg(unix)]
+async fn run_with_unix_controller(
+    config: &BridgeConfig,
+    shutdown: Arc<AtomicBool>,
+    stats: Arc<Stats>,
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    host_switch: HostSwitch,
+) -> Result<()> {
+    let path = config.unix_socket_path();
+    // Bound once: the socket stays open while sessions come and go
+    let (listener, _socket_file) = if config.controller_unix_connect {
+        (None, None)
+    } else {
+        let (listener, socket_file) = unix_socket::bind_listener(&path)?;
+        (Some(Arc::new(listener)), Some(socket_file))
+    };
+    let make_transport = || {
+        let transport = match &listener {
+            Some(listener) => {
+                UnixTransport::new(&path, Role::Server).with_listener(listener.clone())
+            }
+            None => UnixTransport::new(&path, Role::Client),
+        };
+        transport.with_log(log_tx.clone())
+    };
+    let label = format!("UNIX:{}", path.display());
+    run_with_stream_controller(
+        config,
+        &label,
+        make_transport,
+        shutdown,
+        stats,
+        log_tx.clone(),
+        host_switch,
+    )
+    .await
+}
+
+/// Run a byte-stream controller transport (TCP, UNIX socket)
+///
+/// The host transport stays up; `make_transport` opens (or awaits) a new
+/// connection each time the peer disconnects. Length-prefixed framing
+/// (2-byte header).
+async fn run_with_stream_controller<T: Transport>(
+    config: &BridgeConfig,
+    label: &str,
+    make_transport: impl Fn() -> T,
+    shutdown: Arc<AtomicBool>,
+    stats: Arc<Stats>,
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    host_switch: HostSwitch,
+) -> Result<()> {
+    let host_transport =
+        create_host_transport(config, shutdown.clone(), &log_tx, host_switch, &stats).await?;
+    let host_tx = host_transport.tx;
+    let host_bcast_tx = broadcast_host_rx(host_transport.rx);
+
+    logging::try_log(
+        &log_tx,
+        LogEntry::system(format!(
+            "Bridge started: {} (controller) <-> {} (host)",
+            label,
+            format_host_transport_info(config)
+        )),
+        "bridge_started",
+    );
+
+    while !shutdown.load(Ordering::Relaxed) {
+        // Per-connection shutdown, so the host subscriber ends with the session
+        let session_shutdown = Arc::new(AtomicBool::new(false));
+        let controller = match make_transport().spawn(session_shutdown.clone()) {
+            Ok(controller) => controller,
+            Err(e) => {
+                logging::try_log(
+                    &log_tx,
+                    LogEntry::system(format!("{}: {}, retrying", label, e)),
+                    "controller_spawn_failed",
+                );
+                tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+                continue;
+            }
+        };
+        let host = TransportChannels {
+            rx: subscribe_host_rx(&host_bcast_tx, session_shutdown.clone(), stats.clone()),
+            tx: host_tx.clone(),
+        };
+
+        let session = BridgeSession::new(
+            controller,
+            host,
+            LengthPrefixCodec::new(PrefixSize::U16Be, UDP_BUFFER_SIZE),
+            stats.clone(),
+            log_tx.clone(),
+        )
+        .with_duplicate_guard(
+            config.duplicate_guard_enabled,
+            config.duplicate_guard_window_ms,
+        )
+        .with_drain_timeout(drain_timeout(config))
+        .with_payload_warning(config.max_payload_warn_bytes)
+        .with_max_message_size(config.max_message_size);
+
+        // The session ends when the peer disconnects (or on shutdown)
+        {
+            let session_fut = session.run(session_shutdown.clone());
+            tokio::pin!(session_fut);
+            loop {
+                tokio::select! {
+                    _ = &mut session_fut => break,
+                    _ = tokio::time::sleep(Duration::from_millis(100)) => {
+                        if shutdown.load(Ordering::Relaxed) {
+                            session_shutdown.store(true, Ordering::SeqCst);
+                        }
+                    }
+                }
+            }
+        }
+        session_shutdown.store(true, Ordering::SeqCst);
+
+        if !shutdown.load(Ordering::Relaxed) {
+            tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+        }
+    }
+
+    logging::try_log(
+        &log_tx,
+        LogEntry::system("Bridge stopped"),
+        "bridge_stopped",
+    );
+
+    Ok(())
+}
+
 // =============================================================================
 // UDP Controller (no auto-reconnection)
 // =============================================================================
@@ -362,12 +905,15 @@ async fn run_with_udp_controller(
     shutdown: Arc<AtomicBool>,
     stats: Arc<Stats>,
     log_tx: Option<mpsc::Sender<LogEntry>>,
+    host_switch: HostSwitch,
 ) -> Result<()> {
     // Create controller transport
-    let controller = UdpTransport::new(config.controller_udp_port).spawn(shutdown.clone())?;
+    let controller =
+        udp_transport(config, config.controller_udp_port, &log_tx)?.spawn(shutdown.clone())?;
 
     // Create host transport
-    let host = create_host_transport(config, shutdown.clone(), &log_tx).await?;
+    let host =
+        create_host_transport(config, shutdown.clone(), &log_tx, host_switch, &stats).await?;
 
     // Log connection info
     let host_info = format_host_transport_info(config);
@@ -385,8 +931,19 @@ async fn run_with_udp_controller(
         .with_duplicate_guard(
             config.duplicate_guard_enabled,
             config.duplicate_guard_window_ms,
-        );
-    session.run(shutdown).await?;
+        )
+        .with_drain_timeout(drain_timeout(config))
+        .with_payload_warning(config.max_payload_warn_bytes)
+        .with_max_message_size(config.max_message_size);
+    run_listening_session(
+        session,
+        config,
+        "UDP",
+        config.controller_udp_port,
+        shutdown,
+        &log_tx,
+    )
+    .await?;
 
     logging::try_log(
         &log_tx,
@@ -410,13 +967,18 @@ async fn run_with_websocket_controller(
     shutdown: Arc<AtomicBool>,
     stats: Arc<Stats>,
     log_tx: Option<mpsc::Sender<LogEntry>>,
+    host_switch: HostSwitch,
 ) -> Result<()> {
     // Create controller transport (WebSocket server)
-    let controller =
-        WebSocketTransport::new(config.controller_websocket_port).spawn(shutdown.clone())?;
+    let controller = WebSocketTransport::new(config.controller_websocket_port)
+        .with_subprotocol(config.websocket_subprotocol.clone())
+        .with_max_clients(config.websocket_max_clients)
+        .with_tls(config.tls.clone())
+        .spawn(shutdown.clone())?;
 
     // Create host transport
-    let host = create_host_transport(config, shutdown.clone(), &log_tx).await?;
+    let host =
+        create_host_transport(config, shutdown.clone(), &log_tx, host_switch, &stats).await?;
 
     // Log connection info
     let host_info = format_host_transport_info(config);
@@ -434,8 +996,19 @@ async fn run_with_websocket_controller(
         .with_duplicate_guard(
             config.duplicate_guard_enabled,
             config.duplicate_guard_window_ms,
-        );
-    session.run(shutdown).await?;
+        )
+        .with_drain_timeout(drain_timeout(config))
+        .with_payload_warning(config.max_payload_warn_bytes)
+        .with_max_message_size(config.max_message_size);
+    run_listening_session(
+        session,
+        config,
+        "WebSocket",
+        config.controller_websocket_port,
+        shutdown,
+        &log_tx,
+    )
+    .await?;
 
     logging::try_log(
         &log_tx,
@@ -446,28 +1019,267 @@ async fn run_with_websocket_controller(
     Ok(())
 }
 
+/// Run the session of a listening controller transport (UDP, WebSocket)
+///
+/// The controller counts as connected while its server runs: the
+/// `ControllerEvent` entries and webhooks bracket the session, as they do a
+/// serial connection.
+async fn run_listening_session<C: Codec>(
+    session: BridgeSession<C>,
+    config: &BridgeConfig,
+    transport: &str,
+    port: u16,
+    shutdown: Arc<AtomicBool>,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+) -> Result<()> {
+    let port = port.to_string();
+    let webhooks = Webhooks::new(config, log_tx.clone());
+    log_controller_event(
+        log_tx,
+        ControllerEventKind::Connected {
+            port: port.clone(),
+            transport: transport.to_string(),
+        },
+    );
+    webhooks.notify(ConnectionEvent::Connect, &port);
+
+    let result = session.run(shutdown.clone()).await;
+
+    let reason = if shutdown.load(Ordering::Relaxed) {
+        DisconnectReason::Shutdown
+    } else {
+        DisconnectReason::Lost
+    };
+    log_controller_event(log_tx, ControllerEventKind::Disconnected { reason });
+    webhooks.notify(ConnectionEvent::Disconnect, &port);
+    result
+}
+
+// =============================================================================
+// Lifecycle hooks
+// =============================================================================
+
+/// Run `command` in the platform shell, logging each output line
+///
+/// Fails on a non-zero exit, or after `HOOK_TIMEOUT_SECS` (the command is
+/// killed).
+async fn run_hook(
+    name: &'static str,
+    command: &str,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+) -> Result<()> {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/c");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+
+    let child = tokio::process::Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(Duration::from_secs(HOOK_TIMEOUT_SECS), child)
+        .await
+        .map_err(|_| BridgeError::HookFailed {
+            hook: name,
+            reason: format!("timed out after {}s", HOOK_TIMEOUT_SECS),
+        })?
+        .map_err(|source| BridgeError::OsCommand {
+            program: shell,
+            source,
+        })?;
+
+    for stream in [&output.stdout, &output.stderr] {
+        for line in String::from_utf8_lossy(stream).lines() {
+            if !line.trim().is_empty() {
+                logging::try_log(
+                    log_tx,
+                    LogEntry::system(format!("{}: {}", name, line)),
+                    "hook_output",
+                );
+            }
+        }
+    }
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(BridgeError::HookFailed {
+            hook: name,
+            reason: output.status.to_string(),
+        })
+    }
+}
+
 // =============================================================================
 // Host Transport Creation
 // =============================================================================
 
+/// Control plane side of runtime host transport switching
+struct HostSwitch {
+    /// Transport requested via `switch-host`
+    desired_rx: watch::Receiver<HostTransport>,
+    /// Transport currently relaying (reported in status)
+    active_tx: watch::Sender<HostTransport>,
+    /// Clients of the active transport (reported in status)
+    clients: Arc<HostClients>,
+}
+
+/// Running host transport behind the relay
+struct ActiveHost {
+    kind: HostTransport,
+    channels: TransportChannels,
+    shutdown: Arc<AtomicBool>,
+    /// WebSocket listener, handed to the next transport on a switch
+    ws_listener: Option<Arc<TcpListener>>,
+}
+
 /// Create host transport based on configuration
 ///
-/// Supports:
-/// - UDP only
-/// - WebSocket only
-/// - Both (merged channels, broadcast to both)
+/// Tries the configured transport first, then the entries that follow it in
+/// `host_failover_order`, and keeps the first one that binds. The returned
+/// channels are stable: the transport behind them can be swapped without
+/// interrupting the session (see `spawn_host_relay`).
 async fn create_host_transport(
     config: &BridgeConfig,
     shutdown: Arc<AtomicBool>,
     log_tx: &Option<mpsc::Sender<LogEntry>>,
+    switch: HostSwitch,
+    stats: &Arc<Stats>,
 ) -> Result<TransportChannels> {
-    match config.host_transport {
+    let chain = failover_chain(config);
+    let mut last_err = None;
+
+    for (i, &transport) in chain.iter().enumerate() {
+        let attempt_shutdown = child_shutdown(&shutdown);
+        let mut ws_listener = None;
+        let spawned = spawn_host_transport(
+            transport,
+            config,
+            attempt_shutdown.clone(),
+            log_tx,
+            &switch.clients,
+            &mut ws_listener,
+        )
+        .await;
+        match spawned {
+            Ok(channels) => {
+                if i > 0 {
+                    logging::try_log(
+                        log_tx,
+                        LogEntry::system(format!(
+                            "Host transport failover: using {:?} instead of {:?}",
+                            transport, chain[0]
+                        )),
+                        "host_failover",
+                    );
+                }
+                let active = ActiveHost {
+                    kind: transport,
+                    channels,
+                    shutdown: attempt_shutdown,
+                    ws_listener,
+                };
+                return Ok(spawn_host_relay(
+                    config.clone(),
+                    active,
+                    (i > 0).then_some(chain[0]),
+                    switch,
+                    shutdown,
+                    log_tx.clone(),
+                    stats.clone(),
+                ));
+            }
+            Err(e) => {
+                // Release anything the failed attempt managed to bind
+                attempt_shutdown.store(true, Ordering::SeqCst);
+                logging::try_log(
+                    log_tx,
+                    LogEntry::system(format!("Host transport {:?} failed: {}", transport, e)),
+                    "host_transport_failed",
+                );
+                last_err = Some(e);
+            }
+        }
+    }
+
+    Err(last_err.expect("failover chain is never empty"))
+}
+
+/// Transports to try, in order: the configured one, then what follows it
+/// in `host_failover_order` (no failover if it is not listed there)
+fn failover_chain(config: &BridgeConfig) -> Vec<HostTransport> {
+    let order = &config.host_failover_order;
+    let Some(start) = order.iter().position(|&t| t == config.host_transport) else {
+        return vec![config.host_transport];
+    };
+
+    let mut chain: Vec<HostTransport> = Vec::with_capacity(order.len() - start);
+    for &t in &order[start..] {
+        if !chain.contains(&t) {
+            chain.push(t);
+        }
+    }
+    chain
+}
+
+/// UDP transport on `port` with the configured bind interface and allowlist
+fn udp_transport(
+    config: &BridgeConfig,
+    port: u16,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+) -> Result<UdpTransport> {
+    let mut transport = UdpTransport::new(port)
+        .with_bind(config.bind_ip()?)
+        .with_allowed_remotes(config.allowed_remote_cidrs.clone())
+        .with_log(log_tx.clone())
+        .with_payload_warning(config.max_payload_warn_bytes > 0)
+        .with_keepalive(
+            Duration::from_secs(config.nat_keepalive.interval_secs),
+            Bytes::from(config.nat_keepalive.payload.clone()),
+        );
+    // Start at the codec frame size; the RX task adapts from there
+    transport.set_recv_buf_hint(UDP_BUFFER_SIZE);
+    Ok(transport)
+}
+
+/// Time the host side gets to send queued data on shutdown
+fn drain_timeout(config: &BridgeConfig) -> Duration {
+    Duration::from_millis(config.drain_timeout_ms)
+}
+
+/// Spawn a single host transport kind
+///
+/// `ws_listener` is the WebSocket listener to serve on: reused when it is
+/// bound to the configured port, bound otherwise, and left as the one the
+/// new transport uses (`None` for transports without WebSocket).
+async fn spawn_host_transport(
+    transport: HostTransport,
+    config: &BridgeConfig,
+    shutdown: Arc<AtomicBool>,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+    clients: &Arc<HostClients>,
+    ws_listener: &mut Option<Arc<TcpListener>>,
+) -> Result<TransportChannels> {
+    let shared = ws_listener.take();
+    match transport {
         HostTransport::Udp => {
-            let udp = UdpTransport::new(config.host_udp_port).spawn(shutdown)?;
+            let udp = udp_transport(config, config.host_udp_port, log_tx)?
+                .with_clients(clients.clone())
+                .spawn_with_drain(shutdown, drain_timeout(config))?;
             Ok(udp)
         }
         HostTransport::WebSocket => {
-            let ws = WebSocketTransport::new(config.host_websocket_port).spawn(shutdown)?;
+            let listener = host_ws_listener(config, shared)?;
+            *ws_listener = Some(listener.clone());
+            let ws = WebSocketTransport::new(config.host_websocket_port)
+                .with_subprotocol(config.websocket_subprotocol.clone())
+                .with_max_clients(config.websocket_max_clients)
+                .with_tls(config.tls.clone())
+                .with_clients(clients.clone())
+                .with_listener(listener)
+                .spawn(shutdown)?;
             logging::try_log(
                 log_tx,
                 LogEntry::system(format!(
@@ -478,7 +1290,362 @@ async fn create_host_transport(
             );
             Ok(ws)
         }
-        HostTransport::Both => create_merged_host_transport(config, shutdown, log_tx).await,
+        HostTransport::Both => {
+            let listener = host_ws_listener(config, shared)?;
+            *ws_listener = Some(listener.clone());
+            create_merged_host_transport(config, shutdown, log_tx, clients, listener).await
+        }
+        #[cfg(windows)]
+        HostTransport::NamedPipe => {
+            let pipe = NamedPipeTransport::new(HOST_PIPE_NAME).spawn(shutdown)?;
+            logging::try_log(
+                log_tx,
+                LogEntry::system(format!("Host named pipe server on {}", HOST_PIPE_NAME)),
+                "host_pipe_started",
+            );
+            Ok(pipe)
+        }
+    }
+}
+
+/// Listener for the host WebSocket server
+///
+/// Reuses `shared` (the running transport's) when it is bound to the
+/// configured port, so a switch never waits for the port to be released.
+fn host_ws_listener(
+    config: &BridgeConfig,
+    shared: Option<Arc<TcpListener>>,
+) -> Result<Arc<TcpListener>> {
+    let port = config.host_websocket_port;
+    match shared {
+        Some(listener) if listener.local_addr().is_ok_and(|a| a.port() == port) => Ok(listener),
+        _ => Ok(Arc::new(websocket::bind_listener(port)?)),
+    }
+}
+
+/// Per-attempt shutdown flag that follows the global one
+///
+/// Lets a failed or replaced host transport be stopped on its own.
+fn child_shutdown(shutdown: &Arc<AtomicBool>) -> Arc<AtomicBool> {
+    let child = Arc::new(AtomicBool::new(false));
+    let parent = shutdown.clone();
+    let watched = child.clone();
+    tokio::spawn(async move {
+        while !watched.load(Ordering::Relaxed) {
+            if parent.load(Ordering::Relaxed) {
+                watched.store(true, Ordering::SeqCst);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    });
+    child
+}
+
+/// Relay between the session and the active host transport
+///
+/// Returns stable channels for the session; the transport behind them is
+/// swapped when:
+/// - `upgrade_to` is set (running on a failover transport) and the
+///   preferred transport binds again
+/// - the control plane requests another transport (`switch-host`)
+///
+/// A manual switch cancels any pending failover upgrade.
+///
+/// The loop only wakes on channel activity: on shutdown the active transport
+/// closes its channels, which ends the relay. Outgoing messages that find
+/// the transport queue full are counted as channel drops.
+fn spawn_host_relay(
+    config: BridgeConfig,
+    mut active: ActiveHost,
+    mut upgrade_to: Option<HostTransport>,
+    switch: HostSwitch,
+    shutdown: Arc<AtomicBool>,
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    stats: Arc<Stats>,
+) -> TransportChannels {
+    let (in_tx, in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+    let (out_tx, mut out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let HostSwitch {
+            mut desired_rx,
+            active_tx,
+            clients,
+        } = switch;
+        let _ = active_tx.send_replace(active.kind);
+        desired_rx.mark_unchanged();
+        let mut switch_enabled = true;
+
+        let mut retry = tokio::time::interval(Duration::from_secs(HOST_FAILOVER_RETRY_SECS));
+        retry.tick().await; // First tick completes immediately
+
+        while !shutdown.load(Ordering::Relaxed) {
+            tokio::select! {
+                data = active.channels.rx.recv() => match data {
+                    Some(data) => {
+                        if in_tx.send(data).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                data = out_rx.recv() => match data {
+                    Some(data) => match active.channels.tx.try_send(data) {
+                        Ok(()) => {}
+                        Err(mpsc::error::TrySendError::Full(_)) => stats.add_channel_drop(),
+                        // Transport stopping (shutdown): its rx closes next
+                        Err(mpsc::error::TrySendError::Closed(_)) => {}
+                    },
+                    None => break,
+                },
+                changed = desired_rx.changed(), if switch_enabled => {
+                    if changed.is_err() {
+                        // Control plane gone (e.g. disabled): no more switch requests
+                        switch_enabled = false;
+                        continue;
+                    }
+                    let target = *desired_rx.borrow_and_update();
+                    if target != active.kind {
+                        let switched = switch_host(
+                            &config,
+                            &mut active,
+                            target,
+                            &shutdown,
+                            &log_tx,
+                            &clients,
+                            &in_tx,
+                        )
+                        .await;
+                        match switched {
+                            Ok(_) => upgrade_to = None,
+                            Err(e) => logging::try_log(
+                                &log_tx,
+                                LogEntry::system(format!(
+                                    "Host transport switch to {:?} failed: {}",
+                                    target, e
+                                )),
+                                "host_switch_failed",
+                            ),
+                        }
+                    }
+                    // Always notify so the control plane can report the outcome
+                    let _ = active_tx.send_replace(active.kind);
+                }
+                _ = retry.tick(), if upgrade_to.is_some() => {
+                    let Some(preferred) = upgrade_to else { continue };
+                    let shared = active.ws_listener.clone();
+                    let started =
+                        start_host(preferred, &config, &shutdown, &log_tx, &clients, None, shared)
+                            .await;
+                    if let Ok(next) = started {
+                        let previous = std::mem::replace(&mut active, next);
+                        retire_host(previous, &in_tx);
+                        upgrade_to = None;
+                        let _ = active_tx.send_replace(active.kind);
+                        logging::try_log(
+                            &log_tx,
+                            LogEntry::system(format!(
+                                "Host transport restored: {:?}",
+                                preferred
+                            )),
+                            "host_failover_restored",
+                        );
+                    }
+                }
+            }
+        }
+
+        active.shutdown.store(true, Ordering::SeqCst);
+    });
+
+    TransportChannels {
+        rx: in_rx,
+        tx: out_tx,
+    }
+}
+
+/// Replace the active host transport with `target`
+///
+/// The new transport is started before the old one stops, so the swap only
+/// moves channels and no message is lost. The WebSocket listener is handed
+/// over and UDP sockets are bound with SO_REUSEADDR, so transports sharing a
+/// port (e.g. WebSocket -> Both) overlap as well. Where a port still cannot
+/// be shared, the old transport is stopped first and the bind retried until
+/// the port is released. If `target` never binds, the previous transport (or
+/// its failover chain) is started again.
+///
+/// Returns the gap: how long no transport was running (zero on overlap).
+async fn switch_host(
+    config: &BridgeConfig,
+    active: &mut ActiveHost,
+    target: HostTransport,
+    shutdown: &Arc<AtomicBool>,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+    clients: &Arc<HostClients>,
+    in_tx: &mpsc::Sender<Bytes>,
+) -> Result<Duration> {
+    let timeout = Duration::from_millis(HOST_SWITCH_TIMEOUT_MS);
+    let shared = active.ws_listener.clone();
+    let mut gap_start = None;
+
+    let started = start_host(
+        target,
+        config,
+        shutdown,
+        log_tx,
+        clients,
+        None,
+        shared.clone(),
+    )
+    .await;
+    let next = match started {
+        Ok(next) => next,
+        Err(_) => {
+            active.shutdown.store(true, Ordering::SeqCst);
+            gap_start = Some(Instant::now());
+            let deadline = Instant::now() + timeout;
+            let retried = start_host(
+                target,
+                config,
+                shutdown,
+                log_tx,
+                clients,
+                Some(deadline),
+                shared.clone(),
+            )
+            .await;
+            match retried {
+                Ok(next) => next,
+                Err(e) => {
+                    let restored =
+                        restore_host(active.kind, config, shutdown, log_tx, clients, shared)
+                            .await?;
+                    let stopped = std::mem::replace(active, restored);
+                    retire_host(stopped, in_tx);
+                    return Err(e);
+                }
+            }
+        }
+    };
+
+    let previous_kind = active.kind;
+    let previous = std::mem::replace(active, next);
+    retire_host(previous, in_tx);
+
+    let gap = gap_start.map_or(Duration::ZERO, |t| t.elapsed());
+    logging::try_log(
+        log_tx,
+        LogEntry::system(format!(
+            "Host transport switched: {:?} -> {:?} (gap {} ms)",
+            previous_kind,
+            active.kind,
+            gap.as_millis()
+        )),
+        "host_switched",
+    );
+    Ok(gap)
+}
+
+/// Stop a replaced host transport
+///
+/// Data it received before stopping is still relayed to the session.
+fn retire_host(previous: ActiveHost, in_tx: &mpsc::Sender<Bytes>) {
+    previous.shutdown.store(true, Ordering::SeqCst);
+    let mut rx = previous.channels.rx;
+    let in_tx = in_tx.clone();
+    tokio::spawn(async move {
+        while let Some(data) = rx.recv().await {
+            if in_tx.send(data).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Start `kind` again after a failed switch, falling back along its chain
+///
+/// Retries until a transport binds or the bridge shuts down, so the relay is
+/// never left on a stopped transport.
+async fn restore_host(
+    kind: HostTransport,
+    config: &BridgeConfig,
+    shutdown: &Arc<AtomicBool>,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+    clients: &Arc<HostClients>,
+    shared: Option<Arc<TcpListener>>,
+) -> Result<ActiveHost> {
+    let chain = failover_chain(&BridgeConfig {
+        host_transport: kind,
+        ..config.clone()
+    });
+    let timeout = Duration::from_millis(HOST_SWITCH_TIMEOUT_MS);
+    loop {
+        let mut last_err = None;
+        for &transport in &chain {
+            let deadline = Instant::now() + timeout;
+            let started = start_host(
+                transport,
+                config,
+                shutdown,
+                log_tx,
+                clients,
+                Some(deadline),
+                shared.clone(),
+            )
+            .await;
+            match started {
+                Ok(restored) => return Ok(restored),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        if let Some(e) = last_err.filter(|_| shutdown.load(Ordering::Relaxed)) {
+            return Err(e);
+        }
+    }
+}
+
+/// Start a host transport, retrying until `deadline` (single attempt if None)
+///
+/// `shared` is the WebSocket listener of the running transport, if any.
+async fn start_host(
+    kind: HostTransport,
+    config: &BridgeConfig,
+    shutdown: &Arc<AtomicBool>,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+    clients: &Arc<HostClients>,
+    deadline: Option<Instant>,
+    shared: Option<Arc<TcpListener>>,
+) -> Result<ActiveHost> {
+    loop {
+        let attempt_shutdown = child_shutdown(shutdown);
+        let mut ws_listener = shared.clone();
+        let spawned = spawn_host_transport(
+            kind,
+            config,
+            attempt_shutdown.clone(),
+            log_tx,
+            clients,
+            &mut ws_listener,
+        )
+        .await;
+        match spawned {
+            Ok(channels) => {
+                return Ok(ActiveHost {
+                    kind,
+                    channels,
+                    shutdown: attempt_shutdown,
+                    ws_listener,
+                })
+            }
+            Err(e) => {
+                attempt_shutdown.store(true, Ordering::SeqCst);
+                if deadline.is_none_or(|d| Instant::now() >= d) {
+                    return Err(e);
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
     }
 }
 
@@ -490,32 +1657,32 @@ async fn create_merged_host_transport(
     config: &BridgeConfig,
     shutdown: Arc<AtomicBool>,
     log_tx: &Option<mpsc::Sender<LogEntry>>,
+    clients: &Arc<HostClients>,
+    ws_listener: Arc<TcpListener>,
 ) -> Result<TransportChannels> {
-    // Spawn UDP
-    let udp = UdpTransport::new(config.host_udp_port).spawn(shutdown.clone())?;
+    // The WebSocket listener is bound first (see `spawn_host_transport`): a
+    // bind failure fails the whole attempt so the failover chain can move on
+    // (see `create_host_transport`).
+    let ws = WebSocketTransport::new(config.host_websocket_port)
+        .with_subprotocol(config.websocket_subprotocol.clone())
+        .with_max_clients(config.websocket_max_clients)
+        .with_tls(config.tls.clone())
+        .with_clients(clients.clone())
+        .with_listener(ws_listener)
+        .spawn(shutdown.clone())?;
+    logging::try_log(
+        log_tx,
+        LogEntry::system(format!(
+            "Host WebSocket server on port {}",
+            config.host_websocket_port
+        )),
+        "host_ws_started",
+    );
 
-    // Spawn WebSocket
-    let ws = match WebSocketTransport::new(config.host_websocket_port).spawn(shutdown.clone()) {
-        Ok(ws) => {
-            logging::try_log(
-                log_tx,
-                LogEntry::system(format!(
-                    "Host WebSocket server on port {}",
-                    config.host_websocket_port
-                )),
-                "host_ws_started",
-            );
-            ws
-        }
-        Err(e) => {
-            logging::try_log(
-                log_tx,
-                LogEntry::system(format!("Host WebSocket bind failed: {}, using UDP only", e)),
-                "host_ws_bind_failed",
-            );
-            return Ok(udp);
-        }
-    };
+    // Spawn UDP
+    let udp = udp_transport(config, config.host_udp_port, log_tx)?
+        .with_clients(clients.clone())
+        .spawn_with_drain(shutdown.clone(), drain_timeout(config))?;
 
     // Merge channels: combine rx from both, broadcast tx to both
     let (merged_tx, merged_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
@@ -580,6 +1747,33 @@ async fn create_merged_host_transport(
 // Helpers
 // =============================================================================
 
+/// Log traffic spikes until shutdown
+fn spawn_spike_detector(
+    mut detector: SpikeDetector,
+    stats: Arc<Stats>,
+    shutdown: Arc<AtomicBool>,
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SPIKE_CHECK_INTERVAL);
+        while !shutdown.load(Ordering::Relaxed) {
+            interval.tick().await;
+            if let Some(message) = detector.update(&stats) {
+                logging::try_log(&log_tx, LogEntry::system(message), "traffic_spike");
+            }
+        }
+    });
+}
+
+/// Log a structured controller lifecycle event
+fn log_controller_event(log_tx: &Option<mpsc::Sender<LogEntry>>, event: ControllerEventKind) {
+    logging::try_log(
+        log_tx,
+        LogEntry::controller_event(event),
+        "controller_event",
+    );
+}
+
 /// Format host transport info for logging
 fn format_host_transport_info(config: &BridgeConfig) -> String {
     match config.host_transport {
@@ -589,5 +1783,371 @@ fn format_host_transport_info(config: &BridgeConfig) -> String {
             "UDP:{} + WS:{}",
             config.host_udp_port, config.host_websocket_port
         ),
+        #[cfg(windows)]
+        HostTransport::NamedPipe => format!("Pipe:{}", HOST_PIPE_NAME),
+    }
+}
+
+/// Pipe name advertised in the control plane status (named pipe host only)
+#[cfg(windows)]
+fn host_pipe_name(config: &BridgeConfig) -> Option<String> {
+    (config.host_transport == HostTransport::NamedPipe).then(|| HOST_PIPE_NAME.to_string())
+}
+
+#[cfg(not(windows))]
+fn host_pipe_name(_config: &BridgeConfig) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::LogKind;
+
+    fn config_with(host_transport: HostTransport) -> BridgeConfig {
+        BridgeConfig {
+            host_transport,
+            ..BridgeConfig::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hook_logs_output_and_fails_on_exit_code() {
+        let (log_tx, mut log_rx) = mpsc::channel::<LogEntry>(8);
+        let log_tx = Some(log_tx);
+        let mut next_message = move || match log_rx.try_recv().map(|e| e.kind) {
+            Ok(LogKind::System { message }) => message,
+            other => panic!("expected a system entry, got {:?}", other),
+        };
+
+        assert!(run_hook("pre_start_hook", "echo ready", &log_tx)
+            .await
+            .is_ok());
+        assert_eq!(next_message(), "pre_start_hook: ready");
+
+        let err = run_hook("pre_start_hook", "echo oops >&2; exit 3", &log_tx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BridgeError::HookFailed { .. }), "{}", err);
+        assert_eq!(next_message(), "pre_start_hook: oops");
+    }
+
+    #[test]
+    fn test_failover_chain_follows_order_from_configured() {
+        assert_eq!(
+            failover_chain(&config_with(HostTransport::Both)),
+            vec![
+                HostTransport::Both,
+                HostTransport::WebSocket,
+                HostTransport::Udp
+            ]
+        );
+        assert_eq!(
+            failover_chain(&config_with(HostTransport::WebSocket)),
+            vec![HostTransport::WebSocket, HostTransport::Udp]
+        );
+        assert_eq!(
+            failover_chain(&config_with(HostTransport::Udp)),
+            vec![HostTransport::Udp]
+        );
+    }
+
+    #[test]
+    fn test_failover_chain_unlisted_transport_has_no_fallback() {
+        let config = BridgeConfig {
+            host_transport: HostTransport::Both,
+            host_failover_order: vec![HostTransport::Udp],
+            ..BridgeConfig::default()
+        };
+        assert_eq!(failover_chain(&config), vec![HostTransport::Both]);
+    }
+
+    #[tokio::test]
+    async fn test_switch_host_keeps_session_channels() {
+        let udp_port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let ws_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = BridgeConfig {
+            host_udp_port: udp_port,
+            host_websocket_port: ws_port,
+            ..config_with(HostTransport::Udp)
+        };
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (desired_tx, desired_rx) = watch::channel(HostTransport::Udp);
+        let (active_tx, mut active_rx) = watch::channel(HostTransport::Udp);
+        let switch = HostSwitch {
+            desired_rx,
+            active_tx,
+            clients: Arc::default(),
+        };
+        let mut host = create_host_transport(
+            &config,
+            shutdown.clone(),
+            &None,
+            switch,
+            &Arc::new(Stats::new()),
+        )
+        .await
+        .unwrap();
+
+        let client = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.send_to(b"before", ("127.0.0.1", udp_port)).unwrap();
+        let data = tokio::time::timeout(Duration::from_secs(2), host.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&data[..], b"before");
+
+        let _ = desired_tx.send_replace(HostTransport::Both);
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while *active_rx.borrow_and_update() != HostTransport::Both {
+                active_rx.changed().await.unwrap();
+            }
+        })
+        .await
+        .unwrap();
+        assert!(std::net::TcpStream::connect(("127.0.0.1", ws_port)).is_ok());
+
+        // Relayed through the same channels, whichever socket takes it
+        client.send_to(b"after", ("127.0.0.1", udp_port)).unwrap();
+        let data = tokio::time::timeout(Duration::from_secs(2), host.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&data[..], b"after");
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_udp_controller_logs_controller_events() {
+        let (controller_port, _) = free_ports();
+        let (host_port, _) = free_ports();
+        let config = BridgeConfig {
+            controller_transport: ControllerTransport::Udp,
+            controller_udp_port: controller_port,
+            host_udp_port: host_port,
+            ..config_with(HostTransport::Udp)
+        };
+        let (log_tx, mut log_rx) = mpsc::channel::<LogEntry>(64);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (_desired_tx, desired_rx) = watch::channel(HostTransport::Udp);
+        let (active_tx, _active_rx) = watch::channel(HostTransport::Udp);
+        let switch = HostSwitch {
+            desired_rx,
+            active_tx,
+            clients: Arc::default(),
+        };
+
+        let stopper = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            stopper.store(true, Ordering::SeqCst);
+        });
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            run_with_udp_controller(
+                &config,
+                shutdown,
+                Arc::new(Stats::new()),
+                Some(log_tx),
+                switch,
+            ),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let mut events = Vec::new();
+        while let Ok(entry) = log_rx.try_recv() {
+            if let LogKind::ControllerEvent { event } = entry.kind {
+                events.push(event);
+            }
+        }
+        assert_eq!(
+            events,
+            vec![
+                ControllerEventKind::Connected {
+                    port: controller_port.to_string(),
+                    transport: "UDP".to_string(),
+                },
+                ControllerEventKind::Disconnected {
+                    reason: DisconnectReason::Shutdown,
+                },
+            ]
+        );
+    }
+
+    fn free_ports() -> (u16, u16) {
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let ws = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        (
+            udp.local_addr().unwrap().port(),
+            ws.local_addr().unwrap().port(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_switch_host_shared_port_gap_is_under_bound() {
+        let (udp_port, ws_port) = free_ports();
+        let config = BridgeConfig {
+            host_udp_port: udp_port,
+            host_websocket_port: ws_port,
+            ..config_with(HostTransport::WebSocket)
+        };
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let clients = Arc::default();
+        let (in_tx, _in_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let mut active = start_host(
+            HostTransport::WebSocket,
+            &config,
+            &shutdown,
+            &None,
+            &clients,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // WebSocket -> Both shares the WebSocket port, Both -> UDP the UDP port
+        for target in [HostTransport::Both, HostTransport::Udp] {
+            let gap = switch_host(
+                &config,
+                &mut active,
+                target,
+                &shutdown,
+                &None,
+                &clients,
+                &in_tx,
+            )
+            .await
+            .unwrap();
+            assert!(
+                gap < Duration::from_millis(5),
+                "{:?}: gap {:?}",
+                target,
+                gap
+            );
+            assert_eq!(active.kind, target);
+        }
+        assert!(active.ws_listener.is_none());
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_switch_host_failure_keeps_a_running_transport() {
+        let (udp_port, ws_port) = free_ports();
+        // Taken by someone else: WebSocket can never bind
+        let _taken = std::net::TcpListener::bind(("0.0.0.0", ws_port)).unwrap();
+        let config = BridgeConfig {
+            host_udp_port: udp_port,
+            host_websocket_port: ws_port,
+            ..config_with(HostTransport::Udp)
+        };
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let clients = Arc::default();
+        let (in_tx, mut in_rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let mut active = start_host(
+            HostTransport::Udp,
+            &config,
+            &shutdown,
+            &None,
+            &clients,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let switched = switch_host(
+            &config,
+            &mut active,
+            HostTransport::WebSocket,
+            &shutdown,
+            &None,
+            &clients,
+            &in_tx,
+        )
+        .await;
+        assert!(matches!(switched, Err(BridgeError::WebSocketBind { .. })));
+        assert_eq!(active.kind, HostTransport::Udp);
+        assert!(!active.shutdown.load(Ordering::SeqCst));
+
+        // The restored transport relays
+        let client = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .send_to(b"still here", ("127.0.0.1", udp_port))
+            .unwrap();
+        let data = tokio::time::timeout(Duration::from_secs(2), async {
+            tokio::select! {
+                Some(data) = active.channels.rx.recv() => data,
+                Some(data) = in_rx.recv() => data,
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(&data[..], b"still here");
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_host_relay_counts_drops_and_ends_with_transport() {
+        let (transport_in_tx, transport_in_rx) = mpsc::channel::<Bytes>(1);
+        let (transport_out_tx, _transport_out_rx) = mpsc::channel::<Bytes>(1);
+        let active = ActiveHost {
+            kind: HostTransport::Udp,
+            channels: TransportChannels {
+                rx: transport_in_rx,
+                tx: transport_out_tx,
+            },
+            shutdown: Arc::new(AtomicBool::new(false)),
+            ws_listener: None,
+        };
+        let (_desired_tx, desired_rx) = watch::channel(HostTransport::Udp);
+        let (active_tx, _active_rx) = watch::channel(HostTransport::Udp);
+        let switch = HostSwitch {
+            desired_rx,
+            active_tx,
+            clients: Arc::default(),
+        };
+        let stats = Arc::new(Stats::new());
+        let mut relay = spawn_host_relay(
+            config_with(HostTransport::Udp),
+            active,
+            None,
+            switch,
+            Arc::new(AtomicBool::new(false)),
+            None,
+            stats.clone(),
+        );
+
+        // The transport queue holds one message; the other two are dropped
+        for i in 0..3u8 {
+            relay.tx.send(Bytes::from(vec![i])).await.unwrap();
+        }
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while stats.channel_drops() < 2 {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // A transport that closes (as on shutdown) ends the relay
+        drop(transport_in_tx);
+        let ended = tokio::time::timeout(Duration::from_secs(1), relay.rx.recv()).await;
+        assert_eq!(ended.unwrap(), None);
     }
 }
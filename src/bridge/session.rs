@@ -5,23 +5,34 @@
 //! - Codec application (decode/encode)
 //! - Statistics tracking
 //! - Protocol logging
+//! - Batching of small host -> controller messages (optional)
+//! - Controller write queue depth (warns when the serial side falls behind)
 //!
 //! The session does NOT handle:
 //! - Transport lifecycle (that's the caller's responsibility)
 //! - Reconnection logic (handled by the bridge main loop)
 
+use super::backpressure::{Backpressure, BackpressureChange};
 use super::guard::{GuardAction, RelayGuard};
 use super::protocol::parse_message_name;
 use super::stats::Stats;
 use crate::codec::{Codec, Frame};
+use crate::config::BatchConfig;
 use crate::error::Result;
-use crate::logging::{self, LogEntry};
+use crate::logging::{self, Direction, LogEntry};
 use crate::transport::TransportChannels;
 use bytes::Bytes;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+/// Controller write channel fill above which the serial port is the bottleneck
+const WRITE_QUEUE_WARN_RATIO: f64 = 0.8;
+/// Minimum time between two write queue warnings
+const WRITE_QUEUE_WARN_INTERVAL_MS: u64 = 10_000;
 
 /// Bridge session between controller and host transports
 ///
@@ -62,6 +73,27 @@ pub struct BridgeSession<C: Codec> {
     log_tx: Option<mpsc::Sender<LogEntry>>,
     /// Message guard for flood-prone paths
     guard: RelayGuard,
+    /// Controller reader pause when the host channel overflows
+    backpressure: Backpressure,
+    /// Host -> controller batching limits
+    batch: BatchConfig,
+    /// Host messages waiting for the next batched write
+    pending: Vec<Bytes>,
+    pending_bytes: usize,
+    /// `elapsed_ms` when the oldest pending message arrived
+    batch_started_ms: u64,
+    /// Time left for the host transport to take queued data on exit
+    drain_timeout: Duration,
+    /// Controller write queue that jumps ahead of `controller.tx`
+    priority_tx: Option<mpsc::Sender<Bytes>>,
+    /// Warn about controller payloads larger than this (0 = never)
+    max_payload_warn_bytes: usize,
+    /// Messages above this size are dropped (0 = no limit)
+    max_message_size: usize,
+    /// Message names already warned about
+    oversized: HashSet<String>,
+    /// `elapsed_ms` of the last write queue warning
+    write_queue_warned_ms: Option<u64>,
     /// Monotonic time reference for guard intervals
     start_time: Instant,
 }
@@ -82,6 +114,20 @@ impl<C: Codec> BridgeSession<C> {
             stats,
             log_tx,
             guard: RelayGuard::default(),
+            backpressure: Backpressure::default(),
+            batch: BatchConfig {
+                max_batch_age_ms: 0,
+                ..BatchConfig::default()
+            },
+            pending: Vec::new(),
+            pending_bytes: 0,
+            batch_started_ms: 0,
+            drain_timeout: Duration::ZERO,
+            priority_tx: None,
+            max_payload_warn_bytes: 0,
+            max_message_size: 0,
+            oversized: HashSet::new(),
+            write_queue_warned_ms: None,
             start_time: Instant::now(),
         }
     }
@@ -91,6 +137,57 @@ impl<C: Codec> BridgeSession<C> {
         self
     }
 
+    /// Pause the controller reader (via `pause_reader`) when more than
+    /// `max_drops_per_sec` messages to the host are dropped in one second
+    ///
+    /// Reading resumes once the host queue drains to `low_water` messages.
+    /// A threshold of 0 disables backpressure.
+    pub fn with_backpressure(
+        mut self,
+        max_drops_per_sec: u32,
+        low_water: usize,
+        pause_reader: Arc<AtomicBool>,
+    ) -> Self {
+        self.backpressure = Backpressure::new(max_drops_per_sec, low_water, pause_reader);
+        self
+    }
+
+    /// Coalesce host -> controller messages into one encoded write
+    ///
+    /// Requires a self-delimiting controller codec (COBS, length prefix).
+    pub fn with_batching(mut self, batch: BatchConfig) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    /// Wait up to `timeout` on exit for the host transport to take
+    /// everything queued for it (zero = don't wait)
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Send host messages whose name starts with `!` on `priority_tx`,
+    /// ahead of queued writes and without guard or batching
+    pub fn with_priority_tx(mut self, priority_tx: mpsc::Sender<Bytes>) -> Self {
+        self.priority_tx = Some(priority_tx);
+        self
+    }
+
+    /// Log once per message name when a decoded controller payload exceeds
+    /// `max_bytes` (0 = never), e.g. one that won't fit a single datagram
+    pub fn with_payload_warning(mut self, max_bytes: usize) -> Self {
+        self.max_payload_warn_bytes = max_bytes;
+        self
+    }
+
+    /// Drop controller and host messages larger than `max_bytes` (0 = no
+    /// limit), logging each one
+    pub fn with_max_message_size(mut self, max_bytes: usize) -> Self {
+        self.max_message_size = max_bytes;
+        self
+    }
+
     /// Run the bridge session until shutdown or disconnect
     ///
     /// Returns `Ok(())` on clean shutdown or transport disconnect.
@@ -98,6 +195,9 @@ impl<C: Codec> BridgeSession<C> {
     /// reconnection should be attempted.
     pub async fn run(mut self, shutdown: Arc<AtomicBool>) -> Result<()> {
         loop {
+            self.check_host_drained();
+            let batch_wait = self.batch_wait();
+
             tokio::select! {
                 biased;
 
@@ -106,6 +206,13 @@ impl<C: Codec> BridgeSession<C> {
                     if shutdown.load(Ordering::Relaxed) {
                         break;
                     }
+                    // No host sends to sample it: keep the fill ratio current
+                    self.sample_write_queue();
+                }
+
+                // Oldest pending host message reached max_batch_age_ms
+                _ = tokio::time::sleep(batch_wait.unwrap_or_default()), if batch_wait.is_some() => {
+                    self.flush_batch();
                 }
 
                 // Controller -> Host (e.g., Serial -> Bitwig)
@@ -132,6 +239,18 @@ impl<C: Codec> BridgeSession<C> {
             }
         }
 
+        self.flush_batch();
+        // A host that already stopped has nothing left to take
+        if !self.drain_timeout.is_zero()
+            && !self.host.tx.is_closed()
+            && !self.host.drain(self.drain_timeout).await
+        {
+            logging::try_log(
+                &self.log_tx,
+                LogEntry::system("Host queue not drained on exit: pending data dropped"),
+                "host_drain_timeout",
+            );
+        }
         Ok(())
     }
 
@@ -140,22 +259,60 @@ impl<C: Codec> BridgeSession<C> {
     /// Decodes using controller codec, logs, updates stats, sends to host.
     fn relay_controller_to_host(&mut self, data: Bytes) {
         let now_ms = self.elapsed_ms();
+        // One clock read for every message in this read
+        let now = Instant::now();
 
         // Decode data from controller (may produce multiple frames)
         self.controller_codec.decode(&data, |frame| {
             match frame {
                 Frame::Message { name, payload } => {
+                    if drop_oversized(
+                        &self.stats,
+                        &self.log_tx,
+                        self.max_message_size,
+                        payload.len(),
+                    ) {
+                        return;
+                    }
+
                     // Update stats (bytes received from controller)
-                    self.stats.add_rx(payload.len());
+                    self.stats.add_rx_timed(payload.len(), now);
+                    self.stats
+                        .record_message(&name, Direction::In, payload.len());
 
                     // Log protocol message (silently drop if channel full)
                     if let Some(ref tx) = self.log_tx {
                         let _ = tx.try_send(LogEntry::protocol_in(&name, payload.len()));
                     }
 
+                    let max = self.max_payload_warn_bytes;
+                    if max > 0 && payload.len() > max && self.oversized.insert(name.clone()) {
+                        logging::try_log(
+                            &self.log_tx,
+                            LogEntry::system(format!(
+                                "{}: {} byte payload exceeds max_payload_warn_bytes ({})",
+                                name,
+                                payload.len(),
+                                max
+                            )),
+                            "payload_oversized",
+                        );
+                    }
+
                     match self.guard.on_controller_message(payload, now_ms) {
                         GuardAction::Forward(payload) => {
-                            let _ = self.host.tx.try_send(payload);
+                            if let Err(TrySendError::Full(_)) = self.host.tx.try_send(payload) {
+                                self.stats.add_channel_drop();
+                                if self.backpressure.on_drop(now_ms)
+                                    == Some(BackpressureChange::Applied)
+                                {
+                                    logging::try_log(
+                                        &self.log_tx,
+                                        LogEntry::system("Backpressure applied: host is slow"),
+                                        "backpressure_applied",
+                                    );
+                                }
+                            }
                         }
                         GuardAction::DropDuplicate => {
                             self.stats.add_c2h_duplicate_drop();
@@ -170,6 +327,20 @@ impl<C: Codec> BridgeSession<C> {
                 }
             }
         });
+
+        let decode_errors = self.controller_codec.take_decode_errors();
+        if decode_errors > 0 {
+            self.stats.add_cobs_errors(decode_errors);
+        }
+
+        let discarded = self.controller_codec.take_discarded_partial();
+        if discarded > 0 {
+            logging::try_log(
+                &self.log_tx,
+                LogEntry::system(format!("Discarded partial frame ({} bytes)", discarded)),
+                "discarded_partial_frame",
+            );
+        }
     }
 
     /// Relay data from host to controller
@@ -178,11 +349,16 @@ impl<C: Codec> BridgeSession<C> {
     fn relay_host_to_controller(&mut self, data: Bytes) {
         let now_ms = self.elapsed_ms();
 
+        if drop_oversized(&self.stats, &self.log_tx, self.max_message_size, data.len()) {
+            return;
+        }
+
         // Parse message name from raw payload for logging
         let name = parse_message_name(&data).unwrap_or_else(|| "unknown".into());
 
         // Update stats (bytes to send to controller)
         self.stats.add_tx(data.len());
+        self.stats.record_message(&name, Direction::Out, data.len());
 
         // Log protocol message
         logging::try_log(
@@ -191,7 +367,21 @@ impl<C: Codec> BridgeSession<C> {
             "protocol_out",
         );
 
+        if name.starts_with('!') {
+            if let Some(priority_tx) = &self.priority_tx {
+                let mut encoded = Vec::with_capacity(data.len() + 16);
+                self.controller_codec.encode(&data, &mut encoded);
+                if let Err(TrySendError::Full(_)) = priority_tx.try_send(Bytes::from(encoded)) {
+                    self.stats.add_channel_drop();
+                }
+                return;
+            }
+        }
+
         match self.guard.on_host_message(data, now_ms) {
+            GuardAction::Forward(payload) if self.batch.is_enabled() => {
+                self.queue_for_controller(payload, now_ms)
+            }
             GuardAction::Forward(payload) => self.send_to_controller(payload),
             GuardAction::DropDuplicate => {
                 self.stats.add_h2c_duplicate_drop();
@@ -204,8 +394,101 @@ impl<C: Codec> BridgeSession<C> {
         let mut encoded = Vec::with_capacity(data.len() + 16);
         self.controller_codec.encode(&data, &mut encoded);
 
-        // Send to controller (silently drop if channel full)
-        let _ = self.controller.tx.try_send(Bytes::from(encoded));
+        // Send to controller (counted as a drop if channel full)
+        self.check_write_queue();
+        if let Err(TrySendError::Full(_)) = self.controller.tx.try_send(Bytes::from(encoded)) {
+            self.stats.add_channel_drop();
+        }
+    }
+
+    /// Add a message to the pending batch, flushing when it is full
+    fn queue_for_controller(&mut self, payload: Bytes, now_ms: u64) {
+        let max = self.batch.max_batch_size_bytes;
+        if !self.pending.is_empty() && self.pending_bytes + payload.len() > max {
+            self.flush_batch();
+        }
+        if self.pending.is_empty() {
+            self.batch_started_ms = now_ms;
+        }
+        self.pending_bytes += payload.len();
+        self.pending.push(payload);
+        if self.pending_bytes >= max {
+            self.flush_batch();
+        }
+    }
+
+    /// Time left before the pending batch must be written
+    fn batch_wait(&self) -> Option<Duration> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let age = self.elapsed_ms().saturating_sub(self.batch_started_ms);
+        Some(Duration::from_millis(
+            self.batch.max_batch_age_ms.saturating_sub(age),
+        ))
+    }
+
+    /// Encode all pending messages into a single controller write
+    fn flush_batch(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let mut encoded = Vec::with_capacity(self.pending_bytes + 16 * self.pending.len());
+        self.controller_codec
+            .encode_batch(&self.pending, &mut encoded);
+        let messages = self.pending.len() as u64;
+        self.pending.clear();
+        self.pending_bytes = 0;
+        self.stats.add_tx_batch();
+
+        // Every message of the batch is lost with it
+        self.check_write_queue();
+        if let Err(TrySendError::Full(_)) = self.controller.tx.try_send(Bytes::from(encoded)) {
+            self.stats.add_channel_drops(messages);
+        }
+    }
+
+    /// Record the controller write queue depth in `Stats`
+    fn sample_write_queue(&self) {
+        let capacity = self.controller.tx.max_capacity();
+        self.stats
+            .record_write_queue(capacity - self.controller.tx.capacity(), capacity);
+    }
+
+    /// Record the controller write queue; warn (rate-limited) when it is near full
+    fn check_write_queue(&mut self) {
+        self.sample_write_queue();
+        if self.stats.write_channel_fill_ratio() <= WRITE_QUEUE_WARN_RATIO {
+            return;
+        }
+        let now_ms = self.elapsed_ms();
+        if self
+            .write_queue_warned_ms
+            .is_some_and(|last| now_ms - last < WRITE_QUEUE_WARN_INTERVAL_MS)
+        {
+            return;
+        }
+        self.write_queue_warned_ms = Some(now_ms);
+        logging::try_log(
+            &self.log_tx,
+            LogEntry::system("Write channel near full - serial port may be bottleneck"),
+            "write_queue_full",
+        );
+    }
+
+    /// Resume the controller reader once the host channel has drained
+    fn check_host_drained(&mut self) {
+        if !self.backpressure.is_applied() {
+            return;
+        }
+        let queued = self.host.tx.max_capacity() - self.host.tx.capacity();
+        if self.backpressure.on_queue_depth(queued) == Some(BackpressureChange::Released) {
+            logging::try_log(
+                &self.log_tx,
+                LogEntry::system("Backpressure released: host caught up"),
+                "backpressure_released",
+            );
+        }
     }
 
     fn elapsed_ms(&self) -> u64 {
@@ -213,10 +496,33 @@ impl<C: Codec> BridgeSession<C> {
     }
 }
 
+/// Count and log a message above `limit` (0 = no limit); true if dropped
+fn drop_oversized(
+    stats: &Stats,
+    log_tx: &Option<mpsc::Sender<LogEntry>>,
+    limit: usize,
+    len: usize,
+) -> bool {
+    if limit == 0 || len <= limit {
+        return false;
+    }
+    stats.add_oversized_drop();
+    logging::try_log(
+        log_tx,
+        LogEntry::system(format!(
+            "Oversized message dropped: {} bytes > {} limit",
+            len, limit
+        )),
+        "message_oversized",
+    );
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::codec::RawCodec;
+    use crate::codec::{CobsDebugCodec, RawCodec};
+    use crate::logging::LogKind;
     use std::time::Duration;
 
     #[tokio::test]
@@ -418,6 +724,51 @@ mod tests {
         let _ = handle.await;
     }
 
+    #[tokio::test]
+    async fn test_session_backpressure_pauses_reader_until_drained() {
+        let (ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, _ctrl_out_rx) = mpsc::channel(16);
+        let (_host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, mut host_out_rx) = mpsc::channel(1);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let pause_reader = Arc::new(AtomicBool::new(false));
+
+        let session = BridgeSession::new(controller, host, RawCodec, stats.clone(), None)
+            .with_duplicate_guard(false, 0)
+            .with_backpressure(1, 0, pause_reader.clone());
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        // Host never reads: first message fills the channel, the rest are dropped
+        for i in 0..4u8 {
+            ctrl_in_tx.send(Bytes::from(vec![i])).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(stats.channel_drops(), 3);
+        assert!(pause_reader.load(Ordering::Relaxed));
+
+        // Host catches up: reader resumes on the next idle tick
+        assert!(host_out_rx.try_recv().is_ok());
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!pause_reader.load(Ordering::Relaxed));
+
+        shutdown.store(true, Ordering::SeqCst);
+        drop(ctrl_in_tx);
+        let _ = handle.await;
+    }
+
     #[tokio::test]
     async fn test_session_host_disconnect() {
         let (ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
@@ -449,4 +800,446 @@ mod tests {
         // Cleanup
         drop(ctrl_in_tx);
     }
+
+    #[tokio::test]
+    async fn test_session_closed_controller_is_not_a_channel_drop() {
+        let (_ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, ctrl_out_rx) = mpsc::channel(16);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, _host_out_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let session = BridgeSession::new(controller, host, RawCodec, stats.clone(), None);
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        // Controller writer gone (e.g. transport stopping)
+        drop(ctrl_out_rx);
+        host_in_tx.send(Bytes::from_static(b"hello")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+        assert_eq!(stats.channel_drops(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_batches_host_messages_into_one_write() {
+        let (_ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, mut ctrl_out_rx) = mpsc::channel(16);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, _host_out_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let session = BridgeSession::new(
+            controller,
+            host,
+            CobsDebugCodec::new(4096),
+            stats.clone(),
+            None,
+        )
+        .with_duplicate_guard(false, 0)
+        .with_batching(BatchConfig {
+            max_batch_size_bytes: 1024,
+            max_batch_age_ms: 20,
+        });
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        for i in 0..3u8 {
+            host_in_tx
+                .send(Bytes::from(vec![0x04, b'v', b'o', b'l', i + 1]))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert!(ctrl_out_rx.try_recv().is_err(), "batch flushed too early");
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let write = ctrl_out_rx.try_recv().expect("batched write");
+        assert!(ctrl_out_rx.try_recv().is_err());
+
+        // The controller side splits the write back into three frames
+        let mut decoder = CobsDebugCodec::new(4096);
+        let mut frames = Vec::new();
+        decoder.decode(&write, |frame| {
+            if let Frame::Message { payload, .. } = frame {
+                frames.push(payload);
+            }
+        });
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].as_ref(), &[0x04, b'v', b'o', b'l', 3]);
+
+        assert_eq!(stats.tx_messages(), 3);
+        assert_eq!(stats.tx_batches(), 1);
+
+        shutdown.store(true, Ordering::SeqCst);
+        drop(host_in_tx);
+        let _ = handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_session_batch_flushes_when_full() {
+        let (_ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, mut ctrl_out_rx) = mpsc::channel(16);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, _host_out_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let session = BridgeSession::new(
+            controller,
+            host,
+            CobsDebugCodec::new(4096),
+            stats.clone(),
+            None,
+        )
+        .with_duplicate_guard(false, 0)
+        .with_batching(BatchConfig {
+            max_batch_size_bytes: 8,
+            max_batch_age_ms: 10_000,
+        });
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        // 3 x 4 bytes with an 8-byte limit: one full batch, one pending
+        for i in 0..3u8 {
+            host_in_tx
+                .send(Bytes::from(vec![0x11, i + 1, 0x12, 0x13]))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(ctrl_out_rx.try_recv().is_ok());
+        assert!(ctrl_out_rx.try_recv().is_err());
+        assert_eq!(stats.tx_batches(), 1);
+
+        // The pending message is written on shutdown
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+        assert!(ctrl_out_rx.try_recv().is_ok());
+        assert_eq!(stats.tx_batches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_session_dropped_batch_counts_each_message() {
+        let (_ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        // Room for one write, never read
+        let (ctrl_out_tx, _ctrl_out_rx) = mpsc::channel(1);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, _host_out_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let session = BridgeSession::new(
+            controller,
+            host,
+            CobsDebugCodec::new(4096),
+            stats.clone(),
+            None,
+        )
+        .with_duplicate_guard(false, 0)
+        .with_batching(BatchConfig {
+            max_batch_size_bytes: 8,
+            max_batch_age_ms: 10_000,
+        });
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        // Batches of 2 + 2 + 1 messages: only the first one fits
+        for i in 0..5u8 {
+            host_in_tx
+                .send(Bytes::from(vec![0x11, i + 1, 0x12, 0x13]))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+        assert_eq!(stats.tx_batches(), 3);
+        assert_eq!(stats.channel_drops(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_session_priority_messages_skip_batching() {
+        let (_ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, mut ctrl_out_rx) = mpsc::channel(16);
+        let (priority_tx, mut priority_rx) = mpsc::channel(16);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, _host_out_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let session = BridgeSession::new(controller, host, RawCodec, stats, None)
+            .with_batching(BatchConfig {
+                max_batch_size_bytes: 1024,
+                max_batch_age_ms: 10_000,
+            })
+            .with_priority_tx(priority_tx);
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        host_in_tx
+            .send(Bytes::from_static(&[0x01, 3, b'v', b'o', b'l', 7]))
+            .await
+            .unwrap();
+        let stop = Bytes::from_static(&[0x02, 5, b'!', b's', b't', b'o', b'p']);
+        host_in_tx.send(stop.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // "!stop" went out while "vol" is still batched
+        assert_eq!(priority_rx.try_recv().unwrap(), stop);
+        assert!(ctrl_out_rx.try_recv().is_err());
+
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+        assert!(ctrl_out_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_session_warns_when_controller_write_queue_fills() {
+        let (_ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        // Never read: the serial side is stuck
+        let (ctrl_out_tx, _ctrl_out_rx) = mpsc::channel(5);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, _host_out_rx) = mpsc::channel(16);
+        let (log_tx, mut log_rx) = mpsc::channel(32);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let session = BridgeSession::new(controller, host, RawCodec, stats.clone(), Some(log_tx))
+            .with_duplicate_guard(false, 0);
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        for i in 0..8 {
+            host_in_tx
+                .send(Bytes::from(vec![0x02, 1, b'v', i]))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+
+        assert_eq!(stats.write_channel_fill_ratio(), 1.0);
+        assert_eq!(stats.max_write_queue_depth(), 5);
+        let mut warnings = 0;
+        while let Ok(entry) = log_rx.try_recv() {
+            if matches!(&entry.kind, LogKind::System { message } if message.starts_with("Write channel near full"))
+            {
+                warnings += 1;
+            }
+        }
+        assert_eq!(warnings, 1, "rate-limited to one warning per 10 s");
+    }
+
+    #[tokio::test]
+    async fn test_write_fill_ratio_follows_queue_without_traffic() {
+        let (_ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, mut ctrl_out_rx) = mpsc::channel(4);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, _host_out_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let session = BridgeSession::new(controller, host, RawCodec, stats.clone(), None)
+            .with_duplicate_guard(false, 0);
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        for i in 0..5 {
+            host_in_tx
+                .send(Bytes::from(vec![0x02, 1, b'v', i]))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(stats.write_channel_fill_ratio(), 1.0);
+
+        // The serial side catches up while the host is silent
+        while ctrl_out_rx.try_recv().is_ok() {}
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(stats.write_channel_fill_ratio(), 0.0);
+
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_session_warns_once_per_oversized_message() {
+        let (ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, _ctrl_out_rx) = mpsc::channel(16);
+        let (_host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, mut host_out_rx) = mpsc::channel(16);
+        let (log_tx, mut log_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let session = BridgeSession::new(controller, host, RawCodec, stats, Some(log_tx))
+            .with_duplicate_guard(false, 0)
+            .with_payload_warning(8);
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        let big = Bytes::from_static(&[0x01, 4, b'w', b'a', b'v', b'e', 1, 2, 3, 4]);
+        ctrl_in_tx.send(big.clone()).await.unwrap();
+        ctrl_in_tx.send(big).await.unwrap();
+        ctrl_in_tx
+            .send(Bytes::from_static(&[0x02, 2, b'o', b'k']))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Oversized payloads are still relayed
+        for _ in 0..3 {
+            assert!(host_out_rx.try_recv().is_ok());
+        }
+
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+
+        let mut warnings = Vec::new();
+        while let Ok(entry) = log_rx.try_recv() {
+            if let LogKind::System { message } = entry.kind {
+                warnings.push(message);
+            }
+        }
+        assert_eq!(
+            warnings,
+            ["wave: 10 byte payload exceeds max_payload_warn_bytes (8)"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_drops_oversized_messages() {
+        let (ctrl_in_tx, ctrl_in_rx) = mpsc::channel(16);
+        let (ctrl_out_tx, mut ctrl_out_rx) = mpsc::channel(16);
+        let (host_in_tx, host_in_rx) = mpsc::channel(16);
+        let (host_out_tx, mut host_out_rx) = mpsc::channel(16);
+        let (log_tx, mut log_rx) = mpsc::channel(16);
+
+        let controller = TransportChannels {
+            rx: ctrl_in_rx,
+            tx: ctrl_out_tx,
+        };
+        let host = TransportChannels {
+            rx: host_in_rx,
+            tx: host_out_tx,
+        };
+
+        let stats = Arc::new(Stats::new());
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let session = BridgeSession::new(controller, host, RawCodec, stats.clone(), Some(log_tx))
+            .with_duplicate_guard(false, 0)
+            .with_max_message_size(4096);
+        let shutdown_clone = shutdown.clone();
+        let handle = tokio::spawn(async move { session.run(shutdown_clone).await });
+
+        let big = Bytes::from(vec![0x01; 10 * 1024]);
+        ctrl_in_tx.send(big.clone()).await.unwrap();
+        host_in_tx.send(big).await.unwrap();
+        ctrl_in_tx
+            .send(Bytes::from_static(&[0x02, 2, b'o', b'k']))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Only the small message gets through
+        assert_eq!(host_out_rx.try_recv().unwrap().len(), 4);
+        assert!(host_out_rx.try_recv().is_err());
+        assert!(ctrl_out_rx.try_recv().is_err());
+
+        shutdown.store(true, Ordering::SeqCst);
+        let _ = handle.await;
+
+        assert_eq!(stats.oversized_drops(), 2);
+        let mut drops = Vec::new();
+        while let Ok(entry) = log_rx.try_recv() {
+            if let LogKind::System { message } = entry.kind {
+                drops.push(message);
+            }
+        }
+        assert_eq!(
+            drops,
+            ["Oversized message dropped: 10240 bytes > 4096 limit"; 2]
+        );
+    }
 }
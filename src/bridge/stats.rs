@@ -2,10 +2,46 @@
 //!
 //! Thread-safe counters for measuring bytes/sec throughput.
 //! Uses lock-free atomics for all operations.
+//!
+//! Rates come from a ring of 100ms slots covering the last 60 seconds, so
+//! a 1s window and a 60s window are both exact over their own span
+//! (`windowed_rates`).
+//!
+//! `SpikeDetector` compares the last 100ms slot with a 5-second baseline to
+//! flag runaway traffic (`in_spike`).
+//!
+//! `jitter_ms` is the mean absolute deviation of the last
+//! `JITTER_SAMPLES` controller inter-arrival times (the variation metric of
+//! RFC 3550), to the resolution of the stats clock.
+//!
+//! `write_channel_fill_ratio` is how full the controller write channel was
+//! at the last host -> controller send, or at the session's last idle tick
+//! (100 ms); near 1.0 the serial port, not the network, is the bottleneck.
+//!
+//! Time is read with `coarsetime` (a coarse monotonic clock, no syscall on
+//! most platforms), except for arrivals: its 1-4 ms resolution would swamp
+//! the jitter of a few ms, so they use `std::time::Instant`. `add_rx_timed`
+//! lets a caller reuse one reading for a whole batch of messages.
+//!
+//! `record_message` keeps per-message-type counters (`message_stats`) in a
+//! mutex-guarded map: the only lock on the relay path, held for one lookup.
+//!
+//! The daemon appends periodic snapshots to a JSON Lines file so lifetime
+//! totals survive restarts (see `save_snapshot` / `load_cumulative`). Each
+//! snapshot carries its daemon session (pid and start time); saving keeps
+//! only the last snapshot of each session, so the file grows by one line
+//! per restart rather than one per minute.
 
-use crate::constants::RATE_UPDATE_MIN_INTERVAL_SECS;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Instant;
+use crate::config::SpikeDetectorConfig;
+use crate::error::{BridgeError, Result};
+use crate::logging::Direction;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
 
 /// Traffic statistics with rate calculation (fully lock-free)
 pub struct Stats {
@@ -13,22 +49,140 @@ pub struct Stats {
     tx_total: AtomicU64,
     /// Total bytes received (from serial)
     rx_total: AtomicU64,
-    /// Snapshot of tx_total at last rate calculation
-    tx_snapshot: AtomicU64,
-    /// Snapshot of rx_total at last rate calculation
-    rx_snapshot: AtomicU64,
     /// Reference instant for time calculations
-    start_time: Instant,
-    /// Nanoseconds since start_time at last rate calculation
-    last_calc_nanos: AtomicU64,
-    /// Cached TX rate in bytes/sec (stored as f64 bits)
-    tx_rate: AtomicU64,
-    /// Cached RX rate in bytes/sec (stored as f64 bits)
-    rx_rate: AtomicU64,
+    start_time: coarsetime::Instant,
+    /// Reference instant for arrival times (precise clock)
+    arrival_start: std::time::Instant,
+    /// `StatsSnapshot::session` of this process
+    session: String,
+    /// Bytes per `RATE_SLOT_MS` slot over the last `RATE_SLOTS` slots
+    rate_history: Box<[RateSlot]>,
     /// Number of controller -> host messages dropped as exact duplicates
     c2h_duplicate_drops: AtomicU64,
     /// Number of host -> controller messages dropped as exact duplicates
     h2c_duplicate_drops: AtomicU64,
+    /// Number of messages dropped because a relay channel was full
+    channel_drops: AtomicU64,
+    /// Number of messages dropped for exceeding `max_message_size`
+    oversized_drops: AtomicU64,
+    /// Serial connections restored after a loss
+    reconnections: AtomicU64,
+    /// Messages transmitted (to serial)
+    tx_messages: AtomicU64,
+    /// Batched writes to serial (each carries one or more messages)
+    tx_batches: AtomicU64,
+    /// Messages received (from serial)
+    rx_messages: AtomicU64,
+    /// Controller frames that failed COBS decoding
+    cobs_errors: AtomicU64,
+    /// Control plane requests handled
+    control_requests_total: AtomicU64,
+    /// Sum of control plane request latencies, in microseconds
+    control_latency_us_sum: AtomicU64,
+    /// A traffic spike is under way (set by `SpikeDetector`)
+    in_spike: AtomicBool,
+    /// Last controller arrival, in microseconds since `start_time`
+    last_rx_us: AtomicU64,
+    /// Last `JITTER_SAMPLES` inter-arrival times in microseconds (ring)
+    jitter_samples: [AtomicU32; JITTER_SAMPLES],
+    /// Inter-arrival times recorded so far (next ring index)
+    jitter_count: AtomicU64,
+    /// Messages queued for the controller at the last send
+    write_queue_depth: AtomicUsize,
+    /// Capacity of the controller write channel (0 = not known yet)
+    write_queue_capacity: AtomicUsize,
+    /// Deepest controller write queue seen
+    max_write_queue_depth: AtomicUsize,
+    /// Counters per message name
+    per_message: Mutex<HashMap<String, MessageStats>>,
+}
+
+/// Resolution of the rate history
+const RATE_SLOT_MS: u64 = 100;
+/// Slots kept: 60 seconds at 100ms
+const RATE_SLOTS: usize = 600;
+/// How often `SpikeDetector::update` should run (one rate slot)
+pub const SPIKE_CHECK_INTERVAL: Duration = Duration::from_millis(RATE_SLOT_MS);
+/// Window of the spike detection baseline
+const SPIKE_BASELINE_MS: u64 = 5_000;
+/// Inter-arrival times kept for `jitter_ms`
+const JITTER_SAMPLES: usize = 100;
+
+/// Bytes moved during one slot; `epoch` tags which slot period it holds
+#[derive(Default)]
+struct RateSlot {
+    epoch: AtomicU64,
+    tx: AtomicU64,
+    rx: AtomicU64,
+}
+
+/// Traffic of one message type
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageStats {
+    pub rx_count: u64,
+    pub tx_count: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// Milliseconds since the last message of this type (when read)
+    pub idle_ms: u64,
+    /// Last message, in milliseconds since the stats started
+    #[serde(skip)]
+    last_seen_ms: u64,
+}
+
+impl MessageStats {
+    /// Bytes in both directions
+    pub fn total_bytes(&self) -> u64 {
+        self.rx_bytes + self.tx_bytes
+    }
+}
+
+/// Point-in-time counters, one JSON line per snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    /// Daemon session, `<pid>-<start unix ms>` (absent in older files)
+    #[serde(default)]
+    pub session: String,
+    pub timestamp: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_messages: u64,
+    pub tx_messages: u64,
+    /// Batched serial writes carrying `tx_messages` (absent in older files)
+    #[serde(default)]
+    pub tx_batches: u64,
+    pub uptime_secs: u64,
+    pub cobs_errors: u64,
+    pub channel_drops: u64,
+}
+
+/// Lifetime totals summed over all recorded daemon sessions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CumulativeStats {
+    pub sessions: u64,
+    pub first_timestamp: Option<String>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_messages: u64,
+    pub tx_messages: u64,
+    pub tx_batches: u64,
+    pub uptime_secs: u64,
+    pub cobs_errors: u64,
+    pub channel_drops: u64,
+}
+
+impl CumulativeStats {
+    fn add_session(&mut self, last: &StatsSnapshot) {
+        self.sessions += 1;
+        self.rx_bytes += last.rx_bytes;
+        self.tx_bytes += last.tx_bytes;
+        self.rx_messages += last.rx_messages;
+        self.tx_messages += last.tx_messages;
+        self.tx_batches += last.tx_batches;
+        self.uptime_secs += last.uptime_secs;
+        self.cobs_errors += last.cobs_errors;
+        self.channel_drops += last.channel_drops;
+    }
 }
 
 impl Stats {
@@ -36,27 +190,212 @@ impl Stats {
         Self {
             tx_total: AtomicU64::new(0),
             rx_total: AtomicU64::new(0),
-            tx_snapshot: AtomicU64::new(0),
-            rx_snapshot: AtomicU64::new(0),
-            start_time: Instant::now(),
-            last_calc_nanos: AtomicU64::new(0),
-            tx_rate: AtomicU64::new(0),
-            rx_rate: AtomicU64::new(0),
+            start_time: coarsetime::Instant::now(),
+            arrival_start: std::time::Instant::now(),
+            session: format!(
+                "{}-{}",
+                std::process::id(),
+                chrono::Utc::now().timestamp_millis()
+            ),
+            rate_history: (0..RATE_SLOTS).map(|_| RateSlot::default()).collect(),
             c2h_duplicate_drops: AtomicU64::new(0),
             h2c_duplicate_drops: AtomicU64::new(0),
+            channel_drops: AtomicU64::new(0),
+            oversized_drops: AtomicU64::new(0),
+            reconnections: AtomicU64::new(0),
+            tx_messages: AtomicU64::new(0),
+            tx_batches: AtomicU64::new(0),
+            rx_messages: AtomicU64::new(0),
+            cobs_errors: AtomicU64::new(0),
+            control_requests_total: AtomicU64::new(0),
+            control_latency_us_sum: AtomicU64::new(0),
+            in_spike: AtomicBool::new(false),
+            last_rx_us: AtomicU64::new(u64::MAX),
+            jitter_samples: std::array::from_fn(|_| AtomicU32::new(0)),
+            jitter_count: AtomicU64::new(0),
+            write_queue_depth: AtomicUsize::new(0),
+            write_queue_capacity: AtomicUsize::new(0),
+            max_write_queue_depth: AtomicUsize::new(0),
+            per_message: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Add transmitted bytes (Host -> Controller)
+    /// Add one transmitted message of `bytes` (Host -> Controller)
     #[inline]
     pub fn add_tx(&self, bytes: usize) {
         self.tx_total.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.tx_messages.fetch_add(1, Ordering::Relaxed);
+        self.rate_slot(self.elapsed_ms())
+            .tx
+            .fetch_add(bytes as u64, Ordering::Relaxed);
     }
 
-    /// Add received bytes (Controller -> Host)
+    /// Add one received message of `bytes` (Controller -> Host)
     #[inline]
     pub fn add_rx(&self, bytes: usize) {
+        self.add_rx_timed(bytes, std::time::Instant::now());
+    }
+
+    /// `add_rx` with a time read by the caller, e.g. once per decoded batch
+    #[inline]
+    pub fn add_rx_timed(&self, bytes: usize, now: std::time::Instant) {
+        let us = now
+            .saturating_duration_since(self.arrival_start)
+            .as_micros() as u64;
         self.rx_total.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.rx_messages.fetch_add(1, Ordering::Relaxed);
+        self.rate_slot(us / 1000)
+            .rx
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        self.record_arrival(us);
+    }
+
+    /// Add the time since the previous arrival to the jitter samples
+    ///
+    /// Messages decoded from one read share `now` and count as one arrival;
+    /// the first arrival has no predecessor (`last_rx_us` starts at MAX).
+    fn record_arrival(&self, us: u64) {
+        let last = self.last_rx_us.swap(us, Ordering::Relaxed);
+        if us > last {
+            let i = self.jitter_count.fetch_add(1, Ordering::Relaxed) as usize;
+            let gap = (us - last).min(u32::MAX as u64) as u32;
+            self.jitter_samples[i % JITTER_SAMPLES].store(gap, Ordering::Relaxed);
+        }
+    }
+
+    /// Count one `name` message of `bytes` in `direction`
+    pub fn record_message(&self, name: &str, direction: Direction, bytes: usize) {
+        let now_ms = self.elapsed_ms();
+        let mut per_message = self.per_message.lock();
+        // Allocate the key only for the first message of a type
+        let entry = match per_message.get_mut(name) {
+            Some(entry) => entry,
+            None => per_message.entry(name.to_string()).or_default(),
+        };
+        match direction {
+            Direction::In => {
+                entry.rx_count += 1;
+                entry.rx_bytes += bytes as u64;
+            }
+            Direction::Out => {
+                entry.tx_count += 1;
+                entry.tx_bytes += bytes as u64;
+            }
+        }
+        entry.last_seen_ms = now_ms;
+    }
+
+    /// Per-message counters, most bytes first
+    pub fn message_stats(&self) -> Vec<(String, MessageStats)> {
+        let now_ms = self.elapsed_ms();
+        let mut all: Vec<(String, MessageStats)> = self
+            .per_message
+            .lock()
+            .iter()
+            .map(|(name, stats)| {
+                let mut stats = stats.clone();
+                stats.idle_ms = now_ms.saturating_sub(stats.last_seen_ms);
+                (name.clone(), stats)
+            })
+            .collect();
+        all.sort_by(|(a_name, a), (b_name, b)| {
+            b.total_bytes()
+                .cmp(&a.total_bytes())
+                .then_with(|| a_name.cmp(b_name))
+        });
+        all
+    }
+
+    /// The `count` message types with the most bytes
+    pub fn top_messages(&self, count: usize) -> Vec<(String, MessageStats)> {
+        let mut all = self.message_stats();
+        all.truncate(count);
+        all
+    }
+
+    /// Mean absolute deviation of the recent inter-arrival times (ms)
+    pub fn jitter_ms(&self) -> f64 {
+        let count = (self.jitter_count.load(Ordering::Relaxed) as usize).min(JITTER_SAMPLES);
+        if count < 2 {
+            return 0.0;
+        }
+        let samples: Vec<f64> = self.jitter_samples[..count]
+            .iter()
+            .map(|s| s.load(Ordering::Relaxed) as f64)
+            .collect();
+        let mean = samples.iter().sum::<f64>() / count as f64;
+        let deviation = samples.iter().map(|s| (s - mean).abs()).sum::<f64>() / count as f64;
+        deviation / 1000.0
+    }
+
+    /// Record the controller write queue (before a send, and on idle ticks)
+    pub fn record_write_queue(&self, depth: usize, capacity: usize) {
+        self.write_queue_depth.store(depth, Ordering::Relaxed);
+        self.write_queue_capacity.store(capacity, Ordering::Relaxed);
+        self.max_write_queue_depth
+            .fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Queued messages / channel capacity at the last sample (0.0 to 1.0)
+    pub fn write_channel_fill_ratio(&self) -> f64 {
+        let capacity = self.write_queue_capacity.load(Ordering::Relaxed);
+        if capacity == 0 {
+            return 0.0;
+        }
+        self.write_queue_depth.load(Ordering::Relaxed) as f64 / capacity as f64
+    }
+
+    /// Deepest controller write queue seen (messages)
+    pub fn max_write_queue_depth(&self) -> usize {
+        self.max_write_queue_depth.load(Ordering::Relaxed)
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.ms_at(coarsetime::Instant::now())
+    }
+
+    fn ms_at(&self, now: coarsetime::Instant) -> u64 {
+        now.duration_since(self.start_time).as_millis()
+    }
+
+    /// Ring slot for time `now_ms`, cleared when it still holds an older period
+    ///
+    /// A byte count racing with the clear may be lost; rates are for display.
+    fn rate_slot(&self, now_ms: u64) -> &RateSlot {
+        let epoch = now_ms / RATE_SLOT_MS;
+        let slot = &self.rate_history[epoch as usize % RATE_SLOTS];
+        let seen = slot.epoch.load(Ordering::Acquire);
+        if seen != epoch
+            && slot
+                .epoch
+                .compare_exchange(seen, epoch, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        {
+            slot.tx.store(0, Ordering::Relaxed);
+            slot.rx.store(0, Ordering::Relaxed);
+        }
+        slot
+    }
+
+    /// Add one batched write to the controller (messages counted by `add_tx`)
+    #[inline]
+    pub fn add_tx_batch(&self) {
+        self.tx_batches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn tx_messages(&self) -> u64 {
+        self.tx_messages.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn tx_batches(&self) -> u64 {
+        self.tx_batches.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn add_cobs_errors(&self, count: u64) {
+        self.cobs_errors.fetch_add(count, Ordering::Relaxed);
     }
 
     #[inline]
@@ -69,6 +408,60 @@ impl Stats {
         self.h2c_duplicate_drops.fetch_add(1, Ordering::Relaxed);
     }
 
+    #[inline]
+    pub fn add_channel_drop(&self) {
+        self.channel_drops.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count `count` messages lost together (e.g. one batched write)
+    #[inline]
+    pub fn add_channel_drops(&self, count: u64) {
+        self.channel_drops.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn add_oversized_drop(&self) {
+        self.oversized_drops.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count one serial connection restored after a loss
+    #[inline]
+    pub fn add_reconnection(&self) {
+        self.reconnections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn reconnections(&self) -> u64 {
+        self.reconnections.load(Ordering::Relaxed)
+    }
+
+    /// Record one handled control plane request
+    pub fn record_control_request(&self, latency: Duration) {
+        self.control_requests_total.fetch_add(1, Ordering::Relaxed);
+        self.control_latency_us_sum
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn control_requests_total(&self) -> u64 {
+        self.control_requests_total.load(Ordering::Relaxed)
+    }
+
+    /// Average control plane request latency (0 before the first request)
+    pub fn control_latency_ms_avg(&self) -> f64 {
+        let count = self.control_requests_total();
+        if count == 0 {
+            return 0.0;
+        }
+        let sum_us = self.control_latency_us_sum.load(Ordering::Relaxed);
+        sum_us as f64 / count as f64 / 1000.0
+    }
+
+    /// A traffic spike is under way
+    pub fn in_spike(&self) -> bool {
+        self.in_spike.load(Ordering::Relaxed)
+    }
+
     /// Get total transmitted bytes
     #[inline]
     #[allow(dead_code)] // Used in tests
@@ -95,44 +488,120 @@ impl Stats {
         self.h2c_duplicate_drops.load(Ordering::Relaxed)
     }
 
-    /// Update rate calculations and return (tx_kb_s, rx_kb_s)
-    /// Call this periodically (e.g., every 500ms) from the UI thread
-    pub fn update_rates(&self) -> (f64, f64) {
-        let now_nanos = self.start_time.elapsed().as_nanos() as u64;
-        let last_nanos = self.last_calc_nanos.load(Ordering::Relaxed);
-        let elapsed = (now_nanos - last_nanos) as f64 / 1_000_000_000.0;
+    #[inline]
+    pub fn channel_drops(&self) -> u64 {
+        self.channel_drops.load(Ordering::Relaxed)
+    }
 
-        if elapsed < RATE_UPDATE_MIN_INTERVAL_SECS {
-            // Too soon, return cached values
-            let tx = f64::from_bits(self.tx_rate.load(Ordering::Relaxed));
-            let rx = f64::from_bits(self.rx_rate.load(Ordering::Relaxed));
-            return (tx, rx);
+    #[inline]
+    pub fn oversized_drops(&self) -> u64 {
+        self.oversized_drops.load(Ordering::Relaxed)
+    }
+
+    /// Capture the current counters
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            session: self.session.clone(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            rx_bytes: self.rx_total.load(Ordering::Relaxed),
+            tx_bytes: self.tx_total.load(Ordering::Relaxed),
+            rx_messages: self.rx_messages.load(Ordering::Relaxed),
+            tx_messages: self.tx_messages(),
+            tx_batches: self.tx_batches(),
+            uptime_secs: self.start_time.elapsed().as_secs(),
+            cobs_errors: self.cobs_errors.load(Ordering::Relaxed),
+            channel_drops: self.channel_drops(),
         }
+    }
 
-        // Try to claim the update (avoid duplicate calculations)
-        if self
-            .last_calc_nanos
-            .compare_exchange(last_nanos, now_nanos, Ordering::SeqCst, Ordering::Relaxed)
-            .is_err()
-        {
-            // Another thread got there first, return cached values
-            let tx = f64::from_bits(self.tx_rate.load(Ordering::Relaxed));
-            let rx = f64::from_bits(self.rx_rate.load(Ordering::Relaxed));
-            return (tx, rx);
+    /// Record a snapshot of the current counters in `path` (JSON Lines)
+    ///
+    /// The history is compacted to the first snapshot (for the start date)
+    /// and the last one of each session, then replaced atomically. A history
+    /// that does not parse is left alone and the snapshot appended to it.
+    pub fn save_snapshot(&self, path: &Path) -> Result<()> {
+        let map_err = |e| BridgeError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        };
+        let snapshot = self.snapshot();
+        let history = match read_snapshots(path) {
+            Ok(history) => history,
+            Err(BridgeError::Io { source, .. })
+                if source.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Vec::new()
+            }
+            Err(_) => {
+                let line = serde_json::to_string(&snapshot).map_err(|e| map_err(e.into()))?;
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(map_err)?;
+                return writeln!(file, "{}", line).map_err(map_err);
+            }
+        };
+
+        let mut text = String::new();
+        let mut snapshots = history;
+        snapshots.push(snapshot);
+        for snapshot in compact(snapshots) {
+            text += &serde_json::to_string(&snapshot).map_err(|e| map_err(e.into()))?;
+            text.push('\n');
         }
+        let tmp = path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, text).map_err(map_err)?;
+        std::fs::rename(&tmp, path).map_err(map_err)
+    }
+
+    /// Sum the snapshot history in `path` into lifetime totals
+    ///
+    /// Counters restart with each daemon session; the last snapshot of each
+    /// session counts (see `same_session`).
+    pub fn load_cumulative(path: &Path) -> Result<CumulativeStats> {
+        let snapshots = read_snapshots(path)?;
+        let mut total = CumulativeStats {
+            first_timestamp: snapshots.first().map(|s| s.timestamp.clone()),
+            ..CumulativeStats::default()
+        };
+        for last in session_ends(&snapshots) {
+            total.add_session(last);
+        }
+        Ok(total)
+    }
 
-        let tx_now = self.tx_total.load(Ordering::Relaxed);
-        let rx_now = self.rx_total.load(Ordering::Relaxed);
-        let tx_prev = self.tx_snapshot.swap(tx_now, Ordering::Relaxed);
-        let rx_prev = self.rx_snapshot.swap(rx_now, Ordering::Relaxed);
+    /// Average (tx_kb_s, rx_kb_s) over the last `window_secs` (1..=60)
+    pub fn windowed_rates(&self, window_secs: u64) -> (f64, f64) {
+        self.windowed_rates_at(self.elapsed_ms(), window_secs)
+    }
 
-        let tx_rate = (tx_now - tx_prev) as f64 / elapsed / 1024.0; // KB/s
-        let rx_rate = (rx_now - rx_prev) as f64 / elapsed / 1024.0; // KB/s
+    fn windowed_rates_at(&self, now_ms: u64, window_secs: u64) -> (f64, f64) {
+        self.rates_over(now_ms, window_secs * 1000)
+    }
 
-        self.tx_rate.store(tx_rate.to_bits(), Ordering::Relaxed);
-        self.rx_rate.store(rx_rate.to_bits(), Ordering::Relaxed);
+    /// Completed slots only: the current, partial slot is left out
+    fn rates_over(&self, now_ms: u64, window_ms: u64) -> (f64, f64) {
+        let slots = (window_ms / RATE_SLOT_MS).clamp(1, RATE_SLOTS as u64);
+        let now_epoch = now_ms / RATE_SLOT_MS;
+        let oldest = now_epoch.saturating_sub(slots);
 
-        (tx_rate, rx_rate)
+        let (mut tx, mut rx) = (0u64, 0u64);
+        for epoch in oldest..now_epoch {
+            let slot = &self.rate_history[epoch as usize % RATE_SLOTS];
+            if slot.epoch.load(Ordering::Acquire) == epoch {
+                tx += slot.tx.load(Ordering::Relaxed);
+                rx += slot.rx.load(Ordering::Relaxed);
+            }
+        }
+
+        // Shorter than the window right after startup
+        let span_ms = ((now_epoch - oldest) * RATE_SLOT_MS).max(RATE_SLOT_MS);
+        let span_secs = span_ms as f64 / 1000.0;
+        (
+            tx as f64 / span_secs / 1024.0,
+            rx as f64 / span_secs / 1024.0,
+        )
     }
 }
 
@@ -141,3 +610,374 @@ impl Default for Stats {
         Self::new()
     }
 }
+
+/// Flags sustained throughput spikes in `Stats`
+///
+/// The baseline is frozen while a spike is under way, so the spike does
+/// not raise its own threshold.
+pub struct SpikeDetector {
+    /// Rolling 5-second rate (KB/s, tx + rx)
+    baseline_rate: f64,
+    spike_threshold_multiplier: f64,
+    spike_min_duration_ms: u64,
+    baseline_floor: f64,
+    /// Start of the slot where the rate first crossed the threshold
+    spike_started_ms: Option<u64>,
+    reported: bool,
+}
+
+impl SpikeDetector {
+    pub fn new(config: &SpikeDetectorConfig) -> Self {
+        Self {
+            baseline_rate: 0.0,
+            spike_threshold_multiplier: config.threshold_multiplier,
+            spike_min_duration_ms: config.min_duration_ms,
+            baseline_floor: config.baseline_floor_kb_s,
+            spike_started_ms: None,
+            reported: false,
+        }
+    }
+
+    /// Check the latest rates (call every `SPIKE_CHECK_INTERVAL`)
+    ///
+    /// Updates `stats.in_spike`; returns a message once a spike has lasted
+    /// longer than `spike_min_duration_ms`.
+    pub fn update(&mut self, stats: &Stats) -> Option<String> {
+        self.update_at(stats, stats.elapsed_ms())
+    }
+
+    fn update_at(&mut self, stats: &Stats, now_ms: u64) -> Option<String> {
+        let total = |(tx, rx): (f64, f64)| tx + rx;
+        let current = total(stats.rates_over(now_ms, RATE_SLOT_MS));
+        let threshold =
+            self.baseline_rate.max(self.baseline_floor) * self.spike_threshold_multiplier;
+
+        if current <= threshold {
+            self.spike_started_ms = None;
+            self.reported = false;
+            stats.in_spike.store(false, Ordering::Relaxed);
+            self.baseline_rate = total(stats.rates_over(now_ms, SPIKE_BASELINE_MS));
+            return None;
+        }
+
+        // The last completed slot is the first one over the threshold
+        let slot_start = (now_ms / RATE_SLOT_MS).saturating_sub(1) * RATE_SLOT_MS;
+        let started = *self.spike_started_ms.get_or_insert(slot_start);
+        let lasted_ms = now_ms / RATE_SLOT_MS * RATE_SLOT_MS - started;
+        if self.reported || lasted_ms <= self.spike_min_duration_ms {
+            return None;
+        }
+        self.reported = true;
+        stats.in_spike.store(true, Ordering::Relaxed);
+        Some(format!(
+            "Traffic spike: {:.1} KB/s vs {:.1} KB/s baseline",
+            current, self.baseline_rate
+        ))
+    }
+}
+
+/// Every snapshot recorded in `path`
+fn read_snapshots(path: &Path) -> Result<Vec<StatsSnapshot>> {
+    let text = std::fs::read_to_string(path).map_err(|e| BridgeError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| BridgeError::LogParse {
+                line: i + 1,
+                reason: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whether `next` was taken in the same daemon session as `prev`
+///
+/// Snapshots from before session ids were recorded fall back to the uptime:
+/// it goes backwards when the daemon restarts.
+fn same_session(prev: &StatsSnapshot, next: &StatsSnapshot) -> bool {
+    if prev.session.is_empty() || next.session.is_empty() {
+        next.uptime_secs >= prev.uptime_secs
+    } else {
+        prev.session == next.session
+    }
+}
+
+/// The last snapshot of each session, in order
+fn session_ends(snapshots: &[StatsSnapshot]) -> impl Iterator<Item = &StatsSnapshot> {
+    snapshots
+        .iter()
+        .enumerate()
+        .filter_map(|(i, snapshot)| match snapshots.get(i + 1) {
+            Some(next) if same_session(snapshot, next) => None,
+            _ => Some(snapshot),
+        })
+}
+
+/// The first snapshot and the last of each session
+fn compact(snapshots: Vec<StatsSnapshot>) -> Vec<StatsSnapshot> {
+    let mut kept: Vec<StatsSnapshot> = snapshots.first().cloned().into_iter().collect();
+    for (i, last) in session_ends(&snapshots).enumerate() {
+        if i > 0 || !std::ptr::eq(last, &snapshots[0]) {
+            kept.push(last.clone());
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(uptime_secs: u64, rx_bytes: u64) -> StatsSnapshot {
+        StatsSnapshot {
+            session: String::new(),
+            timestamp: format!("t{}", uptime_secs),
+            rx_bytes,
+            tx_bytes: 0,
+            rx_messages: rx_bytes / 10,
+            tx_messages: 0,
+            tx_batches: 0,
+            uptime_secs,
+            cobs_errors: 0,
+            channel_drops: 1,
+        }
+    }
+
+    #[test]
+    fn test_jitter_is_mean_absolute_deviation() {
+        let stats = Stats::new();
+        let at = |ms| stats.arrival_start + Duration::from_millis(ms);
+        assert_eq!(stats.jitter_ms(), 0.0);
+
+        // Gaps of 10, 30, 10, 30 ms: mean 20, deviation 10
+        for ms in [100, 110, 140, 150, 180] {
+            stats.add_rx_timed(1, at(ms));
+            // Same read: not a new arrival
+            stats.add_rx_timed(1, at(ms));
+        }
+        assert_eq!(stats.jitter_ms(), 10.0);
+    }
+
+    #[test]
+    fn test_message_stats_sorted_by_total_bytes() {
+        let stats = Stats::new();
+        stats.record_message("Ping", Direction::In, 4);
+        stats.record_message("Ping", Direction::Out, 4);
+        stats.record_message("Dump", Direction::In, 300);
+        stats.record_message("Set", Direction::Out, 20);
+
+        let all = stats.message_stats();
+        let names: Vec<&str> = all.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Dump", "Set", "Ping"]);
+        let (_, ping) = &all[2];
+        assert_eq!((ping.rx_count, ping.tx_count), (1, 1));
+        assert_eq!((ping.rx_bytes, ping.tx_bytes), (4, 4));
+
+        assert_eq!(stats.top_messages(2).len(), 2);
+    }
+
+    #[test]
+    fn test_write_channel_fill_ratio() {
+        let stats = Stats::new();
+        assert_eq!(stats.write_channel_fill_ratio(), 0.0);
+
+        stats.record_write_queue(900, 1000);
+        stats.record_write_queue(250, 1000);
+        assert_eq!(stats.write_channel_fill_ratio(), 0.25);
+        assert_eq!(stats.max_write_queue_depth(), 900);
+    }
+
+    #[test]
+    fn test_save_snapshot_keeps_first_and_last_of_session() {
+        let path = std::env::temp_dir().join(format!(
+            "oc-bridge-stats-append-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let stats = Stats::new();
+        stats.add_rx(5);
+        stats.add_tx(3);
+        stats.add_tx_batch();
+        stats.add_cobs_errors(2);
+        stats.save_snapshot(&path).unwrap();
+        stats.save_snapshot(&path).unwrap();
+        stats.save_snapshot(&path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        let first: StatsSnapshot = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!((first.rx_bytes, first.rx_messages), (5, 1));
+        assert_eq!((first.tx_bytes, first.tx_messages), (3, 1));
+        assert_eq!(first.tx_batches, 1);
+        assert_eq!(first.cobs_errors, 2);
+
+        // Snapshots written before batching was counted still load
+        let mut old = serde_json::to_value(&first).unwrap();
+        old.as_object_mut().unwrap().remove("tx_batches");
+        let old: StatsSnapshot = serde_json::from_value(old).unwrap();
+        assert_eq!(old.tx_batches, 0);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_cumulative_sums_last_snapshot_per_session() {
+        let path = std::env::temp_dir().join(format!(
+            "oc-bridge-stats-cumulative-{}.jsonl",
+            std::process::id()
+        ));
+        let lines: Vec<String> = [
+            snapshot(60, 100),
+            snapshot(120, 250),
+            // Daemon restarted: counters reset
+            snapshot(60, 40),
+        ]
+        .iter()
+        .map(|s| serde_json::to_string(s).unwrap())
+        .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let total = Stats::load_cumulative(&path).unwrap();
+        assert_eq!(total.sessions, 2);
+        assert_eq!(total.rx_bytes, 290);
+        assert_eq!(total.uptime_secs, 180);
+        assert_eq!(total.channel_drops, 2);
+        assert_eq!(total.first_timestamp.as_deref(), Some("t60"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_sessions_split_on_session_id() {
+        let in_session = |session: &str, uptime_secs, rx_bytes| StatsSnapshot {
+            session: session.to_string(),
+            ..snapshot(uptime_secs, rx_bytes)
+        };
+        let snapshots = vec![
+            // Legacy history without ids
+            snapshot(60, 100),
+            snapshot(120, 250),
+            in_session("1-0", 30, 10),
+            in_session("1-0", 60, 20),
+            // Restarted and already past the previous uptime
+            in_session("2-0", 90, 5),
+            in_session("2-0", 150, 7),
+        ];
+        let ends: Vec<u64> = session_ends(&snapshots).map(|s| s.rx_bytes).collect();
+        assert_eq!(ends, [250, 20, 7]);
+
+        let kept: Vec<u64> = compact(snapshots).iter().map(|s| s.rx_bytes).collect();
+        assert_eq!(kept, [100, 250, 20, 7]);
+        let kept = compact(vec![snapshot(60, 100)]);
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn test_windowed_rates_only_count_their_window() {
+        let stats = Stats::new();
+        // 10 KiB two seconds before "now", 1 KiB in the last second
+        stats
+            .rate_slot(1_000)
+            .rx
+            .fetch_add(10 * 1024, Ordering::Relaxed);
+        stats.rate_slot(2_950).rx.fetch_add(1024, Ordering::Relaxed);
+        stats.rate_slot(2_950).tx.fetch_add(512, Ordering::Relaxed);
+
+        let (tx, rx) = stats.windowed_rates_at(3_000, 1);
+        assert!((rx - 1.0).abs() < 1e-9);
+        assert!((tx - 0.5).abs() < 1e-9);
+
+        let (_, rx) = stats.windowed_rates_at(3_000, 60);
+        assert!((rx - 11.0 / 3.0).abs() < 1e-9);
+
+        // A minute later those slots are out of the window
+        let (_, rx) = stats.windowed_rates_at(63_000, 60);
+        assert_eq!(rx, 0.0);
+    }
+
+    #[test]
+    fn test_spike_detector_flags_sustained_spikes_only() {
+        let stats = Stats::new();
+        let add = |epoch: u64, bytes: u64| {
+            stats
+                .rate_slot(epoch * RATE_SLOT_MS)
+                .rx
+                .fetch_add(bytes, Ordering::Relaxed);
+        };
+        let mut detector = SpikeDetector::new(&SpikeDetectorConfig::default());
+
+        // 5s at 10 KB/s sets the baseline
+        for epoch in 0..50 {
+            add(epoch, 1024);
+        }
+        assert_eq!(detector.update_at(&stats, 5_000), None);
+        assert!((detector.baseline_rate - 10.0).abs() < 1e-9);
+
+        // One 100ms burst at 200x is not long enough
+        add(50, 200 * 1024);
+        assert_eq!(detector.update_at(&stats, 5_100), None);
+        assert!(!stats.in_spike());
+
+        // A second slot is: reported once, baseline kept
+        add(51, 200 * 1024);
+        let message = detector.update_at(&stats, 5_200).unwrap();
+        assert_eq!(message, "Traffic spike: 2000.0 KB/s vs 10.0 KB/s baseline");
+        assert!(stats.in_spike());
+        add(52, 200 * 1024);
+        assert_eq!(detector.update_at(&stats, 5_300), None);
+        assert!(stats.in_spike());
+
+        // Back to normal
+        add(53, 1024);
+        assert_eq!(detector.update_at(&stats, 5_400), None);
+        assert!(!stats.in_spike());
+    }
+
+    #[test]
+    fn test_control_request_latency_average() {
+        let stats = Stats::new();
+        assert_eq!(stats.control_latency_ms_avg(), 0.0);
+
+        stats.record_control_request(Duration::from_millis(2));
+        stats.record_control_request(Duration::from_millis(4));
+        assert_eq!(stats.control_requests_total(), 2);
+        assert_eq!(stats.control_latency_ms_avg(), 3.0);
+    }
+
+    /// One second of traffic at 50,000 msg/s must cost under 1% of that
+    /// second. Timing-sensitive: `cargo test --release -- --ignored`
+    #[test]
+    #[ignore]
+    fn bench_add_rx_timed_50k_messages() {
+        const MESSAGES: usize = 50_000;
+        const BATCH: usize = 16;
+
+        let stats = Stats::new();
+        let start = std::time::Instant::now();
+        for _ in 0..MESSAGES / BATCH {
+            let now = std::time::Instant::now();
+            for _ in 0..BATCH {
+                stats.add_rx_timed(64, now);
+            }
+        }
+        let timed = start.elapsed();
+
+        let start = std::time::Instant::now();
+        for _ in 0..MESSAGES {
+            stats.add_rx(64);
+        }
+        let untimed = start.elapsed();
+
+        assert!(
+            timed < Duration::from_millis(10),
+            "50k add_rx_timed: {:?}, 50k add_rx: {:?}",
+            timed,
+            untimed
+        );
+    }
+}
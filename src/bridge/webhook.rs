@@ -0,0 +1,273 @@
+//! Connection event webhooks
+//!
+//! Controller connects and disconnects notify `on_connect_webhook` /
+//! `on_disconnect_webhook` from a background task, with a JSON body such as
+//! `{"event":"connect","port":"COM3","timestamp":"2025-01-01T12:00:00+01:00"}`.
+//! UDP and WebSocket controllers connect when their server starts (`port` is
+//! then the port number) and disconnect when it stops.
+//!
+//! - `http://` / `https://`: POST (feature `webhooks`, TLS through rustls)
+//! - `file://`: runs the local script with the body on stdin
+//!
+//! Each delivery times out after 5s. A failed POST is retried 3 times, 1s
+//! apart; a script runs once, since a failed run may still have acted. The
+//! outcome is logged as a system entry.
+
+use crate::config::BridgeConfig;
+use crate::logging::{self, LogEntry};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+/// Time allowed per delivery attempt
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+/// POST attempts after the first one
+#[cfg(feature = "webhooks")]
+const WEBHOOK_RETRIES: u32 = 3;
+/// Delay between attempts
+const WEBHOOK_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Controller connection change reported to webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    Connect,
+    Disconnect,
+}
+
+impl ConnectionEvent {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Connect => "connect",
+            Self::Disconnect => "disconnect",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookBody<'a> {
+    event: &'static str,
+    port: &'a str,
+    timestamp: String,
+}
+
+/// Where a webhook is delivered
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    #[cfg(feature = "webhooks")]
+    Http(String),
+    Script(PathBuf),
+}
+
+impl Target {
+    fn parse(url: &str) -> std::result::Result<Self, String> {
+        if let Some(path) = url.strip_prefix("file://") {
+            return Ok(Self::Script(PathBuf::from(path)));
+        }
+        if url.starts_with("http://") || url.starts_with("https://") {
+            #[cfg(feature = "webhooks")]
+            return Ok(Self::Http(url.to_string()));
+            #[cfg(not(feature = "webhooks"))]
+            return Err(format!("{} needs the `webhooks` build feature", url));
+        }
+        Err(format!("unsupported URL {}", url))
+    }
+}
+
+/// Configured connection webhooks
+#[derive(Debug, Clone)]
+pub struct Webhooks {
+    on_connect: Option<Target>,
+    on_disconnect: Option<Target>,
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+}
+
+impl Webhooks {
+    /// Parse the configured URLs; unusable ones are logged and ignored
+    pub fn new(config: &BridgeConfig, log_tx: Option<mpsc::Sender<LogEntry>>) -> Self {
+        let target = |url: &Option<String>| {
+            let url = url.as_deref().filter(|u| !u.trim().is_empty())?;
+            Target::parse(url.trim())
+                .map_err(|e| {
+                    logging::try_log(
+                        &log_tx,
+                        LogEntry::system(format!("Webhook disabled: {}", e)),
+                        "webhook_invalid",
+                    )
+                })
+                .ok()
+        };
+        Self {
+            on_connect: target(&config.on_connect_webhook),
+            on_disconnect: target(&config.on_disconnect_webhook),
+            log_tx,
+        }
+    }
+
+    /// Deliver `event` for `port` in the background (no-op without a webhook)
+    pub fn notify(&self, event: ConnectionEvent, port: &str) {
+        let target = match event {
+            ConnectionEvent::Connect => &self.on_connect,
+            ConnectionEvent::Disconnect => &self.on_disconnect,
+        };
+        let Some(target) = target.clone() else {
+            return;
+        };
+        let body = WebhookBody {
+            event: event.name(),
+            port,
+            timestamp: chrono::Local::now().to_rfc3339(),
+        };
+        let Ok(body) = serde_json::to_string(&body) else {
+            return;
+        };
+
+        let log_tx = self.log_tx.clone();
+        tokio::spawn(async move {
+            let outcome = deliver_with_retries(&target, &body).await;
+            logging::try_log(
+                &log_tx,
+                LogEntry::system(format!("Webhook {}: {}", event.name(), outcome)),
+                "webhook_delivered",
+            );
+        });
+    }
+}
+
+/// Deliver with timeout and retries; returns the outcome to log
+async fn deliver_with_retries(target: &Target, body: &str) -> String {
+    let retries = match target {
+        Target::Script(_) => 0,
+        #[cfg(feature = "webhooks")]
+        Target::Http(_) => WEBHOOK_RETRIES,
+    };
+    let mut last_error = String::new();
+    for attempt in 0..=retries {
+        if attempt > 0 {
+            tokio::time::sleep(WEBHOOK_BACKOFF).await;
+        }
+        match tokio::time::timeout(WEBHOOK_TIMEOUT, deliver(target, body)).await {
+            Ok(Ok(outcome)) => return outcome,
+            Ok(Err(e)) => last_error = e,
+            Err(_) => last_error = "timed out".to_string(),
+        }
+    }
+    match retries {
+        0 => format!("failed ({})", last_error),
+        _ => format!("failed after {} attempts ({})", retries + 1, last_error),
+    }
+}
+
+async fn deliver(target: &Target, body: &str) -> std::result::Result<String, String> {
+    match target {
+        #[cfg(feature = "webhooks")]
+        Target::Http(url) => post(url, body).await,
+        Target::Script(path) => run_script(path, body).await,
+    }
+}
+
+#[cfg(feature = "webhooks")]
+async fn post(url: &str, body: &str) -> std::result::Result<String, String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .timeout(WEBHOOK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(format!("HTTP {}", status.as_u16()))
+    } else {
+        Err(format!("HTTP {}", status.as_u16()))
+    }
+}
+
+/// Run `path` with `body` on stdin; killed if the attempt times out
+async fn run_script(path: &Path, body: &str) -> std::result::Result<String, String> {
+    let mut child = tokio::process::Command::new(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A script that ignores stdin may close it early
+        let _ = stdin.write_all(body.as_bytes()).await;
+    }
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(format!("script {}", status))
+    } else {
+        Err(format!("script {}", status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_parse() {
+        assert_eq!(
+            Target::parse("file:///opt/hooks/connect.sh"),
+            Ok(Target::Script(PathBuf::from("/opt/hooks/connect.sh")))
+        );
+        assert!(Target::parse("ftp://example.com").is_err());
+        #[cfg(feature = "webhooks")]
+        assert_eq!(
+            Target::parse("http://localhost:8080/hook"),
+            Ok(Target::Http("http://localhost:8080/hook".to_string()))
+        );
+        #[cfg(not(feature = "webhooks"))]
+        assert!(Target::parse("http://localhost:8080/hook").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_script_receives_body_on_stdin() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("oc-bridge-webhook-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("hook.sh");
+        let out = dir.join("body.json");
+        std::fs::write(&script, format!("#!/bin/sh\ncat > '{}'\n", out.display())).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let body = r#"{"event":"connect","port":"COM3","timestamp":"t"}"#;
+        let outcome = deliver_with_retries(&Target::Script(script), body).await;
+        assert!(outcome.starts_with("script exit status: 0"), "{}", outcome);
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), body);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_failed_script_is_not_retried() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("oc-bridge-webhook-fail-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("hook.sh");
+        let runs = dir.join("runs");
+        std::fs::write(
+            &script,
+            format!("#!/bin/sh\necho run >> '{}'\nexit 1\n", runs.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let outcome = deliver_with_retries(&Target::Script(script), "{}").await;
+        assert!(outcome.starts_with("failed (script"), "{}", outcome);
+        assert_eq!(std::fs::read_to_string(&runs).unwrap().lines().count(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Provides structured argument parsing with automatic help generation.
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum, ValueHint};
 
 // =============================================================================
 // Controller Transport CLI Argument
@@ -19,14 +19,53 @@ pub enum ControllerArg {
     Udp,
 }
 
+/// Target shell for `shell-init`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ShellArg {
+    #[default]
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl ShellArg {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+}
+
+impl From<ShellArg> for clap_complete::Shell {
+    fn from(shell: ShellArg) -> Self {
+        match shell {
+            ShellArg::Bash => Self::Bash,
+            ShellArg::Zsh => Self::Zsh,
+            ShellArg::Fish => Self::Fish,
+        }
+    }
+}
+
 // =============================================================================
 // CLI Definition
 // =============================================================================
 
+/// Shown after the options in `--help`
+const ENV_HELP: &str = "\
+Environment:
+  Any scalar [bridge] or [logs] config field can be overridden:
+    [bridge] host_udp_port  ->  OC_BRIDGE_HOST_UDP_PORT=9100
+    [logs] max_entries      ->  OC_BRIDGE_LOGS_MAX_ENTRIES=5000
+  Booleans accept 1/0/true/false/yes/no, lists are comma-separated.
+  An invalid value is logged and the config file value is kept.";
+
 /// Serial-to-UDP bridge for open-control framework
 #[derive(Parser, Debug, Default)]
 #[command(name = "oc-bridge")]
 #[command(author, version, about, long_about = None)]
+#[command(after_help = ENV_HELP)]
 pub struct Cli {
     /// Enable verbose debug output
     #[arg(short, long)]
@@ -61,6 +100,12 @@ pub struct Cli {
     #[arg(long, value_name = "PORT", requires = "daemon")]
     pub daemon_log_broadcast_port: Option<u16>,
 
+    /// Start even if another bridge holds the instance lock
+    ///
+    /// For testing only: both instances will compete for the same ports.
+    #[arg(long)]
+    pub force: bool,
+
     /// Controller transport type (requires --headless)
     ///
     /// - websocket (or ws): Listen on WebSocket port for browser/WASM apps
@@ -75,8 +120,16 @@ pub struct Cli {
     #[arg(long, requires = "headless")]
     pub controller_port: Option<u16>,
 
+    /// TLS certificate (PEM) to serve wss:// (requires --headless and --tls-key)
+    #[arg(long, value_name = "PATH", requires_all = ["headless", "tls_key"])]
+    pub tls_cert: Option<std::path::PathBuf>,
+
+    /// TLS private key (PEM) for --tls-cert
+    #[arg(long, value_name = "PATH", requires_all = ["headless", "tls_cert"])]
+    pub tls_key: Option<std::path::PathBuf>,
+
     /// Serial port to use (overrides config)
-    #[arg(long, value_name = "PORT")]
+    #[arg(long, value_name = "PORT", value_hint = ValueHint::FilePath)]
     pub port: Option<String>,
 
     /// Stable logical identifier for this bridge instance
@@ -87,10 +140,41 @@ pub struct Cli {
     #[arg(long, value_name = "SERIAL")]
     pub serial_number: Option<String>,
 
+    /// Overlay `profiles/<NAME>.toml` on top of the config file
+    ///
+    /// Keys set in the profile override the main config; all other keys
+    /// keep their value. Manage profiles with `oc-bridge profile`.
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
     /// UDP port for host communication (default: 9000)
-    #[arg(long, value_name = "PORT")]
+    #[arg(long, value_name = "PORT", value_hint = ValueHint::Other)]
     pub udp_port: Option<u16>,
 
+    /// Log every byte decoded from the serial stream with its COBS context
+    ///
+    /// For firmware bring-up on new hardware; throttled to 100 entries/sec.
+    /// Applies to daemon and headless modes.
+    #[arg(long)]
+    pub trace_cobs: bool,
+
+    /// Record TUI input events (with timing) to a file
+    #[arg(long, value_name = "FILE", conflicts_with = "playback")]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Replay TUI input events from a recording
+    #[arg(long, value_name = "FILE")]
+    pub playback: Option<std::path::PathBuf>,
+
+    /// Replay at 10x speed (requires --playback)
+    #[arg(long, requires = "playback")]
+    pub fast: bool,
+
+    /// Serve the TUI state over HTTP on 127.0.0.1 (GET /state, /state/stream, /logs)
+    #[cfg(feature = "http-api")]
+    #[arg(long, value_name = "PORT")]
+    pub http_port: Option<u16>,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -106,6 +190,66 @@ pub enum Command {
         #[arg(long)]
         control_port: Option<u16>,
     },
+
+    /// Open a log file in the TUI for offline analysis (read-only)
+    ///
+    /// Accepts JSON Lines or the plain-text export format.
+    Analyze {
+        /// Log file to load
+        file: std::path::PathBuf,
+
+        /// Print entry counts and the most frequent messages instead of opening the TUI
+        #[arg(long)]
+        stats: bool,
+    },
+
+    /// List serial ports with USB metadata (* = matches the device preset)
+    #[command(visible_alias = "ports")]
+    ListPorts {
+        /// Print a JSON array instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check a serial port with a loopback connector (TX wired to RX)
+    ///
+    /// Sends COBS test frames and expects each one back. Exit code 0 on
+    /// pass, 1 on fail.
+    TestLoopback {
+        /// Serial port with the loopback connector
+        #[arg(long, required_unless_present = "software_loopback")]
+        port: Option<String>,
+
+        /// Number of test frames
+        #[arg(long, default_value_t = 100)]
+        count: usize,
+
+        /// Use a virtual echoing port instead of hardware (Unix only)
+        #[arg(long, conflicts_with = "port")]
+        software_loopback: bool,
+    },
+
+    /// Print shell functions (`ocbridge-port`) and completions to source
+    ///
+    /// Example: eval "$(oc-bridge shell-init --shell bash)"
+    ShellInit {
+        /// Shell syntax to emit
+        #[arg(long, value_enum, default_value_t = ShellArg::Bash)]
+        shell: ShellArg,
+    },
+
+    /// Manage config profiles (select one with --profile)
+    Profile {
+        #[command(subcommand)]
+        cmd: ProfileCommand,
+    },
+
+    /// Print the completion script for a shell
+    #[command(hide = true)]
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
 }
 
 /// Control subcommands
@@ -124,8 +268,35 @@ pub enum CtlCommand {
     /// Query daemon info (pid/version/config/ports)
     Info,
 
+    /// Print per-message-type traffic counters as JSON
+    Stats,
+
     /// Ask the running daemon to exit
     Shutdown,
+
+    /// Live traffic rates and serial status in the terminal (q to quit)
+    Monitor {
+        /// Redraw interval in milliseconds
+        #[arg(long = "interval", value_name = "MS", default_value_t = 1000)]
+        interval_ms: u64,
+    },
+}
+
+/// Profile subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProfileCommand {
+    /// Create a profile from a copy of the current config
+    Create {
+        /// Profile name (letters, digits, '-', '_', '.')
+        name: String,
+    },
+    /// List available profiles
+    List,
+    /// Delete a profile
+    Delete {
+        /// Profile name
+        name: String,
+    },
 }
 
 // Note: end-user lifecycle is managed by ms-manager.
@@ -199,6 +370,23 @@ mod tests {
         assert_eq!(cli.port, Some("COM3".to_string()));
     }
 
+    #[test]
+    fn test_cli_parse_test_loopback() {
+        let cli = Cli::parse_from(["oc-bridge", "test-loopback", "--port", "COM3"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::TestLoopback { port: Some(p), count: 100, software_loopback: false }) if p == "COM3"
+        ));
+        assert!(Cli::try_parse_from(["oc-bridge", "test-loopback"]).is_err());
+        assert!(Cli::try_parse_from(["oc-bridge", "test-loopback", "--software-loopback"]).is_ok());
+    }
+
+    #[test]
+    fn test_cli_parse_force() {
+        assert!(!Cli::parse_from(["oc-bridge", "--headless"]).force);
+        assert!(Cli::parse_from(["oc-bridge", "--headless", "--force"]).force);
+    }
+
     #[test]
     fn test_cli_parse_instance_id_and_serial_number() {
         let cli = Cli::parse_from([
@@ -221,6 +409,156 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_parse_ctl_monitor() {
+        let cli = Cli::parse_from(["oc-bridge", "ctl", "monitor", "--interval", "250"]);
+        match cli.command {
+            Some(Command::Ctl { cmd, .. }) => {
+                assert!(matches!(cmd, CtlCommand::Monitor { interval_ms: 250 }))
+            }
+            _ => panic!("Expected Ctl"),
+        }
+        let cli = Cli::parse_from(["oc-bridge", "ctl", "monitor"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Ctl {
+                cmd: CtlCommand::Monitor { interval_ms: 1000 },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_completions() {
+        let cli = Cli::parse_from(["oc-bridge", "completions", "powershell"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Completions {
+                shell: clap_complete::Shell::PowerShell
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_profile() {
+        let cli = Cli::parse_from(["oc-bridge", "--profile", "stage"]);
+        assert_eq!(cli.profile, Some("stage".to_string()));
+
+        let cli = Cli::parse_from(["oc-bridge", "profile", "create", "stage"]);
+        match cli.command {
+            Some(Command::Profile {
+                cmd: ProfileCommand::Create { name },
+            }) => assert_eq!(name, "stage"),
+            _ => panic!("Expected Profile Create"),
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_tls() {
+        let cli = Cli::parse_from([
+            "oc-bridge",
+            "--headless",
+            "--tls-cert",
+            "cert.pem",
+            "--tls-key",
+            "key.pem",
+        ]);
+        assert_eq!(cli.tls_cert, Some(std::path::PathBuf::from("cert.pem")));
+        assert_eq!(cli.tls_key, Some(std::path::PathBuf::from("key.pem")));
+
+        assert!(Cli::try_parse_from(["oc-bridge", "--headless", "--tls-cert", "c.pem"]).is_err());
+        assert!(
+            Cli::try_parse_from(["oc-bridge", "--tls-cert", "c.pem", "--tls-key", "k.pem"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_record_playback() {
+        let cli = Cli::parse_from(["oc-bridge", "--playback", "session.rec", "--fast"]);
+        assert_eq!(cli.playback, Some(std::path::PathBuf::from("session.rec")));
+        assert!(cli.fast);
+
+        assert!(Cli::try_parse_from(["oc-bridge", "--fast"]).is_err());
+        assert!(
+            Cli::try_parse_from(["oc-bridge", "--record", "a.rec", "--playback", "b.rec"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_analyze() {
+        let cli = Cli::parse_from(["oc-bridge", "analyze", "session.jsonl"]);
+        match cli.command {
+            Some(Command::Analyze { file, stats }) => {
+                assert_eq!(file, std::path::PathBuf::from("session.jsonl"));
+                assert!(!stats);
+            }
+            _ => panic!("Expected Analyze"),
+        }
+
+        let cli = Cli::parse_from(["oc-bridge", "analyze", "session.jsonl", "--stats"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Analyze { stats: true, .. })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_list_ports() {
+        let cli = Cli::parse_from(["oc-bridge", "list-ports", "--json"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::ListPorts { json: true })
+        ));
+
+        let cli = Cli::parse_from(["oc-bridge", "list-ports"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::ListPorts { json: false })
+        ));
+
+        let cli = Cli::parse_from(["oc-bridge", "ports", "--json"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::ListPorts { json: true })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_shell_init() {
+        let cli = Cli::parse_from(["oc-bridge", "shell-init", "--shell", "zsh"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::ShellInit {
+                shell: ShellArg::Zsh
+            })
+        ));
+
+        let cli = Cli::parse_from(["oc-bridge", "shell-init"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::ShellInit {
+                shell: ShellArg::Bash
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_parse_trace_cobs() {
+        let cli = Cli::parse_from(["oc-bridge", "--daemon", "--trace-cobs"]);
+        assert!(cli.daemon);
+        assert!(cli.trace_cobs);
+    }
+
+    #[test]
+    fn test_cli_parse_ctl_stats() {
+        let cli = Cli::parse_from(["oc-bridge", "ctl", "stats"]);
+        match cli.command {
+            Some(Command::Ctl { cmd, .. }) => assert!(matches!(cmd, CtlCommand::Stats)),
+            _ => panic!("Expected Ctl"),
+        }
+    }
+
     #[test]
     fn test_cli_parse_ctl_info() {
         let cli = Cli::parse_from(["oc-bridge", "ctl", "info"]);
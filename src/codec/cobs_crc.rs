@@ -0,0 +1,203 @@
+//! COBS+Debug codec with a CRC-16 per frame (`codec = "cobs_crc"`)
+//!
+//! Same stream as `CobsDebugCodec` (COBS frames and debug text lines), but
+//! each protocol payload carries a little-endian CRC-16/CCITT-FALSE
+//! appended before COBS encoding. Bit errors on noisy USB hubs then drop
+//! the frame with a warning instead of forwarding a corrupted message.
+//!
+//! Opt-in: firmware that does not append the CRC fails every check.
+//!
+//! Any frame byte may be 0x0A, and a frame prefix may read as text. The CRC
+//! is the inner codec's frame check, so such a `\n` stays inside the frame
+//! rather than ending a text line there.
+
+use super::{cobs, CobsDebugCodec, Codec, Frame, FrameRef};
+use crate::logging::LogLevel;
+
+/// Bytes appended to each payload
+const CRC_SIZE: usize = 2;
+
+/// Debug log emitted in place of a frame that fails the check
+const CRC_MISMATCH: &str = "CRC mismatch, frame dropped";
+
+/// `CobsDebugCodec` with CRC-16/CCITT-FALSE validation of each frame
+pub struct CobsCrcCodec {
+    inner: CobsDebugCodec,
+}
+
+impl CobsCrcCodec {
+    /// Add CRC framing to `inner` (keeps its frame timeout and trace settings)
+    pub fn new(mut inner: CobsDebugCodec) -> Self {
+        inner.set_frame_check(|frame| strip_crc(frame).is_some());
+        Self { inner }
+    }
+}
+
+impl Default for CobsCrcCodec {
+    fn default() -> Self {
+        Self::new(CobsDebugCodec::default())
+    }
+}
+
+impl Codec for CobsCrcCodec {
+    fn decode(&mut self, data: &[u8], mut on_frame: impl FnMut(Frame)) {
+        self.inner.decode_ref(data, |frame| match frame {
+            FrameRef::Message { payload } => match strip_crc(payload) {
+                Some(payload) => on_frame(FrameRef::Message { payload }.to_frame()),
+                None => on_frame(Frame::DebugLog {
+                    level: Some(LogLevel::Warn),
+                    message: CRC_MISMATCH.to_string(),
+                }),
+            },
+            FrameRef::DebugLog { .. } => on_frame(frame.to_frame()),
+        });
+    }
+
+    fn encode(&self, payload: &[u8], output: &mut Vec<u8>) {
+        let mut framed = Vec::with_capacity(payload.len() + CRC_SIZE);
+        framed.extend_from_slice(payload);
+        framed.extend_from_slice(&crc16(payload).to_le_bytes());
+        let _ = cobs::encode_into(&framed, output);
+    }
+
+    fn take_decode_errors(&mut self) -> u64 {
+        self.inner.take_decode_errors()
+    }
+
+    fn take_discarded_partial(&mut self) -> usize {
+        self.inner.take_discarded_partial()
+    }
+}
+
+/// Payload without its CRC, if the CRC matches
+fn strip_crc(frame: &[u8]) -> Option<&[u8]> {
+    let split = frame.len().checked_sub(CRC_SIZE)?;
+    let (payload, crc) = frame.split_at(split);
+    (crc16(payload).to_le_bytes() == crc).then_some(payload)
+}
+
+/// CRC-16/CCITT-FALSE (poly 0x1021, init 0xFFFF, no reflection, no final XOR)
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn decode_all(codec: &mut CobsCrcCodec, data: &[u8]) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        codec.decode(data, |f| frames.push(f));
+        frames
+    }
+
+    fn payload() -> impl Strategy<Value = Vec<u8>> {
+        prop::collection::vec(any::<u8>(), 1..512)
+    }
+
+    fn is_mismatch(frame: &Frame) -> bool {
+        matches!(
+            frame,
+            Frame::DebugLog { level: Some(LogLevel::Warn), message } if message == CRC_MISMATCH
+        )
+    }
+
+    #[test]
+    fn test_crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn test_frame_without_crc_is_dropped() {
+        let mut codec = CobsCrcCodec::default();
+        let mut plain = Vec::new();
+        cobs::encode_into(&[0x01, 0x02, 0x03], &mut plain).unwrap();
+
+        let frames = decode_all(&mut codec, &plain);
+        assert_eq!(frames.len(), 1);
+        assert!(is_mismatch(&frames[0]));
+
+        // Debug lines carry no CRC and pass through
+        let frames = decode_all(&mut codec, b"[1ms] INFO: Boot\n");
+        assert!(matches!(&frames[0], Frame::DebugLog { message, .. } if message == "Boot"));
+    }
+
+    #[test]
+    fn test_newline_in_crc_does_not_split_frame() {
+        let payload = (0..=u16::MAX)
+            .map(u16::to_be_bytes)
+            .find(|p| crc16(p).to_le_bytes().contains(&b'\n'))
+            .unwrap();
+        let mut codec = CobsCrcCodec::default();
+        let mut stream = Vec::new();
+        codec.encode(&payload, &mut stream);
+        stream.extend_from_slice(b"[1ms] INFO: Boot\n");
+
+        let frames = decode_all(&mut codec, &stream);
+        assert_eq!(frames.len(), 2);
+        assert!(matches!(&frames[0], Frame::Message { payload: p, .. } if p.as_ref() == payload));
+        assert!(matches!(&frames[1], Frame::DebugLog { message, .. } if message == "Boot"));
+    }
+
+    #[test]
+    fn test_frame_reading_as_text_is_not_split() {
+        // Code byte b'1' then "hello\n": the frame prefix reads as a text line
+        let mut payload = b"hello\n".to_vec();
+        payload.resize(40, b'x');
+        let mut codec = CobsCrcCodec::default();
+        let mut encoded = Vec::new();
+        codec.encode(&payload, &mut encoded);
+        let mut stream = b"[1ms] INFO: Boot\n".to_vec();
+        stream.extend_from_slice(&encoded);
+
+        let frames = decode_all(&mut codec, &stream);
+        assert_eq!(frames.len(), 2);
+        assert!(matches!(&frames[0], Frame::DebugLog { message, .. } if message == "Boot"));
+        assert!(matches!(&frames[1], Frame::Message { payload: p, .. } if p.as_ref() == payload));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_roundtrip(payload in payload()) {
+            let mut codec = CobsCrcCodec::default();
+            let mut encoded = Vec::new();
+            codec.encode(&payload, &mut encoded);
+
+            let frames = decode_all(&mut codec, &encoded);
+            prop_assert_eq!(frames.len(), 1);
+            match &frames[0] {
+                Frame::Message { payload: decoded, .. } => prop_assert_eq!(decoded.as_ref(), &payload[..]),
+                other => prop_assert!(false, "unexpected {:?}", other),
+            }
+        }
+
+        #[test]
+        fn prop_single_bit_flip_detected(
+            payload in payload(),
+            bit in any::<prop::sample::Index>(),
+        ) {
+            let mut framed = payload.clone();
+            framed.extend_from_slice(&crc16(&payload).to_le_bytes());
+            let bit = bit.index(framed.len() * 8);
+            framed[bit / 8] ^= 1 << (bit % 8);
+
+            let mut encoded = Vec::new();
+            cobs::encode_into(&framed, &mut encoded).unwrap();
+            let frames = decode_all(&mut CobsCrcCodec::default(), &encoded);
+            prop_assert_eq!(frames.len(), 1);
+            prop_assert!(is_mismatch(&frames[0]));
+        }
+    }
+}
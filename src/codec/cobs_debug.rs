@@ -3,10 +3,39 @@
 //! Handles two types of data on the same stream:
 //! - **Protocol messages**: COBS-encoded frames terminated by 0x00
 //! - **Debug logs**: ASCII text terminated by '\n' (OC_LOG or Serial.print)
+//!
+//! A COBS frame may contain 0x0A bytes (payload, code or CRC bytes), so a
+//! `\n` only ends a line when the bytes before it read as text (UTF-8
+//! without control characters). Otherwise they belong to a frame still
+//! open, which ends at its 0x00. A `\n` with nothing before it may be the
+//! code byte of a frame, so it is kept until the next delimiter: leading
+//! `\n`s (blank lines) are dropped from a line, and from a frame that does
+//! not decode with them.
+//!
+//! A frame prefix can still read as text. With a frame check set
+//! (`set_frame_check`, e.g. a CRC), lines are held until the 0x00 or the
+//! end of the read: the frame is taken from the earliest line start whose
+//! bytes pass the check, and only the lines before it are emitted.
+//!
+//! A partial frame left unterminated for longer than the frame timeout is
+//! discarded when the next bytes arrive: a device reset mid-frame never
+//! sends the 0x00, and the stale bytes would corrupt the next frame.
+//!
+//! Trace mode (`set_trace`) logs every incoming byte with its COBS block
+//! context. It is meant for firmware developers bringing up new hardware,
+//! not for production use.
 
-use super::{cobs, oc_log, Codec, Frame};
-use crate::bridge::protocol::parse_message_name;
+use super::{cobs, Codec, Frame, FrameRef};
+use crate::logging::LogEntry;
 use bytes::BytesMut;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Maximum trace entries per second (keeps `LogStore` from flooding)
+const TRACE_MAX_PER_SEC: u32 = 100;
+
+/// Default age after which an unterminated partial frame is discarded
+const DEFAULT_FRAME_TIMEOUT: Duration = Duration::from_millis(500);
 
 /// Codec for Serial USB communication with mixed protocol/debug data
 ///
@@ -17,6 +46,59 @@ pub struct CobsDebugCodec {
     buffer: Vec<u8>,
     decode_buf: BytesMut,
     max_size: usize,
+    /// COBS frames that failed to decode (see `take_decode_errors`)
+    decode_errors: u64,
+    /// Partial frames idle for this long are discarded (zero disables)
+    frame_timeout: Duration,
+    last_byte_time: Option<Instant>,
+    /// Stale bytes discarded (see `take_discarded_partial`)
+    discarded_partial: usize,
+    /// Validates a decoded frame; when set, text lines are held (see module doc)
+    frame_check: Option<fn(&[u8]) -> bool>,
+    /// Buffer offsets just past each held line's `\n`
+    line_ends: Vec<usize>,
+    trace: Option<CobsTrace>,
+}
+
+/// Per-byte trace state
+struct CobsTrace {
+    log_tx: mpsc::Sender<LogEntry>,
+    /// Current COBS code byte (block length + 1)
+    code: u8,
+    /// Bytes left in the current block
+    remaining: u8,
+    window_start: Instant,
+    emitted_in_window: u32,
+}
+
+impl CobsTrace {
+    /// Trace the byte at `index` within the current frame
+    fn byte(&mut self, index: usize, byte: u8) {
+        if index == 0 || self.remaining == 0 {
+            // Frame start or end of block: this byte is a code byte
+            self.code = byte;
+            self.remaining = byte.saturating_sub(1);
+        } else {
+            self.remaining -= 1;
+        }
+
+        if self.window_start.elapsed() >= Duration::from_secs(1) {
+            self.window_start = Instant::now();
+            self.emitted_in_window = 0;
+        }
+        if self.emitted_in_window >= TRACE_MAX_PER_SEC {
+            return;
+        }
+        self.emitted_in_window += 1;
+
+        let _ = self.log_tx.try_send(LogEntry::debug_log(
+            None,
+            format!(
+                "COBS byte {}=0x{:02X} code={} remaining={}",
+                index, byte, self.code, self.remaining
+            ),
+        ));
+    }
 }
 
 impl CobsDebugCodec {
@@ -26,8 +108,50 @@ impl CobsDebugCodec {
             buffer: Vec::with_capacity(max_size),
             decode_buf: BytesMut::with_capacity(max_size),
             max_size,
+            decode_errors: 0,
+            frame_timeout: DEFAULT_FRAME_TIMEOUT,
+            last_byte_time: None,
+            discarded_partial: 0,
+            frame_check: None,
+            line_ends: Vec::new(),
+            trace: None,
         }
     }
+
+    /// Discard partial frames left unterminated for `timeout` (zero disables)
+    pub fn set_frame_timeout(&mut self, timeout: Duration) {
+        self.frame_timeout = timeout;
+    }
+
+    /// Discard the buffered partial frame if no byte arrived within the timeout
+    fn check_timeout(&mut self, now: Instant) {
+        if self.buffer.is_empty() || self.frame_timeout.is_zero() {
+            return;
+        }
+        let stale = self
+            .last_byte_time
+            .is_some_and(|t| now.saturating_duration_since(t) >= self.frame_timeout);
+        if stale {
+            self.discarded_partial += self.buffer.len();
+            self.buffer.clear();
+        }
+    }
+
+    /// Hold text lines until `check` tells which bytes belong to a frame
+    pub fn set_frame_check(&mut self, check: fn(&[u8]) -> bool) {
+        self.frame_check = Some(check);
+    }
+
+    /// Enable or disable per-byte trace logging to `log_tx`
+    pub fn set_trace(&mut self, enabled: bool, log_tx: mpsc::Sender<LogEntry>) {
+        self.trace = enabled.then(|| CobsTrace {
+            log_tx,
+            code: 0,
+            remaining: 0,
+            window_start: Instant::now(),
+            emitted_in_window: 0,
+        });
+    }
 }
 
 impl Default for CobsDebugCodec {
@@ -36,52 +160,171 @@ impl Default for CobsDebugCodec {
     }
 }
 
-impl Codec for CobsDebugCodec {
-    fn decode(&mut self, data: &[u8], mut on_frame: impl FnMut(Frame)) {
+impl CobsDebugCodec {
+    /// Decode incoming bytes without copying frames out of the codec
+    ///
+    /// `on_frame` receives frames borrowing the internal buffers; the
+    /// higher-ranked bound keeps those borrows from escaping the callback.
+    pub fn decode_ref(&mut self, data: &[u8], on_frame: impl for<'a> FnMut(FrameRef<'a>)) {
+        self.decode_at(data, Instant::now(), on_frame);
+    }
+
+    fn decode_at(
+        &mut self,
+        data: &[u8],
+        now: Instant,
+        mut on_frame: impl for<'a> FnMut(FrameRef<'a>),
+    ) {
+        if data.is_empty() {
+            return;
+        }
+        self.check_timeout(now);
+        self.last_byte_time = Some(now);
+
         for &byte in data {
+            if let Some(trace) = &mut self.trace {
+                trace.byte(self.buffer.len(), byte);
+            }
             self.buffer.push(byte);
 
             if byte == 0x00 {
                 // COBS frame complete (protocol message)
-                if self.buffer.len() > 1 {
-                    self.buffer.pop(); // Remove delimiter
-
-                    if cobs::decode_into(&self.buffer, &mut self.decode_buf).is_ok() {
-                        let name = parse_message_name(&self.decode_buf)
-                            .unwrap_or_else(|| "unknown".into());
-                        on_frame(Frame::Message {
-                            name,
-                            payload: self.decode_buf.clone().freeze(),
-                        });
-                    }
-                }
+                self.buffer.pop(); // Remove delimiter
+                self.end_frame(&mut on_frame);
                 self.buffer.clear();
+                self.line_ends.clear();
             } else if byte == b'\n' {
-                // Text line complete (debug log)
-                self.buffer.pop(); // Remove \n
-                if self.buffer.last() == Some(&b'\r') {
-                    self.buffer.pop(); // Remove \r
-                }
-
-                if !self.buffer.is_empty() {
-                    if let Ok(text) = std::str::from_utf8(&self.buffer) {
-                        let (level, message) = oc_log::parse(text);
-                        on_frame(Frame::DebugLog { level, message });
+                let start = self.line_ends.last().copied().unwrap_or(0);
+                let end = self.buffer.len() - 1;
+                // Text line complete (debug log), unless inside a COBS frame
+                if let Some(line) = text_line(&self.buffer[start..end]) {
+                    if self.frame_check.is_some() {
+                        self.line_ends.push(self.buffer.len());
+                    } else {
+                        if !line.is_empty() {
+                            on_frame(FrameRef::DebugLog { line });
+                        }
+                        self.buffer.clear();
                     }
                 }
-                self.buffer.clear();
             }
 
             // Prevent buffer overflow
             if self.buffer.len() > self.max_size {
                 self.buffer.clear();
+                self.line_ends.clear();
+            }
+        }
+        self.flush_lines(&mut on_frame);
+    }
+
+    /// Decode the frame in `buffer` (delimiter removed), after any held lines
+    fn end_frame(&mut self, on_frame: &mut impl for<'a> FnMut(FrameRef<'a>)) {
+        let starts: Vec<usize> = std::iter::once(0)
+            .chain(self.line_ends.iter().copied())
+            .filter(|&start| start < self.buffer.len())
+            .collect();
+        if starts.is_empty() {
+            self.flush_lines(on_frame);
+            return;
+        }
+
+        let mut chosen = None;
+        if let Some(check) = self.frame_check {
+            chosen = starts
+                .iter()
+                .copied()
+                .find(|&start| self.decode_from(start) && check(&self.decode_buf));
+        }
+        if chosen.is_none() {
+            chosen = starts
+                .iter()
+                .copied()
+                .find(|&start| self.decode_from(start));
+        }
+
+        match chosen {
+            Some(start) => {
+                self.emit_lines(start, on_frame);
+                // Decode again: a later candidate may have overwritten decode_buf
+                self.decode_from(start);
+                on_frame(FrameRef::Message {
+                    payload: &self.decode_buf,
+                });
+            }
+            None => {
+                self.flush_lines(on_frame);
+                self.decode_errors += 1;
+            }
+        }
+    }
+
+    /// COBS-decode `buffer[start..]` into `decode_buf`, retried without leading `\n`s
+    fn decode_from(&mut self, start: usize) -> bool {
+        let frame = &self.buffer[start..];
+        let blank_lines = frame.iter().take_while(|&&b| b == b'\n').count();
+        cobs::decode_into(frame, &mut self.decode_buf).is_ok()
+            || (blank_lines > 0
+                && blank_lines < frame.len()
+                && cobs::decode_into(&frame[blank_lines..], &mut self.decode_buf).is_ok())
+    }
+
+    /// Emit the held lines ending at or before `until`
+    fn emit_lines(&self, until: usize, on_frame: &mut impl for<'a> FnMut(FrameRef<'a>)) {
+        let mut start = 0;
+        for &end in self.line_ends.iter().take_while(|&&end| end <= until) {
+            if let Some(line) = text_line(&self.buffer[start..end - 1]) {
+                if !line.is_empty() {
+                    on_frame(FrameRef::DebugLog { line });
+                }
             }
+            start = end;
         }
     }
 
+    /// Emit every held line and drop it from `buffer`
+    fn flush_lines(&mut self, on_frame: &mut impl for<'a> FnMut(FrameRef<'a>)) {
+        let Some(&last) = self.line_ends.last() else {
+            return;
+        };
+        self.emit_lines(last, on_frame);
+        self.buffer.drain(..last);
+        self.line_ends.clear();
+    }
+}
+
+/// `line` (before its `\n`) if it reads as a debug text line
+///
+/// Leading blank lines and a trailing `\r` are removed. `None` for an empty
+/// buffer: that `\n` may be the first code byte of a frame.
+fn text_line(line: &[u8]) -> Option<&str> {
+    if line.is_empty() {
+        return None;
+    }
+    let start = line.iter().take_while(|&&b| b == b'\n').count();
+    let line = &line[start..];
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    std::str::from_utf8(line)
+        .ok()
+        .filter(|text| !text.chars().any(|c| c.is_control() && c != '\t'))
+}
+
+impl Codec for CobsDebugCodec {
+    fn decode(&mut self, data: &[u8], mut on_frame: impl FnMut(Frame)) {
+        self.decode_ref(data, |frame| on_frame(frame.to_frame()));
+    }
+
     fn encode(&self, payload: &[u8], output: &mut Vec<u8>) {
         let _ = cobs::encode_into(payload, output);
     }
+
+    fn take_decode_errors(&mut self) -> u64 {
+        std::mem::take(&mut self.decode_errors)
+    }
+
+    fn take_discarded_partial(&mut self) -> usize {
+        std::mem::take(&mut self.discarded_partial)
+    }
 }
 
 #[cfg(test)]
@@ -135,6 +378,73 @@ mod tests {
         assert_eq!(frame_count, 2);
     }
 
+    #[test]
+    fn test_decode_ref_borrows_payload() {
+        let mut codec = CobsDebugCodec::default();
+        let mut payloads = Vec::new();
+        let mut lines = Vec::new();
+
+        let mut data = vec![0x04, 0x01, 0x02, 0x03, 0x00];
+        data.extend_from_slice(b"[1ms] INFO: Hi\r\n");
+        codec.decode_ref(&data, |frame| match frame {
+            FrameRef::Message { payload } => payloads.push(payload.to_vec()),
+            FrameRef::DebugLog { line } => lines.push(line.to_string()),
+        });
+
+        assert_eq!(payloads, vec![vec![0x01, 0x02, 0x03]]);
+        assert_eq!(lines, vec!["[1ms] INFO: Hi".to_string()]);
+    }
+
+    #[test]
+    fn test_decode_counts_invalid_cobs_frames() {
+        let mut codec = CobsDebugCodec::default();
+        let mut frames = 0;
+
+        // Code byte claims 5 data bytes but only 1 follows
+        codec.decode(&[0x06, 0x01, 0x00, 0x02, 0x0A, 0x00], |_| frames += 1);
+
+        assert_eq!(frames, 1);
+        assert_eq!(codec.take_decode_errors(), 1);
+        assert_eq!(codec.take_decode_errors(), 0);
+    }
+
+    #[test]
+    fn test_trace_logs_each_byte() {
+        let (tx, mut rx) = mpsc::channel(256);
+        let mut codec = CobsDebugCodec::default();
+        codec.set_trace(true, tx);
+
+        let data = [0x03, 0x11, 0x12, 0x02, 0x13, 0x00];
+        codec.decode(&data, |_| {});
+
+        let mut entries = Vec::new();
+        while let Ok(entry) = rx.try_recv() {
+            entries.push(entry);
+        }
+        assert_eq!(entries.len(), data.len());
+        match &entries[3].kind {
+            crate::logging::LogKind::Debug { message, .. } => {
+                assert_eq!(message, "COBS byte 3=0x02 code=2 remaining=1")
+            }
+            _ => panic!("Expected Debug entry"),
+        }
+    }
+
+    #[test]
+    fn test_trace_is_throttled() {
+        let (tx, mut rx) = mpsc::channel(1024);
+        let mut codec = CobsDebugCodec::default();
+        codec.set_trace(true, tx);
+
+        codec.decode(&[0x01; 500], |_| {});
+
+        let mut count = 0;
+        while rx.try_recv().is_ok() {
+            count += 1;
+        }
+        assert_eq!(count, TRACE_MAX_PER_SEC as usize);
+    }
+
     #[test]
     fn test_encode() {
         let codec = CobsDebugCodec::default();
@@ -149,4 +459,31 @@ mod tests {
             assert_ne!(byte, 0x00);
         }
     }
+
+    #[test]
+    fn test_stale_partial_frame_is_discarded() {
+        let mut codec = CobsDebugCodec::default();
+        let mut payloads = Vec::new();
+        let t0 = Instant::now();
+
+        // Device reset mid-frame: no terminator
+        codec.decode_at(&[0x05, 0x01, 0x02], t0, |_| {});
+
+        // Within the timeout the bytes still belong to the same frame
+        codec.decode_at(&[0x03], t0 + Duration::from_millis(100), |_| {});
+        assert_eq!(codec.take_discarded_partial(), 0);
+
+        codec.decode_at(
+            &[0x03, 0x11, 0x12, 0x00],
+            t0 + Duration::from_millis(700),
+            |f| {
+                if let FrameRef::Message { payload } = f {
+                    payloads.push(payload.to_vec());
+                }
+            },
+        );
+        assert_eq!(payloads, vec![vec![0x11, 0x12]]);
+        assert_eq!(codec.take_discarded_partial(), 4);
+        assert_eq!(codec.take_discarded_partial(), 0);
+    }
 }
@@ -0,0 +1,258 @@
+//! Length-prefix framing codec
+//!
+//! Simpler alternative to COBS for devices that frame messages with a
+//! big-endian length header instead of byte stuffing:
+//!
+//! ```text
+//! [len (HEADER_BYTES, big-endian)] [payload (len bytes)]
+//! ```
+//!
+//! Debug logs (newline-terminated ASCII) can share the stream. At a frame
+//! boundary, a printable ASCII byte starts a text line: a header beginning
+//! with such a byte would announce a frame far larger than `max_size`, so
+//! the two cannot be confused.
+
+use super::{oc_log, Codec, Frame};
+use crate::bridge::protocol::parse_message_name;
+use bytes::Bytes;
+
+/// Codec for length-prefixed frames with a `HEADER_BYTES` big-endian header
+///
+/// `HEADER_BYTES` must be 2 or 4 (checked at compile time).
+pub struct FramedLengthCodec<const HEADER_BYTES: usize> {
+    buffer: Vec<u8>,
+    max_size: usize,
+}
+
+/// 2-byte length prefix (payloads up to 65535 bytes)
+pub type FramedLength2Codec = FramedLengthCodec<2>;
+
+/// 4-byte length prefix
+pub type FramedLength4Codec = FramedLengthCodec<4>;
+
+impl<const HEADER_BYTES: usize> FramedLengthCodec<HEADER_BYTES> {
+    const VALID_HEADER: () = assert!(
+        HEADER_BYTES == 2 || HEADER_BYTES == 4,
+        "HEADER_BYTES must be 2 or 4"
+    );
+
+    /// Create a new codec accepting payloads up to `max_size` bytes
+    pub fn new(max_size: usize) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_HEADER;
+        Self {
+            buffer: Vec::with_capacity(max_size + HEADER_BYTES),
+            max_size,
+        }
+    }
+
+    /// Largest payload length representable by the header
+    fn max_encodable() -> usize {
+        if HEADER_BYTES == 2 {
+            u16::MAX as usize
+        } else {
+            u32::MAX as usize
+        }
+    }
+
+    fn read_len(header: &[u8]) -> usize {
+        header[..HEADER_BYTES]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize)
+    }
+
+    /// Try to extract one frame or text line from the front of the buffer
+    ///
+    /// Returns `false` when more data is needed.
+    fn next_frame(&mut self, on_frame: &mut impl FnMut(Frame)) -> bool {
+        let Some(&first) = self.buffer.first() else {
+            return false;
+        };
+
+        if is_text_start(first) {
+            let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') else {
+                if self.buffer.len() > self.max_size {
+                    self.buffer.clear();
+                }
+                return false;
+            };
+
+            let mut end = pos;
+            if end > 0 && self.buffer[end - 1] == b'\r' {
+                end -= 1;
+            }
+            if let Ok(text) = std::str::from_utf8(&self.buffer[..end]) {
+                let (level, message) = oc_log::parse(text);
+                on_frame(Frame::DebugLog { level, message });
+            }
+            self.buffer.drain(..=pos);
+            return true;
+        }
+
+        if self.buffer.len() < HEADER_BYTES {
+            return false;
+        }
+
+        let len = Self::read_len(&self.buffer);
+        if len > self.max_size {
+            // Corrupt header: drop one byte and resynchronize
+            self.buffer.drain(..1);
+            return true;
+        }
+
+        let end = HEADER_BYTES + len;
+        if self.buffer.len() < end {
+            return false;
+        }
+
+        if len > 0 {
+            let payload = Bytes::copy_from_slice(&self.buffer[HEADER_BYTES..end]);
+            let name = parse_message_name(&payload).unwrap_or_else(|| "unknown".into());
+            on_frame(Frame::Message { name, payload });
+        }
+        self.buffer.drain(..end);
+        true
+    }
+}
+
+impl<const HEADER_BYTES: usize> Default for FramedLengthCodec<HEADER_BYTES> {
+    fn default() -> Self {
+        Self::new(4096)
+    }
+}
+
+impl<const HEADER_BYTES: usize> Codec for FramedLengthCodec<HEADER_BYTES> {
+    fn decode(&mut self, data: &[u8], mut on_frame: impl FnMut(Frame)) {
+        self.buffer.extend_from_slice(data);
+        while self.next_frame(&mut on_frame) {}
+    }
+
+    fn encode(&self, payload: &[u8], output: &mut Vec<u8>) {
+        output.clear();
+        if payload.len() > Self::max_encodable() {
+            return;
+        }
+        let len = payload.len().to_be_bytes();
+        output.extend_from_slice(&len[len.len() - HEADER_BYTES..]);
+        output.extend_from_slice(payload);
+    }
+}
+
+/// Printable ASCII starts a debug text line at a frame boundary
+fn is_text_start(byte: u8) -> bool {
+    (0x20..=0x7E).contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::LogLevel;
+    use proptest::prelude::*;
+
+    fn payloads(frames: &[Frame]) -> Vec<Vec<u8>> {
+        frames
+            .iter()
+            .filter_map(|f| match f {
+                Frame::Message { payload, .. } => Some(payload.to_vec()),
+                Frame::DebugLog { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_header() {
+        let mut output = Vec::new();
+        FramedLength2Codec::default().encode(&[0xAA, 0xBB, 0xCC], &mut output);
+        assert_eq!(output, vec![0x00, 0x03, 0xAA, 0xBB, 0xCC]);
+
+        FramedLength4Codec::default().encode(&[0xAA], &mut output);
+        assert_eq!(output, vec![0x00, 0x00, 0x00, 0x01, 0xAA]);
+    }
+
+    #[test]
+    fn test_decode_split_frame() {
+        let mut codec = FramedLength2Codec::default();
+        let mut frames = Vec::new();
+
+        codec.decode(&[0x00], |f| frames.push(f));
+        codec.decode(&[0x02, 0x01], |f| frames.push(f));
+        assert!(frames.is_empty());
+
+        codec.decode(&[0x02], |f| frames.push(f));
+        assert_eq!(payloads(&frames), vec![vec![0x01, 0x02]]);
+    }
+
+    #[test]
+    fn test_decode_debug_line_between_frames() {
+        let mut codec = FramedLength2Codec::default();
+        let mut frames = Vec::new();
+
+        let mut data = vec![0x00, 0x01, 0x10];
+        data.extend_from_slice(b"[5ms] WARN: Hot\r\n");
+        data.extend_from_slice(&[0x00, 0x01, 0x20]);
+        codec.decode(&data, |f| frames.push(f));
+
+        assert_eq!(frames.len(), 3);
+        if let Frame::DebugLog { level, message } = &frames[1] {
+            assert_eq!(*level, Some(LogLevel::Warn));
+            assert_eq!(message, "Hot");
+        } else {
+            panic!("Expected DebugLog frame");
+        }
+        assert_eq!(payloads(&frames), vec![vec![0x10], vec![0x20]]);
+    }
+
+    #[test]
+    fn test_decode_oversized_header_resyncs() {
+        let mut codec = FramedLength2Codec::new(16);
+        let mut frames = Vec::new();
+
+        // 0x1F00 > max_size: dropped byte by byte until a valid frame appears
+        codec.decode(&[0x1F, 0x00, 0x01, 0x42], |f| frames.push(f));
+        assert_eq!(payloads(&frames), vec![vec![0x42]]);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_roundtrip_2(msgs in prop::collection::vec(prop::collection::vec(any::<u8>(), 1..512), 1..16)) {
+            let mut codec = FramedLength2Codec::default();
+            let mut stream = Vec::new();
+            let mut buf = Vec::new();
+            for m in &msgs {
+                codec.encode(m, &mut buf);
+                stream.extend_from_slice(&buf);
+            }
+
+            let mut frames = Vec::new();
+            for chunk in stream.chunks(7) {
+                codec.decode(chunk, |f| frames.push(f));
+            }
+            prop_assert_eq!(payloads(&frames), msgs);
+        }
+
+        #[test]
+        fn prop_roundtrip_4(msgs in prop::collection::vec(prop::collection::vec(any::<u8>(), 1..512), 1..16)) {
+            let mut codec = FramedLength4Codec::default();
+            let mut stream = Vec::new();
+            let mut buf = Vec::new();
+            for m in &msgs {
+                codec.encode(m, &mut buf);
+                stream.extend_from_slice(&buf);
+            }
+
+            let mut frames = Vec::new();
+            codec.decode(&stream, |f| frames.push(f));
+            prop_assert_eq!(payloads(&frames), msgs);
+        }
+
+        #[test]
+        fn fuzz_decode_never_panics(data in prop::collection::vec(any::<u8>(), 0..8192)) {
+            let mut codec2 = FramedLength2Codec::new(256);
+            let mut codec4 = FramedLength4Codec::new(256);
+            codec2.decode(&data, |_| {});
+            codec4.decode(&data, |_| {});
+            prop_assert!(codec2.buffer.len() <= 256 + 2);
+            prop_assert!(codec4.buffer.len() <= 256 + 4);
+        }
+    }
+}
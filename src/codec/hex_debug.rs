@@ -0,0 +1,201 @@
+//! Hex line codec for firmware bring-up
+//!
+//! Frames are hex-encoded ASCII lines, readable and typeable in `screen` or
+//! `minicom` before the COBS firmware library is integrated:
+//!
+//! ```text
+//! 0102030405\n        -> Frame::Message [01 02 03 04 05]
+//! # [5ms] INFO: boot  -> Frame::DebugLog
+//! ```
+//!
+//! Whitespace between digits and a trailing `\r` are ignored. Twice the
+//! bytes of COBS on the wire, so meant for debugging only.
+
+use super::{oc_log, Codec, Frame};
+use crate::bridge::protocol::parse_message_name;
+use bytes::Bytes;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Codec for newline-terminated hex lines, `#` lines being debug comments
+pub struct HexCodec {
+    line: Vec<u8>,
+    max_size: usize,
+    decode_errors: u64,
+}
+
+impl HexCodec {
+    /// Create a new codec accepting payloads up to `max_size` bytes
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            line: Vec::with_capacity(max_size * 2),
+            max_size,
+            decode_errors: 0,
+        }
+    }
+
+    /// Longest line kept while waiting for its terminator (digits + spacing)
+    fn max_line(&self) -> usize {
+        self.max_size * 3 + 2
+    }
+
+    fn process_line(&mut self, on_frame: &mut impl FnMut(Frame)) {
+        let text = String::from_utf8_lossy(&self.line);
+        let text = text.trim();
+
+        if let Some(comment) = text.strip_prefix('#') {
+            let (level, message) = oc_log::parse(comment.trim_start());
+            on_frame(Frame::DebugLog { level, message });
+        } else if !text.is_empty() {
+            match decode_hex(text) {
+                Some(payload) if payload.len() <= self.max_size => {
+                    let name = parse_message_name(&payload).unwrap_or_else(|| "unknown".into());
+                    on_frame(Frame::Message {
+                        name,
+                        payload: Bytes::from(payload),
+                    });
+                }
+                _ => self.decode_errors += 1,
+            }
+        }
+        self.line.clear();
+    }
+}
+
+impl Default for HexCodec {
+    fn default() -> Self {
+        Self::new(4096)
+    }
+}
+
+impl Codec for HexCodec {
+    fn decode(&mut self, data: &[u8], mut on_frame: impl FnMut(Frame)) {
+        for &byte in data {
+            if byte == b'\n' {
+                self.process_line(&mut on_frame);
+            } else if self.line.len() < self.max_line() {
+                self.line.push(byte);
+            } else {
+                // Runaway line: drop it and resynchronize on the next newline
+                self.line.clear();
+                self.decode_errors += 1;
+            }
+        }
+    }
+
+    fn encode(&self, payload: &[u8], output: &mut Vec<u8>) {
+        output.clear();
+        output.reserve(payload.len() * 2 + 1);
+        for &byte in payload {
+            output.push(HEX_DIGITS[(byte >> 4) as usize]);
+            output.push(HEX_DIGITS[(byte & 0x0F) as usize]);
+        }
+        output.push(b'\n');
+    }
+
+    fn take_decode_errors(&mut self) -> u64 {
+        std::mem::take(&mut self.decode_errors)
+    }
+}
+
+/// Decode hex digits (either case), skipping whitespace; None if malformed
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .map(|b| (b as char).to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0] << 4) | pair[1])
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::LogLevel;
+    use proptest::prelude::*;
+
+    fn payloads(frames: &[Frame]) -> Vec<Vec<u8>> {
+        frames
+            .iter()
+            .filter_map(|f| match f {
+                Frame::Message { payload, .. } => Some(payload.to_vec()),
+                Frame::DebugLog { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_uppercase_line() {
+        let mut output = Vec::new();
+        HexCodec::default().encode(&[0x01, 0xAB, 0xff], &mut output);
+        assert_eq!(output, b"01ABFF\n");
+    }
+
+    #[test]
+    fn test_decode_lines_and_comments() {
+        let mut codec = HexCodec::default();
+        let mut frames = Vec::new();
+
+        codec.decode(b"0102", |f| frames.push(f));
+        assert!(frames.is_empty());
+        codec.decode(b"0304\r\n# [5ms] WARN: Hot\n\nab cd\n", |f| frames.push(f));
+
+        assert_eq!(frames.len(), 3);
+        if let Frame::DebugLog { level, message } = &frames[1] {
+            assert_eq!(*level, Some(LogLevel::Warn));
+            assert_eq!(message, "Hot");
+        } else {
+            panic!("Expected DebugLog frame");
+        }
+        assert_eq!(
+            payloads(&frames),
+            vec![vec![0x01, 0x02, 0x03, 0x04], vec![0xAB, 0xCD]]
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed_lines_are_counted() {
+        let mut codec = HexCodec::new(2);
+        let mut frames = Vec::new();
+
+        codec.decode(b"123\nzz\n010203\n11\n", |f| frames.push(f));
+
+        assert_eq!(payloads(&frames), vec![vec![0x11]]);
+        assert_eq!(codec.take_decode_errors(), 3);
+        assert_eq!(codec.take_decode_errors(), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_roundtrip(msgs in prop::collection::vec(prop::collection::vec(any::<u8>(), 1..512), 1..16)) {
+            let mut codec = HexCodec::default();
+            let mut stream = Vec::new();
+            let mut buf = Vec::new();
+            for m in &msgs {
+                codec.encode(m, &mut buf);
+                stream.extend_from_slice(&buf);
+            }
+
+            let mut frames = Vec::new();
+            for chunk in stream.chunks(7) {
+                codec.decode(chunk, |f| frames.push(f));
+            }
+            prop_assert_eq!(payloads(&frames), msgs);
+        }
+
+        #[test]
+        fn fuzz_decode_never_panics(data in prop::collection::vec(any::<u8>(), 0..8192)) {
+            let mut codec = HexCodec::new(256);
+            codec.decode(&data, |_| {});
+            prop_assert!(codec.line.len() <= codec.max_line());
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Length-prefix codec for stream transports (TCP, UNIX socket)
+//!
+//! Stream sockets need no zero-byte elimination, only message boundaries:
+//!
+//! ```text
+//! [len (PrefixSize)] [payload (len bytes)]
+//! ```
+//!
+//! Unlike `FramedLengthCodec`, the stream is binary only (no debug text
+//! lines), so any length up to `max_size` is accepted whatever its first
+//! byte. Decoding is a two-state machine, so frames split across reads are
+//! reassembled without rescanning the buffered bytes.
+
+use super::{Codec, Frame, FrameRef};
+
+/// Width and byte order of the length prefix
+///
+/// The bridge's TCP and UNIX transports use `U16Be`.
+#[allow(dead_code)] // Other widths for controllers with their own framing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixSize {
+    U16Le,
+    U16Be,
+    U32Le,
+    U32Be,
+}
+
+impl PrefixSize {
+    /// Prefix length in bytes
+    pub fn bytes(self) -> usize {
+        match self {
+            Self::U16Le | Self::U16Be => 2,
+            Self::U32Le | Self::U32Be => 4,
+        }
+    }
+
+    /// Largest payload length the prefix can carry
+    fn max_len(self) -> usize {
+        match self {
+            Self::U16Le | Self::U16Be => u16::MAX as usize,
+            Self::U32Le | Self::U32Be => u32::MAX as usize,
+        }
+    }
+
+    /// Payload length from a complete prefix
+    fn read(self, prefix: &[u8]) -> usize {
+        match self {
+            Self::U16Le => u16::from_le_bytes([prefix[0], prefix[1]]) as usize,
+            Self::U16Be => u16::from_be_bytes([prefix[0], prefix[1]]) as usize,
+            Self::U32Le => {
+                u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize
+            }
+            Self::U32Be => {
+                u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize
+            }
+        }
+    }
+
+    /// Append the prefix for `len` (at most `max_len`) to `output`
+    fn write(self, len: usize, output: &mut Vec<u8>) {
+        match self {
+            Self::U16Le => output.extend_from_slice(&(len as u16).to_le_bytes()),
+            Self::U16Be => output.extend_from_slice(&(len as u16).to_be_bytes()),
+            Self::U32Le => output.extend_from_slice(&(len as u32).to_le_bytes()),
+            Self::U32Be => output.extend_from_slice(&(len as u32).to_be_bytes()),
+        }
+    }
+}
+
+/// Decoder position in the stream
+enum State {
+    /// Collecting prefix bytes into `LengthPrefixCodec::prefix`
+    WaitingForLength,
+    /// Collecting the payload announced by the prefix
+    ReadingPayload { remaining: usize, buf: Vec<u8> },
+}
+
+/// Codec for `[len][payload]` frames on a binary byte stream
+pub struct LengthPrefixCodec {
+    prefix_size: PrefixSize,
+    max_size: usize,
+    state: State,
+    /// Prefix bytes received so far
+    prefix: Vec<u8>,
+    /// Prefixes announcing more than `max_size` (see `take_decode_errors`)
+    decode_errors: u64,
+}
+
+impl LengthPrefixCodec {
+    /// Create a codec accepting payloads up to `max_size` bytes
+    pub fn new(prefix_size: PrefixSize, max_size: usize) -> Self {
+        Self {
+            prefix_size,
+            max_size,
+            state: State::WaitingForLength,
+            prefix: Vec::with_capacity(prefix_size.bytes()),
+            decode_errors: 0,
+        }
+    }
+}
+
+impl Codec for LengthPrefixCodec {
+    fn decode(&mut self, mut data: &[u8], mut on_frame: impl FnMut(Frame)) {
+        while !data.is_empty() {
+            match &mut self.state {
+                State::WaitingForLength => {
+                    let take = (self.prefix_size.bytes() - self.prefix.len()).min(data.len());
+                    self.prefix.extend_from_slice(&data[..take]);
+                    data = &data[take..];
+                    if self.prefix.len() < self.prefix_size.bytes() {
+                        continue;
+                    }
+
+                    let len = self.prefix_size.read(&self.prefix);
+                    if len > self.max_size {
+                        // Corrupt prefix: drop one byte and resynchronize
+                        self.decode_errors += 1;
+                        self.prefix.remove(0);
+                        continue;
+                    }
+                    self.prefix.clear();
+                    if len > 0 {
+                        self.state = State::ReadingPayload {
+                            remaining: len,
+                            buf: Vec::with_capacity(len),
+                        };
+                    }
+                }
+                State::ReadingPayload { remaining, buf } => {
+                    let take = (*remaining).min(data.len());
+                    buf.extend_from_slice(&data[..take]);
+                    data = &data[take..];
+                    *remaining -= take;
+                    if *remaining == 0 {
+                        let payload = std::mem::take(buf);
+                        self.state = State::WaitingForLength;
+                        on_frame(FrameRef::Message { payload: &payload }.to_frame());
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode(&self, payload: &[u8], output: &mut Vec<u8>) {
+        output.clear();
+        if payload.len() > self.prefix_size.max_len() {
+            return;
+        }
+        self.prefix_size.write(payload.len(), output);
+        output.extend_from_slice(payload);
+    }
+
+    fn take_decode_errors(&mut self) -> u64 {
+        std::mem::take(&mut self.decode_errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_SIZES: [PrefixSize; 4] = [
+        PrefixSize::U16Le,
+        PrefixSize::U16Be,
+        PrefixSize::U32Le,
+        PrefixSize::U32Be,
+    ];
+
+    fn payloads(frames: &[Frame]) -> Vec<Vec<u8>> {
+        frames
+            .iter()
+            .filter_map(|f| match f {
+                Frame::Message { payload, .. } => Some(payload.to_vec()),
+                Frame::DebugLog { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_prefix() {
+        let mut output = Vec::new();
+        let cases: [(PrefixSize, &[u8]); 4] = [
+            (PrefixSize::U16Le, &[0x03, 0x00]),
+            (PrefixSize::U16Be, &[0x00, 0x03]),
+            (PrefixSize::U32Le, &[0x03, 0x00, 0x00, 0x00]),
+            (PrefixSize::U32Be, &[0x00, 0x00, 0x00, 0x03]),
+        ];
+        for (size, prefix) in cases {
+            LengthPrefixCodec::new(size, 16).encode(&[0xAA, 0xBB, 0xCC], &mut output);
+            assert_eq!(&output[..size.bytes()], prefix, "{:?}", size);
+            assert_eq!(&output[size.bytes()..], &[0xAA, 0xBB, 0xCC]);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_each_prefix_size() {
+        let messages = [b"\x02\x01hello".to_vec(), vec![0x0A; 300], vec![0x00]];
+        for size in ALL_SIZES {
+            let mut codec = LengthPrefixCodec::new(size, 1024);
+            let mut stream = Vec::new();
+            let mut buf = Vec::new();
+            for m in &messages {
+                codec.encode(m, &mut buf);
+                stream.extend_from_slice(&buf);
+            }
+
+            let mut frames = Vec::new();
+            codec.decode(&stream, |f| frames.push(f));
+            assert_eq!(payloads(&frames), messages, "{:?}", size);
+        }
+    }
+
+    #[test]
+    fn test_decode_split_across_calls() {
+        for size in ALL_SIZES {
+            let mut codec = LengthPrefixCodec::new(size, 1024);
+            let (mut first, mut second) = (Vec::new(), Vec::new());
+            codec.encode(&[1, 2, 3, 4, 5], &mut first);
+            codec.encode(&[6, 7], &mut second);
+            first.extend_from_slice(&second);
+
+            // One byte per read: the prefix itself arrives in pieces
+            let mut frames = Vec::new();
+            for byte in &first {
+                codec.decode(std::slice::from_ref(byte), |f| frames.push(f));
+            }
+            assert_eq!(
+                payloads(&frames),
+                vec![vec![1, 2, 3, 4, 5], vec![6, 7]],
+                "{:?}",
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_oversized_prefix_resyncs() {
+        let mut codec = LengthPrefixCodec::new(PrefixSize::U16Be, 16);
+        let mut frames = Vec::new();
+
+        // 0x1F00 > max_size: dropped byte by byte until a valid frame appears
+        codec.decode(&[0x1F, 0x00, 0x01, 0x42], |f| frames.push(f));
+        assert_eq!(payloads(&frames), vec![vec![0x42]]);
+        assert_eq!(codec.take_decode_errors(), 1);
+    }
+}
@@ -1,7 +1,7 @@
 //! Codec abstraction for message encoding/decoding
 //!
 //! Separates encoding concerns from transport:
-//! - **Codec**: How messages are encoded/decoded (COBS, Raw, etc.)
+//! - **Codec**: How messages are encoded/decoded (COBS, length prefix, hex lines, Raw, etc.)
 //! - **Transport**: How bytes flow (Serial, UDP, etc.)
 //!
 //! # Adding a new codec
@@ -11,14 +11,29 @@
 //! 3. Add `pub mod my_codec;` here
 //! 4. No other changes needed
 
+pub mod chained;
 pub mod cobs;
+pub mod cobs_crc;
 pub mod cobs_debug;
+pub mod framed_length;
+pub mod hex_debug;
+pub mod length_prefix;
 mod oc_log;
+pub mod pipeline;
 pub mod raw;
+pub mod strip_header;
 
+pub use chained::ChainedCodec;
+pub use cobs_crc::CobsCrcCodec;
 pub use cobs_debug::CobsDebugCodec;
+pub use framed_length::{FramedLength2Codec, FramedLength4Codec};
+pub use hex_debug::HexCodec;
+pub use length_prefix::{LengthPrefixCodec, PrefixSize};
+pub use pipeline::PipelineCodec;
 pub use raw::RawCodec;
+pub use strip_header::StripHeaderCodec;
 
+use crate::bridge::protocol::parse_message_name;
 use crate::logging::LogLevel;
 use bytes::Bytes;
 
@@ -41,6 +56,34 @@ pub enum Frame {
     },
 }
 
+/// Decoded frame borrowing from the codec's internal buffers
+///
+/// Only valid inside the decode callback: the borrow ends when the callback
+/// returns, so frames that must outlive it are converted with `to_frame`.
+#[derive(Debug, Clone, Copy)]
+pub enum FrameRef<'a> {
+    /// Protocol message payload
+    Message { payload: &'a [u8] },
+    /// Debug log line (unparsed, without the line terminator)
+    DebugLog { line: &'a str },
+}
+
+impl FrameRef<'_> {
+    /// Copy into an owned `Frame` (parses message name and log level)
+    pub fn to_frame(self) -> Frame {
+        match self {
+            FrameRef::Message { payload } => Frame::Message {
+                name: parse_message_name(payload).unwrap_or_else(|| "unknown".into()),
+                payload: Bytes::copy_from_slice(payload),
+            },
+            FrameRef::DebugLog { line } => {
+                let (level, message) = oc_log::parse(line);
+                Frame::DebugLog { level, message }
+            }
+        }
+    }
+}
+
 /// Codec trait for encoding/decoding messages
 ///
 /// A codec transforms raw bytes into structured frames (decode)
@@ -56,4 +99,37 @@ pub trait Codec: Send {
     ///
     /// Writes encoded bytes to `output`.
     fn encode(&self, payload: &[u8], output: &mut Vec<u8>);
+
+    /// Encode several payloads back to back into `output` (one write)
+    ///
+    /// Only meaningful for self-delimiting codecs (COBS, length prefix):
+    /// the decoder splits the concatenated frames again.
+    fn encode_batch(&self, payloads: &[Bytes], output: &mut Vec<u8>) {
+        output.clear();
+        let mut frame = Vec::new();
+        for payload in payloads {
+            frame.clear();
+            self.encode(payload, &mut frame);
+            output.extend_from_slice(&frame);
+        }
+    }
+
+    /// Number of malformed frames dropped since the last call (resets the count)
+    fn take_decode_errors(&mut self) -> u64 {
+        0
+    }
+
+    /// Bytes of stale partial frames discarded since the last call (resets the count)
+    fn take_discarded_partial(&mut self) -> usize {
+        0
+    }
+
+    /// Layer `outer` on top of this codec (see `ChainedCodec`)
+    #[allow(dead_code)]
+    fn chain<O: Codec>(self, outer: O) -> ChainedCodec<Self, O>
+    where
+        Self: Sized,
+    {
+        ChainedCodec::new(self, outer)
+    }
 }
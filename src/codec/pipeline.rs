@@ -0,0 +1,181 @@
+//! Codec pipelines built from config (`codec_pipeline`)
+//!
+//! `Codec` is generic over its frame callback, so codecs picked at runtime
+//! cannot be boxed as trait objects. `PipelineCodec` is the closed set of
+//! stages that can be named in the config, chained with `ChainedCodec`:
+//!
+//! ```toml
+//! codec_pipeline = ["cobs", "strip_header:4"]
+//! ```
+//!
+//! The first stage faces the wire: on receive it decodes first and the next
+//! stage decodes its message payloads; on send the stages run in reverse.
+
+use super::{ChainedCodec, CobsCrcCodec, CobsDebugCodec, Codec, Frame, RawCodec, StripHeaderCodec};
+use crate::error::{BridgeError, Result};
+
+/// Chain `b` after `a`: `a` decodes the wire bytes, `b` their payloads
+pub fn pipeline<A: Codec, B: Codec>(a: A, b: B) -> ChainedCodec<A, B> {
+    ChainedCodec::new(a, b)
+}
+
+/// One named stage, or a chain of them
+pub enum PipelineCodec {
+    Cobs(CobsDebugCodec),
+    CobsCrc(CobsCrcCodec),
+    Raw(RawCodec),
+    StripHeader(StripHeaderCodec),
+    Chain(Box<ChainedCodec<PipelineCodec, PipelineCodec>>),
+}
+
+impl PipelineCodec {
+    /// Build the pipeline for `names`, wire side first
+    ///
+    /// Names: `cobs`, `cobs_crc`, `raw`, `strip_header:<bytes>`.
+    /// `make_cobs` creates the COBS stages (frame timeout, trace).
+    pub fn from_names(names: &[String], make_cobs: impl Fn() -> CobsDebugCodec) -> Result<Self> {
+        let mut stages = names.iter().map(|name| stage(name, &make_cobs));
+        let first = stages
+            .next()
+            .ok_or_else(|| BridgeError::ConfigValidation {
+                field: "codec_pipeline",
+                reason: "no codec listed".to_string(),
+            })??;
+        stages.try_fold(first, |codec, next| {
+            Ok(Self::Chain(Box::new(pipeline(codec, next?))))
+        })
+    }
+
+    /// `decode` with the callback type erased, so nested chains do not
+    /// instantiate a new closure type per level
+    fn decode_dyn(&mut self, data: &[u8], on_frame: &mut dyn FnMut(Frame)) {
+        match self {
+            Self::Cobs(codec) => codec.decode(data, on_frame),
+            Self::CobsCrc(codec) => codec.decode(data, on_frame),
+            Self::Raw(codec) => codec.decode(data, on_frame),
+            Self::StripHeader(codec) => codec.decode(data, on_frame),
+            Self::Chain(codec) => codec.decode(data, on_frame),
+        }
+    }
+}
+
+fn stage(name: &str, make_cobs: &impl Fn() -> CobsDebugCodec) -> Result<PipelineCodec> {
+    let invalid = |reason: String| BridgeError::ConfigValidation {
+        field: "codec_pipeline",
+        reason,
+    };
+    Ok(match name.trim() {
+        "cobs" => PipelineCodec::Cobs(make_cobs()),
+        "cobs_crc" => PipelineCodec::CobsCrc(CobsCrcCodec::new(make_cobs())),
+        "raw" => PipelineCodec::Raw(RawCodec),
+        other => {
+            let len = other
+                .strip_prefix("strip_header:")
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "unknown codec '{}' (cobs, cobs_crc, raw, strip_header:<bytes>)",
+                        other
+                    ))
+                })?
+                .parse()
+                .map_err(|_| invalid(format!("'{}': header size must be a number", other)))?;
+            PipelineCodec::StripHeader(StripHeaderCodec::new(len))
+        }
+    })
+}
+
+impl Codec for PipelineCodec {
+    fn decode(&mut self, data: &[u8], mut on_frame: impl FnMut(Frame)) {
+        self.decode_dyn(data, &mut on_frame);
+    }
+
+    fn encode(&self, payload: &[u8], output: &mut Vec<u8>) {
+        match self {
+            Self::Cobs(codec) => codec.encode(payload, output),
+            Self::CobsCrc(codec) => codec.encode(payload, output),
+            Self::Raw(codec) => codec.encode(payload, output),
+            Self::StripHeader(codec) => codec.encode(payload, output),
+            Self::Chain(codec) => codec.encode(payload, output),
+        }
+    }
+
+    fn take_decode_errors(&mut self) -> u64 {
+        match self {
+            Self::Cobs(codec) => codec.take_decode_errors(),
+            Self::CobsCrc(codec) => codec.take_decode_errors(),
+            Self::Raw(codec) => codec.take_decode_errors(),
+            Self::StripHeader(codec) => codec.take_decode_errors(),
+            Self::Chain(codec) => codec.take_decode_errors(),
+        }
+    }
+
+    fn take_discarded_partial(&mut self) -> usize {
+        match self {
+            Self::Cobs(codec) => codec.take_discarded_partial(),
+            Self::CobsCrc(codec) => codec.take_discarded_partial(),
+            Self::Raw(codec) => codec.take_discarded_partial(),
+            Self::StripHeader(codec) => codec.take_discarded_partial(),
+            Self::Chain(codec) => codec.take_discarded_partial(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(codec: &mut impl Codec, data: &[u8]) -> Vec<Vec<u8>> {
+        let mut payloads = Vec::new();
+        codec.decode(data, |f| {
+            if let Frame::Message { payload, .. } = f {
+                payloads.push(payload.to_vec());
+            }
+        });
+        payloads
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_raw_pipeline_matches_raw() {
+        let mut raw = RawCodec;
+        let mut chained = pipeline(RawCodec, RawCodec);
+        for data in [&b""[..], &[0x01], &[0x02, 0x01, 0x00, 0xFF]] {
+            assert_eq!(decode_all(&mut chained, data), decode_all(&mut raw, data));
+
+            let (mut expected, mut output) = (Vec::new(), Vec::new());
+            raw.encode(data, &mut expected);
+            chained.encode(data, &mut output);
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn test_from_names_cobs_then_strip_header() {
+        let mut codec =
+            PipelineCodec::from_names(&names(&["cobs", "strip_header:2"]), CobsDebugCodec::default)
+                .unwrap();
+
+        let mut wire = Vec::new();
+        codec.encode(&[0x05, 0x06], &mut wire);
+        // Header added before COBS encoding
+        assert_eq!(wire, vec![0x01, 0x01, 0x03, 0x05, 0x06, 0x00]);
+        assert_eq!(decode_all(&mut codec, &wire), vec![vec![0x05, 0x06]]);
+    }
+
+    #[test]
+    fn test_from_names_rejects_unknown() {
+        for bad in [&[][..], &["cobs", "zstd"], &["strip_header:x"]] {
+            let result = PipelineCodec::from_names(&names(bad), CobsDebugCodec::default);
+            assert!(matches!(
+                result,
+                Err(BridgeError::ConfigValidation {
+                    field: "codec_pipeline",
+                    ..
+                })
+            ));
+        }
+    }
+}
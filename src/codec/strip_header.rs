@@ -0,0 +1,71 @@
+//! Fixed-size header codec
+//!
+//! For devices that put a vendor header (sequence number, address, ...) in
+//! front of each message: `decode` drops the first `len` bytes of every
+//! chunk it is given, `encode` puts `len` header bytes back.
+//!
+//! Chunks are not reassembled, so on a byte stream it belongs after a
+//! framing codec (`codec_pipeline = ["cobs", "strip_header:4"]`); on a
+//! datagram transport it can come first.
+
+use super::{Codec, Frame, FrameRef};
+
+/// Drops a `len`-byte header on decode, adds one on encode
+pub struct StripHeaderCodec {
+    header: Vec<u8>,
+    /// Chunks shorter than the header (see `take_decode_errors`)
+    decode_errors: u64,
+}
+
+impl StripHeaderCodec {
+    /// Strip `len` bytes; encoded messages get a zeroed header
+    pub fn new(len: usize) -> Self {
+        Self {
+            header: vec![0; len],
+            decode_errors: 0,
+        }
+    }
+}
+
+impl Codec for StripHeaderCodec {
+    fn decode(&mut self, data: &[u8], mut on_frame: impl FnMut(Frame)) {
+        match data.get(self.header.len()..) {
+            Some([]) => {}
+            Some(payload) => on_frame(FrameRef::Message { payload }.to_frame()),
+            None => self.decode_errors += 1,
+        }
+    }
+
+    fn encode(&self, payload: &[u8], output: &mut Vec<u8>) {
+        output.clear();
+        output.extend_from_slice(&self.header);
+        output.extend_from_slice(payload);
+    }
+
+    fn take_decode_errors(&mut self) -> u64 {
+        std::mem::take(&mut self.decode_errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_and_restore_header() {
+        let mut codec = StripHeaderCodec::new(2);
+        let mut payloads = Vec::new();
+        codec.decode(&[0x01, 0x02, 0x03, 0x04], |f| {
+            if let Frame::Message { payload, .. } = f {
+                payloads.push(payload.to_vec());
+            }
+        });
+        codec.decode(&[0x01], |_| panic!("short chunk decoded"));
+        assert_eq!(payloads, vec![vec![0x03, 0x04]]);
+        assert_eq!(codec.take_decode_errors(), 1);
+
+        let mut output = Vec::new();
+        codec.encode(&[0x03, 0x04], &mut output);
+        assert_eq!(output, vec![0x00, 0x00, 0x03, 0x04]);
+    }
+}
@@ -3,20 +3,50 @@
 //! Config file is stored in a per-user config directory as `config.toml`.
 //! Device presets are stored alongside it in `devices/*.toml`.
 //!
+//! The main config may pull `[device_presets]` tables from other files:
+//!
+//! ```toml
+//! include = ["devices/*.toml"]
+//! ```
+//!
+//! Patterns are relative to the including file. Keys already present in the
+//! including file win over included ones.
+//!
 //! Rationale:
 //! - keeps config stable across app upgrades (binary path changes)
 //! - avoids collisions between multiple installs
 //! - matches standard platform conventions
+//!
+//! `OC_BRIDGE_*` environment variables override `[bridge]` and `[logs]`
+//! fields after loading (see `env`).
+//!
+//! The TUI follows edits through `ConfigWatcher`. Fields marked
+//! "Hot-reloadable" take effect right away; other bridge settings only
+//! apply when the daemon restarts.
+
+mod env;
+mod watcher;
+
+use env::apply_env_overrides;
+pub use watcher::ConfigWatcher;
 
+#[cfg(unix)]
+use crate::constants::DEFAULT_CONTROLLER_UNIX_SOCKET_NAME;
 use crate::constants::{
-    DEFAULT_CONTROLLER_UDP_PORT, DEFAULT_CONTROLLER_WEBSOCKET_PORT, DEFAULT_CONTROL_PORT,
+    DEFAULT_BAUD_RATE, DEFAULT_CONTROLLER_TCP_PORT, DEFAULT_CONTROLLER_UDP_PORT,
+    DEFAULT_CONTROLLER_WEBSOCKET_PORT, DEFAULT_CONTROL_PORT, DEFAULT_DISCOVERY_PORT,
     DEFAULT_HOST_UDP_PORT, DEFAULT_HOST_WEBSOCKET_PORT, DEFAULT_LOG_BROADCAST_PORT,
+    STANDARD_BAUD_RATES, STATUS_MESSAGE_TIMEOUT_SECS,
 };
 use crate::error::{BridgeError, Result};
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use tracing::warn;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{debug, warn};
 
 const DEFAULT_CONFIG_TOML: &str = include_str!("../config/default.toml");
 const DEFAULT_DEVICE_TEENSY_TOML: &str = include_str!("../config/devices/teensy.toml");
@@ -46,6 +76,49 @@ pub struct DeviceConfig {
     /// Example: "00-teensy.rules".
     #[serde(default)]
     pub udev_rules_filename: Option<String>,
+
+    /// Baud rate for this device, overriding `[bridge] baud_rate`
+    #[serde(default)]
+    pub baud_rate: Option<u32>,
+
+    /// Modem lines to set right after the port opens
+    #[serde(default)]
+    pub serial_on_connect: SerialSignals,
+
+    /// Modem lines to set before the port is released (disconnect, pause)
+    #[serde(default)]
+    pub serial_on_disconnect: SerialSignals,
+
+    /// Hold `serial_on_connect` this long, then set the opposite levels
+    ///
+    /// With `serial_on_connect = { dtr = false }` and 200 ms, DTR is pulsed
+    /// low on connect: an Arduino-style reset. 0 keeps the connect levels.
+    #[serde(default)]
+    pub post_connect_delay_ms: u64,
+}
+
+/// DTR/RTS levels to drive on a serial port (`None` leaves a line as is)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerialSignals {
+    #[serde(default)]
+    pub dtr: Option<bool>,
+    #[serde(default)]
+    pub rts: Option<bool>,
+}
+
+impl SerialSignals {
+    /// Whether any line is set
+    pub fn is_empty(&self) -> bool {
+        self.dtr.is_none() && self.rts.is_none()
+    }
+
+    /// The same lines at the opposite levels
+    pub fn inverted(self) -> Self {
+        Self {
+            dtr: self.dtr.map(|level| !level),
+            rts: self.rts.map(|level| !level),
+        }
+    }
 }
 
 /// Platform-specific port name hints for device detection fallback
@@ -74,9 +147,31 @@ struct DevicePresetFile {
 #[serde(default)]
 #[derive(Default)]
 pub struct Config {
+    /// Glob patterns of files whose `[device_presets]` tables are merged in
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
     pub bridge: BridgeConfig,
     pub logs: LogsConfig,
     pub ui: UiConfig,
+    pub keybindings: KeyBindings,
+    /// Inline device presets, keyed by preset name
+    ///
+    /// Take precedence over `devices/<name>.toml`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub device_presets: BTreeMap<String, DeviceConfig>,
+    /// `section.field` keys set from `OC_BRIDGE_*` variables (see `env`)
+    #[serde(skip)]
+    pub env_overrides: BTreeSet<String>,
+}
+
+impl Config {
+    /// Resolve a device preset (inline `[device_presets]` first, then `devices/`)
+    pub fn device_preset(&self, name: &str) -> Result<DeviceConfig> {
+        match self.device_presets.get(name) {
+            Some(device) => Ok(device.clone()),
+            None => load_device_preset(name),
+        }
+    }
 }
 
 // =============================================================================
@@ -102,6 +197,40 @@ pub enum ControllerTransport {
     /// WebSocket server (browser app simulation)
     /// Raw protocol, no encoding.
     WebSocket,
+    /// USB Serial with 2-byte big-endian length prefix framing (instead of COBS)
+    FramedLength2,
+    /// USB Serial with 4-byte big-endian length prefix framing (instead of COBS)
+    FramedLength4,
+    /// USB Serial with hex-encoded ASCII lines (firmware bring-up, see `HexCodec`)
+    HexSerial,
+    /// TCP stream with 2-byte big-endian length prefix framing (lossy links, e.g. Wi-Fi)
+    /// Reconnects when the peer disconnects.
+    Tcp,
+    /// UNIX domain socket with 2-byte big-endian length prefix framing
+    /// (local controller process, e.g. a firmware simulator)
+    #[cfg(unix)]
+    Unix,
+}
+
+/// Framing of `controller_transport = "serial"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialCodec {
+    /// COBS frames, no integrity check
+    #[default]
+    Cobs,
+    /// COBS frames carrying a little-endian CRC-16/CCITT-FALSE (see `CobsCrcCodec`)
+    CobsCrc,
+}
+
+impl ControllerTransport {
+    /// Whether this transport runs over a serial port (auto-reconnect, pause/resume)
+    pub fn is_serial(&self) -> bool {
+        matches!(
+            self,
+            Self::Serial | Self::FramedLength2 | Self::FramedLength4 | Self::HexSerial
+        )
+    }
 }
 
 // =============================================================================
@@ -114,6 +243,7 @@ pub enum ControllerTransport {
 /// - Bitwig extension (Java) via UDP
 /// - Bitwig extension (browser/WASM) via WebSocket
 /// - Both simultaneously for maximum compatibility
+/// - Named pipe for local Windows hosts (no loopback/firewall)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum HostTransport {
@@ -124,13 +254,29 @@ pub enum HostTransport {
     WebSocket,
     /// UDP + WebSocket simultaneously (broadcast to both)
     Both,
+    /// Named pipe `\\.\pipe\oc-bridge-host` (Windows only)
+    #[cfg(windows)]
+    NamedPipe,
+}
+
+impl HostTransport {
+    /// Next transport in the runtime switch cycle (UDP -> WebSocket -> Both)
+    pub fn next_switchable(self) -> Self {
+        match self {
+            Self::Udp => Self::WebSocket,
+            Self::WebSocket => Self::Both,
+            Self::Both => Self::Udp,
+            #[cfg(windows)]
+            Self::NamedPipe => Self::Udp,
+        }
+    }
 }
 
 // =============================================================================
 // Bridge Configuration
 // =============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct BridgeConfig {
     /// Stable logical identifier for this bridge instance.
@@ -147,10 +293,22 @@ pub struct BridgeConfig {
     /// Transport type for the controller
     pub controller_transport: ControllerTransport,
 
+    /// Frame codec for controller_transport = Serial ("cobs_crc" needs
+    /// firmware that appends the CRC)
+    pub codec: SerialCodec,
+
+    /// Codec stages for controller_transport = Serial, wire side first,
+    /// e.g. ["cobs", "strip_header:4"] (empty = `codec` alone)
+    pub codec_pipeline: Vec<String>,
+
     /// Serial port name (empty = auto-detect using device_preset)
-    /// Only used when controller_transport = Serial
+    /// Only used when controller_transport is a serial variant
     pub serial_port: String,
 
+    /// Serial baud rate, for UART devices (RS-232 adapters, non-USB boards)
+    /// USB CDC devices such as the Teensy ignore it.
+    pub baud_rate: u32,
+
     /// Device preset name (filename without .toml in devices/)
     /// Used for auto-detection when serial_port is empty.
     /// Example: "teensy" loads devices/teensy.toml
@@ -164,6 +322,21 @@ pub struct BridgeConfig {
     /// Only used when controller_transport = WebSocket
     pub controller_websocket_port: u16,
 
+    /// TCP address for controller: listened on, or connected to when
+    /// `controller_tcp_connect` is set. Only used when controller_transport = Tcp
+    pub controller_tcp_addr: SocketAddr,
+
+    /// Connect to `controller_tcp_addr` instead of listening on it
+    pub controller_tcp_connect: bool,
+
+    /// UNIX socket path for controller
+    /// (None = `$XDG_RUNTIME_DIR/oc-bridge-controller-<instance_id>.sock`)
+    /// Only used when controller_transport = Unix
+    pub controller_unix_socket: Option<PathBuf>,
+
+    /// Connect to `controller_unix_socket` instead of binding it
+    pub controller_unix_connect: bool,
+
     // =========================================================================
     // Host Side (destination of MIDI messages)
     // =========================================================================
@@ -178,12 +351,44 @@ pub struct BridgeConfig {
     /// Used when host_transport = WebSocket or Both
     pub host_websocket_port: u16,
 
+    /// Subprotocol accepted in WebSocket handshakes (None = "oc-bridge-v1")
+    ///
+    /// Clients requesting no subprotocol are always accepted.
+    pub websocket_subprotocol: Option<String>,
+
+    /// Most WebSocket clients connected at once, per server (0 = no limit)
+    ///
+    /// Outgoing data goes to every client; extra clients are closed (1013).
+    pub websocket_max_clients: usize,
+
+    /// Fallback order when the host transport fails to bind
+    ///
+    /// Starting from `host_transport`, the following entries are tried in
+    /// order. A transport not listed here has no fallback.
+    pub host_failover_order: Vec<HostTransport>,
+
+    // =========================================================================
+    // Network access
+    // =========================================================================
+    /// Address the UDP transports bind to ("0.0.0.0" = all interfaces)
+    pub bind_interface: String,
+
+    /// Remote networks allowed to send UDP datagrams (e.g. "192.168.1.0/24")
+    ///
+    /// Loopback senders are always accepted; empty = localhost only.
+    pub allowed_remote_cidrs: Vec<IpNet>,
+
     // =========================================================================
     // Logs
     // =========================================================================
     /// UDP port for log broadcast from service to TUI
+    ///
+    /// Hot-reloadable (the TUI listens on the new port)
     pub log_broadcast_port: u16,
 
+    /// LZ4-compress log broadcast packets (receivers accept both forms)
+    pub log_broadcast_compress: bool,
+
     // =========================================================================
     // Control
     // =========================================================================
@@ -192,20 +397,197 @@ pub struct BridgeConfig {
     /// Binds to 127.0.0.1 only.
     pub control_port: u16,
 
+    /// UDP port answering LAN discovery scans (0 = not discoverable)
+    ///
+    /// Answers loopback and `allowed_remote_cidrs` senders only.
+    pub discovery_port: u16,
+
+    /// TCP port serving Prometheus metrics at `/metrics` (unset = off)
+    ///
+    /// Listens on all interfaces; answers loopback and `allowed_remote_cidrs`
+    /// clients only.
+    pub metrics_port: Option<u16>,
+
     /// Enable generic exact-duplicate protection in the relay.
     pub duplicate_guard_enabled: bool,
 
     /// Duplicate suppression window, in milliseconds, for identical payloads per direction.
     pub duplicate_guard_window_ms: u64,
+
+    /// Dropped controller -> host messages per second before the controller
+    /// reader is paused until the host catches up (0 = never pause).
+    pub max_drops_per_sec: u32,
+
+    /// Discard a partial COBS frame left unterminated for this long, e.g.
+    /// after a device reset mid-frame (0 = never)
+    pub partial_frame_timeout_ms: u64,
+
+    /// Time allowed on shutdown to send datagrams already queued for the
+    /// host, in milliseconds (0 = drop them)
+    pub drain_timeout_ms: u64,
+
+    /// Warn once per message name when a controller payload is larger than
+    /// this many bytes; UDP also warns when a datagram exceeds the client
+    /// path MTU (0 = no warnings)
+    pub max_payload_warn_bytes: usize,
+
+    /// Drop controller and host messages larger than this many bytes
+    /// (0 = no limit)
+    pub max_message_size: usize,
+
+    /// Host -> controller batching on serial links (`[bridge.batch]`)
+    pub batch: BatchConfig,
+
+    /// Traffic spike warnings (`[bridge.spike_detector]`)
+    ///
+    /// Hot-reloadable (TUI status bar)
+    pub spike_detector: SpikeDetectorConfig,
+
+    /// UDP keepalives for clients behind NAT (`[bridge.nat_keepalive]`)
+    pub nat_keepalive: NatKeepaliveConfig,
+
+    /// Certificate for `wss://` WebSocket servers (`[bridge.tls]`, `tls` feature)
+    pub tls: Option<TlsConfig>,
+
+    // =========================================================================
+    // Notifications
+    // =========================================================================
+    /// Notified when the controller connects (`http(s)://` POST with the
+    /// `webhooks` feature, or `file://` script)
+    pub on_connect_webhook: Option<String>,
+
+    /// Notified when the controller disconnects
+    pub on_disconnect_webhook: Option<String>,
+
+    // =========================================================================
+    // Lifecycle hooks
+    // =========================================================================
+    /// Shell command run before the bridge starts (`sh -c`, `cmd /c` on
+    /// Windows); a non-zero exit or a run over 5s fails the start
+    pub pre_start_hook: Option<String>,
+
+    /// Shell command run after the bridge stops (best effort)
+    pub post_stop_hook: Option<String>,
+
+    /// Log every byte decoded by the COBS codec (set by `--trace-cobs`, never saved)
+    #[serde(skip)]
+    pub trace_cobs: bool,
+}
+
+/// Coalescing of small host -> controller messages into one serial write
+///
+/// Messages are held until `max_batch_size_bytes` is reached or the oldest
+/// one is `max_batch_age_ms` old. An age of 0 disables batching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatchConfig {
+    pub max_batch_size_bytes: usize,
+    pub max_batch_age_ms: u64,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size_bytes: 1024,
+            max_batch_age_ms: 0,
+        }
+    }
+}
+
+impl BatchConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_batch_age_ms > 0 && self.max_batch_size_bytes > 0
+    }
+}
+
+/// Keepalive datagrams that hold NAT port mappings open
+///
+/// When nothing was sent to the UDP client for `interval_secs`, `payload`
+/// is sent to it. The default payload (`0xFF 0xFF`) is not a COBS frame,
+/// so firmware can ignore it. An interval of 0 disables keepalives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NatKeepaliveConfig {
+    pub interval_secs: u64,
+    pub payload: Vec<u8>,
+}
+
+impl Default for NatKeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 0,
+            payload: vec![0xFF, 0xFF],
+        }
+    }
+}
+
+/// TLS certificate and key (PEM files) for the WebSocket servers
+///
+/// When set, the controller and host WebSocket servers only accept
+/// `wss://` connections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_pem: PathBuf,
+    pub key_pem: PathBuf,
+}
+
+/// Throughput spike detection (e.g. a firmware runaway loop)
+///
+/// A spike is a 100ms traffic rate above `threshold_multiplier` times the
+/// 5-second baseline, lasting longer than `min_duration_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpikeDetectorConfig {
+    pub enabled: bool,
+    pub threshold_multiplier: f64,
+    pub min_duration_ms: u64,
+    /// Lowest baseline in KB/s, so the first messages after an idle
+    /// period don't count as a spike
+    pub baseline_floor_kb_s: f64,
+}
+
+impl Default for SpikeDetectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_multiplier: 10.0,
+            min_duration_ms: 100,
+            baseline_floor_kb_s: 1.0,
+        }
+    }
+}
+
+/// File format of log exports (`E`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Same lines as the log view (`.txt`)
+    #[default]
+    Text,
+    /// One JSON `LogEntry` per line (`.jsonl`), for external tools
+    JsonLines,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LogsConfig {
     /// Maximum log entries in memory
+    ///
+    /// Hot-reloadable
     pub max_entries: usize,
+    /// Identical consecutive messages kept before the rest are only counted
+    /// (0 = keep all)
+    ///
+    /// Hot-reloadable
+    pub dedup_threshold: usize,
     /// Maximum log entries when exporting
     pub export_max: usize,
+    /// `"text"` or `"jsonlines"`
+    ///
+    /// Hot-reloadable
+    pub export_format: ExportFormat,
+    /// Recent copies kept for re-copying (TUI, in memory only)
+    pub clipboard_history_size: usize,
 
     // =========================================================================
     // File logging (daemon)
@@ -235,13 +617,160 @@ pub struct LogsConfig {
 
     /// Include system logs in the file.
     pub file_include_system: bool,
+
+    // =========================================================================
+    // File logging (TUI session)
+    // =========================================================================
+    /// Write every entry the TUI receives to `log_dir`, one file per run
+    ///
+    /// Hot-reloadable (as are the three settings below)
+    pub file_logging: bool,
+
+    /// Session log directory (default: `logs/` next to `config.toml`)
+    pub log_dir: Option<PathBuf>,
+
+    /// Start a new file when the current one reaches this size
+    pub max_file_size_mb: u64,
+
+    /// Session log files kept, oldest deleted first
+    pub max_files: usize,
+
+    /// Stream received entries as JSON to WebSocket clients on 127.0.0.1
+    ///
+    /// Hot-reloadable
+    pub ws_stream_port: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct UiConfig {
     /// Default filter: "Protocol", "Debug", or "All"
+    ///
+    /// Hot-reloadable (a change switches the log view to it)
     pub default_filter: String,
+    /// Wrap long log messages instead of truncating them (toggle: Alt+W)
+    ///
+    /// Hot-reloadable
+    pub word_wrap: bool,
+    /// How long status bar messages stay visible
+    ///
+    /// Hot-reloadable
+    pub status_message_timeout_secs: u64,
+}
+
+/// Letters whose Shift variant is a fixed TUI key (annotate, jitter, port
+/// list, reload), checked before the remappable keys
+const FIXED_SHIFT_KEYS: [&str; 4] = ["n", "j", "l", "r"];
+
+/// TUI key for each remappable action (`[keybindings]`)
+///
+/// Each value is a single character, matched case-insensitively. Arrows,
+/// PageUp/PageDown, Home/End, Esc, Backspace and Ctrl combinations are fixed.
+///
+/// Hot-reloadable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub quit: String,
+    pub toggle_bridge: String,
+    pub scroll_up: String,
+    pub scroll_down: String,
+    pub filter_protocol: String,
+    pub filter_debug: String,
+    pub filter_all: String,
+    pub filter_events: String,
+    pub copy: String,
+    pub cut: String,
+    pub toggle_pause: String,
+    pub export: String,
+    pub open_config: String,
+    pub scan: String,
+}
+
+impl KeyBindings {
+    fn slots_mut(&mut self) -> [(&'static str, &mut String); 14] {
+        [
+            ("quit", &mut self.quit),
+            ("toggle_bridge", &mut self.toggle_bridge),
+            ("scroll_up", &mut self.scroll_up),
+            ("scroll_down", &mut self.scroll_down),
+            ("filter_protocol", &mut self.filter_protocol),
+            ("filter_debug", &mut self.filter_debug),
+            ("filter_all", &mut self.filter_all),
+            ("filter_events", &mut self.filter_events),
+            ("copy", &mut self.copy),
+            ("cut", &mut self.cut),
+            ("toggle_pause", &mut self.toggle_pause),
+            ("export", &mut self.export),
+            ("open_config", &mut self.open_config),
+            ("scan", &mut self.scan),
+        ]
+    }
+
+    /// Normalize to one lowercase character per action
+    ///
+    /// Invalid keys, and customized keys that clash with another action or
+    /// with a fixed Shift letter, fall back to their default. Returns one
+    /// warning per fallback.
+    pub fn validated(&self) -> (KeyBindings, Vec<String>) {
+        let mut defaults = KeyBindings::default();
+        let mut keys = self.clone();
+        let mut warnings = Vec::new();
+
+        for ((action, key), (_, default)) in keys.slots_mut().into_iter().zip(defaults.slots_mut())
+        {
+            let mut chars = key.trim().chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_whitespace() => {
+                    *key = c.to_lowercase().to_string();
+                    // The defaults `j` and `n` keep their documented Shift meaning
+                    if key != default && FIXED_SHIFT_KEYS.contains(&key.as_str()) {
+                        warnings.push(format!(
+                            "keybindings.{} = \"{}\": Shift+{} is a fixed key, using \"{}\"",
+                            action,
+                            key,
+                            key.to_uppercase(),
+                            default
+                        ));
+                        *key = default.clone();
+                    }
+                }
+                _ => {
+                    warnings.push(format!(
+                        "keybindings.{} = \"{}\": must be a single character \
+                         (modifier combinations such as Ctrl+C are reserved), using \"{}\"",
+                        action, key, default
+                    ));
+                    *key = default.clone();
+                }
+            }
+        }
+
+        // Resetting one action can clash with another customized one: repeat
+        // until stable (all defaults are distinct, so this terminates)
+        loop {
+            let taken: Vec<String> = keys.slots_mut().map(|(_, k)| k.clone()).to_vec();
+            let mut changed = false;
+            for ((action, key), (_, default)) in
+                keys.slots_mut().into_iter().zip(defaults.slots_mut())
+            {
+                let clashes = taken.iter().filter(|k| *k == key).count() > 1;
+                if clashes && key != default {
+                    warnings.push(format!(
+                        "keybindings.{} = \"{}\": already used by another action, using \"{}\"",
+                        action, key, default
+                    ));
+                    *key = default.clone();
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        (keys, warnings)
+    }
 }
 
 impl Default for BridgeConfig {
@@ -251,23 +780,110 @@ impl Default for BridgeConfig {
             serial_number: None,
             // Controller side
             controller_transport: ControllerTransport::Serial,
+            codec: SerialCodec::Cobs,
+            codec_pipeline: Vec::new(),
             serial_port: String::new(),
+            baud_rate: DEFAULT_BAUD_RATE,
             device_preset: Some("teensy".to_string()),
             controller_udp_port: DEFAULT_CONTROLLER_UDP_PORT,
             controller_websocket_port: DEFAULT_CONTROLLER_WEBSOCKET_PORT,
+            controller_tcp_addr: SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                DEFAULT_CONTROLLER_TCP_PORT,
+            ),
+            controller_tcp_connect: false,
+            controller_unix_socket: None,
+            controller_unix_connect: false,
             // Host side
             host_transport: HostTransport::Udp,
             host_udp_port: DEFAULT_HOST_UDP_PORT,
             host_websocket_port: DEFAULT_HOST_WEBSOCKET_PORT,
+            websocket_subprotocol: None,
+            websocket_max_clients: 0,
+            host_failover_order: vec![
+                HostTransport::Both,
+                HostTransport::WebSocket,
+                HostTransport::Udp,
+            ],
+            // Network access
+            bind_interface: "127.0.0.1".to_string(),
+            allowed_remote_cidrs: Vec::new(),
             // Logs
             log_broadcast_port: DEFAULT_LOG_BROADCAST_PORT,
+            log_broadcast_compress: false,
 
             // Control
             control_port: DEFAULT_CONTROL_PORT,
+            discovery_port: DEFAULT_DISCOVERY_PORT,
+            metrics_port: None,
             duplicate_guard_enabled: true,
             duplicate_guard_window_ms: 12,
+            max_drops_per_sec: 50,
+            partial_frame_timeout_ms: 500,
+            drain_timeout_ms: 100,
+            max_payload_warn_bytes: 0,
+            max_message_size: 4096,
+            batch: BatchConfig::default(),
+            spike_detector: SpikeDetectorConfig::default(),
+            nat_keepalive: NatKeepaliveConfig::default(),
+            tls: None,
+            on_connect_webhook: None,
+            on_disconnect_webhook: None,
+            pre_start_hook: None,
+            post_stop_hook: None,
+            trace_cobs: false,
+        }
+    }
+}
+
+impl BridgeConfig {
+    /// `controller_unix_socket`, or the default path
+    #[cfg(unix)]
+    pub fn unix_socket_path(&self) -> PathBuf {
+        self.controller_unix_socket
+            .clone()
+            .unwrap_or_else(|| default_unix_socket_path(std::env::var_os("XDG_RUNTIME_DIR"), self))
+    }
+
+    /// Parsed `bind_interface`
+    pub fn bind_ip(&self) -> Result<IpAddr> {
+        self.bind_interface
+            .trim()
+            .parse()
+            .map_err(|e| BridgeError::ConfigValidation {
+                field: "bind_interface",
+                reason: format!("'{}': {}", self.bind_interface, e),
+            })
+    }
+
+    /// Baud rate to open the serial port with: the device preset's, else
+    /// `baud_rate`; must be a standard rate
+    pub fn serial_baud_rate(&self, device: Option<&DeviceConfig>) -> Result<u32> {
+        let baud = device.and_then(|d| d.baud_rate).unwrap_or(self.baud_rate);
+        if STANDARD_BAUD_RATES.contains(&baud) {
+            Ok(baud)
+        } else {
+            Err(BridgeError::ConfigValidation {
+                field: "baud_rate",
+                reason: format!(
+                    "{} is not a standard rate (9600, 115200, 921600, ...)",
+                    baud
+                ),
+            })
         }
     }
+
+    /// Warning for a non-loopback bind that no remote host may use
+    pub fn remote_access_warning(&self) -> Option<String> {
+        let ip = self.bind_ip().ok()?;
+        if ip.is_loopback() || !self.allowed_remote_cidrs.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "bind_interface = \"{}\" but allowed_remote_cidrs is empty: remote datagrams will be dropped",
+            self.bind_interface
+        ))
+    }
 }
 
 fn default_instance_id() -> Option<String> {
@@ -278,7 +894,10 @@ impl Default for LogsConfig {
     fn default() -> Self {
         Self {
             max_entries: 200,
+            dedup_threshold: 0,
             export_max: 2000,
+            export_format: ExportFormat::Text,
+            clipboard_history_size: 5,
             file_enabled: true,
             file_max_bytes: 5_000_000,
             file_max_files: 3,
@@ -286,6 +905,33 @@ impl Default for LogsConfig {
             file_include_protocol: false,
             file_include_debug: true,
             file_include_system: true,
+            file_logging: false,
+            log_dir: None,
+            max_file_size_mb: 10,
+            max_files: 10,
+            ws_stream_port: None,
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let key = |c: &str| c.to_string();
+        Self {
+            quit: key("q"),
+            toggle_bridge: key("b"),
+            scroll_up: key("k"),
+            scroll_down: key("j"),
+            filter_protocol: key("1"),
+            filter_debug: key("2"),
+            filter_all: key("3"),
+            filter_events: key("4"),
+            copy: key("c"),
+            cut: key("x"),
+            toggle_pause: key("p"),
+            export: key("e"),
+            open_config: key("f"),
+            scan: key("n"),
         }
     }
 }
@@ -294,6 +940,8 @@ impl Default for UiConfig {
     fn default() -> Self {
         Self {
             default_filter: "All".to_string(),
+            word_wrap: false,
+            status_message_timeout_secs: STATUS_MESSAGE_TIMEOUT_SECS,
         }
     }
 }
@@ -349,6 +997,148 @@ pub fn config_path() -> Result<PathBuf> {
     Ok(config_dir()?.join("config.toml"))
 }
 
+// =============================================================================
+// Profiles
+// =============================================================================
+
+/// Profile selected with `--profile` for this process
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Directory holding named config overlays (`profiles/<name>.toml`)
+pub fn profiles_dir() -> Result<PathBuf> {
+    Ok(config_dir()?.join("profiles"))
+}
+
+/// Path of the profile `name`
+pub fn profile_path(name: &str) -> Result<PathBuf> {
+    validate_profile_name(name)?;
+    Ok(profiles_dir()?.join(format!("{}.toml", name)))
+}
+
+/// Profile names become file names: no separators, no hidden files
+fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(BridgeError::ConfigValidation {
+            field: "profile",
+            reason: format!(
+                "'{}' (use letters, digits, '-', '_' or '.', not starting with '.')",
+                name
+            ),
+        })
+    }
+}
+
+/// Select the profile overlaid by `load()` for the rest of the process
+///
+/// Fails if the profile does not exist, so a typo is not silently ignored.
+pub fn set_active_profile(name: &str) -> Result<()> {
+    let path = profile_path(name)?;
+    if !path.exists() {
+        return Err(BridgeError::ConfigValidation {
+            field: "profile",
+            reason: format!("'{}' not found ({})", name, path.display()),
+        });
+    }
+    let _ = ACTIVE_PROFILE.set(name.to_string());
+    Ok(())
+}
+
+/// Profile selected with `--profile`, if any
+pub fn active_profile() -> Option<&'static str> {
+    ACTIVE_PROFILE.get().map(String::as_str)
+}
+
+/// Config sections a new profile copies from the main config
+///
+/// `[bridge]` holds per-instance values (ports, `instance_id`) that a
+/// profile must not pin; set the ones to change by hand.
+const PROFILE_SECTIONS: [&str; 3] = ["logs", "ui", "keybindings"];
+
+/// Create a profile from the user-facing sections of the current config file
+pub fn create_profile(name: &str) -> Result<PathBuf> {
+    let path = profile_path(name)?;
+    if path.exists() {
+        return Err(BridgeError::ConfigValidation {
+            field: "profile",
+            reason: format!("'{}' already exists ({})", name, path.display()),
+        });
+    }
+
+    let source = ensure_user_config_scaffold()?.join("config.toml");
+    let dir = profiles_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| BridgeError::Io {
+        path: dir.clone(),
+        source: e,
+    })?;
+    let sections = profile_sections(read_toml_table(&source)?);
+    let content = toml::to_string_pretty(&sections).map_err(|e| BridgeError::ConfigValidation {
+        field: "profile",
+        reason: e.to_string(),
+    })?;
+    fs::write(&path, content).map_err(|e| BridgeError::Io {
+        path: path.clone(),
+        source: e,
+    })?;
+    Ok(path)
+}
+
+/// Keep the `PROFILE_SECTIONS` tables of a config table
+fn profile_sections(mut table: toml::Table) -> toml::Table {
+    table.retain(|key, _| PROFILE_SECTIONS.contains(&key));
+    table
+}
+
+/// Names of all profiles, sorted
+pub fn list_profiles() -> Result<Vec<String>> {
+    let dir = profiles_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&dir).map_err(|e| BridgeError::Io {
+        path: dir.clone(),
+        source: e,
+    })?;
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Remove the profile `name`
+pub fn delete_profile(name: &str) -> Result<PathBuf> {
+    let path = profile_path(name)?;
+    fs::remove_file(&path).map_err(|e| BridgeError::Io {
+        path: path.clone(),
+        source: e,
+    })?;
+    Ok(path)
+}
+
+/// TUI session log directory (`[logs] log_dir`, or `logs/` in the config dir)
+pub fn session_log_dir(logs: &LogsConfig) -> Result<PathBuf> {
+    match &logs.log_dir {
+        Some(dir) => Ok(dir.clone()),
+        None => Ok(config_dir()?.join("logs")),
+    }
+}
+
+/// Per-instance stats history (JSON Lines snapshots)
+pub fn stats_path(cfg: &BridgeConfig) -> Result<PathBuf> {
+    Ok(config_dir()?.join(format!("stats.{}.jsonl", effective_instance_id(cfg))))
+}
+
 pub fn normalized_optional_string(value: Option<&str>) -> Option<String> {
     value
         .map(str::trim)
@@ -356,6 +1146,22 @@ pub fn normalized_optional_string(value: Option<&str>) -> Option<String> {
         .map(|value| value.to_string())
 }
 
+/// `<runtime_dir>/oc-bridge-controller-<instance_id>.sock` (temp dir if unset)
+#[cfg(unix)]
+fn default_unix_socket_path(
+    runtime_dir: Option<std::ffi::OsString>,
+    cfg: &BridgeConfig,
+) -> PathBuf {
+    let dir = runtime_dir
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(std::env::temp_dir, PathBuf::from);
+    dir.join(format!(
+        "{}-{}.sock",
+        DEFAULT_CONTROLLER_UNIX_SOCKET_NAME,
+        effective_instance_id(cfg)
+    ))
+}
+
 pub fn effective_instance_id(cfg: &BridgeConfig) -> String {
     let raw = normalized_optional_string(cfg.instance_id.as_deref())
         .unwrap_or_else(|| "default".to_string());
@@ -472,7 +1278,15 @@ pub fn load_device_preset(name: &str) -> Result<DeviceConfig> {
 }
 
 /// Load config from file, or create default if not exists
+///
+/// `OC_BRIDGE_*` environment overrides apply on top, defaults included.
 pub fn load() -> Config {
+    let mut config = load_or_default();
+    apply_env_overrides(&mut config);
+    config
+}
+
+fn load_or_default() -> Config {
     // Ensure a usable per-user config scaffold exists (idempotent).
     // If this fails, we fall back to in-memory defaults.
     if let Err(e) = ensure_user_config_scaffold() {
@@ -490,51 +1304,313 @@ pub fn load() -> Config {
 
     debug_assert!(path.exists(), "config scaffold should create config.toml");
 
-    match fs::read_to_string(&path) {
-        Ok(content) => match toml::from_str(&content) {
-            Ok(config) => config,
-            Err(e) => {
-                warn!("Config parse error in {:?}: {}, using defaults", path, e);
-                Config::default()
-            }
-        },
+    let profile = match active_profile().map(profile_path) {
+        Some(Ok(p)) => Some(p),
+        Some(Err(e)) => {
+            warn!("Ignoring profile: {}", e);
+            None
+        }
+        None => None,
+    };
+
+    match load_from_path(&path, profile.as_deref()) {
+        Ok(config) => config,
+        Err(BridgeError::Io { path, source }) => {
+            warn!(
+                "Failed to read config {:?}: {}, using defaults",
+                path, source
+            );
+            Config::default()
+        }
         Err(e) => {
-            warn!("Failed to read config {:?}: {}, using defaults", path, e);
+            warn!("Config parse error in {:?}: {}, using defaults", path, e);
             Config::default()
         }
     }
 }
 
+/// Load the config file (and active profile) again, without fallbacks
+///
+/// Unlike `load`, a missing, unreadable or half-written file is an error
+/// rather than defaults, so a reload can skip it.
+pub fn reload() -> Result<Config> {
+    let profile = active_profile().map(profile_path).transpose()?;
+    let mut config = load_from_path(&config_path()?, profile.as_deref())?;
+    apply_env_overrides(&mut config);
+    Ok(config)
+}
+
+/// Load a config file and merge the `[device_presets]` of its includes
+///
+/// Keys set in the `profile` file, if any, override the result.
+pub fn load_from_path(path: &Path, profile: Option<&Path>) -> Result<Config> {
+    let mut table = read_toml_table(path)?;
+
+    let mut visited = HashSet::new();
+    visited.insert(canonical(path));
+    let mut included = 0;
+    merge_includes(&mut table, path, &mut visited, &mut included)?;
+    if included > 0 {
+        debug!(
+            "Config: merged device presets from {} included file(s)",
+            included
+        );
+    }
+
+    if let Some(profile) = profile {
+        merge_overriding(&mut table, read_toml_table(profile)?);
+        debug!("Config: applied profile {:?}", profile);
+    }
+
+    table
+        .try_into()
+        .map_err(|e: toml::de::Error| BridgeError::ConfigValidation {
+            field: "config",
+            reason: e.to_string(),
+        })
+}
+
+fn read_toml_table(path: &Path) -> Result<toml::Table> {
+    let content = fs::read_to_string(path).map_err(|e| BridgeError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    content
+        .parse::<toml::Table>()
+        .map_err(|e| BridgeError::ConfigValidation {
+            field: "config",
+            reason: format!("{}: {}", path.display(), e),
+        })
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Expand `include` patterns of `table` (loaded from `path`) recursively
+///
+/// Included `[device_presets]` entries are merged key by key; keys already
+/// present in `table` are kept. Files already on the include chain are
+/// skipped as circular.
+fn merge_includes(
+    table: &mut toml::Table,
+    path: &Path,
+    visited: &mut HashSet<PathBuf>,
+    included: &mut usize,
+) -> Result<()> {
+    let patterns: Vec<String> = match table.get("include") {
+        Some(toml::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => return Ok(()),
+    };
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+    for pattern in patterns {
+        let full = base_dir.join(&pattern);
+        let matches =
+            glob::glob(&full.to_string_lossy()).map_err(|e| BridgeError::ConfigValidation {
+                field: "include",
+                reason: format!("invalid pattern '{}': {}", pattern, e),
+            })?;
+
+        let mut matched = 0;
+        for file in matches.flatten() {
+            matched += 1;
+            let key = canonical(&file);
+            if !visited.insert(key.clone()) {
+                warn!("Config: skipping circular include of {:?}", file);
+                continue;
+            }
+
+            let mut child = read_toml_table(&file)?;
+            merge_includes(&mut child, &file, visited, included)?;
+            visited.remove(&key);
+            *included += 1;
+
+            if let Some(toml::Value::Table(presets)) = child.remove("device_presets") {
+                let target = table
+                    .entry("device_presets")
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+                if let toml::Value::Table(target) = target {
+                    merge_missing(target, presets);
+                }
+            }
+        }
+
+        if matched == 0 {
+            debug!("Config include pattern matched no files: {}", pattern);
+        }
+    }
+    Ok(())
+}
+
+/// Insert keys from `from` that `into` lacks, recursing into nested tables
+fn merge_missing(into: &mut toml::Table, from: toml::Table) {
+    for (key, value) in from {
+        match (into.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+                merge_missing(existing, incoming);
+            }
+            (Some(_), _) => {}
+            (None, value) => {
+                into.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Replace keys of `into` with those of `from`, recursing into nested tables
+fn merge_overriding(into: &mut toml::Table, from: toml::Table) {
+    for (key, value) in from {
+        match (into.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => {
+                merge_overriding(existing, incoming);
+            }
+            (_, value) => {
+                into.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Open config file in default editor
 pub fn open_in_editor() -> Result<()> {
     let root = ensure_user_config_scaffold()?;
     crate::platform::open_file(&root.join("config.toml"))
 }
 
-/// Detect serial port from config (explicit port or auto-detection via device preset)
+/// Set `[bridge] serial_port` in the config file at `path`
+///
+/// The line is replaced in place so comments and layout survive; the key is
+/// added under `[bridge]` (or a new `[bridge]` table) when missing.
+pub fn set_serial_port(path: &Path, port: &str) -> Result<()> {
+    let io_err = |source| BridgeError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let content = fs::read_to_string(path).map_err(io_err)?;
+    let setting = format!("serial_port = {}", toml::Value::String(port.to_string()));
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut bridge_header = None;
+    let mut in_bridge = false;
+    let mut replaced = false;
+    for (i, line) in lines.iter_mut().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_bridge = trimmed.split('#').next().map(str::trim) == Some("[bridge]");
+            if in_bridge {
+                bridge_header = Some(i);
+            }
+        } else if in_bridge
+            && trimmed
+                .split_once('=')
+                .is_some_and(|(key, _)| key.trim() == "serial_port")
+        {
+            *line = setting.clone();
+            replaced = true;
+            break;
+        }
+    }
+    if !replaced {
+        match bridge_header {
+            Some(i) => lines.insert(i + 1, setting),
+            None => lines.extend([String::new(), "[bridge]".to_string(), setting]),
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    fs::write(path, updated).map_err(io_err)
+}
+
+/// True when `serial_port` was set by `OC_BRIDGE_SERIAL_PORT` / `OC_BRIDGE_PORT`
+pub fn serial_port_from_env(cfg: &Config) -> bool {
+    cfg.env_overrides.contains("bridge.serial_port")
+        && normalized_optional_string(Some(&cfg.bridge.serial_port)).is_some()
+}
+
+/// Device preset used for auto-detection
+///
+/// The configured preset, or the built-in Teensy preset when none is set
+/// (what the bridge detected before presets existed). A configured preset
+/// that fails to load yields `None`.
+pub fn detection_preset(cfg: &Config) -> Option<DeviceConfig> {
+    match cfg.bridge.device_preset.as_deref() {
+        Some(name) => cfg.device_preset(name).ok(),
+        None => toml::from_str::<DevicePresetFile>(DEFAULT_DEVICE_TEENSY_TOML)
+            .ok()
+            .map(|file| file.device),
+    }
+}
+
+/// Detect serial port from config
+///
+/// Tries, in order: the configured `serial_port` (or its env override) if the
+/// port exists, the device preset, then the built-in Teensy preset when no
+/// preset is configured.
 pub fn detect_serial(cfg: &Config) -> Option<String> {
+    let ports = serialport::available_ports().unwrap_or_default();
+    detect_serial_among(cfg, &ports)
+}
+
+/// Every port `detect_serial` could pick, in priority order
+pub fn detect_serial_all(cfg: &Config) -> Vec<String> {
+    let ports = serialport::available_ports().unwrap_or_default();
+    detect_serial_all_among(cfg, &ports)
+}
+
+fn detect_serial_among(cfg: &Config, ports: &[serialport::SerialPortInfo]) -> Option<String> {
     use crate::transport::SerialTransport;
 
-    // If port is explicitly configured, use it
-    if !cfg.bridge.serial_port.is_empty() {
-        return Some(cfg.bridge.serial_port.clone());
+    if let Some(port) = explicit_serial_port(cfg, ports) {
+        return Some(port);
     }
+    let device_config = detection_preset(cfg)?;
+    SerialTransport::detect_among(ports, &device_config, &serial_match_request(cfg)).ok()
+}
 
-    // Otherwise, try auto-detection with device preset
-    let device_config = cfg
-        .bridge
-        .device_preset
-        .as_ref()
-        .and_then(|name| load_device_preset(name).ok())?;
-    if normalized_optional_string(cfg.bridge.serial_number.as_deref()).is_none() {
-        return SerialTransport::detect(&device_config).ok();
+fn detect_serial_all_among(cfg: &Config, ports: &[serialport::SerialPortInfo]) -> Vec<String> {
+    use crate::transport::SerialTransport;
+
+    let mut found: Vec<String> = explicit_serial_port(cfg, ports).into_iter().collect();
+    if let Some(device_config) = detection_preset(cfg) {
+        for port in
+            SerialTransport::detect_all_among(ports, &device_config, &serial_match_request(cfg))
+        {
+            if !found.contains(&port) {
+                found.push(port);
+            }
+        }
     }
+    found
+}
 
-    let request = crate::transport::SerialMatchRequest {
-        serial_number: normalized_optional_string(cfg.bridge.serial_number.as_deref()),
+/// Port set by the user: `serial_port` (from the file or the environment)
+///
+/// The daemon opens this port as-is (it waits for it to appear), so this is
+/// what it is connected to once the serial link is up.
+pub fn configured_serial_port(cfg: &Config) -> Option<String> {
+    normalized_optional_string(Some(&cfg.bridge.serial_port))
+}
+
+/// Configured port, only if the port exists
+///
+/// Ports missing from the enumeration still count when the path exists
+/// (pseudo-terminals such as `test-loopback --software-loopback` are not listed).
+fn explicit_serial_port(cfg: &Config, ports: &[serialport::SerialPortInfo]) -> Option<String> {
+    let exists = |name: &str| {
+        ports.iter().any(|info| info.port_name == name) || std::path::Path::new(name).exists()
     };
+    configured_serial_port(cfg).filter(|port| exists(port))
+}
 
-    SerialTransport::detect_with_request(&device_config, &request).ok()
+fn serial_match_request(cfg: &Config) -> crate::transport::SerialMatchRequest {
+    crate::transport::SerialMatchRequest {
+        serial_number: normalized_optional_string(cfg.bridge.serial_number.as_deref()),
+    }
 }
 
 // ============================================================================
@@ -572,6 +1648,7 @@ mod tests {
 
         // Logs
         assert_eq!(config.log_broadcast_port, DEFAULT_LOG_BROADCAST_PORT);
+        assert_eq!(config.discovery_port, DEFAULT_DISCOVERY_PORT);
     }
 
     #[test]
@@ -647,6 +1724,72 @@ mod tests {
         assert_eq!(ws.transport, ControllerTransport::WebSocket);
     }
 
+    #[test]
+    fn test_controller_transport_framed_length_is_serial() {
+        #[derive(Serialize, Deserialize)]
+        struct Wrapper {
+            transport: ControllerTransport,
+        }
+
+        let len2: Wrapper = toml::from_str("transport = \"framedlength2\"").unwrap();
+        let len4: Wrapper = toml::from_str("transport = \"framedlength4\"").unwrap();
+
+        assert_eq!(len2.transport, ControllerTransport::FramedLength2);
+        assert_eq!(len4.transport, ControllerTransport::FramedLength4);
+        assert!(len2.transport.is_serial());
+        assert!(len4.transport.is_serial());
+        assert!(!ControllerTransport::Udp.is_serial());
+
+        let hex: Wrapper = toml::from_str("transport = \"hexserial\"").unwrap();
+        assert_eq!(hex.transport, ControllerTransport::HexSerial);
+        assert!(hex.transport.is_serial());
+    }
+
+    #[test]
+    fn test_controller_tcp_config() {
+        let config: BridgeConfig = toml::from_str(
+            "controller_transport = \"tcp\"\ncontroller_tcp_addr = \"0.0.0.0:9100\"",
+        )
+        .unwrap();
+        assert_eq!(config.controller_transport, ControllerTransport::Tcp);
+        assert!(!config.controller_transport.is_serial());
+        assert_eq!(config.controller_tcp_addr, "0.0.0.0:9100".parse().unwrap());
+        assert!(!config.controller_tcp_connect);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_controller_unix_config() {
+        let config: BridgeConfig = toml::from_str("controller_transport = \"unix\"").unwrap();
+        assert_eq!(config.controller_transport, ControllerTransport::Unix);
+        assert!(!config.controller_transport.is_serial());
+        assert_eq!(
+            default_unix_socket_path(Some("/run/user/1000".into()), &config),
+            PathBuf::from("/run/user/1000/oc-bridge-controller-default.sock")
+        );
+        let mut stage = config.clone();
+        stage.instance_id = Some("stage".to_string());
+        assert_eq!(
+            default_unix_socket_path(None, &stage),
+            std::env::temp_dir().join("oc-bridge-controller-stage.sock")
+        );
+
+        let config: BridgeConfig =
+            toml::from_str("controller_unix_socket = \"/run/sim.sock\"").unwrap();
+        assert_eq!(config.unix_socket_path(), PathBuf::from("/run/sim.sock"));
+    }
+
+    #[test]
+    fn test_tls_config() {
+        assert_eq!(BridgeConfig::default().tls, None);
+
+        let config: BridgeConfig =
+            toml::from_str("[tls]\ncert_pem = \"cert.pem\"\nkey_pem = \"key.pem\"").unwrap();
+        let tls = config.tls.unwrap();
+        assert_eq!(tls.cert_pem, PathBuf::from("cert.pem"));
+        assert_eq!(tls.key_pem, PathBuf::from("key.pem"));
+    }
+
     // =========================================================================
     // Host transport serialization tests
     // =========================================================================
@@ -714,15 +1857,23 @@ mod tests {
                 control_port: 9106,
                 duplicate_guard_enabled: true,
                 duplicate_guard_window_ms: 12,
+                ..BridgeConfig::default()
             },
             logs: LogsConfig {
                 max_entries: 500,
                 export_max: 5000,
+                export_format: ExportFormat::JsonLines,
+                clipboard_history_size: 5,
+                file_logging: true,
+                log_dir: Some(PathBuf::from("/var/log/oc-bridge")),
                 ..LogsConfig::default()
             },
             ui: UiConfig {
                 default_filter: "Protocol".to_string(),
+                word_wrap: true,
+                status_message_timeout_secs: 5,
             },
+            ..Config::default()
         };
 
         // Serialize to TOML
@@ -757,7 +1908,15 @@ mod tests {
         assert_eq!(restored.bridge.log_broadcast_port, 9105);
         assert_eq!(restored.logs.max_entries, 500);
         assert_eq!(restored.logs.export_max, 5000);
+        assert_eq!(restored.logs.export_format, ExportFormat::JsonLines);
+        assert!(restored.logs.file_logging);
+        assert_eq!(
+            restored.logs.log_dir.as_deref(),
+            Some(Path::new("/var/log/oc-bridge"))
+        );
         assert_eq!(restored.ui.default_filter, "Protocol");
+        assert!(restored.ui.word_wrap);
+        assert_eq!(restored.ui.status_message_timeout_secs, 5);
     }
 
     #[test]
@@ -801,6 +1960,276 @@ host_udp_port = 9500
         assert_eq!(config.ui.default_filter, "All");
     }
 
+    // =========================================================================
+    // Include tests
+    // =========================================================================
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "oc-bridge-config-{}-{}-{}",
+            name,
+            std::process::id(),
+            ts
+        ));
+        fs::create_dir_all(dir.join("devices")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_from_path_merges_included_device_presets() {
+        let dir = unique_temp_dir("include");
+        fs::write(
+            dir.join("config.toml"),
+            r#"
+include = ["devices/*.toml", "missing/*.toml"]
+
+[bridge]
+device_preset = "studio"
+
+[device_presets.studio]
+name = "Studio (main)"
+vid = 0x16C0
+pid_list = [0x0489]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("devices").join("lab.toml"),
+            r#"
+[device_presets.studio]
+name = "Studio (included)"
+vid = 0x1234
+pid_list = [1]
+
+[device_presets.lab]
+name = "Lab"
+vid = 0x1234
+pid_list = [2, 3]
+"#,
+        )
+        .unwrap();
+
+        let config = load_from_path(&dir.join("config.toml"), None).unwrap();
+
+        let studio = config.device_preset("studio").unwrap();
+        assert_eq!(studio.name, "Studio (main)");
+        assert_eq!(studio.vid, 0x16C0);
+        let lab = config.device_preset("lab").unwrap();
+        assert_eq!(lab.pid_list, vec![2, 3]);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_load_from_path_skips_circular_include() {
+        let dir = unique_temp_dir("cycle");
+        fs::write(dir.join("config.toml"), "include = [\"devices/a.toml\"]\n").unwrap();
+        fs::write(
+            dir.join("devices").join("a.toml"),
+            r#"
+include = ["../config.toml", "a.toml"]
+
+[device_presets.a]
+name = "A"
+vid = 1
+pid_list = [1]
+"#,
+        )
+        .unwrap();
+
+        let config = load_from_path(&dir.join("config.toml"), None).unwrap();
+        assert_eq!(config.device_presets.len(), 1);
+        assert!(config.device_presets.contains_key("a"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_load_from_path_profile_overrides_keys() {
+        let dir = unique_temp_dir("profile");
+        fs::write(
+            dir.join("config.toml"),
+            "[bridge]\nhost_udp_port = 9000\ncontrol_port = 7999\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("stage.toml"),
+            "[bridge]\nhost_udp_port = 9100\nhost_transport = \"both\"\n",
+        )
+        .unwrap();
+
+        let config =
+            load_from_path(&dir.join("config.toml"), Some(&dir.join("stage.toml"))).unwrap();
+        assert_eq!(config.bridge.host_udp_port, 9100);
+        assert_eq!(config.bridge.host_transport, HostTransport::Both);
+        // Keys the profile does not set come from the main config
+        assert_eq!(config.bridge.control_port, 7999);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_profile_sections_skip_per_instance_values() {
+        let table: toml::Table = "include = [\"presets/*.toml\"]\n\
+             [bridge]\ninstance_id = \"stage\"\nhost_udp_port = 9100\n\
+             [logs]\nmax_entries = 500\n[ui]\ntheme = \"light\"\n"
+            .parse()
+            .unwrap();
+
+        let sections = profile_sections(table);
+        assert!(!sections.contains_key("bridge"));
+        assert!(!sections.contains_key("include"));
+        assert_eq!(sections["logs"]["max_entries"].as_integer(), Some(500));
+        assert_eq!(sections["ui"]["theme"].as_str(), Some("light"));
+    }
+
+    #[test]
+    fn test_bind_interface_and_remote_access_warning() {
+        let mut config = BridgeConfig::default();
+        assert!(config.bind_ip().unwrap().is_loopback());
+        assert!(config.remote_access_warning().is_none());
+
+        config.bind_interface = "0.0.0.0".to_string();
+        assert!(config.remote_access_warning().is_some());
+
+        let parsed: Config = toml::from_str(
+            "[bridge]\nbind_interface = \"0.0.0.0\"\nallowed_remote_cidrs = [\"192.168.1.0/24\"]\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.bridge.allowed_remote_cidrs.len(), 1);
+        assert!(parsed.bridge.remote_access_warning().is_none());
+
+        config.bind_interface = "lan0".to_string();
+        assert!(config.bind_ip().is_err());
+    }
+
+    #[test]
+    fn test_device_preset_serial_signals() {
+        let preset: DevicePresetFile = toml::from_str(
+            r#"
+[device]
+name = "Uno"
+vid = 0x2341
+pid_list = [0x0043]
+serial_on_connect = { dtr = false }
+serial_on_disconnect = { dtr = false, rts = false }
+post_connect_delay_ms = 200
+"#,
+        )
+        .unwrap();
+        let device = preset.device;
+        assert_eq!(device.serial_on_connect.dtr, Some(false));
+        assert_eq!(device.serial_on_connect.rts, None);
+        assert_eq!(
+            device.serial_on_connect.inverted(),
+            SerialSignals {
+                dtr: Some(true),
+                rts: None
+            }
+        );
+        assert!(!device.serial_on_disconnect.is_empty());
+        assert_eq!(device.post_connect_delay_ms, 200);
+    }
+
+    #[test]
+    fn test_set_serial_port_edits_the_bridge_table_in_place() {
+        let dir = unique_temp_dir("set-serial-port");
+        let path = dir.join("config.toml");
+        fs::write(
+            &path,
+            "[bridge]\n# picked with Shift+L\nserial_port = \"\"\n\n[logs]\nserial_port = 1\n",
+        )
+        .unwrap();
+        set_serial_port(&path, "/dev/ttyACM0").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[bridge]\n# picked with Shift+L\nserial_port = \"/dev/ttyACM0\"\n\n[logs]\nserial_port = 1\n"
+        );
+
+        fs::write(&path, "[logs]\nmax_entries = 10\n").unwrap();
+        set_serial_port(&path, r"\\.\COM10").unwrap();
+        let cfg = load_from_path(&path, None).unwrap();
+        assert_eq!(cfg.bridge.serial_port, r"\\.\COM10");
+        assert_eq!(cfg.logs.max_entries, 10);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_serial_baud_rate_validated_and_overridden_by_device() {
+        let mut config = BridgeConfig::default();
+        assert_eq!(config.serial_baud_rate(None).unwrap(), 115200);
+
+        let mut device = detection_config("", None).device_presets["synth"].clone();
+        device.baud_rate = Some(9600);
+        assert_eq!(config.serial_baud_rate(Some(&device)).unwrap(), 9600);
+
+        config.baud_rate = 12345;
+        assert!(matches!(
+            config.serial_baud_rate(None),
+            Err(BridgeError::ConfigValidation {
+                field: "baud_rate",
+                ..
+            })
+        ));
+        device.baud_rate = Some(31250); // MIDI DIN: not a termios speed
+        assert!(config.serial_baud_rate(Some(&device)).is_err());
+    }
+
+    #[test]
+    fn test_keybindings_validated_falls_back_on_invalid_and_clashing_keys() {
+        let (keys, warnings) = KeyBindings::default().validated();
+        assert_eq!(keys, KeyBindings::default());
+        assert!(warnings.is_empty());
+
+        let custom = KeyBindings {
+            scroll_up: "H".into(),
+            quit: "ctrl+c".into(),
+            copy: "x".into(),
+            ..KeyBindings::default()
+        };
+        let (keys, warnings) = custom.validated();
+        assert_eq!(keys.scroll_up, "h");
+        assert_eq!(keys.quit, "q");
+        // Clashes with the default `cut` key
+        assert_eq!(keys.copy, "c");
+        assert_eq!(warnings.len(), 2);
+
+        // Shift+L lists ports whatever `l` is bound to
+        let shadowed = KeyBindings {
+            scroll_down: "l".into(),
+            ..KeyBindings::default()
+        };
+        let (keys, warnings) = shadowed.validated();
+        assert_eq!(keys.scroll_down, "j");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Shift+L"), "{}", warnings[0]);
+
+        // Swapping two keys is not a clash
+        let swapped = KeyBindings {
+            copy: "x".into(),
+            cut: "c".into(),
+            ..KeyBindings::default()
+        };
+        let (keys, warnings) = swapped.validated();
+        assert_eq!((keys.copy.as_str(), keys.cut.as_str()), ("x", "c"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("stage").is_ok());
+        assert!(validate_profile_name("dev-2.local_x").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name(".hidden").is_err());
+        assert!(validate_profile_name("../config").is_err());
+        assert!(validate_profile_name("a/b").is_err());
+    }
+
     #[test]
     fn test_effective_instance_id_sanitizes_invalid_chars() {
         let config = BridgeConfig {
@@ -809,4 +2238,78 @@ host_udp_port = 9500
         };
         assert_eq!(effective_instance_id(&config), "bitwig_hw_17081760");
     }
+
+    fn usb_port(name: &str, vid: u16, pid: u16, serial: &str) -> serialport::SerialPortInfo {
+        serialport::SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: serialport::SerialPortType::UsbPort(serialport::UsbPortInfo {
+                vid,
+                pid,
+                serial_number: Some(serial.to_string()),
+                manufacturer: None,
+                product: None,
+            }),
+        }
+    }
+
+    fn detection_config(serial_port: &str, preset: Option<&str>) -> Config {
+        let mut cfg = Config::default();
+        cfg.bridge.serial_port = serial_port.to_string();
+        cfg.bridge.device_preset = preset.map(str::to_string);
+        cfg.device_presets.insert(
+            "synth".to_string(),
+            DeviceConfig {
+                name: "Synth".to_string(),
+                vid: 0x1234,
+                pid_list: vec![0x0001],
+                name_hint: PlatformNameHint::default(),
+                udev_rules: None,
+                udev_rules_filename: None,
+                baud_rate: None,
+                serial_on_connect: SerialSignals::default(),
+                serial_on_disconnect: SerialSignals::default(),
+                post_connect_delay_ms: 0,
+            },
+        );
+        cfg
+    }
+
+    #[test]
+    fn test_detect_serial_priority() {
+        let ports = [
+            usb_port("/dev/ttyACM0", 0x16C0, 0x0483, "teensy"),
+            usb_port("/dev/ttyACM1", 0x1234, 0x0001, "synth"),
+            usb_port("/dev/ttyUSB0", 0x0403, 0x6001, "ftdi"),
+        ];
+
+        // An existing configured port wins over any preset
+        let cfg = detection_config("/dev/ttyUSB0", Some("synth"));
+        assert_eq!(
+            detect_serial_among(&cfg, &ports).as_deref(),
+            Some("/dev/ttyUSB0")
+        );
+        assert_eq!(
+            detect_serial_all_among(&cfg, &ports),
+            vec!["/dev/ttyUSB0", "/dev/ttyACM1"]
+        );
+
+        // A missing configured port falls through to the preset
+        let cfg = detection_config("/dev/ttyOC-missing", Some("synth"));
+        assert_eq!(
+            detect_serial_among(&cfg, &ports).as_deref(),
+            Some("/dev/ttyACM1")
+        );
+
+        // No preset: the built-in Teensy preset
+        let cfg = detection_config("", None);
+        assert_eq!(
+            detect_serial_among(&cfg, &ports).as_deref(),
+            Some("/dev/ttyACM0")
+        );
+
+        // A preset that matches nothing does not fall back to Teensy
+        let cfg = detection_config("", Some("synth"));
+        assert_eq!(detect_serial_among(&cfg, &ports[..1]), None);
+        assert!(detect_serial_all_among(&cfg, &ports[..1]).is_empty());
+    }
 }
@@ -0,0 +1,344 @@
+//! `OC_BRIDGE_*` environment overrides
+//!
+//! For Docker and systemd units: every scalar `[bridge]` and `[logs]` field
+//! can be set without editing the TOML file.
+//!
+//! - `[bridge]` field `host_udp_port` → `OC_BRIDGE_HOST_UDP_PORT`
+//! - `[logs]` field `max_entries` → `OC_BRIDGE_LOGS_MAX_ENTRIES`
+//!
+//! Values are read with the type of the field: booleans accept
+//! `1/0/true/false/yes/no`, lists are comma-separated (or a TOML array).
+//! Unset optional fields take the first type that fits: integer, boolean,
+//! then string. Nested tables (`[bridge.batch]`, ...) are not covered. An
+//! invalid value is logged and the file value is kept.
+//!
+//! Precedence: a set variable always wins over the config file (and the
+//! active profile). `OC_BRIDGE_PORT`, exported by `oc-bridge shell-init`,
+//! is only a fallback: it is used when no `serial_port` is configured (in
+//! the file or by `OC_BRIDGE_SERIAL_PORT`). Applied fields are recorded in
+//! `Config::env_overrides` (the TUI marks the serial port `(from env)`).
+
+use super::{BridgeConfig, Config, LogsConfig};
+use serde::de::{DeserializeOwned, Deserializer, Visitor};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tracing::warn;
+
+/// Prefix of `[bridge]` overrides
+const BRIDGE_PREFIX: &str = "OC_BRIDGE_";
+
+/// Prefix of `[logs]` overrides
+const LOGS_PREFIX: &str = "OC_BRIDGE_LOGS_";
+
+/// Serial port used when none is configured (see `oc-bridge shell-init`)
+const SERIAL_PORT_FALLBACK: &str = "OC_BRIDGE_PORT";
+
+/// Overlay `OC_BRIDGE_*` variables on a loaded config
+pub fn apply_env_overrides(cfg: &mut Config) {
+    apply_overrides(cfg, &|name| std::env::var(name).ok());
+}
+
+fn apply_overrides(cfg: &mut Config, var: &dyn Fn(&str) -> Option<String>) {
+    let mut applied = BTreeSet::new();
+    cfg.bridge = overlay::<BridgeConfig>(&cfg.bridge, BRIDGE_PREFIX, var, &mut applied, "bridge");
+    cfg.logs = overlay::<LogsConfig>(&cfg.logs, LOGS_PREFIX, var, &mut applied, "logs");
+    if cfg.bridge.serial_port.trim().is_empty() {
+        if let Some(port) = var(SERIAL_PORT_FALLBACK).filter(|p| !p.trim().is_empty()) {
+            cfg.bridge.serial_port = port.trim().to_string();
+            applied.insert("bridge.serial_port".to_string());
+        }
+    }
+    cfg.env_overrides = applied;
+}
+
+/// Fields of `section` with their `{prefix}{FIELD}` value applied
+///
+/// Each override is checked on its own, so one bad value does not discard
+/// the others. Applied fields are added to `applied` as `{name}.{field}`.
+fn overlay<T>(
+    section: &T,
+    prefix: &str,
+    var: &dyn Fn(&str) -> Option<String>,
+    applied: &mut BTreeSet<String>,
+    name: &str,
+) -> T
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    let Ok(mut table) = toml::Table::try_from(section) else {
+        return section.clone();
+    };
+    let mut result = section.clone();
+
+    let section_name = name;
+    for &key in field_names::<T>() {
+        let name = format!("{}{}", prefix, key.to_ascii_uppercase());
+        let Some(raw) = var(&name) else {
+            continue;
+        };
+        let current = table.get(key);
+        if current.is_some_and(toml::Value::is_table) {
+            warn!("{}: nested tables cannot be set from the environment", name);
+            continue;
+        }
+        let values = parse_value(&raw, current);
+        if values.is_empty() {
+            warn!(
+                "{}={:?}: invalid value, keeping the config file value",
+                name, raw
+            );
+            continue;
+        }
+
+        let mut error = None;
+        for value in values {
+            let mut candidate = table.clone();
+            candidate.insert(key.to_string(), value);
+            match candidate.clone().try_into::<T>() {
+                Ok(parsed) => {
+                    table = candidate;
+                    result = parsed;
+                    applied.insert(format!("{}.{}", section_name, key));
+                    error = None;
+                    break;
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+        if let Some(e) = error {
+            warn!(
+                "{}={:?}: {}, keeping the config file value",
+                name,
+                raw,
+                e.message()
+            );
+        }
+    }
+    result
+}
+
+/// Field names of a struct, including unset `Option` fields
+///
+/// Serde hands the field list to `deserialize_struct`; this deserializer
+/// records it and stops there.
+fn field_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldNames<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("field names only"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Read `raw` as the TOML type of `current`, empty if it does not parse
+///
+/// An unset optional field (`None`) has no type to go by: every reading of
+/// `raw` is returned, integer first, then boolean, then string.
+fn parse_value(raw: &str, current: Option<&toml::Value>) -> Vec<toml::Value> {
+    use toml::Value;
+
+    let raw = raw.trim();
+    let Some(current) = current else {
+        return [
+            raw.parse().ok().map(Value::Integer),
+            parse_bool(raw).map(Value::Boolean),
+            Some(Value::String(raw.to_string())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+    };
+    parse_typed(raw, current).into_iter().collect()
+}
+
+fn parse_typed(raw: &str, current: &toml::Value) -> Option<toml::Value> {
+    use toml::Value;
+
+    Some(match current {
+        Value::Boolean(_) => Value::Boolean(parse_bool(raw)?),
+        Value::Integer(_) => Value::Integer(raw.parse().ok()?),
+        Value::Float(_) => Value::Float(raw.parse().ok()?),
+        Value::Array(_) if raw.starts_with('[') => parse_toml_value(raw)?,
+        Value::Array(_) => Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        ),
+        Value::Datetime(_) => parse_toml_value(raw)?,
+        Value::String(_) | Value::Table(_) => Value::String(raw.to_string()),
+    })
+}
+
+fn parse_toml_value(raw: &str) -> Option<toml::Value> {
+    let mut table: toml::Table = toml::from_str(&format!("v = {}", raw)).ok()?;
+    table.remove("v")
+}
+
+/// `1/0/true/false/yes/no`, case-insensitive
+fn parse_bool(raw: &str) -> Option<bool> {
+    match raw.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ControllerTransport, HostTransport};
+    use std::collections::HashMap;
+
+    fn apply(vars: &[(&str, &str)]) -> Config {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let mut cfg = Config::default();
+        apply_overrides(&mut cfg, &|name| vars.get(name).cloned());
+        cfg
+    }
+
+    #[test]
+    fn test_env_overrides_scalar_fields() {
+        let cfg = apply(&[
+            ("OC_BRIDGE_HOST_UDP_PORT", "9100"),
+            ("OC_BRIDGE_CONTROLLER_TCP_CONNECT", "yes"),
+            ("OC_BRIDGE_LOG_BROADCAST_COMPRESS", "1"),
+            ("OC_BRIDGE_SERIAL_PORT", "/dev/ttyACM3"),
+            ("OC_BRIDGE_INSTANCE_ID", "stage"),
+            ("OC_BRIDGE_CONTROLLER_TRANSPORT", "tcp"),
+            ("OC_BRIDGE_CONTROLLER_TCP_ADDR", "10.0.0.2:8200"),
+            ("OC_BRIDGE_HOST_FAILOVER_ORDER", "websocket, udp"),
+            ("OC_BRIDGE_ALLOWED_REMOTE_CIDRS", "[\"192.168.1.0/24\"]"),
+            ("OC_BRIDGE_LOGS_MAX_ENTRIES", "5000"),
+            ("OC_BRIDGE_LOGS_FILE_ENABLED", "No"),
+        ]);
+        let bridge = &cfg.bridge;
+        assert_eq!(bridge.host_udp_port, 9100);
+        assert!(bridge.controller_tcp_connect);
+        assert!(bridge.log_broadcast_compress);
+        assert_eq!(bridge.serial_port, "/dev/ttyACM3");
+        assert_eq!(bridge.instance_id.as_deref(), Some("stage"));
+        assert_eq!(bridge.controller_transport, ControllerTransport::Tcp);
+        assert_eq!(bridge.controller_tcp_addr, "10.0.0.2:8200".parse().unwrap());
+        assert_eq!(
+            bridge.host_failover_order,
+            [HostTransport::WebSocket, HostTransport::Udp]
+        );
+        assert_eq!(bridge.allowed_remote_cidrs.len(), 1);
+        assert_eq!(cfg.logs.max_entries, 5000);
+        assert!(!cfg.logs.file_enabled);
+
+        assert!(cfg.env_overrides.contains("bridge.serial_port"));
+        assert!(cfg.env_overrides.contains("logs.max_entries"));
+        assert!(!cfg.env_overrides.contains("bridge.control_port"));
+    }
+
+    #[test]
+    fn test_env_port_is_a_fallback_for_unconfigured_serial_port() {
+        let cfg = apply(&[("OC_BRIDGE_PORT", "/dev/ttyACM1")]);
+        assert_eq!(cfg.bridge.serial_port, "/dev/ttyACM1");
+        assert!(cfg.env_overrides.contains("bridge.serial_port"));
+
+        let cfg = apply(&[
+            ("OC_BRIDGE_PORT", "/dev/ttyACM1"),
+            ("OC_BRIDGE_SERIAL_PORT", "/dev/ttyACM3"),
+        ]);
+        assert_eq!(cfg.bridge.serial_port, "/dev/ttyACM3");
+
+        // A port from the config file is kept
+        let mut cfg = Config::default();
+        cfg.bridge.serial_port = "COM3".to_string();
+        apply_overrides(&mut cfg, &|name| {
+            (name == "OC_BRIDGE_PORT").then(|| "/dev/ttyACM1".to_string())
+        });
+        assert_eq!(cfg.bridge.serial_port, "COM3");
+        assert!(cfg.env_overrides.is_empty());
+
+        assert!(apply(&[]).env_overrides.is_empty());
+    }
+
+    #[test]
+    fn test_env_unset_optional_fields_infer_their_type() {
+        let cfg = apply(&[
+            ("OC_BRIDGE_METRICS_PORT", "9100"),
+            ("OC_BRIDGE_LOGS_WS_STREAM_PORT", "9200"),
+            ("OC_BRIDGE_INSTANCE_ID", "1"),
+        ]);
+        assert_eq!(cfg.bridge.metrics_port, Some(9100));
+        assert_eq!(cfg.logs.ws_stream_port, Some(9200));
+        // Not an integer field: falls through to the string reading
+        assert_eq!(cfg.bridge.instance_id.as_deref(), Some("1"));
+        assert!(cfg.env_overrides.contains("bridge.metrics_port"));
+        assert!(cfg.env_overrides.contains("logs.ws_stream_port"));
+
+        let cfg = apply(&[("OC_BRIDGE_METRICS_PORT", "ninety-one")]);
+        assert_eq!(cfg.bridge.metrics_port, None);
+        assert!(cfg.env_overrides.is_empty());
+    }
+
+    #[test]
+    fn test_env_invalid_value_keeps_file_value() {
+        let defaults = Config::default();
+        let cfg = apply(&[
+            ("OC_BRIDGE_HOST_UDP_PORT", "70000"),
+            ("OC_BRIDGE_CONTROLLER_TCP_CONNECT", "maybe"),
+            ("OC_BRIDGE_CONTROLLER_TRANSPORT", "carrier-pigeon"),
+            ("OC_BRIDGE_BATCH", "1"),
+            ("OC_BRIDGE_CONTROL_PORT", "7100"),
+        ]);
+        assert_eq!(cfg.bridge.host_udp_port, defaults.bridge.host_udp_port);
+        assert_eq!(
+            cfg.bridge.controller_tcp_connect,
+            defaults.bridge.controller_tcp_connect
+        );
+        assert_eq!(
+            cfg.bridge.controller_transport,
+            defaults.bridge.controller_transport
+        );
+        assert_eq!(cfg.bridge.batch, defaults.bridge.batch);
+        // Valid overrides next to invalid ones still apply
+        assert_eq!(cfg.bridge.control_port, 7100);
+        assert_eq!(
+            cfg.env_overrides.iter().collect::<Vec<_>>(),
+            ["bridge.control_port"]
+        );
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        for raw in ["1", "true", "YES", "True"] {
+            assert_eq!(parse_bool(raw), Some(true));
+        }
+        for raw in ["0", "false", "no", "NO"] {
+            assert_eq!(parse_bool(raw), Some(false));
+        }
+        assert_eq!(parse_bool("on"), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Config file watching for the TUI
+//!
+//! Watches the config directory (main file, profiles, included device
+//! presets) and publishes each successfully reloaded `Config` on a `watch`
+//! channel. Editors often save in several steps (truncate, write, rename):
+//! a half-written file fails to parse and is skipped; the next event of the
+//! same save brings the complete one.
+
+use super::Config;
+use crate::error::{BridgeError, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use tokio::sync::watch;
+use tracing::debug;
+
+/// Reloads the config when a `.toml` file under the watched directory changes
+pub struct ConfigWatcher {
+    /// Stops watching when dropped
+    _watcher: RecommendedWatcher,
+    rx: watch::Receiver<Config>,
+}
+
+impl ConfigWatcher {
+    /// Watch `dir` recursively, starting from `initial`
+    ///
+    /// `reload` runs on the watcher thread for each change; errors are skipped.
+    pub fn new<F>(dir: &Path, initial: Config, reload: F) -> Result<Self>
+    where
+        F: Fn() -> Result<Config> + Send + 'static,
+    {
+        let (tx, rx) = watch::channel(initial);
+        let map_err = |source| BridgeError::ConfigWatch {
+            path: dir.to_path_buf(),
+            source,
+        };
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if !is_toml_change(&event) {
+                return;
+            }
+            match reload() {
+                Ok(config) => {
+                    tx.send_replace(config);
+                }
+                Err(e) => debug!("Config reload skipped: {}", e),
+            }
+        })
+        .map_err(map_err)?;
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(map_err)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            rx,
+        })
+    }
+
+    /// Latest reloaded config, if any since the last call
+    pub fn changed(&mut self) -> Option<Config> {
+        if !self.rx.has_changed().unwrap_or(false) {
+            return None;
+        }
+        Some(self.rx.borrow_and_update().clone())
+    }
+}
+
+/// A `.toml` file was created, written, renamed or removed
+fn is_toml_change(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event
+        .paths
+        .iter()
+        .any(|p| p.extension().is_some_and(|ext| ext == "toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_from_path;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_watcher_publishes_valid_edits() {
+        let dir = std::env::temp_dir().join(format!("oc-bridge-watch-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "[logs]\nmax_entries = 100\n").unwrap();
+
+        let initial = load_from_path(&path, None).unwrap();
+        let reload_path = path.clone();
+        let mut watcher =
+            ConfigWatcher::new(&dir, initial, move || load_from_path(&reload_path, None)).unwrap();
+        assert!(watcher.changed().is_none());
+
+        // A half-written file is skipped, the complete one is published
+        std::fs::write(&path, "[logs\n").unwrap();
+        std::fs::write(&path, "[logs]\nmax_entries = 300\n").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut max_entries = 100;
+        while max_entries != 300 && Instant::now() < deadline {
+            if let Some(config) = watcher.changed() {
+                max_entries = config.logs.max_entries;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(max_entries, 300);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
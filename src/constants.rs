@@ -11,6 +11,7 @@
 //
 //   800X = Controller UDP (native apps): 8000=core, 8001=bitwig
 //   810X = Controller WS (wasm apps):    8100=core, 8101=bitwig
+//   8200 = Controller TCP (reliable links, e.g. Wi-Fi)
 //   900X = Host UDP:                     9000=hardware, 9001=native, 9002=wasm
 // =============================================================================
 
@@ -22,6 +23,16 @@ pub const DEFAULT_CONTROLLER_UDP_PORT: u16 = 8000;
 /// Note: Apps override this per-app (8100=core, 8101=bitwig)
 pub const DEFAULT_CONTROLLER_WEBSOCKET_PORT: u16 = 8100;
 
+/// Default TCP port for controller (length-prefixed stream)
+pub const DEFAULT_CONTROLLER_TCP_PORT: u16 = 8200;
+
+/// Default UNIX socket name for controller (length-prefixed stream)
+///
+/// Created as `<name>-<instance_id>.sock` in `$XDG_RUNTIME_DIR` (else the
+/// temp directory), so users and instances do not share it.
+#[cfg(unix)]
+pub const DEFAULT_CONTROLLER_UNIX_SOCKET_NAME: &str = "oc-bridge-controller";
+
 // =============================================================================
 // Network - Host Side (destination: Bitwig, DAW)
 // =============================================================================
@@ -38,6 +49,10 @@ pub const DEFAULT_HOST_UDP_PORT: u16 = 9000;
 /// Default WebSocket port for host communication (future use)
 pub const DEFAULT_HOST_WEBSOCKET_PORT: u16 = 8000;
 
+/// Named pipe path for host communication (Windows only)
+#[cfg(windows)]
+pub const HOST_PIPE_NAME: &str = r"\\.\pipe\oc-bridge-host";
+
 // =============================================================================
 // Network - Logs
 // =============================================================================
@@ -50,6 +65,15 @@ pub const DEFAULT_LOG_BROADCAST_PORT: u16 = 9999;
 /// Convention: 7999 = control plane (local only)
 pub const DEFAULT_CONTROL_PORT: u16 = 7999;
 
+/// Default UDP port answering LAN discovery scans (`discovery.rs`)
+pub const DEFAULT_DISCOVERY_PORT: u16 = 9099;
+
+/// How long a network scan collects answers
+pub const DISCOVERY_SCAN_TIMEOUT_MS: u64 = 500;
+
+/// Pause after a failed receive on the discovery responder socket
+pub const DISCOVERY_RECV_BACKOFF_MS: u64 = 100;
+
 // =============================================================================
 // Timing - Reconnection
 // =============================================================================
@@ -60,12 +84,18 @@ pub const RECONNECT_DELAY_SECS: u64 = 2;
 /// Delay after connection loss before retry (seconds)
 pub const POST_DISCONNECT_DELAY_SECS: u64 = 3;
 
+/// Interval between retries of the preferred host transport after failover (seconds)
+pub const HOST_FAILOVER_RETRY_SECS: u64 = 10;
+
+/// How long a runtime host transport switch waits for a shared port to be released (ms)
+pub const HOST_SWITCH_TIMEOUT_MS: u64 = 1000;
+
+/// How long `pre_start_hook` / `post_stop_hook` may run (seconds)
+pub const HOOK_TIMEOUT_SECS: u64 = 5;
+
 /// Status message display timeout (seconds)
 pub const STATUS_MESSAGE_TIMEOUT_SECS: u64 = 2;
 
-/// Minimum interval between rate updates (seconds)
-pub const RATE_UPDATE_MIN_INTERVAL_SECS: f64 = 0.1;
-
 // =============================================================================
 // Retry
 // =============================================================================
@@ -89,6 +119,36 @@ pub const PAGE_SCROLL_LINES: usize = 10;
 /// Auto-scroll threshold (lines from bottom)
 pub const AUTO_SCROLL_THRESHOLD: usize = 5;
 
+/// Bound on each blocking query made while the TUI starts (milliseconds)
+pub const STARTUP_QUERY_TIMEOUT_MS: u64 = 500;
+
+/// How often the recording size shown in the status bar is refreshed (seconds)
+pub const RECORDING_SIZE_REFRESH_SECS: u64 = 5;
+
+/// Daemon status update interval requested by the TUI (milliseconds)
+pub const DAEMON_WATCH_INTERVAL_MS: u64 = 500;
+
+/// Delay before the TUI reconnects its daemon status stream (milliseconds)
+pub const DAEMON_WATCH_RETRY_MS: u64 = 600;
+
+/// Entries queued per WebSocket log stream client before it lags
+pub const LOG_STREAM_CAPACITY: usize = 1024;
+
+/// Pause after a failed accept on the log stream (e.g. out of file descriptors)
+pub const LOG_STREAM_ACCEPT_BACKOFF_MS: u64 = 100;
+
+/// Quiet time after which a deduplicated run reports its repeat count (ms)
+pub const DEDUP_FLUSH_IDLE_MS: u64 = 1000;
+
+/// Message types listed in the TUI message stats (Tab)
+pub const TOP_MESSAGES: usize = 10;
+
+/// Window over which the TUI measures log packet loss (seconds)
+pub const LOG_LINK_SAMPLE_SECS: u64 = 5;
+
+/// Log packet loss above which the TUI logs a warning (percent)
+pub const LOG_LINK_LOSS_WARN_PERCENT: f64 = 1.0;
+
 /// Timeout before considering log connection lost (seconds)
 pub const LOG_CONNECTION_TIMEOUT_SECS: u64 = 5;
 
@@ -105,12 +165,28 @@ pub const SIDEBAR_WIDTH: u16 = 16;
 /// UDP receive buffer size
 pub const UDP_BUFFER_SIZE: usize = 4096;
 
+/// Interval between stats snapshots appended by the daemon (seconds)
+pub const STATS_SNAPSHOT_INTERVAL_SECS: u64 = 60;
+
 /// Channel capacity for async message passing
 pub const CHANNEL_CAPACITY: usize = 256;
 
+/// Host queue depth below which a paused controller reader resumes
+pub const BACKPRESSURE_LOW_WATER: usize = CHANNEL_CAPACITY / 4;
+
 // =============================================================================
 // Serial
 // =============================================================================
 
 /// Consecutive zero-byte reads before assuming port disconnected
 pub const SERIAL_DISCONNECT_THRESHOLD: u32 = 10;
+
+/// Default serial baud rate (USB CDC devices ignore it and run at USB speed)
+pub const DEFAULT_BAUD_RATE: u32 = 115200;
+
+/// Baud rates accepted in the config (termios standard speeds)
+pub const STANDARD_BAUD_RATES: &[u32] = &[
+    50, 75, 110, 134, 150, 200, 300, 600, 1200, 1800, 2400, 4800, 9600, 19200, 38400, 57600,
+    115200, 230400, 460800, 500000, 576000, 921600, 1000000, 1152000, 1500000, 2000000, 2500000,
+    3000000, 3500000, 4000000,
+];
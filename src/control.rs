@@ -5,21 +5,53 @@
 //!
 //! This is intentionally minimal:
 //! - TCP on 127.0.0.1 only
-//! - One JSON request per connection
-//! - Small command set: pause/resume/status
+//! - One JSON request per connection; `batch` runs several commands on it
+//!   (e.g. pause, status, resume) and returns one response per command
+//! - Small command set: pause/resume/status, plus switch-host to cycle the
+//!   host transport of a running bridge
+//! - `watch` keeps the connection open and streams one status line every
+//!   `interval_ms` until the client disconnects
+//! - `stats` answers with the per-message-type counters
+//! - `subscribe` keeps the connection open and streams `ControlEvent`s as
+//!   JSON lines: serial port changes and log entries as they happen, a
+//!   `stats` line every second and a `ping` every 5 s
+//! - `profile` (feature `profiling`) samples the daemon for
+//!   `PROFILE_DURATION` and answers with the flamegraph SVG path
 
+use crate::bridge::stats::{MessageStats, Stats};
+use crate::config::HostTransport;
 use crate::error::{BridgeError, Result};
+use crate::logging::LogEntry;
+use crate::transport::HostClients;
 use serde::{Deserialize, Serialize};
+use std::io::BufRead;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::ops::ControlFlow;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
+use tracing::{info, warn};
 
 pub const CONTROL_SCHEMA: u32 = 1;
 
+/// `watch` update interval when the request has no `interval_ms`
+const WATCH_DEFAULT_INTERVAL_MS: u64 = 1000;
+/// Fastest accepted `watch` update interval
+const WATCH_MIN_INTERVAL_MS: u64 = 50;
+/// Time allowed for all commands of a `batch`
+const BATCH_MAX_DURATION: Duration = Duration::from_secs(30);
+/// Length of a `profile` capture
+pub const PROFILE_DURATION: Duration = Duration::from_secs(5);
+/// Events buffered per `subscribe` client before it starts missing some
+const EVENT_CAPACITY: usize = 256;
+/// Interval of the `stats` events sent to subscribers
+const SUBSCRIBE_STATS_INTERVAL: Duration = Duration::from_secs(1);
+/// Interval of the `ping` events sent to subscribers
+const SUBSCRIBE_PING_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SerialRunState {
     Running,
@@ -32,22 +64,64 @@ impl SerialRunState {
     }
 }
 
+/// One line of a `subscribe` stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ControlEvent {
+    SerialConnected {
+        port: String,
+    },
+    SerialDisconnected,
+    /// Traffic totals since the daemon started
+    Stats {
+        rx_bytes: u64,
+        tx_bytes: u64,
+        rx_messages: u64,
+        tx_messages: u64,
+        /// Batched serial writes carrying `tx_messages`
+        #[serde(default)]
+        tx_batches: u64,
+        /// Serial connections restored after a loss
+        #[serde(default)]
+        reconnections: u64,
+        /// Messages dropped because a relay channel was full
+        #[serde(default)]
+        channel_drops: u64,
+    },
+    Log {
+        entry: LogEntry,
+    },
+    /// Heartbeat, so idle clients can tell the daemon is alive
+    Ping,
+}
+
 #[derive(Clone)]
 pub struct ControlState {
     desired_tx: watch::Sender<SerialRunState>,
     serial_open_rx: watch::Receiver<bool>,
     resolved_serial_port_rx: watch::Receiver<Option<String>>,
+    desired_host_tx: watch::Sender<HostTransport>,
+    active_host_rx: watch::Receiver<HostTransport>,
     shutdown: Arc<AtomicBool>,
     info: ControlInfo,
+    /// Request count and latency sink
+    stats: Option<Arc<Stats>>,
+    /// Host transport clients (reported in status)
+    host_clients: Option<Arc<HostClients>>,
+    /// Events for `subscribe` clients
+    events_tx: broadcast::Sender<ControlEvent>,
 }
 
 pub struct ControlRuntime {
     pub desired_rx: watch::Receiver<SerialRunState>,
     pub serial_open_tx: watch::Sender<bool>,
     pub resolved_serial_port_tx: watch::Sender<Option<String>>,
+    pub desired_host_rx: watch::Receiver<HostTransport>,
+    pub active_host_tx: watch::Sender<HostTransport>,
 }
 
-#[derive(Debug, Clone)]
+/// Identity of a running bridge (also its LAN discovery answer)
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControlInfo {
     pub pid: u32,
     pub version: String,
@@ -55,9 +129,16 @@ pub struct ControlInfo {
     pub instance_id: String,
     pub controller_serial: Option<String>,
     pub host_udp_port: u16,
+    pub host_pipe_name: Option<String>,
     pub log_broadcast_port: u16,
     pub control_port: u16,
     pub serial_supported: bool,
+    pub cobs_trace: bool,
+    /// Host transport configured at startup
+    pub host_transport: HostTransport,
+    /// Prometheus metrics port (`metrics_port`)
+    #[serde(default)]
+    pub metrics_port: Option<u16>,
 }
 
 impl ControlState {
@@ -65,22 +146,43 @@ impl ControlState {
         let (desired_tx, desired_rx) = watch::channel(SerialRunState::Running);
         let (serial_open_tx, serial_open_rx) = watch::channel(false);
         let (resolved_serial_port_tx, resolved_serial_port_rx) = watch::channel(None);
+        let (desired_host_tx, desired_host_rx) = watch::channel(info.host_transport);
+        let (active_host_tx, active_host_rx) = watch::channel(info.host_transport);
         (
             Self {
                 desired_tx,
                 serial_open_rx,
                 resolved_serial_port_rx,
+                desired_host_tx,
+                active_host_rx,
                 shutdown,
                 info,
+                stats: None,
+                host_clients: None,
+                events_tx: broadcast::channel(EVENT_CAPACITY).0,
             },
             ControlRuntime {
                 desired_rx,
                 serial_open_tx,
                 resolved_serial_port_tx,
+                desired_host_rx,
+                active_host_tx,
             },
         )
     }
 
+    /// Count requests and their latency in `stats`
+    pub fn with_stats(mut self, stats: Arc<Stats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Report the host transport clients tracked in `clients`
+    pub fn with_host_clients(mut self, clients: Arc<HostClients>) -> Self {
+        self.host_clients = Some(clients);
+        self
+    }
+
     pub fn set_desired(&self, state: SerialRunState) {
         let _ = self.desired_tx.send_replace(state);
     }
@@ -97,6 +199,11 @@ impl ControlState {
         self.resolved_serial_port_rx.borrow().clone()
     }
 
+    /// Host transport currently relaying (may differ from config after failover)
+    pub fn active_host(&self) -> HostTransport {
+        *self.active_host_rx.borrow()
+    }
+
     pub fn request_shutdown(&self) {
         self.shutdown.store(true, Ordering::SeqCst);
     }
@@ -104,6 +211,32 @@ impl ControlState {
     pub fn info(&self) -> &ControlInfo {
         &self.info
     }
+
+    /// Send `event` to the `subscribe` clients, if any
+    pub fn publish(&self, event: ControlEvent) {
+        let _ = self.events_tx.send(event);
+    }
+
+    /// Whether a `subscribe` client is connected (skip building events otherwise)
+    pub fn has_subscribers(&self) -> bool {
+        self.events_tx.receiver_count() > 0
+    }
+
+    /// Publish `serial_connected` / `serial_disconnected` as the resolved
+    /// serial port changes, until the bridge drops its sender
+    pub fn spawn_serial_events(&self) {
+        let state = self.clone();
+        let mut port_rx = self.resolved_serial_port_rx.clone();
+        tokio::spawn(async move {
+            while port_rx.changed().await.is_ok() {
+                let port = port_rx.borrow_and_update().clone();
+                state.publish(match port {
+                    Some(port) => ControlEvent::SerialConnected { port },
+                    None => ControlEvent::SerialDisconnected,
+                });
+            }
+        });
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -111,6 +244,15 @@ struct Request {
     #[serde(default)]
     schema: Option<u32>,
     cmd: String,
+    /// `watch` update interval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interval_ms: Option<u64>,
+    /// `batch` commands, run in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    commands: Vec<String>,
+    /// `batch`: stop after the first failed command
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    fail_fast: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -136,10 +278,55 @@ pub struct Response {
     pub resolved_serial_port: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub host_udp_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_pipe_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log_broadcast_port: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub control_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cobs_trace: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_transport: Option<HostTransport>,
+    /// Controller -> host rate over the last second (KB/s, `watch` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rx_rate_kbps: Option<f64>,
+    /// Host -> controller rate over the last second (KB/s, `watch` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_rate_kbps: Option<f64>,
+    /// Controller inter-arrival jitter (ms, mean absolute deviation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter_ms: Option<f64>,
+    /// Controller write channel fill at the last host send (0.0 to 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_fill_ratio: Option<f64>,
+    /// Deepest controller write queue seen (messages)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_write_queue_depth: Option<usize>,
+    /// Controller messages dropped for exceeding `max_message_size`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oversized_drops: Option<u64>,
+    /// Control plane requests handled so far
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_requests_total: Option<u64>,
+    /// Average control plane request latency (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_latency_ms_avg: Option<f64>,
+    /// Open WebSocket host connections (WebSocket / Both)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_ws_clients: Option<usize>,
+    /// Messages dropped for lagging WebSocket host clients (WebSocket / Both)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_ws_dropped: Option<u64>,
+    /// Last UDP host sender (UDP / Both)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_udp_peer: Option<String>,
+    /// Per-message-type counters, most bytes first (`stats` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_stats: Option<Vec<(String, MessageStats)>>,
+    /// Prometheus scrape URL, when `metrics_port` is set (`info` only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_url: Option<String>,
 }
 
 pub async fn bind_listener(port: u16) -> Result<TcpListener> {
@@ -158,20 +345,78 @@ pub async fn run_server_with_listener(
         let accept =
             tokio::time::timeout(std::time::Duration::from_millis(250), listener.accept()).await;
 
-        let Ok(Ok((stream, _))) = accept else {
+        let Ok(Ok((stream, client_addr))) = accept else {
             continue;
         };
 
         let st = state.clone();
         tokio::spawn(async move {
-            let _ = handle_connection(stream, st).await;
+            let _ = handle_connection(stream, client_addr, st).await;
         });
     }
 
     Ok(())
 }
 
-async fn handle_connection(mut stream: TcpStream, state: ControlState) -> Result<()> {
+/// Serve one request, tracing and counting it
+///
+/// Each request gets a `control_request` span with `cmd`, `client_addr`,
+/// `ok` and `duration_ms`. Requests slower than `SLOW_REQUEST` are warned
+/// about (usually a pause or host switch waiting for its ACK); `watch` and
+/// `subscribe` streams are long-lived by design and are neither warned about
+/// nor counted.
+#[tracing::instrument(
+    name = "control_request",
+    skip_all,
+    fields(
+        client_addr = %client_addr,
+        cmd = tracing::field::Empty,
+        ok = tracing::field::Empty,
+        duration_ms = tracing::field::Empty,
+    )
+)]
+async fn handle_connection(
+    mut stream: TcpStream,
+    client_addr: SocketAddr,
+    state: ControlState,
+) -> Result<()> {
+    const SLOW_REQUEST: Duration = Duration::from_millis(100);
+
+    let started = Instant::now();
+    let result = serve_request(&mut stream, &state).await;
+    let elapsed = started.elapsed();
+
+    let span = tracing::Span::current();
+    span.record("duration_ms", elapsed.as_millis() as u64);
+    let watch = matches!(&result, Ok((cmd, _)) if cmd == "watch" || cmd == "subscribe");
+    match &result {
+        Ok((cmd, ok)) => {
+            span.record("cmd", cmd.as_str());
+            span.record("ok", *ok);
+            info!("control request handled");
+        }
+        Err(e) => {
+            span.record("ok", false);
+            warn!("control request failed: {}", e);
+        }
+    }
+    if watch {
+        return Ok(());
+    }
+    if elapsed > SLOW_REQUEST {
+        warn!("slow control request: {} ms", elapsed.as_millis());
+    }
+    if let Some(stats) = &state.stats {
+        stats.record_control_request(elapsed);
+    }
+
+    result.map(|_| ())
+}
+
+/// Read one JSON request, execute it and write the response
+///
+/// Returns the normalized command and whether it succeeded.
+async fn serve_request(stream: &mut TcpStream, state: &ControlState) -> Result<(String, bool)> {
     // Read up to 4KB (one request)
     let mut buf = vec![0u8; 4096];
     let n = stream
@@ -195,14 +440,81 @@ async fn handle_connection(mut stream: TcpStream, state: ControlState) -> Result
     })?;
 
     let cmd = req.cmd.to_ascii_lowercase();
+    if cmd == "watch" {
+        let interval_ms = req
+            .interval_ms
+            .unwrap_or(WATCH_DEFAULT_INTERVAL_MS)
+            .max(WATCH_MIN_INTERVAL_MS);
+        serve_watch(stream, state, Duration::from_millis(interval_ms)).await?;
+        return Ok((cmd, true));
+    }
+
+    if cmd == "subscribe" {
+        serve_subscribe(stream, state).await?;
+        return Ok((cmd, true));
+    }
+
+    if cmd == "batch" {
+        let responses = serve_batch(&req.commands, req.fail_fast, state).await;
+        let ok = responses.iter().all(|r| r.ok);
+        write_response(stream, &responses).await?;
+        return Ok((cmd, ok));
+    }
+
+    let (ok, message) = execute(&cmd, state).await;
+    write_response(stream, &build_response(&cmd, state, ok, message)).await?;
+    Ok((cmd, ok))
+}
+
+/// Write one JSON line and close the connection
+async fn write_response(stream: &mut TcpStream, response: &impl Serialize) -> Result<()> {
+    let out = serde_json::to_vec(response).map_err(|e| BridgeError::ControlProtocol {
+        message: e.to_string(),
+    })?;
+
+    let _ = stream.write_all(&out).await;
+    let _ = stream.write_all(b"\n").await;
+    let _ = stream.shutdown().await;
+    Ok(())
+}
+
+/// Run `commands` in order within `BATCH_MAX_DURATION`, one response each
+///
+/// With `fail_fast`, stops after the first failure.
+async fn serve_batch(commands: &[String], fail_fast: bool, state: &ControlState) -> Vec<Response> {
+    let deadline = tokio::time::Instant::now() + BATCH_MAX_DURATION;
+    let mut responses = Vec::with_capacity(commands.len());
+    for cmd in commands {
+        let cmd = cmd.to_ascii_lowercase();
+        let (ok, message) = match cmd.as_str() {
+            "batch" | "watch" | "subscribe" => (false, Some(format!("{cmd} not allowed in batch"))),
+            _ => tokio::time::timeout_at(deadline, execute(&cmd, state))
+                .await
+                .unwrap_or_else(|_| {
+                    let limit = BATCH_MAX_DURATION.as_secs();
+                    (false, Some(format!("batch time limit ({limit}s) exceeded")))
+                }),
+        };
+        responses.push(build_response(&cmd, state, ok, message));
+        let expired = tokio::time::Instant::now() >= deadline;
+        if (!ok && fail_fast) || expired {
+            break;
+        }
+    }
+    responses
+}
+
+/// Execute one command; returns whether it succeeded and an optional message
+async fn execute(cmd: &str, state: &ControlState) -> (bool, Option<String>) {
     let mut message: Option<String> = None;
     let mut ok = true;
 
     // For pause, we want to return only when the serial port is actually released.
     // This avoids races where the flasher immediately tries to open the COM port.
     const PAUSE_ACK_TIMEOUT: Duration = Duration::from_secs(2);
+    const HOST_SWITCH_ACK_TIMEOUT: Duration = Duration::from_secs(2);
 
-    match cmd.as_str() {
+    match cmd {
         "pause" => {
             if !state.info.serial_supported {
                 ok = false;
@@ -236,24 +548,144 @@ async fn handle_connection(mut stream: TcpStream, state: ControlState) -> Result
                 state.set_desired(SerialRunState::Running)
             }
         }
-        "status" | "ping" | "info" => {}
+        "switch-host" => {
+            // Like pause: reply once the new transport is relaying (or failed)
+            let target = state.active_host().next_switchable();
+            let mut active_rx = state.active_host_rx.clone();
+            active_rx.mark_unchanged();
+            let _ = state.desired_host_tx.send_replace(target);
+
+            match tokio::time::timeout(HOST_SWITCH_ACK_TIMEOUT, active_rx.changed()).await {
+                Ok(Ok(())) if *active_rx.borrow() == target => {}
+                Ok(_) => {
+                    ok = false;
+                    message = Some(format!("switch to {:?} failed", target));
+                }
+                Err(_) => {
+                    ok = false;
+                    message = Some("timeout waiting for host transport switch".to_string());
+                }
+            }
+        }
+        "status" | "ping" | "info" | "stats" => {}
         "shutdown" => state.request_shutdown(),
+        #[cfg(all(feature = "profiling", unix))]
+        "profile" => {
+            let capture = tokio::task::spawn_blocking(|| {
+                crate::bridge::profiling::capture_flamegraph(PROFILE_DURATION)
+            });
+            match capture.await {
+                Ok(Ok(path)) => message = Some(path.display().to_string()),
+                Ok(Err(e)) => {
+                    ok = false;
+                    message = Some(e.to_string());
+                }
+                Err(e) => {
+                    ok = false;
+                    message = Some(format!("profiling task failed: {}", e));
+                }
+            }
+        }
+        #[cfg(not(all(feature = "profiling", unix)))]
+        "profile" => {
+            ok = false;
+            message = Some("profiling not built in (feature `profiling`, Unix only)".into());
+        }
         other => {
             ok = false;
             message = Some(format!("unknown cmd: {other}"));
         }
     }
 
-    let out = serde_json::to_vec(&build_response(&cmd, &state, ok, message)).map_err(|e| {
-        BridgeError::ControlProtocol {
+    (ok, message)
+}
+
+/// Stream `ControlEvent`s as JSON lines until the client disconnects
+///
+/// Missed events (a client too slow to keep up) are skipped.
+async fn serve_subscribe(stream: &mut TcpStream, state: &ControlState) -> Result<()> {
+    let (mut reader, mut writer) = stream.split();
+    let mut events = state.events_tx.subscribe();
+    let mut stats_ticker = tokio::time::interval(SUBSCRIBE_STATS_INTERVAL);
+    let mut ping_ticker = tokio::time::interval_at(
+        tokio::time::Instant::now() + SUBSCRIBE_PING_INTERVAL,
+        SUBSCRIBE_PING_INTERVAL,
+    );
+    let mut discard = [0u8; 64];
+
+    loop {
+        let event = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            _ = stats_ticker.tick() => {
+                let Some(stats) = &state.stats else {
+                    continue;
+                };
+                let snapshot = stats.snapshot();
+                ControlEvent::Stats {
+                    rx_bytes: snapshot.rx_bytes,
+                    tx_bytes: snapshot.tx_bytes,
+                    rx_messages: snapshot.rx_messages,
+                    tx_messages: snapshot.tx_messages,
+                    tx_batches: snapshot.tx_batches,
+                    reconnections: stats.reconnections(),
+                    channel_drops: snapshot.channel_drops,
+                }
+            }
+            _ = ping_ticker.tick() => ControlEvent::Ping,
+            // Nothing more is expected from the client; EOF or error ends the stream
+            read = reader.read(&mut discard) => {
+                if !matches!(read, Ok(n) if n > 0) {
+                    return Ok(());
+                }
+                continue;
+            }
+        };
+
+        let mut out = serde_json::to_vec(&event).map_err(|e| BridgeError::ControlProtocol {
             message: e.to_string(),
+        })?;
+        out.push(b'\n');
+        if writer.write_all(&out).await.is_err() {
+            return Ok(());
         }
-    })?;
+    }
+}
 
-    let _ = stream.write_all(&out).await;
-    let _ = stream.write_all(b"\n").await;
-    let _ = stream.shutdown().await;
-    Ok(())
+/// Write a `watch` response every `interval` until the client disconnects
+async fn serve_watch(
+    stream: &mut TcpStream,
+    state: &ControlState,
+    interval: Duration,
+) -> Result<()> {
+    let (mut reader, mut writer) = stream.split();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut discard = [0u8; 64];
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let mut out = serde_json::to_vec(&build_response("watch", state, true, None))
+                    .map_err(|e| BridgeError::ControlProtocol {
+                        message: e.to_string(),
+                    })?;
+                out.push(b'\n');
+                if writer.write_all(&out).await.is_err() {
+                    return Ok(());
+                }
+            }
+            // Nothing more is expected from the client; EOF or error ends the stream
+            read = reader.read(&mut discard) => {
+                if !matches!(read, Ok(n) if n > 0) {
+                    return Ok(());
+                }
+            }
+        }
+    }
 }
 
 fn build_response(cmd: &str, state: &ControlState, ok: bool, message: Option<String>) -> Response {
@@ -273,8 +705,24 @@ fn build_response(cmd: &str, state: &ControlState, ok: bool, message: Option<Str
         controller_serial: None,
         resolved_serial_port: None,
         host_udp_port: None,
+        host_pipe_name: None,
         log_broadcast_port: None,
         control_port: None,
+        cobs_trace: None,
+        host_transport: None,
+        rx_rate_kbps: None,
+        tx_rate_kbps: None,
+        jitter_ms: None,
+        write_fill_ratio: None,
+        max_write_queue_depth: None,
+        oversized_drops: None,
+        control_requests_total: None,
+        control_latency_ms_avg: None,
+        host_ws_clients: None,
+        host_ws_dropped: None,
+        host_udp_peer: None,
+        message_stats: None,
+        metrics_url: None,
     };
 
     if cmd == "status" || cmd == "info" {
@@ -286,17 +734,55 @@ fn build_response(cmd: &str, state: &ControlState, ok: bool, message: Option<Str
         resp.controller_serial = info.controller_serial.clone();
         resp.resolved_serial_port = state.resolved_serial_port();
         resp.host_udp_port = Some(info.host_udp_port);
+        resp.host_pipe_name = info.host_pipe_name.clone();
         resp.log_broadcast_port = Some(info.log_broadcast_port);
         resp.control_port = Some(info.control_port);
+        resp.cobs_trace = Some(info.cobs_trace);
+    }
+    if cmd == "info" {
+        resp.metrics_url = state.info().metrics_port.map(crate::metrics::metrics_url);
+    }
+    if cmd == "status" || cmd == "info" || cmd == "switch-host" || cmd == "watch" {
+        resp.host_transport = Some(state.active_host());
+    }
+    if cmd == "status" || cmd == "watch" {
+        resp.jitter_ms = state.stats.as_ref().map(|s| s.jitter_ms());
+        resp.write_fill_ratio = state.stats.as_ref().map(|s| s.write_channel_fill_ratio());
+        resp.max_write_queue_depth = state.stats.as_ref().map(|s| s.max_write_queue_depth());
+        resp.oversized_drops = state.stats.as_ref().map(|s| s.oversized_drops());
+        resp.control_requests_total = state.stats.as_ref().map(|s| s.control_requests_total());
+        resp.control_latency_ms_avg = state.stats.as_ref().map(|s| s.control_latency_ms_avg());
+        if let Some(clients) = &state.host_clients {
+            let active = state.active_host();
+            if matches!(active, HostTransport::WebSocket | HostTransport::Both) {
+                resp.host_ws_clients = Some(clients.ws_clients());
+                resp.host_ws_dropped = Some(clients.ws_dropped());
+            }
+            if matches!(active, HostTransport::Udp | HostTransport::Both) {
+                resp.host_udp_peer = clients.udp_peer().map(|a| a.to_string());
+            }
+        }
+    }
+    if cmd == "watch" {
+        resp.cobs_trace = Some(state.info().cobs_trace);
+        if let Some(stats) = &state.stats {
+            let (tx, rx) = stats.windowed_rates(1);
+            resp.rx_rate_kbps = Some(rx);
+            resp.tx_rate_kbps = Some(tx);
+        }
+    }
+    if cmd == "stats" {
+        resp.message_stats = state.stats.as_ref().map(|s| s.message_stats());
     }
     resp
 }
 
-pub fn send_command_blocking(
+/// Connect and send one request (blocking)
+fn send_request_blocking(
     port: u16,
-    cmd: &str,
+    request: &Request,
     timeout: std::time::Duration,
-) -> Result<Response> {
+) -> Result<std::net::TcpStream> {
     let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
     let mut stream = std::net::TcpStream::connect_timeout(&addr, timeout)
         .map_err(|e| BridgeError::ControlConnect { port, source: e })?;
@@ -307,11 +793,7 @@ pub fn send_command_blocking(
         .set_write_timeout(Some(timeout))
         .map_err(|e| BridgeError::ControlConnect { port, source: e })?;
 
-    let req = serde_json::to_string(&Request {
-        schema: Some(CONTROL_SCHEMA),
-        cmd: cmd.to_string(),
-    })
-    .map_err(|e| BridgeError::ControlProtocol {
+    let req = serde_json::to_string(request).map_err(|e| BridgeError::ControlProtocol {
         message: e.to_string(),
     })?;
     use std::io::Write;
@@ -324,39 +806,176 @@ pub fn send_command_blocking(
     stream
         .flush()
         .map_err(|e| BridgeError::ControlConnect { port, source: e })?;
+    Ok(stream)
+}
+
+pub fn send_command_blocking(
+    port: u16,
+    cmd: &str,
+    timeout: std::time::Duration,
+) -> Result<Response> {
+    let request = Request {
+        schema: Some(CONTROL_SCHEMA),
+        cmd: cmd.to_string(),
+        interval_ms: None,
+        commands: Vec::new(),
+        fail_fast: false,
+    };
+    let stream = send_request_blocking(port, &request, timeout)?;
+    read_response_blocking(stream, port)
+}
+
+/// Open a `subscribe` stream (blocking)
+///
+/// The daemon pings every `SUBSCRIBE_PING_INTERVAL`; a read times out after
+/// twice that, so a daemon that went away ends the stream with an error.
+pub fn subscribe_blocking(port: u16, timeout: std::time::Duration) -> Result<EventStream> {
+    let request = Request {
+        schema: Some(CONTROL_SCHEMA),
+        cmd: "subscribe".to_string(),
+        interval_ms: None,
+        commands: Vec::new(),
+        fail_fast: false,
+    };
+    let stream = send_request_blocking(port, &request, timeout)?;
+    stream
+        .set_read_timeout(Some(SUBSCRIBE_PING_INTERVAL * 2))
+        .map_err(|e| BridgeError::ControlConnect { port, source: e })?;
+    Ok(EventStream {
+        lines: std::io::BufReader::new(stream).lines(),
+        port,
+    })
+}
+
+/// Events of a `subscribe` stream, until the daemon closes it
+pub struct EventStream {
+    lines: std::io::Lines<std::io::BufReader<std::net::TcpStream>>,
+    port: u16,
+}
+
+impl Iterator for EventStream {
+    type Item = Result<ControlEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => {
+                return Some(Err(BridgeError::ControlConnect {
+                    port: self.port,
+                    source: e,
+                }))
+            }
+        };
+        Some(
+            serde_json::from_str(&line).map_err(|e| BridgeError::ControlProtocol {
+                message: format!("invalid event: {e}"),
+            }),
+        )
+    }
+}
+
+/// Run `commands` in order on one connection, one response each (blocking)
+///
+/// With `fail_fast`, the bridge stops after the first failed command.
+/// `timeout` bounds the whole batch, e.g. a pause waiting for its ACK.
+#[allow(dead_code)] // For external tools; `ctl` sends one command
+pub fn send_batch_commands_blocking(
+    port: u16,
+    commands: &[&str],
+    fail_fast: bool,
+    timeout: std::time::Duration,
+) -> Result<Vec<Response>> {
+    let request = Request {
+        schema: Some(CONTROL_SCHEMA),
+        cmd: "batch".to_string(),
+        interval_ms: None,
+        commands: commands.iter().map(|c| c.to_string()).collect(),
+        fail_fast,
+    };
+    let stream = send_request_blocking(port, &request, timeout)?;
+    read_response_blocking(stream, port)
+}
 
+/// Read the response line until the bridge closes the connection
+fn read_response_blocking<T: serde::de::DeserializeOwned>(
+    mut stream: std::net::TcpStream,
+    port: u16,
+) -> Result<T> {
     let mut out = String::new();
     use std::io::Read;
     stream
         .read_to_string(&mut out)
         .map_err(|e| BridgeError::ControlConnect { port, source: e })?;
 
-    let out = out.trim();
-    let resp: Response = serde_json::from_str(out).map_err(|e| BridgeError::ControlProtocol {
+    serde_json::from_str(out.trim()).map_err(|e| BridgeError::ControlProtocol {
         message: format!("invalid response: {e}"),
-    })?;
-    Ok(resp)
+    })
+}
+
+/// Send `watch` and call `on_update` for each streamed status (blocking)
+///
+/// Returns `Ok(())` when the bridge closes the stream or `on_update` breaks.
+/// `timeout` bounds the connect and the wait for each update, so it must
+/// exceed `interval_ms`.
+pub fn send_watch_command(
+    port: u16,
+    interval_ms: u64,
+    timeout: std::time::Duration,
+    mut on_update: impl FnMut(Response) -> ControlFlow<()>,
+) -> Result<()> {
+    let request = Request {
+        schema: Some(CONTROL_SCHEMA),
+        cmd: "watch".to_string(),
+        interval_ms: Some(interval_ms),
+        commands: Vec::new(),
+        fail_fast: false,
+    };
+    let stream = send_request_blocking(port, &request, timeout)?;
+
+    for line in std::io::BufReader::new(stream).lines() {
+        let line = line.map_err(|e| BridgeError::ControlConnect { port, source: e })?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let resp: Response =
+            serde_json::from_str(line).map_err(|e| BridgeError::ControlProtocol {
+                message: format!("invalid response: {e}"),
+            })?;
+        if on_update(resp).is_break() {
+            break;
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::AsyncBufReadExt;
 
-    #[test]
-    fn test_control_info_status_includes_instance_identity() {
-        let shutdown = Arc::new(AtomicBool::new(false));
-        let info = ControlInfo {
+    fn test_info() -> ControlInfo {
+        ControlInfo {
             pid: 42,
             version: "1.2.3".to_string(),
             config_path: "C:/config.toml".to_string(),
             instance_id: "bitwig-hw-17081760".to_string(),
             controller_serial: Some("17081760".to_string()),
             host_udp_port: 9000,
+            host_pipe_name: Some(r"\\.\pipe\oc-bridge-host".to_string()),
             log_broadcast_port: 9999,
             control_port: 7999,
             serial_supported: true,
-        };
-        let (state, runtime) = ControlState::new(shutdown, info);
+            cobs_trace: false,
+            host_transport: HostTransport::Udp,
+            metrics_port: Some(9464),
+        }
+    }
+
+    #[test]
+    fn test_control_info_status_includes_instance_identity() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (state, runtime) = ControlState::new(shutdown, test_info());
         let _ = runtime.serial_open_tx.send_replace(true);
         let _ = runtime
             .resolved_serial_port_tx
@@ -366,5 +985,204 @@ mod tests {
         assert_eq!(response.instance_id, Some("bitwig-hw-17081760".to_string()));
         assert_eq!(response.controller_serial, Some("17081760".to_string()));
         assert_eq!(response.resolved_serial_port, Some("COM3".to_string()));
+        assert_eq!(
+            response.host_pipe_name.as_deref(),
+            Some(r"\\.\pipe\oc-bridge-host")
+        );
+        assert_eq!(
+            response.metrics_url.as_deref(),
+            Some("http://127.0.0.1:9464/metrics")
+        );
+    }
+
+    #[test]
+    fn test_control_stats_reports_message_counters() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (state, _runtime) = ControlState::new(shutdown, test_info());
+        let stats = Arc::new(Stats::new());
+        stats.record_message("Ping", crate::logging::Direction::In, 4);
+        let state = state.with_stats(stats);
+
+        let response = build_response("stats", &state, true, None);
+        let messages = response.message_stats.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, "Ping");
+        assert_eq!(messages[0].1.rx_bytes, 4);
+        assert!(build_response("status", &state, true, None)
+            .message_stats
+            .is_none());
+    }
+
+    #[test]
+    fn test_control_status_reports_drops_and_requests() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (state, _runtime) = ControlState::new(shutdown, test_info());
+        let stats = Arc::new(Stats::new());
+        stats.add_oversized_drop();
+        stats.record_control_request(Duration::from_millis(4));
+        let state = state.with_stats(stats);
+
+        let response = build_response("status", &state, true, None);
+        assert_eq!(response.oversized_drops, Some(1));
+        assert_eq!(response.control_requests_total, Some(1));
+        assert_eq!(response.control_latency_ms_avg, Some(4.0));
+    }
+
+    #[tokio::test]
+    async fn test_watch_streams_status_until_disconnect() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (state, runtime) = ControlState::new(shutdown.clone(), test_info());
+        let state = state.with_stats(Arc::new(Stats::new()));
+        let _ = runtime.serial_open_tx.send_replace(true);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(run_server_with_listener(listener, state, shutdown.clone()));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"{\"cmd\":\"watch\",\"interval_ms\":50}\n")
+            .await
+            .unwrap();
+
+        let mut lines = tokio::io::BufReader::new(stream).lines();
+        for _ in 0..2 {
+            let line = tokio::time::timeout(Duration::from_secs(2), lines.next_line())
+                .await
+                .unwrap()
+                .unwrap()
+                .expect("watch update");
+            let resp: Response = serde_json::from_str(&line).unwrap();
+            assert!(resp.ok && resp.serial_open && !resp.paused);
+            assert_eq!(resp.rx_rate_kbps, Some(0.0));
+            assert_eq!(resp.tx_rate_kbps, Some(0.0));
+        }
+
+        drop(lines);
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_send_watch_command_stops_when_callback_breaks() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (state, _runtime) = ControlState::new(shutdown.clone(), test_info());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(run_server_with_listener(listener, state, shutdown.clone()));
+
+        let updates = tokio::task::spawn_blocking(move || {
+            let mut updates = 0;
+            send_watch_command(port, 20, Duration::from_secs(2), |_| {
+                updates += 1;
+                if updates == 2 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+            .map(|()| updates)
+        });
+        let updates = tokio::time::timeout(Duration::from_secs(5), updates)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(updates, 2);
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_streams_events() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (state, runtime) = ControlState::new(shutdown.clone(), test_info());
+        let state = state.with_stats(Arc::new(Stats::new()));
+        state.spawn_serial_events();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(run_server_with_listener(
+            listener,
+            state.clone(),
+            shutdown.clone(),
+        ));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"{\"cmd\":\"subscribe\"}\n")
+            .await
+            .unwrap();
+        let mut lines = tokio::io::BufReader::new(stream).lines();
+        let mut next_event = async || {
+            let line = tokio::time::timeout(Duration::from_secs(2), lines.next_line())
+                .await
+                .unwrap()
+                .unwrap()
+                .expect("event");
+            serde_json::from_str::<serde_json::Value>(&line).unwrap()
+        };
+
+        // Stats come first (immediate tick), then the published events
+        let stats = next_event().await;
+        assert_eq!(stats["event"], "stats");
+        assert_eq!(stats["rx_bytes"], 0);
+        assert_eq!(stats["channel_drops"], 0);
+
+        while !state.has_subscribers() {
+            tokio::task::yield_now().await;
+        }
+        let _ = runtime
+            .resolved_serial_port_tx
+            .send_replace(Some("/dev/ttyACM0".to_string()));
+        let connected = next_event().await;
+        assert_eq!(connected["event"], "serial_connected");
+        assert_eq!(connected["port"], "/dev/ttyACM0");
+
+        state.publish(ControlEvent::Log {
+            entry: LogEntry::system("hello"),
+        });
+        let log = next_event().await;
+        assert_eq!(log["event"], "log");
+        assert_eq!(log["entry"]["kind"]["System"]["message"], "hello");
+
+        let _ = runtime.resolved_serial_port_tx.send_replace(None);
+        assert_eq!(next_event().await["event"], "serial_disconnected");
+
+        // The stream ends with the client
+        drop(lines);
+        while state.has_subscribers() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_batch_runs_commands_on_one_connection() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (state, _runtime) = ControlState::new(shutdown.clone(), test_info());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(run_server_with_listener(listener, state, shutdown.clone()));
+
+        let batch = move |fail_fast| {
+            tokio::task::spawn_blocking(move || {
+                let commands = ["pause", "bogus", "status", "resume"];
+                send_batch_commands_blocking(port, &commands, fail_fast, Duration::from_secs(5))
+            })
+        };
+
+        let responses = batch(false).await.unwrap().unwrap();
+        let ok: Vec<bool> = responses.iter().map(|r| r.ok).collect();
+        assert_eq!(ok, [true, false, true, true]);
+        assert!(responses[0].paused);
+        assert_eq!(responses[1].message.as_deref(), Some("unknown cmd: bogus"));
+        assert_eq!(responses[2].pid, Some(42));
+        assert!(!responses[3].paused);
+
+        let responses = batch(true).await.unwrap().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert!(!responses[1].ok);
+
+        shutdown.store(true, Ordering::SeqCst);
     }
 }
@@ -0,0 +1,160 @@
+//! LAN discovery of running bridges
+//!
+//! Each daemon listens on `discovery_port` (UDP, all interfaces) and answers
+//! a discovery request with its `ControlInfo` as JSON. A scan broadcasts the
+//! request to `255.255.255.255:<discovery_port>` (and to loopback, for
+//! bridges on this machine) and collects answers for a short while.
+//!
+//! Requests are only answered for loopback senders and `allowed_remote_cidrs`,
+//! like the UDP transports: by default a bridge is discoverable from its own
+//! machine only.
+
+use crate::constants::DISCOVERY_RECV_BACKOFF_MS;
+use crate::control::ControlInfo;
+use crate::error::{BridgeError, Result};
+use crate::transport::udp::is_allowed;
+use ipnet::IpNet;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket as StdUdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+/// Payload of a discovery request
+pub const DISCOVERY_REQUEST: &[u8] = b"OC_BRIDGE_DISCOVER/1";
+
+/// A bridge that answered a scan
+#[derive(Debug, Clone)]
+pub struct BridgeInstance {
+    /// Address the answer came from
+    pub addr: SocketAddr,
+    pub info: ControlInfo,
+}
+
+/// Bind the discovery responder socket on all interfaces
+///
+/// `SO_REUSEADDR` lets several bridges on one machine share the port (each
+/// receives broadcasts).
+pub fn bind_responder(port: u16) -> Result<UdpSocket> {
+    let map_err = |e| BridgeError::UdpBind { port, source: e };
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).map_err(map_err)?;
+    socket.set_reuse_address(true).map_err(map_err)?;
+    socket.set_nonblocking(true).map_err(map_err)?;
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port);
+    socket.bind(&addr.into()).map_err(map_err)?;
+    UdpSocket::from_std(socket.into()).map_err(map_err)
+}
+
+/// Answer discovery requests until shutdown
+pub async fn run_responder(
+    socket: UdpSocket,
+    info: ControlInfo,
+    allowed: Vec<IpNet>,
+    shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    let reply = serde_json::to_vec(&info).map_err(|e| BridgeError::ControlProtocol {
+        message: e.to_string(),
+    })?;
+    let mut buf = [0u8; 64];
+
+    while !shutdown.load(Ordering::Relaxed) {
+        let recv =
+            tokio::time::timeout(Duration::from_millis(250), socket.recv_from(&mut buf)).await;
+        let (n, from) = match recv {
+            Ok(Ok(received)) => received,
+            Ok(Err(e)) => {
+                // Errors tend to repeat (e.g. ICMP unreachable on Windows): do not spin
+                debug!("Discovery: receive failed: {}", e);
+                tokio::time::sleep(Duration::from_millis(DISCOVERY_RECV_BACKOFF_MS)).await;
+                continue;
+            }
+            Err(_) => continue,
+        };
+        if &buf[..n] == DISCOVERY_REQUEST && is_allowed(from.ip(), &allowed) {
+            let _ = socket.send_to(&reply, from).await;
+        }
+    }
+    Ok(())
+}
+
+/// Broadcast a discovery request and collect answers for `timeout`
+///
+/// Blocking. Unreachable networks are not an error: the scan just finds
+/// nothing there.
+pub fn scan(port: u16, timeout: Duration) -> Vec<BridgeInstance> {
+    let Ok(socket) = StdUdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)) else {
+        return Vec::new();
+    };
+    let _ = socket.set_broadcast(true);
+    let _ = socket.send_to(DISCOVERY_REQUEST, (Ipv4Addr::BROADCAST, port));
+    let _ = socket.send_to(DISCOVERY_REQUEST, (Ipv4Addr::LOCALHOST, port));
+
+    let deadline = Instant::now() + timeout;
+    let mut found: Vec<BridgeInstance> = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || socket.set_read_timeout(Some(remaining)).is_err() {
+            break;
+        }
+        let Ok((n, addr)) = socket.recv_from(&mut buf) else {
+            break;
+        };
+        let Ok(info) = serde_json::from_slice::<ControlInfo>(&buf[..n]) else {
+            continue;
+        };
+        // A local bridge may answer both the broadcast and the loopback request
+        let duplicate = found.iter().any(|b| {
+            b.info.pid == info.pid
+                && b.info.instance_id == info.instance_id
+                && b.info.control_port == info.control_port
+        });
+        if !duplicate {
+            found.push(BridgeInstance { addr, info });
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HostTransport;
+
+    fn info() -> ControlInfo {
+        ControlInfo {
+            pid: 42,
+            version: "1.2.3".to_string(),
+            config_path: String::new(),
+            instance_id: "rack-a".to_string(),
+            controller_serial: Some("17081760".to_string()),
+            host_udp_port: 9000,
+            host_pipe_name: None,
+            log_broadcast_port: 9999,
+            control_port: 7999,
+            serial_supported: true,
+            cobs_trace: false,
+            host_transport: HostTransport::Udp,
+            metrics_port: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_finds_local_responder() {
+        let socket = bind_responder(0).unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        tokio::spawn(run_responder(socket, info(), Vec::new(), shutdown.clone()));
+
+        let found = tokio::task::spawn_blocking(move || scan(port, Duration::from_millis(300)))
+            .await
+            .unwrap();
+        shutdown.store(true, Ordering::Relaxed);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].info.instance_id, "rack-a");
+        assert_eq!(found[0].info.controller_serial.as_deref(), Some("17081760"));
+    }
+}
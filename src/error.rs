@@ -20,10 +20,29 @@ pub enum BridgeError {
     UdpBind { port: u16, source: std::io::Error },
     /// Failed to bind WebSocket server
     WebSocketBind { port: u16, source: std::io::Error },
+    /// Failed to bind TCP listener
+    TcpBind {
+        addr: std::net::SocketAddr,
+        source: std::io::Error,
+    },
+    /// Failed to bind UNIX socket
+    #[cfg(unix)]
+    UnixBind {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// TLS certificate or key cannot be used
+    TlsConfig { path: PathBuf, reason: String },
     /// Failed to accept WebSocket connection
     WebSocketAccept {
         source: Box<tokio_tungstenite::tungstenite::Error>,
     },
+    /// Failed to create named pipe server instance
+    #[cfg(windows)]
+    NamedPipeCreate {
+        name: String,
+        source: std::io::Error,
+    },
 
     /// Failed to bind control server port
     ControlBind { port: u16, source: std::io::Error },
@@ -31,6 +50,9 @@ pub enum BridgeError {
     ControlConnect { port: u16, source: std::io::Error },
     /// Control protocol error
     ControlProtocol { message: String },
+    /// Failed to bind the HTTP state API port
+    #[cfg(feature = "http-api")]
+    HttpBind { port: u16, source: std::io::Error },
 
     // === IO ===
     /// File system operation failed
@@ -40,6 +62,19 @@ pub enum BridgeError {
     },
     /// Invalid config value
     ConfigValidation { field: &'static str, reason: String },
+    /// Failed to watch the config directory for changes
+    ConfigWatch {
+        path: PathBuf,
+        source: notify::Error,
+    },
+    /// Unparseable line in an imported log file
+    LogParse { line: usize, reason: String },
+    /// Unparseable line in a TUI input recording
+    RecordingParse {
+        path: PathBuf,
+        line: usize,
+        reason: String,
+    },
 
     // === OS Commands ===
     /// Failed to spawn an OS command
@@ -47,16 +82,23 @@ pub enum BridgeError {
         program: &'static str,
         source: std::io::Error,
     },
+    /// Lifecycle hook exited non-zero or timed out
+    HookFailed { hook: &'static str, reason: String },
+    /// CPU profile capture failed
+    #[cfg(all(feature = "profiling", unix))]
+    Profiling { reason: String },
 
     // === Detection ===
     /// No device found matching configuration
     NoDeviceFound,
     /// Multiple devices found matching configuration
     MultipleDevicesFound { count: usize },
+    /// Failed to listen for serial hotplug events
+    #[cfg(target_os = "linux")]
+    HotplugWatch { source: std::io::Error },
 
     // === Platform ===
     /// Feature not supported on this platform
-    #[cfg(not(windows))]
     PlatformNotSupported { feature: &'static str },
 
     // === Runtime ===
@@ -65,7 +107,11 @@ pub enum BridgeError {
 
     // === Instance ===
     /// Another oc-bridge daemon instance is already running.
-    InstanceAlreadyRunning { lock_path: PathBuf },
+    InstanceAlreadyRunning {
+        lock_path: PathBuf,
+        /// PID recorded by the running instance, when readable
+        pid: Option<u32>,
+    },
     /// Failed to take or create the instance lock.
     InstanceLock {
         path: PathBuf,
@@ -79,6 +125,7 @@ impl std::error::Error for BridgeError {
             Self::SerialOpen { source, .. }
             | Self::UdpBind { source, .. }
             | Self::WebSocketBind { source, .. }
+            | Self::TcpBind { source, .. }
             | Self::ControlBind { source, .. }
             | Self::ControlConnect { source, .. }
             | Self::Io { source, .. }
@@ -86,6 +133,15 @@ impl std::error::Error for BridgeError {
             | Self::Runtime { source }
             | Self::InstanceLock { source, .. } => Some(source),
             Self::WebSocketAccept { source } => Some(source.as_ref()),
+            Self::ConfigWatch { source, .. } => Some(source),
+            #[cfg(feature = "http-api")]
+            Self::HttpBind { source, .. } => Some(source),
+            #[cfg(windows)]
+            Self::NamedPipeCreate { source, .. } => Some(source),
+            #[cfg(unix)]
+            Self::UnixBind { source, .. } => Some(source),
+            #[cfg(target_os = "linux")]
+            Self::HotplugWatch { source } => Some(source),
             _ => None,
         }
     }
@@ -97,33 +153,72 @@ impl fmt::Display for BridgeError {
             Self::SerialOpen { port, .. } => write!(f, "Cannot open serial port: {}", port),
             Self::UdpBind { port, .. } => write!(f, "Cannot bind UDP port {}", port),
             Self::WebSocketBind { port, .. } => write!(f, "Cannot bind WebSocket port {}", port),
+            Self::TcpBind { addr, .. } => write!(f, "Cannot bind TCP address {}", addr),
+            #[cfg(unix)]
+            Self::UnixBind { path, .. } => {
+                write!(f, "Cannot bind UNIX socket {}", path.display())
+            }
+            Self::TlsConfig { path, reason } => {
+                write!(f, "Invalid TLS file {}: {}", path.display(), reason)
+            }
             Self::WebSocketAccept { .. } => write!(f, "Failed to accept WebSocket connection"),
+            #[cfg(windows)]
+            Self::NamedPipeCreate { name, .. } => write!(f, "Cannot create named pipe {}", name),
             Self::ControlBind { port, .. } => write!(f, "Cannot bind control port {}", port),
             Self::ControlConnect { port, .. } => {
                 write!(f, "Cannot connect to control port {}", port)
             }
             Self::ControlProtocol { message } => write!(f, "Control protocol error: {}", message),
+            #[cfg(feature = "http-api")]
+            Self::HttpBind { port, .. } => write!(f, "Cannot bind HTTP API port {}", port),
             Self::Io { path, .. } => write!(f, "IO error: {}", path.display()),
             Self::ConfigValidation { field, reason } => {
                 write!(f, "Invalid {}: {}", field, reason)
             }
+            Self::ConfigWatch { path, .. } => {
+                write!(f, "Cannot watch config directory {}", path.display())
+            }
+            Self::LogParse { line, reason } => {
+                write!(f, "Invalid log line {}: {}", line, reason)
+            }
+            Self::RecordingParse { path, line, reason } => {
+                write!(
+                    f,
+                    "Invalid recording {} line {}: {}",
+                    path.display(),
+                    line,
+                    reason
+                )
+            }
             Self::OsCommand { program, source } => {
                 write!(f, "Command failed: {}: {}", program, source)
             }
+            Self::HookFailed { hook, reason } => write!(f, "{} failed: {}", hook, reason),
+            #[cfg(all(feature = "profiling", unix))]
+            Self::Profiling { reason } => write!(f, "Profiling failed: {}", reason),
             Self::NoDeviceFound => write!(f, "No device found"),
             Self::MultipleDevicesFound { count } => {
                 write!(f, "Multiple devices found ({})", count)
             }
-            #[cfg(not(windows))]
+            #[cfg(target_os = "linux")]
+            Self::HotplugWatch { source } => {
+                write!(f, "Cannot watch serial hotplug events: {}", source)
+            }
             Self::PlatformNotSupported { feature } => {
                 write!(f, "{} not supported on this platform", feature)
             }
             Self::Runtime { .. } => write!(f, "Failed to create runtime"),
-            Self::InstanceAlreadyRunning { lock_path } => write!(
-                f,
-                "oc-bridge is already running (lock: {})",
-                lock_path.display()
-            ),
+            Self::InstanceAlreadyRunning { lock_path, pid } => {
+                match pid {
+                    Some(pid) => write!(f, "oc-bridge is already running (pid {}, ", pid)?,
+                    None => write!(f, "oc-bridge is already running (")?,
+                }
+                write!(
+                    f,
+                    "lock: {}); check it with `oc-bridge ctl info`, or pass --force",
+                    lock_path.display()
+                )
+            }
             Self::InstanceLock { path, .. } => {
                 write!(f, "Cannot lock instance file: {}", path.display())
             }
@@ -1,9 +1,12 @@
 //! Input event handling
 //!
-//! Translates keyboard events into app commands.
+//! Translates keyboard events into app commands, and command palette
+//! text into command pipelines.
 
+use crate::config::KeyBindings;
 use crate::logging::{FilterMode, LogLevel};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::Duration;
 
 /// Command to execute on the App
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,6 +15,7 @@ pub enum AppCommand {
 
     // Bridge control (daemon must already be running)
     ToggleBridgePause,
+    CycleHostTransport,
 
     // Scrolling
     ScrollUp,
@@ -25,6 +29,7 @@ pub enum AppCommand {
     FilterProtocol,
     FilterDebug,
     FilterAll,
+    FilterEvents,
     FilterDebugLevel(Option<LogLevel>),
 
     // Log actions
@@ -33,59 +38,383 @@ pub enum AppCommand {
     CutLogs,
     ClearLogs,
     ExportLogs,
+    ToggleWordWrap,
+    /// Show / hide controller jitter in the status widget (Shift+J)
+    ToggleJitter,
+    /// Show / hide the per-message-type traffic table (Tab)
+    ToggleMessageStats,
     OpenConfig,
+    /// Show `[bridge]` edits held back until the daemon restarts (Shift+R);
+    /// the daemon itself is restarted by its supervisor, not the TUI
+    ApplyBridgeConfig,
+    /// Record received log entries to a file (Alt+R)
+    ToggleRecording,
+    /// CPU flamegraph of the daemon (Alt+P, `profiling` feature)
+    ExportFlamegraph,
+    /// Clear the terminal and redraw everything (Ctrl+L)
+    Redraw,
+
+    // Bookmarks
+    ToggleBookmark,
+    NextBookmark,
+    PrevBookmark,
+
+    // Annotations (user notes on log entries)
+    AnnotateEntry,
+    NoteInput(char),
+    NoteBackspace,
+    NoteSubmit,
+    NoteCancel,
+    NextAnnotation,
+    PrevAnnotation,
+
+    // Search (`/` or Ctrl+F, then `n` / `N` through the matches)
+    OpenSearch,
+    SearchInput(char),
+    SearchBackspace,
+    SearchSubmit,
+    SearchCancel,
+    SearchNext,
+    SearchPrev,
+
+    // Clipboard history popup
+    ToggleClipboardHistory,
+    ClipboardHistoryUp,
+    ClipboardHistoryDown,
+    ClipboardHistoryCopy,
+
+    // Discovered bridges popup
+    NetworkScan,
+    PeersUp,
+    PeersDown,
+    ClosePeers,
+
+    // Serial port picker (Shift+L)
+    ListPorts,
+    PortsUp,
+    PortsDown,
+    /// Save the selected port as `serial_port` (Enter)
+    PickPort,
+    /// Copy the selected port name (c)
+    CopyPort,
+    ClosePorts,
+
+    // Log statistics popup
+    ShowStatistics,
+    CloseStatistics,
+
+    // Command palette
+    OpenCommandPalette,
+    PaletteInput(char),
+    PaletteBackspace,
+    PaletteSubmit,
+    PaletteCancel,
+
+    /// Pipeline pseudo-command: wait before running the next command
+    Sleep(Duration),
 
     None,
 }
 
-/// Translate a key press into an AppCommand
-pub fn translate_key(key: KeyEvent, filter_mode: FilterMode) -> AppCommand {
-    match key.code {
-        // Quit
-        KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => AppCommand::Quit,
+/// Command names accepted in the command palette
+///
+/// Matched case-insensitively, ignoring `_` and `-` (`ClearLogs`,
+/// `clear_logs` and `clear-logs` are the same command).
+const PALETTE_COMMANDS: &[(&str, AppCommand)] = &[
+    ("quit", AppCommand::Quit),
+    ("togglebridge", AppCommand::ToggleBridgePause),
+    ("cyclehost", AppCommand::CycleHostTransport),
+    ("scrolltop", AppCommand::ScrollToTop),
+    ("scrollbottom", AppCommand::ScrollToBottom),
+    ("filterprotocol", AppCommand::FilterProtocol),
+    ("filterdebug", AppCommand::FilterDebug),
+    ("filterall", AppCommand::FilterAll),
+    ("filterevents", AppCommand::FilterEvents),
+    ("togglepause", AppCommand::TogglePause),
+    ("copylogs", AppCommand::CopyLogs),
+    ("cutlogs", AppCommand::CutLogs),
+    ("clearlogs", AppCommand::ClearLogs),
+    ("exportlogs", AppCommand::ExportLogs),
+    ("togglewordwrap", AppCommand::ToggleWordWrap),
+    ("togglejitter", AppCommand::ToggleJitter),
+    ("togglemessagestats", AppCommand::ToggleMessageStats),
+    ("openconfig", AppCommand::OpenConfig),
+    ("applybridgeconfig", AppCommand::ApplyBridgeConfig),
+    ("networkscan", AppCommand::NetworkScan),
+    ("listports", AppCommand::ListPorts),
+    ("nextbookmark", AppCommand::NextBookmark),
+    ("prevbookmark", AppCommand::PrevBookmark),
+    ("nextannotation", AppCommand::NextAnnotation),
+    ("prevannotation", AppCommand::PrevAnnotation),
+    ("redraw", AppCommand::Redraw),
+    ("togglerecording", AppCommand::ToggleRecording),
+    ("exportflamegraph", AppCommand::ExportFlamegraph),
+    ("showstatistics", AppCommand::ShowStatistics),
+];
 
-        // Bridge
-        KeyCode::Char('b') | KeyCode::Char('B') => AppCommand::ToggleBridgePause,
+/// Parse a space-separated pipeline, e.g. `FilterAll ClearLogs Sleep(500) ExportLogs`
+///
+/// `Sleep(N)` waits N milliseconds. Returns the first unknown word as error.
+pub fn parse_pipeline(text: &str) -> Result<Vec<AppCommand>, String> {
+    text.split_whitespace()
+        .map(|word| parse_palette_command(word).ok_or_else(|| word.to_string()))
+        .collect()
+}
+
+fn parse_palette_command(word: &str) -> Option<AppCommand> {
+    let name: String = word
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
 
-        // Scrolling
-        KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => AppCommand::ScrollUp,
-        KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => AppCommand::ScrollDown,
+    if let Some(ms) = name
+        .strip_prefix("sleep(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return ms
+            .parse()
+            .ok()
+            .map(|ms| AppCommand::Sleep(Duration::from_millis(ms)));
+    }
+    PALETTE_COMMANDS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, cmd)| cmd.clone())
+}
+
+/// Translate a key press into an AppCommand
+///
+/// Character keys come from `keys` (see `KeyBindings::validated`); the
+/// fixed keys (arrows, Esc, Ctrl combinations...) are checked first.
+pub fn translate_key(key: KeyEvent, filter_mode: FilterMode, keys: &KeyBindings) -> AppCommand {
+    match key.code {
+        // Fixed keys
+        KeyCode::Esc => AppCommand::Quit,
+        KeyCode::Char('h') | KeyCode::Char('H')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::CycleHostTransport
+        }
+        // Ctrl+Y ("yank"): legacy terminals report Ctrl+Shift+C as Ctrl+C
+        KeyCode::Char('y') | KeyCode::Char('Y')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::ToggleClipboardHistory
+        }
+        KeyCode::Char('b') | KeyCode::Char('B')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::ToggleBookmark
+        }
+        KeyCode::Char(']') => AppCommand::NextBookmark,
+        KeyCode::Char('[') => AppCommand::PrevBookmark,
+        // Shift+N only: plain `n` is a remappable key (scan)
+        KeyCode::Char('N') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            AppCommand::AnnotateEntry
+        }
+        // Shift+J only: plain `j` scrolls by default
+        KeyCode::Char('J') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            AppCommand::ToggleJitter
+        }
+        // Shift+R only: Alt+R records, plain `r` filters errors in Debug mode
+        KeyCode::Char('R')
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            AppCommand::ApplyBridgeConfig
+        }
+        KeyCode::Tab => AppCommand::ToggleMessageStats,
+        KeyCode::Char('/') => AppCommand::OpenSearch,
+        KeyCode::Char('f') | KeyCode::Char('F')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::OpenSearch
+        }
+        KeyCode::Char('}') => AppCommand::NextAnnotation,
+        KeyCode::Char('{') => AppCommand::PrevAnnotation,
+        KeyCode::Char('x') | KeyCode::Char('X')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::OpenCommandPalette
+        }
+        KeyCode::Char('l') | KeyCode::Char('L')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::Redraw
+        }
+        // Shift+L only: plain `l` stays free for key bindings
+        KeyCode::Char('L') => AppCommand::ListPorts,
+        KeyCode::Char('s') | KeyCode::Char('S')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::ShowStatistics
+        }
+        KeyCode::Char('w') | KeyCode::Char('W') if key.modifiers.contains(KeyModifiers::ALT) => {
+            AppCommand::ToggleWordWrap
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') if key.modifiers.contains(KeyModifiers::ALT) => {
+            AppCommand::ToggleRecording
+        }
+        KeyCode::Char('p') | KeyCode::Char('P') if key.modifiers.contains(KeyModifiers::ALT) => {
+            AppCommand::ExportFlamegraph
+        }
+        KeyCode::Up => AppCommand::ScrollUp,
+        KeyCode::Down => AppCommand::ScrollDown,
         KeyCode::PageUp => AppCommand::ScrollPageUp,
         KeyCode::PageDown => AppCommand::ScrollPageDown,
         KeyCode::Home => AppCommand::ScrollToTop,
         KeyCode::End => AppCommand::ScrollToBottom,
+        KeyCode::Backspace => AppCommand::ClearLogs,
 
-        // Filter shortcuts
-        KeyCode::Char('1') => AppCommand::FilterProtocol,
-        KeyCode::Char('2') => AppCommand::FilterDebug,
-        KeyCode::Char('3') => AppCommand::FilterAll,
+        KeyCode::Char(c) => translate_char(c, filter_mode, keys),
+        _ => AppCommand::None,
+    }
+}
 
-        // Clipboard operations
-        KeyCode::Char('c') | KeyCode::Char('C') => AppCommand::CopyLogs,
-        KeyCode::Char('x') | KeyCode::Char('X') => AppCommand::CutLogs,
-        KeyCode::Backspace => AppCommand::ClearLogs,
+/// Remappable character keys, then the Debug-mode level filters
+fn translate_char(c: char, filter_mode: FilterMode, keys: &KeyBindings) -> AppCommand {
+    let pressed = c.to_lowercase().to_string();
+    let bound = [
+        (&keys.quit, AppCommand::Quit),
+        (&keys.toggle_bridge, AppCommand::ToggleBridgePause),
+        (&keys.scroll_up, AppCommand::ScrollUp),
+        (&keys.scroll_down, AppCommand::ScrollDown),
+        (&keys.filter_protocol, AppCommand::FilterProtocol),
+        (&keys.filter_debug, AppCommand::FilterDebug),
+        (&keys.filter_all, AppCommand::FilterAll),
+        (&keys.filter_events, AppCommand::FilterEvents),
+        (&keys.copy, AppCommand::CopyLogs),
+        (&keys.cut, AppCommand::CutLogs),
+        (&keys.toggle_pause, AppCommand::TogglePause),
+        (&keys.export, AppCommand::ExportLogs),
+        (&keys.open_config, AppCommand::OpenConfig),
+        (&keys.scan, AppCommand::NetworkScan),
+    ];
+    if let Some((_, cmd)) = bound.into_iter().find(|(k, _)| **k == pressed) {
+        return cmd;
+    }
 
-        // Pause toggle
-        KeyCode::Char('p') | KeyCode::Char('P') => AppCommand::TogglePause,
+    // Debug level filters (only in Debug mode)
+    if filter_mode != FilterMode::Debug {
+        return AppCommand::None;
+    }
+    match c {
+        'd' => AppCommand::FilterDebugLevel(Some(LogLevel::Debug)),
+        'w' => AppCommand::FilterDebugLevel(Some(LogLevel::Warn)),
+        'r' => AppCommand::FilterDebugLevel(Some(LogLevel::Error)),
+        'a' => AppCommand::FilterDebugLevel(None),
+        _ => AppCommand::None,
+    }
+}
 
-        // Export/Config
-        KeyCode::Char('e') | KeyCode::Char('E') => AppCommand::ExportLogs,
-        KeyCode::Char('f') | KeyCode::Char('F') => AppCommand::OpenConfig,
+/// Translate a key press while the clipboard history popup is open
+pub fn translate_popup_key(key: KeyEvent) -> AppCommand {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => AppCommand::ClipboardHistoryUp,
+        KeyCode::Down | KeyCode::Char('j') => AppCommand::ClipboardHistoryDown,
+        KeyCode::Enter => AppCommand::ClipboardHistoryCopy,
+        KeyCode::Esc
+        | KeyCode::Char('q')
+        | KeyCode::Char('c')
+        | KeyCode::Char('C')
+        | KeyCode::Char('y') => AppCommand::ToggleClipboardHistory,
+        _ => AppCommand::None,
+    }
+}
 
-        // Debug level filters (only in Debug mode)
-        KeyCode::Char('d') if filter_mode == FilterMode::Debug => {
-            AppCommand::FilterDebugLevel(Some(LogLevel::Debug))
+/// Translate a key press while the discovered bridges popup is open
+pub fn translate_peers_key(key: KeyEvent) -> AppCommand {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => AppCommand::PeersUp,
+        KeyCode::Down | KeyCode::Char('j') => AppCommand::PeersDown,
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('n') => {
+            AppCommand::ClosePeers
         }
-        KeyCode::Char('w') if filter_mode == FilterMode::Debug => {
-            AppCommand::FilterDebugLevel(Some(LogLevel::Warn))
+        _ => AppCommand::None,
+    }
+}
+
+/// Translate a key press while the serial port picker is open
+pub fn translate_ports_key(key: KeyEvent) -> AppCommand {
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => AppCommand::PortsUp,
+        KeyCode::Down | KeyCode::Char('j') => AppCommand::PortsDown,
+        KeyCode::Enter => AppCommand::PickPort,
+        KeyCode::Char('c') => AppCommand::CopyPort,
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => AppCommand::ClosePorts,
+        _ => AppCommand::None,
+    }
+}
+
+/// Translate a key press while the log statistics popup is open
+pub fn translate_stats_key(key: KeyEvent) -> AppCommand {
+    match key.code {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => AppCommand::CloseStatistics,
+        KeyCode::Char('s') | KeyCode::Char('S')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppCommand::CloseStatistics
         }
-        KeyCode::Char('r') if filter_mode == FilterMode::Debug => {
-            AppCommand::FilterDebugLevel(Some(LogLevel::Error))
+        _ => AppCommand::None,
+    }
+}
+
+/// Translate a key press while the note input is open
+pub fn translate_note_key(key: KeyEvent) -> AppCommand {
+    match key.code {
+        KeyCode::Enter => AppCommand::NoteSubmit,
+        KeyCode::Esc => AppCommand::NoteCancel,
+        KeyCode::Backspace => AppCommand::NoteBackspace,
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            AppCommand::NoteInput(c)
         }
-        KeyCode::Char('a') if filter_mode == FilterMode::Debug => {
-            AppCommand::FilterDebugLevel(None)
+        _ => AppCommand::None,
+    }
+}
+
+/// Translate a key press while the search query is typed
+pub fn translate_search_key(key: KeyEvent) -> AppCommand {
+    match key.code {
+        KeyCode::Enter => AppCommand::SearchSubmit,
+        KeyCode::Esc => AppCommand::SearchCancel,
+        KeyCode::Backspace => AppCommand::SearchBackspace,
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            AppCommand::SearchInput(c)
         }
+        _ => AppCommand::None,
+    }
+}
 
+/// Translate a key press while search matches are highlighted
+///
+/// `n` / `N` move between matches (instead of scan / annotate) and Esc
+/// ends the search (instead of quitting); other keys work as usual.
+pub fn translate_search_results_key(
+    key: KeyEvent,
+    filter_mode: FilterMode,
+    keys: &KeyBindings,
+) -> AppCommand {
+    match key.code {
+        KeyCode::Char('n') if key.modifiers.is_empty() => AppCommand::SearchNext,
+        KeyCode::Char('N') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            AppCommand::SearchPrev
+        }
+        KeyCode::Esc => AppCommand::SearchCancel,
+        _ => translate_key(key, filter_mode, keys),
+    }
+}
+
+/// Translate a key press while the command palette is open
+pub fn translate_palette_key(key: KeyEvent) -> AppCommand {
+    match key.code {
+        KeyCode::Enter => AppCommand::PaletteSubmit,
+        KeyCode::Esc => AppCommand::PaletteCancel,
+        KeyCode::Backspace => AppCommand::PaletteBackspace,
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            AppCommand::PaletteInput(c)
+        }
         _ => AppCommand::None,
     }
 }
@@ -93,7 +422,6 @@ pub fn translate_key(key: KeyEvent, filter_mode: FilterMode) -> AppCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crossterm::event::KeyModifiers;
 
     fn key(code: KeyCode) -> KeyEvent {
         KeyEvent::new(code, KeyModifiers::NONE)
@@ -102,11 +430,15 @@ mod tests {
     #[test]
     fn test_quit_keys() {
         assert_eq!(
-            translate_key(key(KeyCode::Char('q')), FilterMode::All),
+            translate_key(
+                key(KeyCode::Char('q')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
             AppCommand::Quit
         );
         assert_eq!(
-            translate_key(key(KeyCode::Esc), FilterMode::All),
+            translate_key(key(KeyCode::Esc), FilterMode::All, &KeyBindings::default()),
             AppCommand::Quit
         );
     }
@@ -114,11 +446,15 @@ mod tests {
     #[test]
     fn test_scroll_keys() {
         assert_eq!(
-            translate_key(key(KeyCode::Up), FilterMode::All),
+            translate_key(key(KeyCode::Up), FilterMode::All, &KeyBindings::default()),
             AppCommand::ScrollUp
         );
         assert_eq!(
-            translate_key(key(KeyCode::Char('j')), FilterMode::All),
+            translate_key(
+                key(KeyCode::Char('j')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
             AppCommand::ScrollDown
         );
     }
@@ -126,12 +462,328 @@ mod tests {
     #[test]
     fn test_debug_level_only_in_debug_mode() {
         assert_eq!(
-            translate_key(key(KeyCode::Char('d')), FilterMode::Debug),
+            translate_key(
+                key(KeyCode::Char('d')),
+                FilterMode::Debug,
+                &KeyBindings::default()
+            ),
             AppCommand::FilterDebugLevel(Some(LogLevel::Debug))
         );
         assert_eq!(
-            translate_key(key(KeyCode::Char('d')), FilterMode::All),
+            translate_key(
+                key(KeyCode::Char('d')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
             AppCommand::None
         );
     }
+
+    #[test]
+    fn test_ctrl_h_cycles_host_transport() {
+        let ctrl_h = KeyEvent::new(KeyCode::Char('h'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(ctrl_h, FilterMode::All, &KeyBindings::default()),
+            AppCommand::CycleHostTransport
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('h')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
+            AppCommand::None
+        );
+    }
+
+    #[test]
+    fn test_alt_r_toggles_recording() {
+        let alt_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::ALT);
+        assert_eq!(
+            translate_key(alt_r, FilterMode::Debug, &KeyBindings::default()),
+            AppCommand::ToggleRecording
+        );
+    }
+
+    #[test]
+    fn test_ctrl_s_toggles_statistics() {
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(ctrl_s, FilterMode::All, &KeyBindings::default()),
+            AppCommand::ShowStatistics
+        );
+        assert_eq!(translate_stats_key(ctrl_s), AppCommand::CloseStatistics);
+        let s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(translate_stats_key(s), AppCommand::None);
+    }
+
+    #[test]
+    fn test_shift_l_opens_port_picker() {
+        let keys = KeyBindings::default();
+        let shift_l = KeyEvent::new(KeyCode::Char('L'), KeyModifiers::SHIFT);
+        let ctrl_l = KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(shift_l, FilterMode::All, &keys),
+            AppCommand::ListPorts
+        );
+        assert_eq!(
+            translate_key(ctrl_l, FilterMode::All, &keys),
+            AppCommand::Redraw
+        );
+        assert_eq!(translate_ports_key(shift_l), AppCommand::ClosePorts);
+        assert_eq!(
+            translate_ports_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+            AppCommand::PickPort
+        );
+        assert_eq!(
+            translate_ports_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE)),
+            AppCommand::CopyPort
+        );
+    }
+
+    #[test]
+    fn test_alt_w_toggles_word_wrap_even_in_debug_mode() {
+        let alt_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::ALT);
+        assert_eq!(
+            translate_key(alt_w, FilterMode::Debug, &KeyBindings::default()),
+            AppCommand::ToggleWordWrap
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('w')),
+                FilterMode::Debug,
+                &KeyBindings::default()
+            ),
+            AppCommand::FilterDebugLevel(Some(LogLevel::Warn))
+        );
+    }
+
+    #[test]
+    fn test_ctrl_y_opens_clipboard_history() {
+        let ctrl_y = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(ctrl_y, FilterMode::All, &KeyBindings::default()),
+            AppCommand::ToggleClipboardHistory
+        );
+        // What legacy terminals send for Ctrl+Shift+C
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_ne!(
+            translate_key(ctrl_c, FilterMode::All, &KeyBindings::default()),
+            AppCommand::ToggleClipboardHistory
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('c')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
+            AppCommand::CopyLogs
+        );
+
+        // Popup keys do not leak to the log view
+        assert_eq!(
+            translate_popup_key(key(KeyCode::Esc)),
+            AppCommand::ToggleClipboardHistory
+        );
+        assert_eq!(
+            translate_popup_key(key(KeyCode::Enter)),
+            AppCommand::ClipboardHistoryCopy
+        );
+        assert_eq!(
+            translate_popup_key(key(KeyCode::Char('b'))),
+            AppCommand::None
+        );
+    }
+
+    #[test]
+    fn test_ctrl_x_opens_command_palette() {
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(ctrl_x, FilterMode::All, &KeyBindings::default()),
+            AppCommand::OpenCommandPalette
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('x')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
+            AppCommand::CutLogs
+        );
+
+        // Palette keys type text instead of triggering bindings
+        assert_eq!(
+            translate_palette_key(key(KeyCode::Char('q'))),
+            AppCommand::PaletteInput('q')
+        );
+        assert_eq!(
+            translate_palette_key(key(KeyCode::Esc)),
+            AppCommand::PaletteCancel
+        );
+    }
+
+    #[test]
+    fn test_parse_pipeline() {
+        assert_eq!(
+            parse_pipeline("FilterAll clear_logs  sleep(500) export-logs"),
+            Ok(vec![
+                AppCommand::FilterAll,
+                AppCommand::ClearLogs,
+                AppCommand::Sleep(Duration::from_millis(500)),
+                AppCommand::ExportLogs,
+            ])
+        );
+        assert_eq!(parse_pipeline(""), Ok(vec![]));
+        assert_eq!(
+            parse_pipeline("ClearLogs StartBridge"),
+            Err("StartBridge".to_string())
+        );
+        assert_eq!(
+            parse_pipeline("Sleep(soon)"),
+            Err("Sleep(soon)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bookmark_keys() {
+        let ctrl_b = KeyEvent::new(KeyCode::Char('b'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(ctrl_b, FilterMode::All, &KeyBindings::default()),
+            AppCommand::ToggleBookmark
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('b')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
+            AppCommand::ToggleBridgePause
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char(']')),
+                FilterMode::Debug,
+                &KeyBindings::default()
+            ),
+            AppCommand::NextBookmark
+        );
+    }
+
+    #[test]
+    fn test_jitter_key() {
+        let shift_j = KeyEvent::new(KeyCode::Char('J'), KeyModifiers::SHIFT);
+        assert_eq!(
+            translate_key(shift_j, FilterMode::All, &KeyBindings::default()),
+            AppCommand::ToggleJitter
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('j')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
+            AppCommand::ScrollDown
+        );
+    }
+
+    #[test]
+    fn test_shift_r_applies_bridge_config() {
+        let shift_r = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT);
+        assert_eq!(
+            translate_key(shift_r, FilterMode::All, &KeyBindings::default()),
+            AppCommand::ApplyBridgeConfig
+        );
+        let alt_r = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT | KeyModifiers::ALT);
+        assert_eq!(
+            translate_key(alt_r, FilterMode::All, &KeyBindings::default()),
+            AppCommand::ToggleRecording
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('r')),
+                FilterMode::Debug,
+                &KeyBindings::default()
+            ),
+            AppCommand::FilterDebugLevel(Some(LogLevel::Error))
+        );
+    }
+
+    #[test]
+    fn test_annotation_keys() {
+        let shift_n = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+        assert_eq!(
+            translate_key(shift_n, FilterMode::All, &KeyBindings::default()),
+            AppCommand::AnnotateEntry
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('n')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
+            AppCommand::NetworkScan
+        );
+        assert_eq!(
+            translate_key(
+                key(KeyCode::Char('{')),
+                FilterMode::All,
+                &KeyBindings::default()
+            ),
+            AppCommand::PrevAnnotation
+        );
+        assert_eq!(
+            translate_note_key(key(KeyCode::Char('q'))),
+            AppCommand::NoteInput('q')
+        );
+        assert_eq!(
+            translate_note_key(key(KeyCode::Esc)),
+            AppCommand::NoteCancel
+        );
+    }
+
+    #[test]
+    fn test_search_keys() {
+        let keys = KeyBindings::default();
+        let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(ctrl_f, FilterMode::All, &keys),
+            AppCommand::OpenSearch
+        );
+        assert_eq!(
+            translate_key(key(KeyCode::Char('/')), FilterMode::All, &keys),
+            AppCommand::OpenSearch
+        );
+        assert_eq!(
+            translate_key(key(KeyCode::Tab), FilterMode::All, &keys),
+            AppCommand::ToggleMessageStats
+        );
+
+        // Typing goes to the query, bindings included
+        assert_eq!(
+            translate_search_key(key(KeyCode::Char('b'))),
+            AppCommand::SearchInput('b')
+        );
+        assert_eq!(
+            translate_search_key(key(KeyCode::Esc)),
+            AppCommand::SearchCancel
+        );
+
+        // With matches shown: n / N cycle, Esc ends the search
+        let shift_n = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+        let results = |k| translate_search_results_key(k, FilterMode::All, &keys);
+        assert_eq!(results(key(KeyCode::Char('n'))), AppCommand::SearchNext);
+        assert_eq!(results(shift_n), AppCommand::SearchPrev);
+        assert_eq!(results(key(KeyCode::Esc)), AppCommand::SearchCancel);
+        assert_eq!(results(key(KeyCode::Char('q'))), AppCommand::Quit);
+    }
+
+    #[test]
+    fn test_ctrl_l_redraws() {
+        let ctrl_l = KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL);
+        assert_eq!(
+            translate_key(ctrl_l, FilterMode::All, &KeyBindings::default()),
+            AppCommand::Redraw
+        );
+        assert_eq!(parse_pipeline("redraw"), Ok(vec![AppCommand::Redraw]));
+    }
 }
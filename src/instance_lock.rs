@@ -1,5 +1,6 @@
 use fs2::FileExt;
 use std::fs::OpenOptions;
+use std::io::{Read, Seek, Write};
 use std::path::{Path, PathBuf};
 
 use crate::error::{BridgeError, Result};
@@ -58,13 +59,35 @@ impl InstanceLock {
             })?;
 
         match file.try_lock_exclusive() {
-            Ok(()) => Ok(Self { _file: file }),
+            Ok(()) => {
+                // The PID is informational: the lock itself is what counts
+                let _ = Self::write_pid(&file);
+                Ok(Self { _file: file })
+            }
             Err(e) if Self::is_contended_lock_error(&e) => {
-                Err(BridgeError::InstanceAlreadyRunning { lock_path: path })
+                let pid = Self::read_pid(&file);
+                Err(BridgeError::InstanceAlreadyRunning {
+                    lock_path: path,
+                    pid,
+                })
             }
             Err(e) => Err(BridgeError::InstanceLock { path, source: e }),
         }
     }
+
+    fn write_pid(mut file: &std::fs::File) -> std::io::Result<()> {
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()
+    }
+
+    /// PID written by the lock holder (unreadable on Windows while locked)
+    fn read_pid(mut file: &std::fs::File) -> Option<u32> {
+        let mut content = String::new();
+        file.read_to_string(&mut content).ok()?;
+        content.trim().parse().ok()
+    }
 }
 
 #[cfg(test)]
@@ -108,6 +131,11 @@ mod tests {
             Err(err) => err,
         };
         assert!(matches!(err, BridgeError::InstanceAlreadyRunning { .. }));
+        #[cfg(unix)]
+        assert!(matches!(
+            err,
+            BridgeError::InstanceAlreadyRunning { pid: Some(pid), .. } if pid == std::process::id()
+        ));
         drop(lock);
         let _ = std::fs::remove_dir_all(dir);
     }
@@ -2,25 +2,80 @@
 //!
 //! Sends LogEntry messages via UDP to localhost for monitoring.
 //! The service broadcasts on a UDP port, and the TUI listens to receive logs.
+//!
+//! Packets are newline-terminated JSON: a `LogEntry` plus a `seq` number
+//! counting up from 0, so receivers can detect lost packets and sender
+//! restarts (older receivers ignore the field). With compression enabled, a packet
+//! is `COMPRESSED_MAGIC` followed by the LZ4 block (size-prepended) of that
+//! JSON, sent only when it is actually smaller. Receivers detect the magic,
+//! so compressed and plain senders interoperate.
 
 use super::LogEntry;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::net::UdpSocket;
 use std::sync::mpsc;
 use std::thread;
 
+/// Prefix of an LZ4-compressed packet (never starts a JSON text)
+pub const COMPRESSED_MAGIC: [u8; 2] = [0xAC, 0xE0];
+
+/// Largest decompressed packet accepted (a sender's JSON fits one datagram)
+const MAX_DECOMPRESSED_LEN: usize = 65535;
+
+/// Wire format of one log packet
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogPacket {
+    /// Packet counter of the sending process (absent from older senders)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    #[serde(flatten)]
+    pub entry: LogEntry,
+}
+
 /// Create a log broadcast channel with a custom port
-pub fn create_log_broadcaster_with_port(port: u16) -> mpsc::Sender<LogEntry> {
+pub fn create_log_broadcaster_with_port(port: u16, compress: bool) -> mpsc::Sender<LogEntry> {
     let (tx, rx) = mpsc::channel::<LogEntry>();
 
     thread::spawn(move || {
-        run_broadcaster(rx, port);
+        run_broadcaster(rx, port, compress);
     });
 
     tx
 }
 
+/// Build the UDP packet for one JSON line
+fn encode_packet(line: &str, compress: bool) -> Cow<'_, [u8]> {
+    if compress {
+        let block = lz4_flex::compress_prepend_size(line.as_bytes());
+        if block.len() + COMPRESSED_MAGIC.len() < line.len() {
+            let mut packet = Vec::with_capacity(COMPRESSED_MAGIC.len() + block.len());
+            packet.extend_from_slice(&COMPRESSED_MAGIC);
+            packet.extend_from_slice(&block);
+            return Cow::Owned(packet);
+        }
+    }
+    Cow::Borrowed(line.as_bytes())
+}
+
+/// Recover the JSON text of a packet (plain or compressed)
+///
+/// The size prefix comes from the network: sizes above one datagram are
+/// rejected before anything is allocated.
+pub fn decode_packet(packet: &[u8]) -> Option<Cow<'_, [u8]>> {
+    let Some(block) = packet.strip_prefix(&COMPRESSED_MAGIC) else {
+        return Some(Cow::Borrowed(packet));
+    };
+    let (size, block) = block.split_first_chunk::<4>()?;
+    let size = u32::from_le_bytes(*size) as usize;
+    if size > MAX_DECOMPRESSED_LEN {
+        return None;
+    }
+    lz4_flex::decompress(block, size).ok().map(Cow::Owned)
+}
+
 /// Run the broadcaster loop (blocking, runs in thread)
-fn run_broadcaster(rx: mpsc::Receiver<LogEntry>, port: u16) {
+fn run_broadcaster(rx: mpsc::Receiver<LogEntry>, port: u16, compress: bool) {
     // Bind to any available port for sending
     let socket = match UdpSocket::bind("127.0.0.1:0") {
         Ok(s) => s,
@@ -30,10 +85,16 @@ fn run_broadcaster(rx: mpsc::Receiver<LogEntry>, port: u16) {
     let target = format!("127.0.0.1:{}", port);
 
     // Process messages until channel closes
+    let mut seq = 0u64;
     for entry in rx {
-        if let Ok(json) = serde_json::to_string(&entry) {
+        let packet = LogPacket {
+            seq: Some(seq),
+            entry,
+        };
+        if let Ok(json) = serde_json::to_string(&packet) {
             let msg = format!("{}\n", json);
-            let _ = socket.send_to(msg.as_bytes(), &target);
+            let _ = socket.send_to(&encode_packet(&msg, compress), &target);
+            seq += 1;
         }
     }
 }
@@ -85,4 +146,47 @@ mod tests {
             _ => panic!("Expected System log kind"),
         }
     }
+
+    #[test]
+    fn test_log_packet_seq_is_optional() {
+        let packet = LogPacket {
+            seq: Some(7),
+            entry: LogEntry::system("Test"),
+        };
+        let json = serde_json::to_string(&packet).unwrap();
+
+        // Older receivers read the entry and ignore `seq`
+        let entry: LogEntry = serde_json::from_str(&json).unwrap();
+        assert!(matches!(entry.kind, LogKind::System { .. }));
+
+        // Older senders omit it
+        let plain = serde_json::to_string(&LogEntry::system("Test")).unwrap();
+        let parsed: LogPacket = serde_json::from_str(&plain).unwrap();
+        assert_eq!(parsed.seq, None);
+        assert_eq!(
+            serde_json::from_str::<LogPacket>(&json).unwrap().seq,
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn test_packet_compression_roundtrip() {
+        let entry = LogEntry::debug_log(Some(LogLevel::Info), "tick ".repeat(40));
+        let line = format!("{}\n", serde_json::to_string(&entry).unwrap());
+
+        let packet = encode_packet(&line, true);
+        assert!(packet.starts_with(&COMPRESSED_MAGIC));
+        assert!(packet.len() < line.len());
+        assert_eq!(decode_packet(&packet).unwrap().as_ref(), line.as_bytes());
+
+        // Plain packets pass through unchanged
+        let plain = encode_packet(&line, false);
+        assert_eq!(plain.as_ref(), line.as_bytes());
+        assert_eq!(decode_packet(&plain).unwrap().as_ref(), line.as_bytes());
+
+        // Corrupt compressed payloads are rejected
+        assert!(decode_packet(&[0xAC, 0xE0, 0xFF]).is_none());
+        // A size prefix above one datagram is refused, not allocated
+        assert!(decode_packet(&[0xAC, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]).is_none());
+    }
 }
@@ -1,8 +1,25 @@
 //! Log entry types
 //!
 //! Core types for representing log entries from the bridge.
+//!
+//! On the wire (log broadcast, recordings, control plane) an entry keeps its
+//! original layout, `{"timestamp":…,"kind":{"Protocol":{…}}}`, which every
+//! TUI version reads. The JSON Lines export (`to_json_line`) tags the kind
+//! instead: `"kind":{"kind":"Protocol","direction":"In",…}`. Both load.
+//!
+//! Timestamps have microsecond resolution. On the wire an entry carries the
+//! original `"timestamp":"HH:MM:SS.mmm"` string, which older TUIs read, plus
+//! `"ts_us"`: Unix epoch microseconds. The export writes epoch microseconds
+//! as `timestamp`. Entries with only the string load as that time of day on
+//! a date given by the reader (today for the live broadcast, the file date
+//! for recordings). They display as `HH:MM:SS.mmm`; `{:.6}` shows the
+//! microseconds.
 
-use serde::{Deserialize, Serialize};
+use crate::error::{BridgeError, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 
 /// Log level for debug messages (matches OC_LOG levels)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -20,6 +37,41 @@ pub enum Direction {
     Out, // Host -> Controller
 }
 
+/// Why a controller connection ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisconnectReason {
+    /// Transport closed or failed (e.g. device unplugged)
+    Lost,
+    /// Serial port released via the control plane
+    Paused,
+    /// Bridge shutting down
+    Shutdown,
+}
+
+/// Controller connection lifecycle event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ControllerEventKind {
+    Connected { port: String, transport: String },
+    Disconnected { reason: DisconnectReason },
+    Reconnecting { attempt: u32 },
+    ReconnectFailed,
+}
+
+impl fmt::Display for ControllerEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connected { port, transport } => write!(f, "Connected: {}:{}", transport, port),
+            Self::Disconnected { reason } => match reason {
+                DisconnectReason::Lost => write!(f, "Connection lost"),
+                DisconnectReason::Paused => write!(f, "Disconnected (serial released)"),
+                DisconnectReason::Shutdown => write!(f, "Disconnected (shutdown)"),
+            },
+            Self::Reconnecting { attempt } => write!(f, "Reconnecting (attempt {})", attempt),
+            Self::ReconnectFailed => write!(f, "Reconnect failed"),
+        }
+    }
+}
+
 /// Type of log entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LogKind {
@@ -36,64 +88,352 @@ pub enum LogKind {
     },
     /// System message from bridge itself
     System { message: String },
+    /// Controller connection lifecycle (structured, for filtering)
+    ControllerEvent { event: ControllerEventKind },
+}
+
+/// Wall-clock time of a log entry, in microseconds since the Unix epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    pub fn now() -> Self {
+        Self(Local::now().timestamp_micros().max(0) as u64)
+    }
+
+    pub fn as_micros(self) -> u64 {
+        self.0
+    }
+
+    /// Epoch microseconds, or a local `HH:MM:SS[.fff…]` time of day today
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::parse_on(s, Local::now().date_naive())
+    }
+
+    /// Epoch microseconds, or a local `HH:MM:SS[.fff…]` time of day on `date`
+    pub fn parse_on(s: &str, date: NaiveDate) -> Option<Self> {
+        if let Ok(micros) = s.parse::<u64>() {
+            return Some(Self(micros));
+        }
+        let time = NaiveTime::parse_from_str(s, "%H:%M:%S%.f").ok()?;
+        let local = date.and_time(time).and_local_timezone(Local).earliest()?;
+        Some(Self(local.timestamp_micros().max(0) as u64))
+    }
+
+    fn local(self) -> DateTime<Local> {
+        DateTime::from_timestamp_micros(self.0 as i64)
+            .unwrap_or_default()
+            .with_timezone(&Local)
+    }
+}
+
+/// `HH:MM:SS.mmm`; the precision sets the fraction digits (`{:.6}`: µs)
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = f.precision().unwrap_or(3).min(6);
+        let time = self.local().format("%H:%M:%S");
+        if digits == 0 {
+            return write!(f, "{}", time);
+        }
+        let fraction = (self.0 % 1_000_000) / 10u64.pow(6 - digits as u32);
+        write!(f, "{}.{:0width$}", time, fraction, width = digits)
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct TimestampVisitor;
+
+        impl Visitor<'_> for TimestampVisitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("epoch microseconds or an HH:MM:SS.mmm string")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Timestamp, E> {
+                Ok(Timestamp(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Timestamp, E> {
+                u64::try_from(v)
+                    .map(Timestamp)
+                    .map_err(|_| E::custom("negative timestamp"))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Timestamp, E> {
+                Timestamp::parse(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(TimestampVisitor)
+    }
 }
 
 /// Log entry from bridge operations (serializable for UDP broadcast)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "WireEntry")]
 pub struct LogEntry {
-    pub timestamp: String, // HH:MM:SS.mmm
+    pub timestamp: Timestamp,
     pub kind: LogKind,
+    /// Note added by the user in the TUI (`LogStore::annotate`)
+    pub user_note: Option<String>,
+}
+
+/// `LogEntry` as read from JSON, before its time of day gets a date
+#[derive(Deserialize)]
+struct WireEntry {
+    /// Epoch microseconds, or `HH:MM:SS.mmm`
+    timestamp: WireTime,
+    /// Epoch microseconds next to the string (absent from older writers)
+    #[serde(default)]
+    ts_us: Option<u64>,
+    #[serde(deserialize_with = "deserialize_kind")]
+    kind: LogKind,
+    #[serde(default)]
+    user_note: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WireTime {
+    Micros(u64),
+    Text(String),
+}
+
+impl WireEntry {
+    /// Resolve the timestamp, dating a bare time of day on `date`
+    fn into_entry(self, date: NaiveDate) -> std::result::Result<LogEntry, String> {
+        let timestamp = match (self.ts_us, self.timestamp) {
+            (Some(micros), _) | (None, WireTime::Micros(micros)) => Timestamp(micros),
+            (None, WireTime::Text(text)) => Timestamp::parse_on(&text, date)
+                .ok_or_else(|| format!("invalid timestamp {:?}", text))?,
+        };
+        Ok(LogEntry {
+            timestamp,
+            kind: self.kind,
+            user_note: self.user_note,
+        })
+    }
+}
+
+impl TryFrom<WireEntry> for LogEntry {
+    type Error = String;
+
+    fn try_from(wire: WireEntry) -> std::result::Result<Self, String> {
+        wire.into_entry(Local::now().date_naive())
+    }
+}
+
+impl Serialize for LogEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct WireEntryRef<'a> {
+            timestamp: String,
+            ts_us: u64,
+            kind: &'a LogKind,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            user_note: Option<&'a str>,
+        }
+        WireEntryRef {
+            timestamp: self.timestamp.to_string(),
+            ts_us: self.timestamp.as_micros(),
+            kind: &self.kind,
+            user_note: self.user_note.as_deref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// `LogKind` in the JSON Lines export (internally tagged)
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "LogKind", tag = "kind")]
+enum TaggedLogKind {
+    Protocol {
+        direction: Direction,
+        message_name: String,
+        size: usize,
+    },
+    Debug {
+        level: Option<LogLevel>,
+        message: String,
+    },
+    System {
+        message: String,
+    },
+    ControllerEvent {
+        event: ControllerEventKind,
+    },
+}
+
+/// Read either the wire or the export `LogKind` form
+fn deserialize_kind<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<LogKind, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyKind {
+        Wire(LogKind),
+        Tagged(#[serde(with = "TaggedLogKind")] LogKind),
+    }
+    match AnyKind::deserialize(deserializer)? {
+        AnyKind::Wire(kind) | AnyKind::Tagged(kind) => Ok(kind),
+    }
+}
+
+/// Serializes a `LogKind` in the export form (`{"kind":"Protocol",…}`)
+pub struct TaggedKind<'a>(pub &'a LogKind);
+
+impl Serialize for TaggedKind<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        TaggedLogKind::serialize(self.0, serializer)
+    }
 }
 
 impl LogEntry {
-    /// Current timestamp as HH:MM:SS.mmm
-    #[inline]
-    fn now() -> String {
-        chrono::Local::now().format("%H:%M:%S%.3f").to_string()
+    /// One JSON Lines export record, with the tagged `kind`
+    pub fn to_json_line(&self) -> serde_json::Result<String> {
+        #[derive(Serialize)]
+        struct ExportEntry<'a> {
+            timestamp: Timestamp,
+            kind: TaggedKind<'a>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            user_note: Option<&'a str>,
+        }
+        serde_json::to_string(&ExportEntry {
+            timestamp: self.timestamp,
+            kind: TaggedKind(&self.kind),
+            user_note: self.user_note.as_deref(),
+        })
+    }
+
+    /// Parse one JSON Lines record (`LogStore::to_jsonl`, recordings)
+    ///
+    /// Errors report line 1; callers reading a file set the real line.
+    #[cfg(test)]
+    pub fn from_json_line(s: &str) -> Result<Self> {
+        Self::from_json_line_on(s, Local::now().date_naive())
+    }
+
+    /// `from_json_line`, dating bare `HH:MM:SS.mmm` timestamps on `date`
+    pub fn from_json_line_on(s: &str, date: NaiveDate) -> Result<Self> {
+        let parse_error = |reason: String| BridgeError::LogParse { line: 1, reason };
+        serde_json::from_str::<WireEntry>(s.trim())
+            .map_err(|e| parse_error(e.to_string()))?
+            .into_entry(date)
+            .map_err(parse_error)
     }
 
     /// Create a system log entry
     pub fn system(message: impl Into<String>) -> Self {
         Self {
-            timestamp: Self::now(),
+            timestamp: Timestamp::now(),
             kind: LogKind::System {
                 message: message.into(),
             },
+            user_note: None,
         }
     }
 
     /// Create a protocol log entry for incoming message
     pub fn protocol_in(message_name: impl Into<String>, size: usize) -> Self {
         Self {
-            timestamp: Self::now(),
+            timestamp: Timestamp::now(),
             kind: LogKind::Protocol {
                 direction: Direction::In,
                 message_name: message_name.into(),
                 size,
             },
+            user_note: None,
         }
     }
 
     /// Create a protocol log entry for outgoing message
     pub fn protocol_out(message_name: impl Into<String>, size: usize) -> Self {
         Self {
-            timestamp: Self::now(),
+            timestamp: Timestamp::now(),
             kind: LogKind::Protocol {
                 direction: Direction::Out,
                 message_name: message_name.into(),
                 size,
             },
+            user_note: None,
+        }
+    }
+
+    /// Create a controller lifecycle entry
+    pub fn controller_event(event: ControllerEventKind) -> Self {
+        Self {
+            timestamp: Timestamp::now(),
+            kind: LogKind::ControllerEvent { event },
+            user_note: None,
         }
     }
 
     /// Create a debug log entry
     pub fn debug_log(level: Option<LogLevel>, message: impl Into<String>) -> Self {
         Self {
-            timestamp: Self::now(),
+            timestamp: Timestamp::now(),
             kind: LogKind::Debug {
                 level,
                 message: message.into(),
             },
+            user_note: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_display_and_wire_format() {
+        let ts = Timestamp::parse("12:34:56.789012").unwrap();
+        assert_eq!(ts.to_string(), "12:34:56.789");
+        assert_eq!(format!("{:.6}", ts), "12:34:56.789012");
+        assert_eq!(Timestamp::parse(&ts.as_micros().to_string()), Some(ts));
+
+        // Older readers get the string they expect; newer ones the µs
+        let mut entry = LogEntry::system("ok");
+        entry.timestamp = ts;
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.starts_with(&format!(
+            "{{\"timestamp\":\"12:34:56.789\",\"ts_us\":{},",
+            ts.as_micros()
+        )));
+        assert_eq!(LogEntry::from_json_line(&json).unwrap().timestamp, ts);
+        let export = entry.to_json_line().unwrap();
+        assert!(export.starts_with(&format!("{{\"timestamp\":{},", ts.as_micros())));
+        assert_eq!(LogEntry::from_json_line(&export).unwrap().timestamp, ts);
+
+        // Entries from before the microsecond timestamps, dated by the reader
+        let legacy = r#"{"timestamp":"12:34:56.789","kind":{"System":{"message":"ok"}}}"#;
+        let entry = LogEntry::from_json_line(legacy).unwrap();
+        assert_eq!(format!("{:.6}", entry.timestamp), "12:34:56.789000");
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let entry = LogEntry::from_json_line_on(legacy, date).unwrap();
+        assert_eq!(entry.timestamp.local().date_naive(), date);
+    }
+
+    #[test]
+    fn test_kind_wire_layout_is_unchanged() {
+        // What TUIs from before the export format parse
+        let entry = LogEntry::protocol_in("NoteOn", 9);
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["kind"]["Protocol"]["message_name"], "NoteOn");
+
+        let export = entry.to_json_line().unwrap();
+        assert!(export.contains(r#""kind":{"kind":"Protocol","direction":"In""#));
+        for line in [json.to_string(), export] {
+            let entry = LogEntry::from_json_line(&line).unwrap();
+            assert!(matches!(entry.kind, LogKind::Protocol { size: 9, .. }));
         }
     }
 }
@@ -3,7 +3,12 @@
 //! The bridge dataplane must stay responsive, so file logging is implemented as:
 //! - a bounded queue (non-blocking `try_send`)
 //! - a dedicated thread with buffered writes and periodic flush
+//!
+//! `FileLogger` is the TUI counterpart (`[logs] file_logging`): it keeps what
+//! the TUI received across restarts, one `tui-<start>-<n>.log` series per run,
+//! in the `LogStore::to_text` format so `oc-bridge analyze` opens them.
 
+use super::store::format_log_entry_text;
 use super::{Direction, LogEntry, LogKind, LogLevel};
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, BufWriter, Write};
@@ -24,7 +29,7 @@ impl FileLogFilter {
         match &entry.kind {
             LogKind::Protocol { .. } => self.include_protocol,
             LogKind::Debug { .. } => self.include_debug,
-            LogKind::System { .. } => self.include_system,
+            LogKind::System { .. } | LogKind::ControllerEvent { .. } => self.include_system,
         }
     }
 }
@@ -113,6 +118,121 @@ fn run_logger(rx: Receiver<LogEntry>, cfg: FileLoggerConfig, file: File, start_s
     }
 }
 
+// =============================================================================
+// TUI session logs
+// =============================================================================
+
+/// File name prefix of TUI session logs
+const SESSION_LOG_PREFIX: &str = "tui-";
+
+/// Appends TUI log entries to size-capped files in `dir`
+///
+/// Writes are buffered: call `flush` after a batch of `append`s.
+pub struct FileLogger {
+    dir: PathBuf,
+    max_file_size_bytes: u64,
+    max_files: usize,
+    /// Start of this run, shared by its files
+    stamp: String,
+    /// Files opened so far in this run
+    index: usize,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    size: u64,
+}
+
+impl FileLogger {
+    /// Create `dir` and open the first file of this run
+    pub fn new(dir: PathBuf, max_file_size_bytes: u64, max_files: usize) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let start = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+        // Another TUI started in the same second: `_1`, `_2`... sort after it
+        let mut attempt = 0;
+        let (stamp, path, file) = loop {
+            let stamp = match attempt {
+                0 => start.clone(),
+                n => format!("{}_{}", start, n),
+            };
+            let path = session_log_path(&dir, &stamp, 0);
+            match open_new(&path) {
+                Ok(file) => break (stamp, path, file),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        };
+        let writer = BufWriter::new(file);
+        let logger = Self {
+            dir,
+            max_file_size_bytes: max_file_size_bytes.max(1024),
+            max_files: max_files.max(1),
+            stamp,
+            index: 0,
+            path,
+            writer,
+            size: 0,
+        };
+        logger.prune();
+        Ok(logger)
+    }
+
+    /// File currently written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write `entry` as one line, moving to a new file once the size is reached
+    pub fn append(&mut self, entry: &LogEntry) -> io::Result<()> {
+        let line = format_log_entry_text(entry);
+        write_line(&mut self.writer, &line)?;
+        self.size = self.size.saturating_add(line.len() as u64 + 1);
+        if self.size >= self.max_file_size_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        self.index += 1;
+        self.path = session_log_path(&self.dir, &self.stamp, self.index);
+        self.writer = BufWriter::new(open_new(&self.path)?);
+        self.size = 0;
+        self.prune();
+        Ok(())
+    }
+
+    /// Delete the oldest session logs beyond `max_files` (best effort)
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(SESSION_LOG_PREFIX) && n.ends_with(".log"))
+            })
+            .collect();
+        // Names sort by start time, then by index
+        files.sort();
+        let excess = files.len().saturating_sub(self.max_files);
+        for old in &files[..excess] {
+            let _ = fs::remove_file(old);
+        }
+    }
+}
+
+/// `{dir}/tui-<stamp>-<index>.log`, zero-padded so names sort in order
+fn session_log_path(dir: &Path, stamp: &str, index: usize) -> PathBuf {
+    dir.join(format!("{}{}-{:04}.log", SESSION_LOG_PREFIX, stamp, index))
+}
+
 fn write_line(writer: &mut BufWriter<File>, line: &str) -> io::Result<()> {
     writer.write_all(line.as_bytes())?;
     writer.write_all(b"\n")?;
@@ -121,7 +241,8 @@ fn write_line(writer: &mut BufWriter<File>, line: &str) -> io::Result<()> {
 
 fn format_entry(entry: &LogEntry) -> String {
     match &entry.kind {
-        LogKind::System { message } => format!("{} [SYS] {}", entry.timestamp, message),
+        LogKind::System { message } => format!("{:.6} [SYS] {}", entry.timestamp, message),
+        LogKind::ControllerEvent { event } => format!("{:.6} [EVT] {}", entry.timestamp, event),
         LogKind::Debug { level, message } => {
             let level_str = match level {
                 Some(LogLevel::Debug) => "[DEBUG]",
@@ -130,7 +251,7 @@ fn format_entry(entry: &LogEntry) -> String {
                 Some(LogLevel::Error) => "[ERROR]",
                 None => "[LOG]",
             };
-            format!("{} {} {}", entry.timestamp, level_str, message)
+            format!("{:.6} {} {}", entry.timestamp, level_str, message)
         }
         LogKind::Protocol {
             direction,
@@ -142,7 +263,7 @@ fn format_entry(entry: &LogEntry) -> String {
                 Direction::Out => "OUT",
             };
             format!(
-                "{} [PROTO] {} {} ({} B)",
+                "{:.6} [PROTO] {} {} ({} B)",
                 entry.timestamp, dir, message_name, size
             )
         }
@@ -163,6 +284,11 @@ fn open_truncate(path: &Path) -> io::Result<File> {
         .open(path)
 }
 
+/// Fails instead of truncating when `path` exists (another run's file)
+fn open_new(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
 fn rotate_files(path: &Path, max_files: usize) -> io::Result<()> {
     if max_files == 0 {
         return Ok(());
@@ -228,4 +354,65 @@ mod tests {
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn test_file_logger_rotates_and_prunes() {
+        let dir = unique_temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        // Session logs of an earlier run, and an unrelated file
+        fs::write(dir.join("tui-20000101_000000-0000.log"), "old").unwrap();
+        fs::write(dir.join("tui-20000101_000000-0001.log"), "old").unwrap();
+        fs::write(dir.join("notes.txt"), "keep").unwrap();
+
+        let mut logger = FileLogger::new(dir.clone(), 1024, 2).unwrap();
+        let first = logger.path().to_path_buf();
+        assert!(!dir.join("tui-20000101_000000-0000.log").exists());
+        assert!(dir.join("tui-20000101_000000-0001.log").exists());
+
+        let entry = LogEntry::system("x".repeat(100));
+        for _ in 0..25 {
+            logger.append(&entry).unwrap();
+        }
+        logger.flush().unwrap();
+
+        // Over 2 KB written: a third file was opened, the first one deleted
+        assert!(logger
+            .path()
+            .ends_with(format!("tui-{}-0002.log", logger.stamp)));
+        assert!(!first.exists());
+        let kept = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("tui-"))
+            .count();
+        assert_eq!(kept, 2);
+        let text = fs::read_to_string(logger.path()).unwrap();
+        assert!(text
+            .lines()
+            .all(|l| l.ends_with(&format!("[SYS] {}", "x".repeat(100)))));
+        assert!(dir.join("notes.txt").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_file_loggers_started_together_get_own_files() {
+        let dir = unique_temp_dir().join("same-second");
+        let mut a = FileLogger::new(dir.clone(), 1024, 10).unwrap();
+        let mut b = FileLogger::new(dir.clone(), 1024, 10).unwrap();
+        assert_ne!(a.path(), b.path());
+
+        a.append(&LogEntry::system("from a")).unwrap();
+        a.flush().unwrap();
+        b.append(&LogEntry::system("from b")).unwrap();
+        b.flush().unwrap();
+        assert!(fs::read_to_string(a.path())
+            .unwrap()
+            .ends_with("[SYS] from a\n"));
+        assert!(fs::read_to_string(b.path())
+            .unwrap()
+            .ends_with("[SYS] from b\n"));
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
 }
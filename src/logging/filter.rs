@@ -14,6 +14,21 @@ pub enum FilterMode {
     Protocol,
     /// Show only debug logs
     Debug,
+    /// Show only controller lifecycle events and system messages
+    Events,
+}
+
+impl FilterMode {
+    /// Parse a `ui.default_filter` value ("Protocol", "Debug", "All", "Events")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "all" => Some(Self::All),
+            "protocol" => Some(Self::Protocol),
+            "debug" => Some(Self::Debug),
+            "events" => Some(Self::Events),
+            _ => None,
+        }
+    }
 }
 
 /// Log filter configuration
@@ -22,6 +37,7 @@ pub struct LogFilter {
     pub show_protocol: bool,
     pub show_debug: bool,
     pub show_system: bool,
+    pub show_events: bool,
     pub show_direction_in: bool,
     pub show_direction_out: bool,
     pub message_types: HashSet<String>, // Empty = all allowed
@@ -34,6 +50,7 @@ impl Default for LogFilter {
             show_protocol: true,
             show_debug: true,
             show_system: true,
+            show_events: true,
             show_direction_in: true,
             show_direction_out: true,
             message_types: HashSet::new(),
@@ -77,6 +94,7 @@ impl LogFilter {
                 }
             }
             LogKind::System { .. } => self.show_system,
+            LogKind::ControllerEvent { .. } => self.show_events,
         }
     }
 }
@@ -284,6 +302,7 @@ mod tests {
             show_protocol: true,
             show_debug: true,
             show_system: false,
+            show_events: false,
             show_direction_in: true,
             show_direction_out: false,
             message_types: ["NoteOn"].iter().map(|s| s.to_string()).collect(),
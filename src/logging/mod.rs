@@ -1,9 +1,10 @@
 //! Unified logging system
 //!
 //! Centralizes all log-related types and utilities:
-//! - `LogEntry` - Individual log entries (protocol, debug, system)
+//! - `LogEntry` - Individual log entries (protocol, debug, system, controller events)
 //! - `LogStore` - In-memory log storage with filtering
 //! - `broadcast/receiver` - UDP log streaming (service ↔ TUI)
+//! - `ws_stream` - WebSocket feed of received entries for external tools
 
 pub mod broadcast;
 pub mod entry;
@@ -11,10 +12,13 @@ pub mod file;
 pub mod filter;
 pub mod receiver;
 pub mod store;
+pub mod ws_stream;
 
-pub use entry::{Direction, LogEntry, LogKind, LogLevel};
+pub use entry::{
+    ControllerEventKind, Direction, DisconnectReason, LogEntry, LogKind, LogLevel, Timestamp,
+};
 pub use filter::{FilterMode, LogFilter};
-pub use store::LogStore;
+pub use store::{console_line, match_ranges, search_text, LogStore, EVENT_TAG};
 
 /// Initialize internal tracing for bridge debug output
 ///
@@ -1,21 +1,82 @@
 //! Log receiver for TUI ← daemon communication
 //!
 //! Receives LogEntry messages via UDP from `oc-bridge --daemon`.
+//!
+//! Packet sequence numbers (see `broadcast::LogPacket`) give the link
+//! quality: gaps count as dropped packets, and a sequence that starts over
+//! means the daemon restarted (a reconnect). Entries the TUI channel cannot
+//! take are counted as dropped too.
 
+use super::broadcast::{decode_packet, LogPacket};
 use super::LogEntry;
 use crate::constants::CHANNEL_CAPACITY;
+use parking_lot::Mutex;
 use std::net::UdpSocket;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
+/// Log link quality as seen by the receiver
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogReceiverStats {
+    pub packets_received: u64,
+    pub packets_dropped: u64,
+    /// Daemon restarts seen (sequence started over)
+    pub reconnect_count: u32,
+    /// Time since the last packet (zero before the first one)
+    pub last_packet_ago: Duration,
+}
+
+/// Counters updated by the receiver thread
+#[derive(Debug, Default)]
+struct Counters {
+    received: u64,
+    dropped: u64,
+    reconnects: u32,
+    last_packet: Option<Instant>,
+    next_seq: Option<u64>,
+}
+
+impl Counters {
+    fn on_packet(&mut self, seq: Option<u64>, now: Instant) {
+        self.received += 1;
+        self.last_packet = Some(now);
+        let Some(seq) = seq else {
+            return;
+        };
+        match self.next_seq {
+            Some(expected) if seq > expected => self.dropped += seq - expected,
+            Some(expected) if seq < expected => self.reconnects += 1,
+            _ => {}
+        }
+        self.next_seq = Some(seq + 1);
+    }
+}
+
+/// Receiver side of `LogReceiverStats`, cheap to clone
+#[derive(Debug, Clone, Default)]
+pub struct ReceiverStatsHandle(Arc<Mutex<Counters>>);
+
+impl ReceiverStatsHandle {
+    pub fn snapshot(&self) -> LogReceiverStats {
+        let counters = self.0.lock();
+        LogReceiverStats {
+            packets_received: counters.received,
+            packets_dropped: counters.dropped,
+            reconnect_count: counters.reconnects,
+            last_packet_ago: counters.last_packet.map_or(Duration::ZERO, |t| t.elapsed()),
+        }
+    }
+}
+
 /// Spawn a UDP log receiver with a custom port
 pub fn spawn_log_receiver_with_port(
     shutdown: Arc<AtomicBool>,
     port: u16,
-) -> std::io::Result<mpsc::Receiver<LogEntry>> {
+) -> std::io::Result<(mpsc::Receiver<LogEntry>, ReceiverStatsHandle)> {
     let (tx, rx) = mpsc::channel::<LogEntry>(CHANNEL_CAPACITY);
+    let stats = ReceiverStatsHandle::default();
 
     // Bind up-front so callers can handle port-in-use cleanly.
     let socket = UdpSocket::bind(format!("127.0.0.1:{port}"))?;
@@ -23,29 +84,43 @@ pub fn spawn_log_receiver_with_port(
         .set_read_timeout(Some(Duration::from_millis(100)))
         .ok();
 
+    let counters = stats.clone();
     std::thread::spawn(move || {
-        run_receiver(socket, tx, shutdown);
+        run_receiver(socket, tx, counters, shutdown);
     });
 
-    Ok(rx)
+    Ok((rx, stats))
 }
 
 /// Run the receiver loop (blocking, runs in thread)
-fn run_receiver(socket: UdpSocket, tx: mpsc::Sender<LogEntry>, shutdown: Arc<AtomicBool>) {
+fn run_receiver(
+    socket: UdpSocket,
+    tx: mpsc::Sender<LogEntry>,
+    stats: ReceiverStatsHandle,
+    shutdown: Arc<AtomicBool>,
+) {
     let mut buf = [0u8; 65535];
 
     loop {
-        if shutdown.load(Ordering::Relaxed) {
+        // The TUI dropped the receiver (e.g. to listen on another port)
+        if shutdown.load(Ordering::Relaxed) || tx.is_closed() {
             break;
         }
 
         match socket.recv_from(&mut buf) {
             Ok((len, _addr)) => {
-                if let Ok(text) = std::str::from_utf8(&buf[..len]) {
+                let Some(packet) = decode_packet(&buf[..len]) else {
+                    continue;
+                };
+                if let Ok(text) = std::str::from_utf8(&packet) {
                     // Handle potential multiple JSON messages in one packet
                     for line in text.lines() {
-                        if let Ok(entry) = serde_json::from_str::<LogEntry>(line) {
-                            let _ = tx.try_send(entry);
+                        if let Ok(packet) = serde_json::from_str::<LogPacket>(line) {
+                            let mut counters = stats.0.lock();
+                            counters.on_packet(packet.seq, Instant::now());
+                            if tx.try_send(packet.entry).is_err() {
+                                counters.dropped += 1;
+                            }
                         }
                     }
                 }
@@ -71,12 +146,31 @@ mod tests {
     use super::*;
     use crate::logging::{LogKind, LogLevel};
 
+    #[test]
+    fn test_counters_track_gaps_and_restarts() {
+        let now = Instant::now();
+        let mut counters = Counters::default();
+        for seq in [0, 1, 2, 5, 6] {
+            counters.on_packet(Some(seq), now);
+        }
+        assert_eq!((counters.received, counters.dropped), (5, 2));
+
+        // Daemon restarted: sequence starts over
+        counters.on_packet(Some(0), now);
+        counters.on_packet(Some(1), now);
+        assert_eq!((counters.dropped, counters.reconnects), (2, 1));
+
+        // Older senders have no sequence: counted, never dropped
+        counters.on_packet(None, now);
+        assert_eq!((counters.received, counters.dropped), (8, 2));
+    }
+
     #[test]
     fn test_log_entry_deserialization() {
         let json = r#"{"timestamp":"12:34:56.789","kind":{"Protocol":{"direction":"In","message_name":"DeviceChange","size":128}}}"#;
         let entry: LogEntry = serde_json::from_str(json).unwrap();
 
-        assert_eq!(entry.timestamp, "12:34:56.789");
+        assert_eq!(entry.timestamp.to_string(), "12:34:56.789");
         match entry.kind {
             LogKind::Protocol {
                 message_name, size, ..
@@ -114,4 +208,19 @@ mod tests {
             _ => panic!("Expected System kind"),
         }
     }
+
+    #[test]
+    fn test_controller_event_deserialization() {
+        use crate::logging::ControllerEventKind;
+
+        let json = r#"{"timestamp":"12:34:56.789","kind":{"ControllerEvent":{"event":{"Reconnecting":{"attempt":3}}}}}"#;
+        let entry: LogEntry = serde_json::from_str(json).unwrap();
+
+        match entry.kind {
+            LogKind::ControllerEvent { event } => {
+                assert_eq!(event, ControllerEventKind::Reconnecting { attempt: 3 });
+            }
+            _ => panic!("Expected ControllerEvent kind"),
+        }
+    }
 }
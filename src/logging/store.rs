@@ -1,10 +1,73 @@
 //! Log storage with filtering, scrolling, and export
 //!
-//! Pure data structure for managing log entries with no I/O side effects.
+//! Pure data structure for managing log entries with no I/O side effects
+//! (apart from `from_file`, used to import logs for offline analysis).
+
+use super::{Direction, FilterMode, LogEntry, LogFilter, LogKind, LogLevel, Timestamp};
+use crate::constants::{AUTO_SCROLL_THRESHOLD, DEDUP_FLUSH_IDLE_MS};
+use crate::error::{BridgeError, Result};
+use chrono::NaiveDate;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Message names listed in `LogStatistics::top_message_types`
+const TOP_MESSAGE_TYPES: usize = 10;
+
+/// Separates an entry from its user note in the text export
+const NOTE_SEPARATOR: &str = " ✎ ";
+
+/// Aggregate counts over every entry in a `LogStore`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogStatistics {
+    pub total_entries: usize,
+    pub protocol_count: usize,
+    pub debug_count: usize,
+    pub system_count: usize,
+    /// Controller connection events
+    pub event_count: usize,
+    /// Debug entries per level: Debug, Info, Warn, Error (unleveled excluded)
+    pub debug_level_counts: [usize; 4],
+    /// Most frequent protocol message names, most frequent first
+    pub top_message_types: Vec<(String, usize)>,
+    pub first_timestamp: Option<String>,
+    pub last_timestamp: Option<String>,
+    /// Time from the first to the last entry (assumes less than a day)
+    pub duration_secs: Option<f64>,
+}
 
-use super::{Direction, FilterMode, LogEntry, LogFilter, LogKind, LogLevel};
-use crate::constants::AUTO_SCROLL_THRESHOLD;
-use std::collections::VecDeque;
+impl LogStatistics {
+    /// Plain-text table, one line per row (CLI and TUI popup)
+    pub fn table_lines(&self) -> Vec<String> {
+        let [dbg, inf, wrn, err] = self.debug_level_counts;
+        let mut lines = vec![
+            format!("{:<16}{}", "Entries", self.total_entries),
+            format!("{:<16}{}", "Protocol", self.protocol_count),
+            format!(
+                "{:<16}{}  (debug {}, info {}, warn {}, error {})",
+                "Debug", self.debug_count, dbg, inf, wrn, err
+            ),
+            format!("{:<16}{}", "System", self.system_count),
+            format!("{:<16}{}", "Events", self.event_count),
+        ];
+        if let (Some(first), Some(last)) = (&self.first_timestamp, &self.last_timestamp) {
+            lines.push(format!("{:<16}{} - {}", "Time span", first, last));
+        }
+        if let Some(secs) = self.duration_secs {
+            lines.push(format!("{:<16}{:.3} s", "Duration", secs));
+        }
+        if !self.top_message_types.is_empty() {
+            lines.push(String::new());
+            lines.push("Top message types".to_string());
+            for (name, count) in &self.top_message_types {
+                lines.push(format!("  {:<30} {:>8}", name, count));
+            }
+        }
+        lines
+    }
+}
 
 /// Log storage with filtering, scrolling, and text export.
 ///
@@ -17,6 +80,9 @@ use std::collections::VecDeque;
 /// - **Filtering**: By log type (Protocol/Debug/System) with cached count
 /// - **Scrolling**: Manual scroll with auto-scroll to bottom on new entries
 /// - **Pause**: Freeze scroll position while still receiving logs
+/// - **Bookmarks**: Marked entries by absolute index (kept across filter changes)
+/// - **Search**: Filtered entries whose message contains a query
+/// - **Dedup**: Long runs of one repeated message collapse into a count
 /// - **Export**: Format filtered logs as plain text
 pub struct LogStore {
     entries: VecDeque<LogEntry>,
@@ -28,6 +94,32 @@ pub struct LogStore {
     /// Cached count of filtered entries (O(1) access)
     filtered_cache: usize,
     paused: bool,
+    /// Bumped on every change that affects rendering
+    revision: u64,
+    /// Absolute index of `entries[0]` (entries ever added before it)
+    first_index: usize,
+    /// Absolute indices of bookmarked entries still in the buffer
+    bookmarks: BTreeSet<usize>,
+    /// Text searched in messages (`set_search`), as typed
+    search_query: Option<String>,
+    /// Filtered positions of the entries matching `search_query`, ascending
+    search_matches: Vec<usize>,
+    /// Identical consecutive entries kept before the rest are counted (0 = off)
+    dedup_threshold: usize,
+    dedup: DedupState,
+}
+
+/// Run of identical consecutive entries (`LogStore::set_dedup_threshold`)
+#[derive(Debug, Default)]
+struct DedupState {
+    /// Key of the last entry added (`None`: never deduplicated)
+    last_key: Option<String>,
+    /// Last entry of the run counted (see `flush_idle_dedup`)
+    last_repeat: Option<Instant>,
+    /// Entries of the run stored so far (up to the threshold)
+    stored: usize,
+    /// Entries of the run dropped after the threshold
+    count: u64,
 }
 
 impl LogStore {
@@ -42,13 +134,149 @@ impl LogStore {
             filter_mode: FilterMode::All,
             filtered_cache: 0,
             paused: false,
+            revision: 0,
+            first_index: 0,
+            bookmarks: BTreeSet::new(),
+            search_query: None,
+            search_matches: Vec::new(),
+            dedup_threshold: 0,
+            dedup: DedupState::default(),
+        }
+    }
+
+    // === Import ===
+
+    /// Load a log file for offline analysis
+    ///
+    /// Accepts JSON Lines (one serialized `LogEntry` per line, as broadcast
+    /// by the daemon) or the plain-text export format. Only the most recent
+    /// `max_entries` entries are kept.
+    pub fn from_file(path: &Path, max_entries: usize) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| BridgeError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        let is_json = text
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .is_some_and(|l| l.starts_with('{'));
+
+        if is_json {
+            // Entries written with only a time of day get the file's date
+            let date = std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .map(|modified| chrono::DateTime::<chrono::Local>::from(modified).date_naive())
+                .unwrap_or_else(|_| chrono::Local::now().date_naive());
+            Self::from_json_lines_on(&text, max_entries, date)
+        } else {
+            Self::from_text(&text, max_entries)
+        }
+    }
+
+    /// Parse JSON Lines (one serialized `LogEntry` per line)
+    #[cfg(test)]
+    pub fn from_json_lines(text: &str, max_entries: usize) -> Result<Self> {
+        Self::from_json_lines_on(text, max_entries, chrono::Local::now().date_naive())
+    }
+
+    /// `from_json_lines`, dating bare `HH:MM:SS.mmm` timestamps on `date`
+    fn from_json_lines_on(text: &str, max_entries: usize, date: NaiveDate) -> Result<Self> {
+        let mut store = Self::new(max_entries);
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let entry = LogEntry::from_json_line_on(line, date).map_err(|e| match e {
+                BridgeError::LogParse { reason, .. } => BridgeError::LogParse {
+                    line: i + 1,
+                    reason,
+                },
+                e => e,
+            })?;
+            store.add(entry);
         }
+        Ok(store)
+    }
+
+    /// Parse plain-text logs in the `to_text` export format
+    pub fn from_text(text: &str, max_entries: usize) -> Result<Self> {
+        let mut store = Self::new(max_entries);
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = parse_log_entry_text(line).ok_or_else(|| BridgeError::LogParse {
+                line: i + 1,
+                reason: "unrecognized format".to_string(),
+            })?;
+            store.add(entry);
+        }
+        Ok(store)
     }
 
     // === Log addition ===
 
     /// Add a log entry, rotating out old entries if at capacity
+    ///
+    /// With deduplication on, a repeat beyond the threshold is only counted;
+    /// the next different entry, or `flush_idle_dedup` once the run goes
+    /// quiet, appends a system "Last message repeated N times" entry.
     pub fn add(&mut self, entry: LogEntry) {
+        if self.dedup_threshold > 0 {
+            let key = dedup_key(&entry);
+            if key.is_some() && key == self.dedup.last_key {
+                if self.dedup.stored >= self.dedup_threshold {
+                    self.dedup.count += 1;
+                    self.dedup.last_repeat = Some(Instant::now());
+                    return;
+                }
+                self.dedup.stored += 1;
+            } else {
+                self.flush_dedup();
+                self.dedup.last_key = key;
+                self.dedup.stored = 1;
+            }
+        }
+        self.push(entry);
+    }
+
+    /// Keep at most `threshold` identical consecutive entries (0 = keep all)
+    pub fn set_dedup_threshold(&mut self, threshold: usize) {
+        self.flush_dedup();
+        self.dedup = DedupState::default();
+        self.dedup_threshold = threshold;
+    }
+
+    /// Report the repeat count of a run quiet for `DEDUP_FLUSH_IDLE_MS`
+    ///
+    /// Called on every UI tick: a run that simply stops has no next entry to
+    /// flush it. Further repeats keep being counted for the next summary.
+    pub fn flush_idle_dedup(&mut self, now: Instant) {
+        let idle = self.dedup.last_repeat.is_some_and(|t| {
+            now.saturating_duration_since(t) >= Duration::from_millis(DEDUP_FLUSH_IDLE_MS)
+        });
+        if idle {
+            self.flush_dedup();
+        }
+    }
+
+    /// Append the repeat count of the current run, if entries were dropped
+    fn flush_dedup(&mut self) {
+        self.dedup.last_repeat = None;
+        let count = std::mem::take(&mut self.dedup.count);
+        if count > 0 {
+            self.push(LogEntry::system(format!(
+                "Last message repeated {} times",
+                count
+            )));
+        }
+    }
+
+    fn push(&mut self, entry: LogEntry) {
+        self.touch();
         // Check if new entry matches filter
         let entry_matches_filter = self.filter.matches(&entry);
 
@@ -57,9 +285,14 @@ impl LogStore {
             if let Some(removed) = self.entries.front() {
                 if self.filter.matches(removed) {
                     self.filtered_cache = self.filtered_cache.saturating_sub(1);
+                    // Filtered positions move down by one
+                    self.search_matches.retain(|&p| p > 0);
+                    self.search_matches.iter_mut().for_each(|p| *p -= 1);
                 }
             }
             self.entries.pop_front();
+            self.first_index += 1;
+            self.bookmarks.remove(&(self.first_index - 1));
             // When paused, adjust scroll to compensate for removed filtered entry
             if self.paused && entry_matches_filter && self.scroll > 0 {
                 self.scroll = self.scroll.saturating_sub(1);
@@ -70,6 +303,10 @@ impl LogStore {
         // Update cache
         if entry_matches_filter {
             self.filtered_cache += 1;
+            let entry = self.entries.back().expect("just pushed");
+            if self.matches_search(entry) {
+                self.search_matches.push(self.filtered_cache - 1);
+            }
         }
 
         // Only update scroll if auto_scroll AND the new entry matches the current filter
@@ -79,8 +316,31 @@ impl LogStore {
         }
     }
 
+    /// Change the capacity, dropping the oldest entries beyond it
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.touch();
+        self.max_entries = max_entries;
+        while self.entries.len() > max_entries {
+            let Some(removed) = self.entries.pop_front() else {
+                break;
+            };
+            if self.filter.matches(&removed) {
+                self.filtered_cache = self.filtered_cache.saturating_sub(1);
+                self.scroll = self.scroll.saturating_sub(1);
+            }
+            self.bookmarks.remove(&self.first_index);
+            self.first_index += 1;
+        }
+        self.recalculate_search();
+    }
+
     /// Clear all log entries
     pub fn clear(&mut self) {
+        self.touch();
+        self.first_index += self.entries.len();
+        self.bookmarks.clear();
+        self.search_matches.clear();
+        self.dedup = DedupState::default();
         self.entries.clear();
         self.scroll = 0;
         self.filtered_cache = 0;
@@ -90,12 +350,14 @@ impl LogStore {
 
     /// Scroll up one line
     pub fn scroll_up(&mut self) {
+        self.touch();
         self.auto_scroll = false;
         self.scroll = self.scroll.saturating_sub(1);
     }
 
     /// Scroll down one line
     pub fn scroll_down(&mut self) {
+        self.touch();
         let filtered_count = self.filtered_count();
         if self.scroll < filtered_count.saturating_sub(1) {
             self.scroll += 1;
@@ -107,12 +369,14 @@ impl LogStore {
 
     /// Scroll to the top
     pub fn scroll_to_top(&mut self) {
+        self.touch();
         self.auto_scroll = false;
         self.scroll = 0;
     }
 
     /// Scroll to the bottom
     pub fn scroll_to_bottom(&mut self) {
+        self.touch();
         self.auto_scroll = true;
         let filtered_count = self.filtered_count();
         self.scroll = filtered_count.saturating_sub(1);
@@ -127,6 +391,7 @@ impl LogStore {
 
     /// Toggle pause state, returns new paused state
     pub fn toggle_pause(&mut self) -> bool {
+        self.touch();
         self.paused = !self.paused;
         if self.paused {
             self.auto_scroll = false;
@@ -142,20 +407,231 @@ impl LogStore {
         self.paused
     }
 
+    // === Bookmarks ===
+
+    /// Add or remove a bookmark on the entry with absolute index `entry_index`
+    ///
+    /// Returns true if the entry is now bookmarked (false for entries no
+    /// longer in the buffer).
+    pub fn toggle_bookmark(&mut self, entry_index: usize) -> bool {
+        if !self.contains_index(entry_index) {
+            return false;
+        }
+        self.touch();
+        if self.bookmarks.remove(&entry_index) {
+            false
+        } else {
+            self.bookmarks.insert(entry_index)
+        }
+    }
+
+    pub fn is_bookmarked(&self, entry_index: usize) -> bool {
+        self.bookmarks.contains(&entry_index)
+    }
+
+    pub fn bookmark_count(&self) -> usize {
+        self.bookmarks.len()
+    }
+
+    /// First bookmark after absolute index `from`
+    pub fn next_bookmark(&self, from: usize) -> Option<usize> {
+        self.bookmarks.range(from + 1..).next().copied()
+    }
+
+    /// Last bookmark before absolute index `from`
+    pub fn prev_bookmark(&self, from: usize) -> Option<usize> {
+        self.bookmarks.range(..from).next_back().copied()
+    }
+
+    /// Scroll to the next (or previous) bookmark visible under the filter
+    ///
+    /// Returns false when there is none in that direction.
+    pub fn jump_to_bookmark(&mut self, forward: bool) -> bool {
+        self.jump_to(|store, from| {
+            if forward {
+                store.next_bookmark(from)
+            } else {
+                store.prev_bookmark(from)
+            }
+        })
+    }
+
+    // === Search ===
+
+    /// Search messages for `query` (ASCII case-insensitive); empty or None ends the search
+    ///
+    /// Scrolls to the first match, if any.
+    pub fn set_search(&mut self, query: Option<String>) {
+        self.touch();
+        self.search_query = query.filter(|q| !q.is_empty());
+        self.recalculate_search();
+        if let Some(&first) = self.search_matches.first() {
+            self.auto_scroll = false;
+            self.scroll = first;
+        }
+    }
+
+    pub fn search_query(&self) -> Option<&str> {
+        self.search_query.as_deref()
+    }
+
+    /// Filtered positions of the matching entries, ascending
+    pub fn search_matches(&self) -> &[usize] {
+        &self.search_matches
+    }
+
+    /// Scroll to the next (or previous) match, wrapping around
+    ///
+    /// Returns the index of the match in `search_matches`.
+    pub fn jump_to_match(&mut self, forward: bool) -> Option<usize> {
+        let matches = &self.search_matches;
+        let index = if forward {
+            matches.iter().position(|&p| p > self.scroll).unwrap_or(0)
+        } else {
+            matches
+                .iter()
+                .rposition(|&p| p < self.scroll)
+                .unwrap_or(matches.len().checked_sub(1)?)
+        };
+        let position = *matches.get(index)?;
+        self.touch();
+        self.auto_scroll = false;
+        self.scroll = position;
+        Some(index)
+    }
+
+    fn matches_search(&self, entry: &LogEntry) -> bool {
+        self.search_query
+            .as_deref()
+            .is_some_and(|q| !match_ranges(&search_text(entry), q).is_empty())
+    }
+
+    fn recalculate_search(&mut self) {
+        self.search_matches = self
+            .entries
+            .iter()
+            .filter(|e| self.filter.matches(e))
+            .enumerate()
+            .filter(|(_, e)| self.matches_search(e))
+            .map(|(position, _)| position)
+            .collect();
+    }
+
+    // === Annotations ===
+
+    /// Set (or clear, with an empty `note`) the user note of the entry with
+    /// absolute index `entry_index`
+    ///
+    /// Returns false for entries no longer in the buffer.
+    pub fn annotate(&mut self, entry_index: usize, note: String) -> bool {
+        if !self.contains_index(entry_index) {
+            return false;
+        }
+        self.touch();
+        let note = note.trim();
+        self.entries[entry_index - self.first_index].user_note =
+            (!note.is_empty()).then(|| note.to_string());
+        true
+    }
+
+    /// Entry with absolute index `entry_index`, if still in the buffer
+    pub fn get(&self, entry_index: usize) -> Option<&LogEntry> {
+        self.entries.get(entry_index.checked_sub(self.first_index)?)
+    }
+
+    /// Annotated entries with their absolute index, oldest first
+    pub fn annotated_entries(&self) -> Vec<(usize, &LogEntry)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.user_note.is_some())
+            .map(|(i, e)| (self.first_index + i, e))
+            .collect()
+    }
+
+    /// Scroll to the next (or previous) annotated entry visible under the
+    /// filter; returns its absolute index
+    pub fn jump_to_annotation(&mut self, forward: bool) -> Option<usize> {
+        let annotated: Vec<usize> = self.annotated_entries().iter().map(|(i, _)| *i).collect();
+        let jumped = self.jump_to(|_, from| {
+            if forward {
+                annotated.iter().find(|i| **i > from).copied()
+            } else {
+                annotated.iter().rev().find(|i| **i < from).copied()
+            }
+        });
+        jumped.then(|| self.index_at(self.scroll)).flatten()
+    }
+
+    /// Scroll to the first target given by `next` (called with the previous
+    /// candidate) that is visible under the filter
+    fn jump_to(&mut self, next: impl Fn(&Self, usize) -> Option<usize>) -> bool {
+        let Some(mut from) = self.index_at(self.scroll) else {
+            return false;
+        };
+        loop {
+            let Some(target) = next(self, from) else {
+                return false;
+            };
+            if let Some(position) = self.position_of(target) {
+                self.touch();
+                self.auto_scroll = false;
+                self.scroll = position;
+                return true;
+            }
+            // Hidden by the current filter: keep looking
+            from = target;
+        }
+    }
+
+    /// Absolute index of the filtered entry at `position`
+    pub fn index_at(&self, position: usize) -> Option<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| self.filter.matches(e))
+            .nth(position)
+            .map(|(i, _)| self.first_index + i)
+    }
+
+    /// Filtered position of absolute index `entry_index` (None if filtered out)
+    fn position_of(&self, entry_index: usize) -> Option<usize> {
+        if !self.contains_index(entry_index) {
+            return None;
+        }
+        let i = entry_index - self.first_index;
+        if !self.filter.matches(&self.entries[i]) {
+            return None;
+        }
+        Some(
+            self.entries
+                .range(..i)
+                .filter(|e| self.filter.matches(e))
+                .count(),
+        )
+    }
+
+    fn contains_index(&self, entry_index: usize) -> bool {
+        (self.first_index..self.first_index + self.entries.len()).contains(&entry_index)
+    }
+
     // === Filtering ===
 
     /// Set filter mode (Protocol, Debug, or All)
     pub fn set_filter(&mut self, mode: FilterMode) {
+        self.touch();
         // Configure visibility based on mode
-        let (protocol, debug, system) = match mode {
-            FilterMode::Protocol => (true, false, false),
-            FilterMode::Debug => (false, true, false),
-            FilterMode::All => (true, true, true),
+        let (protocol, debug, system, events) = match mode {
+            FilterMode::Protocol => (true, false, false, false),
+            FilterMode::Debug => (false, true, false, false),
+            FilterMode::Events => (false, false, true, true),
+            FilterMode::All => (true, true, true, true),
         };
 
         self.filter.show_protocol = protocol;
         self.filter.show_debug = debug;
         self.filter.show_system = system;
+        self.filter.show_events = events;
         self.filter.show_direction_in = true;
         self.filter.show_direction_out = true;
 
@@ -171,6 +647,7 @@ impl LogStore {
 
     /// Set debug level filter
     pub fn set_debug_level(&mut self, level: Option<LogLevel>) {
+        self.touch();
         self.filter.debug_level = level;
         self.recalculate_filtered_cache();
         self.reset_scroll_for_filter();
@@ -184,6 +661,7 @@ impl LogStore {
     }
 
     /// Get current filter
+    #[allow(dead_code)] // Used in tests
     pub fn filter(&self) -> &LogFilter {
         &self.filter
     }
@@ -195,6 +673,15 @@ impl LogStore {
 
     // === Data access ===
 
+    /// Change counter, used by the UI to skip redraws when nothing changed
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn touch(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
     /// Get all entries
     pub fn entries(&self) -> &VecDeque<LogEntry> {
         &self.entries
@@ -205,6 +692,53 @@ impl LogStore {
         self.filtered_cache
     }
 
+    /// Filtered entries `[start, start + len)` (clamped to the filtered count),
+    /// with their absolute index
+    ///
+    /// Walks from whichever end of the buffer is closer to the window, so
+    /// following the tail of a large log only touches the last few entries.
+    pub fn visible_window(&self, start: usize, len: usize) -> Vec<(usize, &LogEntry)> {
+        let total = self.filtered_cache;
+        let start = start.min(total);
+        let end = start.saturating_add(len).min(total);
+        let after = total - end;
+        let indexed = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (self.first_index + i, e));
+
+        if start <= after {
+            indexed
+                .filter(|(_, e)| self.filter.matches(e))
+                .skip(start)
+                .take(end - start)
+                .collect()
+        } else {
+            let mut window: Vec<(usize, &LogEntry)> = indexed
+                .rev()
+                .filter(|(_, e)| self.filter.matches(e))
+                .skip(after)
+                .take(end - start)
+                .collect();
+            window.reverse();
+            window
+        }
+    }
+
+    /// Visual rows taken by the first `end` filtered entries
+    ///
+    /// `rows` gives the row count of one entry (more than 1 when it wraps).
+    /// The filtered cache stays entry-based; this is O(end).
+    pub fn visual_line_count(&self, end: usize, rows: impl Fn(&LogEntry) -> usize) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| self.filter.matches(e))
+            .take(end)
+            .map(rows)
+            .sum()
+    }
+
     /// Recalculate filtered cache (call when filter changes)
     fn recalculate_filtered_cache(&mut self) {
         self.filtered_cache = self
@@ -212,6 +746,68 @@ impl LogStore {
             .iter()
             .filter(|e| self.filter.matches(e))
             .count();
+        self.recalculate_search();
+    }
+
+    // === Analysis ===
+
+    /// Aggregate counts over all entries (ignores the filter), O(N)
+    pub fn statistics(&self) -> LogStatistics {
+        let mut stats = LogStatistics {
+            total_entries: self.entries.len(),
+            first_timestamp: self.entries.front().map(|e| e.timestamp.to_string()),
+            last_timestamp: self.entries.back().map(|e| e.timestamp.to_string()),
+            ..Default::default()
+        };
+        let mut names: HashMap<&str, usize> = HashMap::new();
+
+        for entry in &self.entries {
+            match &entry.kind {
+                LogKind::Protocol { message_name, .. } => {
+                    stats.protocol_count += 1;
+                    *names.entry(message_name).or_default() += 1;
+                }
+                LogKind::Debug { level, .. } => {
+                    stats.debug_count += 1;
+                    let index = match level {
+                        Some(LogLevel::Debug) => Some(0),
+                        Some(LogLevel::Info) => Some(1),
+                        Some(LogLevel::Warn) => Some(2),
+                        Some(LogLevel::Error) => Some(3),
+                        None => None,
+                    };
+                    if let Some(i) = index {
+                        stats.debug_level_counts[i] += 1;
+                    }
+                }
+                LogKind::System { .. } => stats.system_count += 1,
+                LogKind::ControllerEvent { .. } => stats.event_count += 1,
+            }
+        }
+
+        let mut top: Vec<(String, usize)> = names
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(TOP_MESSAGE_TYPES);
+        stats.top_message_types = top;
+
+        if let (Some(first), Some(last)) = (&stats.first_timestamp, &stats.last_timestamp) {
+            stats.duration_secs =
+                timestamp_secs(first)
+                    .zip(timestamp_secs(last))
+                    .map(|(first, last)| {
+                        // Past midnight the clock wraps
+                        let secs = last - first;
+                        if secs < 0.0 {
+                            secs + 86_400.0
+                        } else {
+                            secs
+                        }
+                    });
+        }
+        stats
     }
 
     // === Export (pure methods) ===
@@ -228,25 +824,99 @@ impl LogStore {
 
     /// Format filtered logs as text, limited to max entries (most recent)
     pub fn to_text_limited(&self, max: usize) -> String {
-        let filtered: Vec<&LogEntry> = self
+        self.filtered_tail(max)
+            .into_iter()
+            .map(format_log_entry_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Format filtered logs as JSON Lines: one compact `LogEntry` per line
+    #[cfg(test)]
+    pub fn to_jsonl(&self) -> String {
+        self.to_jsonl_limited(usize::MAX)
+    }
+
+    /// Format filtered logs as JSON Lines, limited to max entries (most recent)
+    pub fn to_jsonl_limited(&self, max: usize) -> String {
+        self.filtered_tail(max)
+            .into_iter()
+            .filter_map(|e| e.to_json_line().ok())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The last `max` entries passing the filter, oldest first
+    fn filtered_tail(&self, max: usize) -> Vec<&LogEntry> {
+        let mut filtered: Vec<&LogEntry> = self
             .entries
             .iter()
             .filter(|e| self.filter.matches(e))
             .collect();
+        filtered.split_off(filtered.len().saturating_sub(max))
+    }
+}
 
-        let start = filtered.len().saturating_sub(max);
+/// Message text of an entry, as searched (and shown in the log view)
+pub fn search_text(entry: &LogEntry) -> Cow<'_, str> {
+    match &entry.kind {
+        LogKind::Protocol { message_name, .. } => Cow::Borrowed(message_name),
+        LogKind::Debug { message, .. } | LogKind::System { message } => Cow::Borrowed(message),
+        LogKind::ControllerEvent { event } => Cow::Owned(event.to_string()),
+    }
+}
 
-        filtered[start..]
-            .iter()
-            .map(|e| format_log_entry_text(e))
-            .collect::<Vec<_>>()
-            .join("\n")
+/// Tag marking controller lifecycle events in the log view and on stdout
+pub const EVENT_TAG: &str = "[EVT]";
+
+/// Console line for a bridge-originated entry (headless stdout)
+///
+/// System messages and controller lifecycle events only, with the same text
+/// and `[EVT]` tag as the log view; protocol and firmware entries give `None`.
+pub fn console_line(entry: &LogEntry) -> Option<String> {
+    match &entry.kind {
+        LogKind::System { .. } => Some(format!("{} {}", entry.timestamp, search_text(entry))),
+        LogKind::ControllerEvent { .. } => Some(format!(
+            "{} {} {}",
+            entry.timestamp,
+            EVENT_TAG,
+            search_text(entry)
+        )),
+        LogKind::Protocol { .. } | LogKind::Debug { .. } => None,
     }
 }
 
-/// Format a log entry as plain text
-fn format_log_entry_text(entry: &LogEntry) -> String {
+/// Byte ranges of `query` in `text`, ignoring ASCII case
+pub fn match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // ASCII folding keeps byte offsets (and char boundaries) unchanged
+    let query = query.to_ascii_lowercase();
+    text.to_ascii_lowercase()
+        .match_indices(&query)
+        .map(|(start, m)| start..start + m.len())
+        .collect()
+}
+
+/// What makes two consecutive entries identical for deduplication
+///
+/// System messages and controller events are never deduplicated.
+fn dedup_key(entry: &LogEntry) -> Option<String> {
     match &entry.kind {
+        LogKind::Debug { level, message } => Some(format!("{:?}{}", level, message)),
+        LogKind::Protocol {
+            direction,
+            message_name,
+            ..
+        } => Some(format!("{}{:?}", message_name, direction)),
+        LogKind::System { .. } | LogKind::ControllerEvent { .. } => None,
+    }
+}
+
+/// Format a log entry as plain text, with its note after `NOTE_SEPARATOR`
+pub(super) fn format_log_entry_text(entry: &LogEntry) -> String {
+    let line = match &entry.kind {
         LogKind::Protocol {
             direction,
             message_name,
@@ -256,7 +926,10 @@ fn format_log_entry_text(entry: &LogEntry) -> String {
                 Direction::In => "←",
                 Direction::Out => "→",
             };
-            format!("{} {} {} ({} B)", entry.timestamp, dir, message_name, size)
+            format!(
+                "{:.6} {} {} ({} B)",
+                entry.timestamp, dir, message_name, size
+            )
         }
         LogKind::Debug { level, message } => {
             let level_str = match level {
@@ -266,14 +939,96 @@ fn format_log_entry_text(entry: &LogEntry) -> String {
                 Some(LogLevel::Error) => "[ERROR]",
                 None => "",
             };
-            format!("{} {} {}", entry.timestamp, level_str, message)
+            format!("{:.6} {} {}", entry.timestamp, level_str, message)
         }
         LogKind::System { message } => {
-            format!("{} [SYS] {}", entry.timestamp, message)
+            format!("{:.6} [SYS] {}", entry.timestamp, message)
+        }
+        LogKind::ControllerEvent { event } => {
+            format!("{:.6} [EVT] {}", entry.timestamp, event)
         }
+    };
+    match &entry.user_note {
+        Some(note) => format!("{}{}{}", line, NOTE_SEPARATOR, note),
+        None => line,
     }
 }
 
+/// Parse one line produced by `format_log_entry_text`
+fn parse_log_entry_text(line: &str) -> Option<LogEntry> {
+    let (line, user_note) = match line.rsplit_once(NOTE_SEPARATOR) {
+        Some((line, note)) => (line, Some(note.to_string())),
+        None => (line, None),
+    };
+    let (timestamp, rest) = line.split_once(' ')?;
+    if !is_timestamp(timestamp) {
+        return None;
+    }
+
+    // Controller events are imported as system messages: only their text is exported
+    let kind = if let Some(rest) = rest
+        .strip_prefix("[SYS] ")
+        .or_else(|| rest.strip_prefix("[EVT] "))
+    {
+        LogKind::System {
+            message: rest.to_string(),
+        }
+    } else if let Some((direction, rest)) = rest
+        .strip_prefix("← ")
+        .map(|r| (Direction::In, r))
+        .or_else(|| rest.strip_prefix("→ ").map(|r| (Direction::Out, r)))
+    {
+        let (name, size) = rest.rsplit_once(" (")?;
+        let size = size.strip_suffix(" B)")?.parse().ok()?;
+        LogKind::Protocol {
+            direction,
+            message_name: name.to_string(),
+            size,
+        }
+    } else {
+        let levels = [
+            ("[DEBUG] ", Some(LogLevel::Debug)),
+            ("[INFO] ", Some(LogLevel::Info)),
+            ("[WARN] ", Some(LogLevel::Warn)),
+            ("[ERROR] ", Some(LogLevel::Error)),
+            // No level: empty level string leaves a leading space
+            (" ", None),
+        ];
+        let (level, message) = levels
+            .iter()
+            .find_map(|(prefix, level)| rest.strip_prefix(prefix).map(|m| (*level, m)))?;
+        LogKind::Debug {
+            level,
+            message: message.to_string(),
+        }
+    };
+
+    Some(LogEntry {
+        timestamp: Timestamp::parse(timestamp)?,
+        kind,
+        user_note,
+    })
+}
+
+/// Seconds since midnight of an `HH:MM:SS.mmm` timestamp
+fn timestamp_secs(s: &str) -> Option<f64> {
+    if !is_timestamp(s) {
+        return None;
+    }
+    let field = |range: std::ops::Range<usize>| s[range].parse::<f64>().ok();
+    Some(field(0..2)? * 3600.0 + field(3..5)? * 60.0 + field(6..s.len())?)
+}
+
+/// Check for the `HH:MM:SS.mmm` (or `HH:MM:SS.mmmmmm`) timestamp format
+fn is_timestamp(s: &str) -> bool {
+    (s.len() == 12 || s.len() == 15)
+        && s.bytes().enumerate().all(|(i, b)| match i {
+            2 | 5 => b == b':',
+            8 => b == b'.',
+            _ => b.is_ascii_digit(),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,6 +1044,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_statistics_counts_and_top_names() {
+        let mut store = LogStore::new(100);
+        for name in ["Volume", "Volume", "Volume", "Pan", "Pan", "Mute"] {
+            store.add(make_protocol_log(name, Direction::In));
+        }
+        store.add(LogEntry::debug_log(Some(LogLevel::Warn), "hot"));
+        store.add(LogEntry::debug_log(None, "plain"));
+        store.add(make_system_log("ready"));
+        for (entry, timestamp) in store
+            .entries
+            .iter_mut()
+            .zip(["23:59:59.500", "00:00:01.000"])
+        {
+            entry.timestamp = Timestamp::parse(timestamp).unwrap();
+        }
+        store.entries.back_mut().unwrap().timestamp = Timestamp::parse("00:00:01.250").unwrap();
+
+        let stats = store.statistics();
+        assert_eq!(stats.total_entries, 9);
+        assert_eq!(
+            (stats.protocol_count, stats.debug_count, stats.system_count),
+            (6, 2, 1)
+        );
+        assert_eq!(stats.debug_level_counts, [0, 0, 1, 0]);
+        assert_eq!(
+            stats.top_message_types,
+            [
+                ("Volume".to_string(), 3),
+                ("Pan".to_string(), 2),
+                ("Mute".to_string(), 1)
+            ]
+        );
+        assert_eq!(stats.first_timestamp.as_deref(), Some("23:59:59.500"));
+        // Wraps past midnight
+        assert_eq!(stats.duration_secs, Some(1.75));
+
+        assert_eq!(LogStore::new(10).statistics().duration_secs, None);
+    }
+
     #[test]
     fn test_add_rotates_when_full() {
         let mut store = LogStore::new(3);
@@ -330,6 +1125,44 @@ mod tests {
         assert_eq!(store.filtered_count(), 1);
     }
 
+    #[test]
+    fn test_filter_events_shows_lifecycle_and_system() {
+        use crate::logging::{ControllerEventKind, DisconnectReason};
+
+        let mut store = LogStore::new(10);
+        store.add(make_system_log("sys"));
+        store.add(make_protocol_log("NoteOn", Direction::In));
+        store.add(LogEntry::debug_log(Some(LogLevel::Info), "debug"));
+        store.add(LogEntry::controller_event(
+            ControllerEventKind::Disconnected {
+                reason: DisconnectReason::Lost,
+            },
+        ));
+
+        store.set_filter(FilterMode::Events);
+        assert_eq!(store.filtered_count(), 2);
+        assert!(store.to_text().ends_with("[EVT] Connection lost"));
+    }
+
+    #[test]
+    fn test_console_line_prints_lifecycle_events() {
+        use crate::logging::ControllerEventKind;
+
+        let event = LogEntry::controller_event(ControllerEventKind::Connected {
+            port: "COM3".to_string(),
+            transport: "Serial".to_string(),
+        });
+        let line = console_line(&event).unwrap();
+        assert!(line.ends_with("[EVT] Connected: Serial:COM3"));
+        assert!(line.starts_with(&event.timestamp.to_string()));
+
+        assert!(console_line(&make_system_log("sys"))
+            .unwrap()
+            .ends_with(" sys"));
+        assert!(console_line(&make_protocol_log("NoteOn", Direction::In)).is_none());
+        assert!(console_line(&LogEntry::debug_log(Some(LogLevel::Info), "debug")).is_none());
+    }
+
     #[test]
     fn test_scroll_up_stops_at_zero() {
         let mut store = LogStore::new(10);
@@ -440,6 +1273,156 @@ mod tests {
         assert_eq!(store.filtered_count(), 3); // Still 3, not 4
     }
 
+    #[test]
+    fn test_set_max_entries_drops_oldest() {
+        let mut store = LogStore::new(10);
+        for i in 0..6 {
+            store.add(make_system_log(&i.to_string()));
+        }
+        store.add(make_protocol_log("Test", Direction::In));
+        store.set_filter(FilterMode::Protocol);
+        assert_eq!(store.filtered_count(), 1);
+
+        store.set_max_entries(3);
+        assert_eq!(store.entries.len(), 3);
+        assert_eq!(store.filtered_count(), 1);
+        assert_eq!(store.first_index, 4);
+
+        // New capacity applies to later entries
+        store.set_max_entries(5);
+        for i in 0..4 {
+            store.add(make_system_log(&i.to_string()));
+        }
+        assert_eq!(store.entries.len(), 5);
+    }
+
+    #[test]
+    fn test_from_file_fixture() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/session.jsonl");
+
+        let store = LogStore::from_file(&path, 200).unwrap();
+        assert_eq!(store.entries.len(), 100);
+        assert_eq!(store.filtered_count(), 100);
+
+        // max_entries keeps the most recent entries
+        let store = LogStore::from_file(&path, 10).unwrap();
+        assert_eq!(store.entries.len(), 10);
+        assert_eq!(
+            store.entries.back().unwrap().timestamp.to_string(),
+            "12:00:09.900"
+        );
+
+        // The fixture only has times of day: they are dated like the file
+        let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+        let file_date = chrono::DateTime::<chrono::Local>::from(modified).date_naive();
+        let micros = store.entries.back().unwrap().timestamp.as_micros();
+        let entry_date = chrono::DateTime::from_timestamp_micros(micros as i64)
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .date_naive();
+        assert_eq!(entry_date, file_date);
+    }
+
+    #[test]
+    fn test_from_text_roundtrip() {
+        let mut store = LogStore::new(10);
+        store.add(make_system_log("Connected: Serial:COM3"));
+        store.add(make_protocol_log("NoteOn", Direction::In));
+        store.add(make_protocol_log("Track (1)", Direction::Out));
+        store.add(LogEntry::debug_log(Some(LogLevel::Warn), "Low memory"));
+        store.add(LogEntry::debug_log(None, "raw print"));
+
+        let text = store.to_text();
+        let imported = LogStore::from_text(&text, 10).unwrap();
+        assert_eq!(imported.to_text(), text);
+
+        match &imported.entries[2].kind {
+            LogKind::Protocol {
+                direction,
+                message_name,
+                size,
+            } => {
+                assert_eq!(*direction, Direction::Out);
+                assert_eq!(message_name, "Track (1)");
+                assert_eq!(*size, 10);
+            }
+            _ => panic!("Expected Protocol log"),
+        }
+    }
+
+    #[test]
+    fn test_to_jsonl_roundtrip() {
+        let mut store = LogStore::new(10);
+        store.add(make_system_log("Connected: Serial:COM3"));
+        store.add(make_protocol_log("NoteOn", Direction::In));
+        store.add(LogEntry::debug_log(Some(LogLevel::Warn), "Low memory"));
+        store.set_filter(FilterMode::Protocol);
+
+        // Filtered, one compact object per line, tagged with its kind
+        let jsonl = store.to_jsonl();
+        assert_eq!(jsonl.lines().count(), 1);
+        assert!(jsonl.contains(r#""kind":{"kind":"Protocol","direction":"In""#));
+
+        store.set_filter(FilterMode::All);
+        let jsonl = store.to_jsonl();
+        let imported = LogStore::from_json_lines(&jsonl, 10).unwrap();
+        assert_eq!(imported.to_text(), store.to_text());
+        assert_eq!(store.to_jsonl_limited(1).lines().count(), 1);
+
+        let entry = LogEntry::from_json_line(jsonl.lines().last().unwrap()).unwrap();
+        assert!(matches!(
+            entry.kind,
+            LogKind::Debug {
+                level: Some(LogLevel::Warn),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_from_json_lines_reports_line() {
+        let text = "{\"timestamp\":\"12:00:00.000\",\"kind\":{\"kind\":\"System\",\"message\":\"ok\"}}\n{}";
+        let result = LogStore::from_json_lines(text, 10);
+        assert!(matches!(result, Err(BridgeError::LogParse { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_from_text_rejects_garbage() {
+        let result = LogStore::from_text("12:00:00.000 [SYS] ok\nnot a log line", 10);
+        assert!(matches!(result, Err(BridgeError::LogParse { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_visible_window_matches_linear_scan() {
+        let mut store = LogStore::new(5000);
+        for i in 0..5000 {
+            if i % 3 == 0 {
+                store.add(LogEntry::system(format!("sys {}", i)));
+            } else {
+                store.add(LogEntry::protocol_in(format!("msg{}", i), i));
+            }
+        }
+        store.set_filter(FilterMode::Protocol);
+        let total = store.filtered_count();
+
+        for &(start, len) in &[(0, 20), (1500, 40), (total - 30, 30), (total - 5, 30)] {
+            let expected: Vec<&LogEntry> = store
+                .entries()
+                .iter()
+                .filter(|e| store.filter().matches(e))
+                .skip(start)
+                .take(len)
+                .collect();
+            let window = store.visible_window(start, len);
+            assert_eq!(window.len(), expected.len());
+            assert!(window
+                .iter()
+                .zip(&expected)
+                .all(|((i, a), b)| std::ptr::eq(*a, *b) && std::ptr::eq(&store.entries()[*i], *b)));
+        }
+        assert!(store.visible_window(total + 10, 5).is_empty());
+    }
+
     #[test]
     fn test_filtered_cache_clear() {
         let mut store = LogStore::new(10);
@@ -450,4 +1433,174 @@ mod tests {
         store.clear();
         assert_eq!(store.filtered_count(), 0);
     }
+
+    #[test]
+    fn test_bookmarks_navigate_and_survive_filter_changes() {
+        let mut store = LogStore::new(10);
+        for i in 0..6 {
+            if i % 2 == 0 {
+                store.add(make_system_log(&format!("sys {}", i)));
+            } else {
+                store.add(make_protocol_log(&format!("msg{}", i), Direction::In));
+            }
+        }
+
+        assert!(store.toggle_bookmark(1));
+        assert!(store.toggle_bookmark(4));
+        assert!(store.toggle_bookmark(5));
+        assert!(!store.toggle_bookmark(5));
+        assert!(!store.toggle_bookmark(42));
+        assert_eq!(store.bookmark_count(), 2);
+        assert_eq!(store.next_bookmark(1), Some(4));
+        assert_eq!(store.prev_bookmark(4), Some(1));
+        assert_eq!(store.next_bookmark(4), None);
+
+        // From the tail (index 5), back to 4 then to 1
+        assert!(store.jump_to_bookmark(false));
+        assert_eq!(store.index_at(store.scroll_position()), Some(4));
+        assert!(store.jump_to_bookmark(false));
+        assert_eq!(store.index_at(store.scroll_position()), Some(1));
+        assert!(!store.jump_to_bookmark(false));
+
+        // Protocol view: entry 4 (system) is hidden, 1 stays reachable
+        store.set_filter(FilterMode::Protocol);
+        assert_eq!(store.bookmark_count(), 2);
+        assert!(store.jump_to_bookmark(false));
+        assert_eq!(store.index_at(store.scroll_position()), Some(1));
+        assert!(!store.jump_to_bookmark(true));
+    }
+
+    #[test]
+    fn test_bookmarks_follow_rotation_and_clear() {
+        let mut store = LogStore::new(3);
+        for i in 0..3 {
+            store.add(make_system_log(&format!("{}", i)));
+        }
+        store.toggle_bookmark(0);
+        store.toggle_bookmark(2);
+
+        store.add(make_system_log("3"));
+        assert!(!store.is_bookmarked(0));
+        assert!(store.is_bookmarked(2));
+        assert_eq!(store.visible_window(0, 3)[1].0, 2);
+
+        store.clear();
+        assert_eq!(store.bookmark_count(), 0);
+        store.add(make_system_log("4"));
+        assert_eq!(store.index_at(0), Some(4));
+    }
+
+    #[test]
+    fn test_annotations_survive_filters_and_export() {
+        let mut store = LogStore::new(10);
+        for i in 0..4 {
+            store.add(make_system_log(&format!("sys {}", i)));
+            store.add(make_protocol_log(&format!("msg{}", i), Direction::In));
+        }
+
+        assert!(store.annotate(1, "firmware crashed here".to_string()));
+        assert!(store.annotate(6, "  reset  ".to_string()));
+        assert!(!store.annotate(42, "gone".to_string()));
+        let annotated: Vec<usize> = store.annotated_entries().iter().map(|(i, _)| *i).collect();
+        assert_eq!(annotated, [1, 6]);
+        assert_eq!(store.get(6).unwrap().user_note.as_deref(), Some("reset"));
+
+        // Protocol view: entry 6 (system) is hidden, 1 stays reachable
+        store.set_filter(FilterMode::Protocol);
+        assert_eq!(store.jump_to_annotation(false), Some(1));
+        assert_eq!(store.jump_to_annotation(false), None);
+        store.set_filter(FilterMode::All); // back at the tail
+        assert_eq!(store.jump_to_annotation(false), Some(6));
+
+        let text = store.to_text();
+        assert!(text.contains("← msg0 (10 B) ✎ firmware crashed here"));
+        let imported = LogStore::from_text(&text, 10).unwrap();
+        assert_eq!(imported.annotated_entries().len(), 2);
+        let json = serde_json::to_string(store.get(1).unwrap()).unwrap();
+        assert!(json.contains(r#""user_note":"firmware crashed here""#));
+
+        assert!(store.annotate(1, String::new()));
+        assert_eq!(store.annotated_entries().len(), 1);
+    }
+
+    #[test]
+    fn test_dedup_collapses_repeats() {
+        let mut store = LogStore::new(100);
+        store.set_dedup_threshold(2);
+        for _ in 0..5 {
+            store.add(make_protocol_log("Volume", Direction::In));
+        }
+        // The other direction is a different message
+        store.add(make_protocol_log("Volume", Direction::Out));
+        for _ in 0..3 {
+            store.add(make_system_log("ready"));
+        }
+        assert_eq!(store.entries().len(), 7);
+        assert!(matches!(
+            &store.get(2).unwrap().kind,
+            LogKind::System { message } if message == "Last message repeated 3 times"
+        ));
+        assert!(matches!(
+            store.get(3).unwrap().kind,
+            LogKind::Protocol {
+                direction: Direction::Out,
+                ..
+            }
+        ));
+
+        // The summary is no protocol message: per-message views skip it
+        store.set_filter(FilterMode::Protocol);
+        assert_eq!(store.filtered_count(), 3);
+
+        store.set_dedup_threshold(0);
+        for _ in 0..4 {
+            store.add(LogEntry::debug_log(None, "tick"));
+        }
+        assert_eq!(store.entries().len(), 11);
+    }
+
+    #[test]
+    fn test_dedup_keeps_levels_apart() {
+        let mut store = LogStore::new(100);
+        store.set_dedup_threshold(1);
+        for _ in 0..3 {
+            store.add(LogEntry::debug_log(Some(LogLevel::Warn), "overrun"));
+        }
+        // Same text at another level is a new run
+        store.add(LogEntry::debug_log(Some(LogLevel::Error), "overrun"));
+        assert_eq!(store.entries().len(), 3);
+        assert!(matches!(
+            &store.get(1).unwrap().kind,
+            LogKind::System { message } if message == "Last message repeated 2 times"
+        ));
+
+        store.set_filter(FilterMode::Debug);
+        assert_eq!(store.filtered_count(), 2);
+    }
+
+    #[test]
+    fn test_dedup_reports_a_run_that_stops() {
+        let mut store = LogStore::new(100);
+        store.set_dedup_threshold(1);
+        for _ in 0..4 {
+            store.add(LogEntry::debug_log(None, "tick"));
+        }
+        let now = Instant::now();
+        store.flush_idle_dedup(now);
+        assert_eq!(store.entries().len(), 1);
+
+        store.flush_idle_dedup(now + Duration::from_millis(DEDUP_FLUSH_IDLE_MS));
+        assert_eq!(store.entries().len(), 2);
+        assert!(matches!(
+            &store.get(1).unwrap().kind,
+            LogKind::System { message } if message == "Last message repeated 3 times"
+        ));
+
+        // Repeats after the summary are counted again
+        store.add(LogEntry::debug_log(None, "tick"));
+        store.flush_idle_dedup(now + Duration::from_secs(10));
+        assert_eq!(store.entries().len(), 3);
+        store.flush_idle_dedup(now + Duration::from_secs(20));
+        assert_eq!(store.entries().len(), 3);
+    }
 }
@@ -0,0 +1,197 @@
+//! Live log feed over WebSocket (`[logs] ws_stream_port`)
+//!
+//! For web dashboards and test scripts: every entry the TUI receives is
+//! pushed to each connected client as one JSON text message,
+//! `{"ts":<unix_us>,"kind":"Protocol","direction":"In",…}` (the `LogKind`
+//! fields, plus `user_note` when set). Bound to 127.0.0.1.
+//!
+//! Clients share a `broadcast` channel of `LOG_STREAM_CAPACITY` entries. A
+//! client that falls behind gets a `System` entry
+//! `lagged, N entries dropped` in place of the entries it missed.
+
+use super::entry::TaggedKind;
+use super::LogEntry;
+use crate::constants::{LOG_STREAM_ACCEPT_BACKOFF_MS, LOG_STREAM_CAPACITY};
+use crate::error::{BridgeError, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+/// JSON form of one streamed entry
+#[derive(Serialize)]
+struct StreamMessage<'a> {
+    ts: u64,
+    #[serde(flatten)]
+    kind: TaggedKind<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_note: Option<&'a str>,
+}
+
+/// WebSocket server forwarding log entries to every client
+///
+/// Stops accepting when dropped; connected clients are closed once their
+/// queued entries are sent.
+pub struct LogStreamServer {
+    #[cfg(test)]
+    port: u16,
+    tx: broadcast::Sender<LogEntry>,
+    accept_task: JoinHandle<()>,
+}
+
+impl LogStreamServer {
+    /// Bind 127.0.0.1:`port` and accept clients in the background
+    ///
+    /// Must be called within the tokio runtime.
+    pub fn start(port: u16) -> Result<Self> {
+        let map_err = |e| BridgeError::WebSocketBind { port, source: e };
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+        let listener = std::net::TcpListener::bind(addr).map_err(map_err)?;
+        listener.set_nonblocking(true).map_err(map_err)?;
+        let listener = TcpListener::from_std(listener).map_err(map_err)?;
+        #[cfg(test)]
+        let port = listener.local_addr().map_or(port, |a| a.port());
+
+        let (tx, _) = broadcast::channel(LOG_STREAM_CAPACITY);
+        let accept_task = tokio::spawn(accept_clients(listener, tx.clone()));
+        Ok(Self {
+            #[cfg(test)]
+            port,
+            tx,
+            accept_task,
+        })
+    }
+
+    /// Bound port (the OS-assigned one when started with port 0)
+    #[cfg(test)]
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Forward an entry to the connected clients (none: nothing is cloned)
+    pub fn send(&self, entry: &LogEntry) {
+        if self.tx.receiver_count() > 0 {
+            let _ = self.tx.send(entry.clone());
+        }
+    }
+}
+
+impl Drop for LogStreamServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Accept clients until aborted
+///
+/// A failed accept (e.g. `EMFILE`) usually persists for a while: it is
+/// logged and retried after a short pause instead of spinning.
+async fn accept_clients(listener: TcpListener, tx: broadcast::Sender<LogEntry>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(stream_to_client(stream, tx.subscribe()));
+            }
+            Err(e) => {
+                warn!("Log stream: failed to accept a client: {}", e);
+                tokio::time::sleep(Duration::from_millis(LOG_STREAM_ACCEPT_BACKOFF_MS)).await;
+            }
+        }
+    }
+}
+
+/// Send entries until the client leaves or the server stops
+async fn stream_to_client(stream: TcpStream, mut rx: broadcast::Receiver<LogEntry>) {
+    let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (mut sink, mut incoming) = ws.split();
+
+    loop {
+        let entry = tokio::select! {
+            received = rx.recv() => match received {
+                Ok(entry) => entry,
+                Err(broadcast::error::RecvError::Lagged(dropped)) => {
+                    LogEntry::system(format!("lagged, {} entries dropped", dropped))
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Pings are answered by tungstenite; anything else is ignored
+            message = incoming.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => continue,
+            },
+        };
+        if sink.send(Message::text(stream_json(&entry))).await.is_err() {
+            return;
+        }
+    }
+    let _ = sink.close().await;
+}
+
+/// `{"ts":…,"kind":…,…}` text of an entry
+fn stream_json(entry: &LogEntry) -> String {
+    serde_json::to_string(&StreamMessage {
+        ts: entry.timestamp.as_micros(),
+        kind: TaggedKind(&entry.kind),
+        user_note: entry.user_note.as_deref(),
+    })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn next_json<S, E>(client: &mut S) -> serde_json::Value
+    where
+        S: futures_util::Stream<Item = std::result::Result<Message, E>> + Unpin,
+        E: std::fmt::Debug,
+    {
+        let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(message.to_text().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_stream_json_schema() {
+        let entry = LogEntry::protocol_in("DeviceChange", 12);
+        let json: serde_json::Value = serde_json::from_str(&stream_json(&entry)).unwrap();
+        assert_eq!(json["ts"], entry.timestamp.as_micros());
+        assert_eq!(json["kind"], "Protocol");
+        assert_eq!(json["direction"], "In");
+        assert_eq!(json["message_name"], "DeviceChange");
+        assert!(json.get("user_note").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_forwards_entries_and_reports_lag() {
+        let server = LogStreamServer::start(0).unwrap();
+        let url = format!("ws://127.0.0.1:{}", server.port());
+        let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        // Wait for the server side to subscribe
+        while server.tx.receiver_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        server.send(&LogEntry::system("hello"));
+        assert_eq!(next_json(&mut client).await["message"], "hello");
+
+        // Overrun the channel before the client task runs again
+        for i in 0..LOG_STREAM_CAPACITY + 5 {
+            let _ = server.tx.send(LogEntry::system(i.to_string()));
+        }
+        let lagged = next_json(&mut client).await;
+        assert_eq!(lagged["kind"], "System");
+        assert_eq!(lagged["message"], "lagged, 5 entries dropped");
+    }
+}
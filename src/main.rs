@@ -13,6 +13,15 @@
 //! oc-bridge --headless --controller udp  Run headless for native apps
 //! oc-bridge ctl pause|resume|status       Control running daemon
 //! oc-bridge ctl ping|info                 Query daemon state/info
+//! oc-bridge analyze <file>               Browse a saved log file (read-only)
+//! oc-bridge analyze <file> --stats       Print statistics of a saved log file
+//! oc-bridge list-ports [--json]          List serial ports with USB details
+//! oc-bridge test-loopback --port COM3    Check a serial port with a loopback connector
+//! oc-bridge profile create|list|delete   Manage config profiles
+//! oc-bridge shell-init [--shell zsh]     Print shell helpers + completions
+//! oc-bridge --profile stage              Run with profiles/stage.toml overlaid
+//! oc-bridge --record session.rec         Record TUI input for bug reports
+//! oc-bridge --playback session.rec       Replay recorded TUI input (--fast = 10x)
 //! oc-bridge --help                       Show all options
 //! ```
 
@@ -23,18 +32,21 @@ mod codec;
 mod config;
 mod constants;
 mod control;
+mod discovery;
 mod error;
 mod input;
 mod instance_lock;
 mod logging;
+mod metrics;
 mod platform;
+mod shell;
 mod transport;
 mod ui;
 
 use bridge::stats::Stats;
 use clap::Parser;
-use cli::{Cli, Command, ControllerArg, CtlCommand};
-use config::{BridgeConfig, ControllerTransport, HostTransport};
+use cli::{Cli, Command, ControllerArg, CtlCommand, ProfileCommand};
+use config::{BridgeConfig, ControllerTransport, HostTransport, TlsConfig};
 use constants::{
     DEFAULT_CONTROLLER_UDP_PORT, DEFAULT_CONTROLLER_WEBSOCKET_PORT, DEFAULT_HOST_UDP_PORT,
 };
@@ -48,22 +60,59 @@ fn main() -> Result<()> {
     // Initialize tracing for internal debug output
     logging::init_tracing(cli.verbose);
 
+    if let Some(Command::Profile { cmd }) = &cli.command {
+        return run_profile(cmd);
+    }
+
+    if let Some(Command::ShellInit { shell }) = &cli.command {
+        print!("{}", shell::init_script(*shell));
+        return Ok(());
+    }
+
+    if let Some(Command::Completions { shell }) = &cli.command {
+        print!("{}", shell::completions(*shell));
+        return Ok(());
+    }
+
+    // Select the profile before any config::load()
+    if let Some(name) = &cli.profile {
+        config::set_active_profile(name)?;
+    }
+
     // Handle control commands (pause/resume/status)
     if let Some(Command::Ctl { cmd, control_port }) = &cli.command {
         let cfg = config::load();
         let port = control_port.unwrap_or(cfg.bridge.control_port);
-        return run_ctl(*cmd, port);
+        return run_ctl(
+            *cmd,
+            port,
+            config::stats_path(&cfg.bridge).ok(),
+            cfg.logs.ws_stream_port,
+        );
+    }
+
+    // Diagnostics: no bridge, no terminal relaunch
+    if let Some(Command::ListPorts { json }) = &cli.command {
+        run_list_ports(*json);
+        return Ok(());
+    }
+    if let Some(Command::TestLoopback {
+        port,
+        count,
+        software_loopback,
+    }) = &cli.command
+    {
+        let passed = run_test_loopback(port.as_deref(), *count, *software_loopback)?;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+    if let Some(Command::Analyze { file, stats: true }) = &cli.command {
+        return run_analyze_stats(file);
     }
 
     // Handle daemon mode (background, per-user)
     if cli.daemon {
         // Ensure a single daemon instance.
-        let mut lock_cfg = config::load();
-        if let Some(instance_id) = &cli.instance_id {
-            lock_cfg.bridge.instance_id = Some(instance_id.clone());
-        }
-        let instance_id = config::effective_instance_id(&lock_cfg.bridge);
-        let _lock = match instance_lock::InstanceLock::acquire_daemon(&instance_id) {
+        let _lock = match acquire_instance_lock(&cli) {
             Ok(lock) => lock,
             Err(crate::error::BridgeError::InstanceAlreadyRunning { .. }) => {
                 // Already running is not an error for a background entrypoint.
@@ -92,17 +141,27 @@ fn main() -> Result<()> {
             cli.udp_port,
             cli.daemon_control_port,
             cli.daemon_log_broadcast_port,
+            cli.trace_cobs,
         ));
     }
 
     // Handle headless mode (UDP/WS for dev)
     if cli.headless {
+        // Same lock as the daemon: both would bind the same ports
+        let _lock = acquire_instance_lock(&cli)?;
         let rt = tokio::runtime::Runtime::new()
             .map_err(|e| error::BridgeError::Runtime { source: e })?;
+        // clap requires both flags together
+        let tls = cli
+            .tls_cert
+            .zip(cli.tls_key)
+            .map(|(cert_pem, key_pem)| TlsConfig { cert_pem, key_pem });
         return rt.block_on(run_headless(
             cli.controller,
             cli.controller_port,
             cli.udp_port,
+            cli.trace_cobs,
+            tls,
         ));
     }
 
@@ -111,27 +170,124 @@ fn main() -> Result<()> {
         return platform::relaunch_in_terminal();
     }
 
+    let events = event_source(&cli)?;
+
     // Handle subcommands
     match cli.command {
-        Some(Command::Ctl { .. }) => unreachable!(),
+        Some(Command::Ctl { .. })
+        | Some(Command::ListPorts { .. })
+        | Some(Command::TestLoopback { .. })
+        | Some(Command::Profile { .. })
+        | Some(Command::ShellInit { .. })
+        | Some(Command::Completions { .. }) => unreachable!(),
+
+        Some(Command::Analyze { file, .. }) => {
+            let rt = tokio::runtime::Runtime::new()
+                .map_err(|e| error::BridgeError::Runtime { source: e })?;
+            rt.block_on(run_analyze(&file, events))
+        }
 
         // Default: run TUI
         None => {
             let rt = tokio::runtime::Runtime::new()
                 .map_err(|e| error::BridgeError::Runtime { source: e })?;
-            rt.block_on(run_tui())
+            #[cfg(feature = "http-api")]
+            let tui = run_tui(events, cli.http_port);
+            #[cfg(not(feature = "http-api"))]
+            let tui = run_tui(events);
+            rt.block_on(tui)
         }
     }
 }
 
-async fn run_tui() -> Result<()> {
-    let mut app = app::App::new();
-    ui::run(&mut app).await
+/// Take the per-instance lock (`None` with `--force`)
+fn acquire_instance_lock(cli: &Cli) -> Result<Option<instance_lock::InstanceLock>> {
+    if cli.force {
+        return Ok(None);
+    }
+    let mut lock_cfg = config::load();
+    if let Some(instance_id) = &cli.instance_id {
+        lock_cfg.bridge.instance_id = Some(instance_id.clone());
+    }
+    let instance_id = config::effective_instance_id(&lock_cfg.bridge);
+    instance_lock::InstanceLock::acquire_daemon(&instance_id).map(Some)
+}
+
+async fn run_tui(
+    events: Box<dyn ui::recording::EventSource>,
+    #[cfg(feature = "http-api")] http_port: Option<u16>,
+) -> Result<()> {
+    let init = async move {
+        let app = app::App::new_async().await;
+        #[cfg(feature = "http-api")]
+        let app = match http_port {
+            Some(port) => app.with_http_api(port).await,
+            None => app,
+        };
+        app
+    };
+    ui::run_starting(init, events).await
+}
+
+async fn run_analyze(
+    path: &std::path::Path,
+    events: Box<dyn ui::recording::EventSource>,
+) -> Result<()> {
+    let mut app = app::App::analyze(path)?;
+    ui::run(&mut app, events).await
+}
+
+/// Print the statistics table of a saved log file (all of its entries)
+fn run_analyze_stats(path: &std::path::Path) -> Result<()> {
+    // At most one entry per line; read errors are reported by from_file
+    let lines = std::fs::read_to_string(path).map_or(0, |text| text.lines().count());
+    let logs = logging::LogStore::from_file(path, lines.max(1))?;
+    println!("{}", path.display());
+    println!();
+    for line in logs.statistics().table_lines() {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Run the serial loopback self-test and print its report; true on pass
+fn run_test_loopback(port: Option<&str>, count: usize, software: bool) -> Result<bool> {
+    let rt =
+        tokio::runtime::Runtime::new().map_err(|e| error::BridgeError::Runtime { source: e })?;
+    let echo_shutdown = Arc::new(AtomicBool::new(false));
+    let port = match port {
+        Some(port) if !software => port.to_string(),
+        _ => platform::spawn_serial_echo(echo_shutdown.clone())?,
+    };
+
+    println!("Loopback test on {} ({} frames)", port, count);
+    let report = rt.block_on(bridge::loopback::run_loopback(&port, count));
+    echo_shutdown.store(true, Ordering::Relaxed);
+    let report = report?;
+    for line in report.summary_lines() {
+        println!("{}", line);
+    }
+    Ok(report.passed())
+}
+
+/// TUI input: live terminal, optionally recorded, or a playback file
+fn event_source(cli: &Cli) -> Result<Box<dyn ui::recording::EventSource>> {
+    use ui::recording::{Player, Recorder, TerminalEvents, FAST_PLAYBACK_SPEED};
+
+    if let Some(path) = &cli.playback {
+        let speed = if cli.fast { FAST_PLAYBACK_SPEED } else { 1.0 };
+        return Ok(Box::new(Player::open(path, speed)?));
+    }
+    if let Some(path) = &cli.record {
+        return Ok(Box::new(Recorder::create(TerminalEvents, path)?));
+    }
+    Ok(Box::new(TerminalEvents))
 }
 
 /// Run the bridge in daemon mode (background, no TUI)
 ///
 /// Uses the per-user config and is intended to be launched by a per-user supervisor (ms-manager).
+#[allow(clippy::too_many_arguments)]
 async fn run_daemon(
     verbose: bool,
     port: Option<String>,
@@ -140,6 +296,7 @@ async fn run_daemon(
     udp_port: Option<u16>,
     control_port: Option<u16>,
     log_broadcast_port: Option<u16>,
+    trace_cobs: bool,
 ) -> Result<()> {
     let mut cfg = config::load();
 
@@ -165,16 +322,26 @@ async fn run_daemon(
         cfg.bridge.log_broadcast_port = log_broadcast_port;
     }
 
+    cfg.bridge.trace_cobs = trace_cobs;
+
     // Print startup info
     let controller_info = match cfg.bridge.controller_transport {
-        ControllerTransport::Serial => {
-            let port = config::detect_serial(&cfg).unwrap_or_else(|| "(auto-detect)".to_string());
+        ControllerTransport::Serial
+        | ControllerTransport::FramedLength2
+        | ControllerTransport::FramedLength4
+        | ControllerTransport::HexSerial => {
+            let port = config::configured_serial_port(&cfg)
+                .or_else(|| config::detect_serial(&cfg))
+                .unwrap_or_else(|| "(auto-detect)".to_string());
             let serial = config::normalized_optional_string(cfg.bridge.serial_number.as_deref())
                 .unwrap_or_else(|| "(any compatible)".to_string());
             format!("Serial:{} target={}", port, serial)
         }
         ControllerTransport::Udp => format!("UDP:{}", cfg.bridge.controller_udp_port),
         ControllerTransport::WebSocket => format!("WS:{}", cfg.bridge.controller_websocket_port),
+        ControllerTransport::Tcp => format!("TCP:{}", cfg.bridge.controller_tcp_addr),
+        #[cfg(unix)]
+        ControllerTransport::Unix => format!("UNIX:{}", cfg.bridge.unix_socket_path().display()),
     };
 
     let host_info = match cfg.bridge.host_transport {
@@ -184,6 +351,8 @@ async fn run_daemon(
             "UDP:{} + WS:{}",
             cfg.bridge.host_udp_port, cfg.bridge.host_websocket_port
         ),
+        #[cfg(windows)]
+        HostTransport::NamedPipe => format!("Pipe:{}", constants::HOST_PIPE_NAME),
     };
 
     println!("oc-bridge daemon mode");
@@ -196,6 +365,9 @@ async fn run_daemon(
     if verbose {
         println!("  Verbose:    enabled");
     }
+    if trace_cobs {
+        println!("  COBS trace: enabled (firmware debugging only)");
+    }
     println!();
 
     // Setup shutdown signal
@@ -211,8 +383,10 @@ async fn run_daemon(
     // Logs:
     // - UDP broadcast for dev TUI monitoring (localhost)
     // - rotating file logs for product supervisors (ms-manager)
-    let log_tx =
-        logging::broadcast::create_log_broadcaster_with_port(cfg.bridge.log_broadcast_port);
+    let log_tx = logging::broadcast::create_log_broadcaster_with_port(
+        cfg.bridge.log_broadcast_port,
+        cfg.bridge.log_broadcast_compress,
+    );
 
     let file_filter = logging::file::FileLogFilter {
         include_protocol: cfg.logs.file_include_protocol,
@@ -276,7 +450,56 @@ async fn run_daemon(
 
     // Run bridge with config
     let stats = Arc::new(Stats::new());
-    bridge::run_with_shutdown(&cfg.bridge, shutdown, stats, Some(tokio_tx)).await
+    let stats_path = config::stats_path(&cfg.bridge).ok();
+    if let Some(path) = stats_path.clone() {
+        spawn_stats_snapshots(path, stats.clone(), shutdown.clone(), log_tx.clone());
+    }
+
+    let device = config::detection_preset(&cfg);
+    let result =
+        bridge::run_with_shutdown(&cfg.bridge, device, shutdown, stats.clone(), Some(tokio_tx))
+            .await;
+
+    // Final snapshot so short sessions are counted too
+    if let Some(path) = stats_path {
+        if let Err(e) = stats.save_snapshot(&path) {
+            tracing::warn!("Stats snapshot skipped: {}", e);
+        }
+    }
+    result
+}
+
+/// Append a stats snapshot every `STATS_SNAPSHOT_INTERVAL_SECS`
+///
+/// Write failures (e.g. disk full) are logged and the next interval retries.
+fn spawn_stats_snapshots(
+    path: std::path::PathBuf,
+    stats: Arc<Stats>,
+    shutdown: Arc<AtomicBool>,
+    log_tx: std::sync::mpsc::Sender<logging::LogEntry>,
+) {
+    tokio::spawn(async move {
+        let period = std::time::Duration::from_secs(constants::STATS_SNAPSHOT_INTERVAL_SECS);
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        while !shutdown.load(Ordering::Relaxed) {
+            interval.tick().await;
+            if shutdown.load(Ordering::Relaxed) {
+                break;
+            }
+            let stats = stats.clone();
+            let path = path.clone();
+            let result = tokio::task::spawn_blocking(move || stats.save_snapshot(&path)).await;
+            if let Ok(Err(e)) = result {
+                let cause = std::error::Error::source(&e)
+                    .map(|s| s.to_string())
+                    .unwrap_or_default();
+                let _ = log_tx.send(logging::LogEntry::system(format!(
+                    "Warning: stats snapshot skipped: {} {}",
+                    e, cause
+                )));
+            }
+        }
+    });
 }
 
 /// Run the bridge in headless mode (no TUI, logs to stdout)
@@ -287,6 +510,8 @@ async fn run_headless(
     controller: Option<ControllerArg>,
     controller_port: Option<u16>,
     host_port: Option<u16>,
+    trace_cobs: bool,
+    tls: Option<TlsConfig>,
 ) -> Result<()> {
     let controller_transport = controller.unwrap_or_default();
 
@@ -312,17 +537,26 @@ async fn run_headless(
         // Headless mode is a dev tool; disable the control plane to avoid port collisions
         // with a running daemon.
         control_port: 0,
+        tls,
         ..BridgeConfig::default()
     };
 
     // Print startup info
     let transport_name = match controller_transport {
+        ControllerArg::Websocket if config.tls.is_some() => "WebSocket (wss)",
         ControllerArg::Websocket => "WebSocket",
         ControllerArg::Udp => "UDP",
     };
     println!("oc-bridge headless mode");
     println!("  Controller: {} port {}", transport_name, ctrl_port);
     println!("  Host:       UDP port {}", host_udp_port);
+    if trace_cobs {
+        // Headless controllers are UDP/WebSocket: nothing is COBS-framed
+        println!("  COBS trace: ignored (no serial controller in headless mode)");
+    }
+    if config.tls.is_some() && controller_transport == ControllerArg::Udp {
+        println!("  TLS:        ignored (applies to WebSocket only)");
+    }
     println!("Press Ctrl+C to stop");
     println!();
 
@@ -344,16 +578,90 @@ async fn run_headless(
         tokio::sync::mpsc::channel::<logging::LogEntry>(constants::CHANNEL_CAPACITY);
     tokio::spawn(async move {
         while let Some(entry) = log_rx.recv().await {
-            if let logging::LogKind::System { message } = &entry.kind {
-                println!("{} {}", entry.timestamp, message);
+            if let Some(line) = logging::console_line(&entry) {
+                println!("{}", line);
             }
         }
     });
 
-    bridge::run_with_shutdown(&config, shutdown, stats, Some(log_tx)).await
+    bridge::run_with_shutdown(&config, None, shutdown, stats, Some(log_tx)).await
+}
+
+/// Create, list or delete config profiles
+fn run_profile(cmd: &ProfileCommand) -> Result<()> {
+    match cmd {
+        ProfileCommand::Create { name } => {
+            let path = config::create_profile(name)?;
+            println!("Created profile '{}': {}", name, path.display());
+        }
+        ProfileCommand::List => {
+            let names = config::list_profiles()?;
+            if names.is_empty() {
+                println!("No profiles in {}", config::profiles_dir()?.display());
+            }
+            for name in names {
+                println!("{}", name);
+            }
+        }
+        ProfileCommand::Delete { name } => {
+            let path = config::delete_profile(name)?;
+            println!("Deleted profile '{}': {}", name, path.display());
+        }
+    }
+    Ok(())
 }
 
-fn run_ctl(cmd: CtlCommand, control_port: u16) -> Result<()> {
+/// Print available serial ports (always succeeds: diagnostic output only)
+fn run_list_ports(json: bool) {
+    let cfg = config::load();
+    let preset_name = cfg.bridge.device_preset.as_deref().unwrap_or("teensy");
+    let preset = config::detection_preset(&cfg);
+
+    let listings = transport::ports::list_ports(preset.as_ref()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        Vec::new()
+    });
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&listings).unwrap_or_else(|_| "[]".to_string())
+        );
+        return;
+    }
+
+    if listings.is_empty() {
+        println!("No serial ports found");
+        return;
+    }
+
+    let rows = transport::ports::table(&listings);
+    println!("{}", rows[0]);
+    for (row, p) in rows[1..].iter().zip(&listings) {
+        println!("{}", row);
+        let details = p.serial_number.iter().map(|sn| ("serial", sn.as_str()));
+        for (k, v) in details.chain(p.attributes.iter().map(|(k, v)| (k.as_str(), v.as_str()))) {
+            println!("      {}={}", k, v);
+        }
+    }
+    println!();
+    println!("* = matches device preset '{}'", preset_name);
+
+    let candidates = config::detect_serial_all(&cfg);
+    if candidates.len() > 1 {
+        println!(
+            "  detection order (configured port, then most recently plugged in): {}",
+            candidates.join(", ")
+        );
+    }
+}
+
+fn run_ctl(
+    cmd: CtlCommand,
+    control_port: u16,
+    stats_path: Option<std::path::PathBuf>,
+    log_stream_port: Option<u16>,
+) -> Result<()> {
     let timeout = std::time::Duration::from_secs(2);
     let cmd_str = match cmd {
         CtlCommand::Pause => "pause",
@@ -361,7 +669,14 @@ fn run_ctl(cmd: CtlCommand, control_port: u16) -> Result<()> {
         CtlCommand::Status => "status",
         CtlCommand::Ping => "ping",
         CtlCommand::Info => "info",
+        CtlCommand::Stats => "stats",
         CtlCommand::Shutdown => "shutdown",
+        CtlCommand::Monitor { interval_ms } => {
+            return ui::monitor::run(
+                control_port,
+                std::time::Duration::from_millis(interval_ms.max(1)),
+            );
+        }
     };
 
     let resp = control::send_command_blocking(control_port, cmd_str, timeout)?;
@@ -371,9 +686,21 @@ fn run_ctl(cmd: CtlCommand, control_port: u16) -> Result<()> {
         });
     }
 
+    if cmd_str == "stats" {
+        let messages: std::collections::BTreeMap<_, _> =
+            resp.message_stats.unwrap_or_default().into_iter().collect();
+        let json = serde_json::to_string_pretty(&messages).map_err(|e| {
+            error::BridgeError::ControlProtocol {
+                message: e.to_string(),
+            }
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
     if cmd_str == "info" {
         println!(
-            "ok: cmd={} paused={} serial_open={} port={} pid={:?} version={:?} config={:?} instance_id={:?} controller_serial={:?} resolved_serial_port={:?} host_udp={:?} log_udp={:?}",
+            "ok: cmd={} paused={} serial_open={} port={} pid={:?} version={:?} config={:?} instance_id={:?} controller_serial={:?} resolved_serial_port={:?} host_udp={:?} log_udp={:?} log_ws={:?} metrics_url={:?}",
             cmd_str,
             resp.paused,
             resp.serial_open,
@@ -385,7 +712,9 @@ fn run_ctl(cmd: CtlCommand, control_port: u16) -> Result<()> {
             resp.controller_serial,
             resp.resolved_serial_port,
             resp.host_udp_port,
-            resp.log_broadcast_port
+            resp.log_broadcast_port,
+            log_stream_port,
+            resp.metrics_url
         );
     } else {
         println!(
@@ -393,5 +722,23 @@ fn run_ctl(cmd: CtlCommand, control_port: u16) -> Result<()> {
             cmd_str, resp.paused, resp.serial_open, control_port
         );
     }
+    if let (CtlCommand::Status, Some(path)) = (cmd, stats_path) {
+        // Lifetime totals are best-effort: no history yet is not an error
+        if let Ok(total) = Stats::load_cumulative(&path) {
+            println!(
+                "lifetime: sessions={} since={} uptime_secs={} rx_bytes={} tx_bytes={} rx_messages={} tx_messages={} tx_batches={} cobs_errors={} channel_drops={}",
+                total.sessions,
+                total.first_timestamp.as_deref().unwrap_or("-"),
+                total.uptime_secs,
+                total.rx_bytes,
+                total.tx_bytes,
+                total.rx_messages,
+                total.tx_messages,
+                total.tx_batches,
+                total.cobs_errors,
+                total.channel_drops
+            );
+        }
+    }
     Ok(())
 }
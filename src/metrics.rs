@@ -0,0 +1,334 @@
+//! Prometheus metrics endpoint (`metrics_port`)
+//!
+//! Answers `GET /metrics` with the daemon's traffic counters in the
+//! Prometheus text format, for scraping into Grafana and the like. Like
+//! discovery, it listens on all interfaces but only serves loopback and
+//! `allowed_remote_cidrs` clients.
+//!
+//! Just enough HTTP for a scraper: one request per connection, the answer
+//! is sent with `Connection: close`. Each connection runs on its own task,
+//! so concurrent scrapes do not wait for each other.
+
+use crate::bridge::stats::{MessageStats, Stats};
+use crate::control::ControlState;
+use crate::error::{BridgeError, Result};
+use crate::transport::udp::is_allowed;
+use ipnet::IpNet;
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Longest request head read before answering 400
+const MAX_REQUEST_BYTES: usize = 8192;
+
+/// Time a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `Content-Type` of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Bind the metrics port on all interfaces
+pub async fn bind_listener(port: u16) -> Result<TcpListener> {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port);
+    TcpListener::bind(addr)
+        .await
+        .map_err(|source| BridgeError::TcpBind { addr, source })
+}
+
+/// URL scraped for `port`, as reported by `ctl info`
+pub fn metrics_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}/metrics", port)
+}
+
+/// Serve scrapes until shutdown
+pub async fn run_server(
+    listener: TcpListener,
+    state: ControlState,
+    stats: Arc<Stats>,
+    allowed: Vec<IpNet>,
+    shutdown: Arc<AtomicBool>,
+) {
+    while !shutdown.load(Ordering::Relaxed) {
+        let accept = tokio::time::timeout(Duration::from_millis(250), listener.accept()).await;
+        let Ok(Ok((stream, from))) = accept else {
+            continue;
+        };
+        if !is_allowed(from.ip(), &allowed) {
+            continue;
+        }
+        let state = state.clone();
+        let stats = stats.clone();
+        tokio::spawn(async move {
+            // A client going away mid-answer is not an error worth reporting
+            let _ = handle_connection(stream, &state, &stats).await;
+        });
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    state: &ControlState,
+    stats: &Stats,
+) -> std::io::Result<()> {
+    let head = match tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream)).await {
+        Ok(head) => head?,
+        Err(_) => return Ok(()),
+    };
+    let response = match request_target(&head) {
+        Some(("GET", "/metrics")) => {
+            http_response("200 OK", CONTENT_TYPE, &render(stats, state.serial_open()))
+        }
+        Some(("GET", _)) => http_response("404 Not Found", "text/plain", "not found\n"),
+        Some(_) => http_response("405 Method Not Allowed", "text/plain", "GET only\n"),
+        None => http_response("400 Bad Request", "text/plain", "bad request\n"),
+    };
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Read up to the end of the request head (`\r\n\r\n`) or `MAX_REQUEST_BYTES`
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while head.len() < MAX_REQUEST_BYTES && !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(head)
+}
+
+/// Method and path of an HTTP request line (query string dropped)
+fn request_target(head: &[u8]) -> Option<(&str, &str)> {
+    let line = std::str::from_utf8(head).ok()?.lines().next()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts.next()?.starts_with("HTTP/").then_some(())?;
+    let path = target.split('?').next().unwrap_or(target);
+    Some((method, path))
+}
+
+fn http_response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+/// Metrics in the Prometheus text format
+fn render(stats: &Stats, serial_connected: bool) -> String {
+    let snapshot = stats.snapshot();
+    let messages = stats.message_stats();
+    let mut out = String::new();
+
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, labels, value);
+        }
+    };
+    fn single(value: impl std::fmt::Display) -> [(String, String); 1] {
+        [(String::new(), value.to_string())]
+    }
+    let per_name = |count: fn(&MessageStats) -> u64| -> Vec<(String, String)> {
+        messages
+            .iter()
+            .map(|(name, m)| {
+                let labels = format!("{{name=\"{}\"}}", escape_label(name));
+                (labels, count(m).to_string())
+            })
+            .collect()
+    };
+
+    metric(
+        "oc_bridge_rx_bytes_total",
+        "counter",
+        "Bytes received from the controller",
+        &single(snapshot.rx_bytes),
+    );
+    metric(
+        "oc_bridge_tx_bytes_total",
+        "counter",
+        "Bytes sent to the controller",
+        &single(snapshot.tx_bytes),
+    );
+    metric(
+        "oc_bridge_rx_messages_total",
+        "counter",
+        "Messages received from the controller, by message type",
+        &per_name(|m| m.rx_count),
+    );
+    metric(
+        "oc_bridge_tx_messages_total",
+        "counter",
+        "Messages sent to the controller, by message type",
+        &per_name(|m| m.tx_count),
+    );
+    metric(
+        "oc_bridge_tx_batches_total",
+        "counter",
+        "Batched writes to the controller (each carries one or more messages)",
+        &single(snapshot.tx_batches),
+    );
+    metric(
+        "oc_bridge_reconnections_total",
+        "counter",
+        "Serial connections restored after a loss",
+        &single(stats.reconnections()),
+    );
+    metric(
+        "oc_bridge_channel_drops_total",
+        "counter",
+        "Messages dropped because a relay channel was full",
+        &single(snapshot.channel_drops),
+    );
+    metric(
+        "oc_bridge_oversized_drops_total",
+        "counter",
+        "Controller messages dropped for exceeding max_message_size",
+        &single(stats.oversized_drops()),
+    );
+    metric(
+        "oc_bridge_control_requests_total",
+        "counter",
+        "Control plane requests handled",
+        &single(stats.control_requests_total()),
+    );
+    metric(
+        "oc_bridge_control_latency_ms_avg",
+        "gauge",
+        "Average control plane request latency in milliseconds",
+        &single(stats.control_latency_ms_avg()),
+    );
+    metric(
+        "oc_bridge_serial_connected",
+        "gauge",
+        "1 while the serial port is open",
+        &single(u64::from(serial_connected)),
+    );
+    metric(
+        "oc_bridge_uptime_seconds",
+        "gauge",
+        "Seconds since the bridge started",
+        &single(snapshot.uptime_secs),
+    );
+    out
+}
+
+/// Escape a label value (`\`, `"` and newlines)
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::ControlInfo;
+    use crate::logging::Direction;
+
+    fn control_state() -> ControlState {
+        let info = ControlInfo {
+            pid: 1,
+            version: "test".to_string(),
+            config_path: String::new(),
+            instance_id: "default".to_string(),
+            controller_serial: None,
+            host_udp_port: 9000,
+            host_pipe_name: None,
+            log_broadcast_port: 9999,
+            control_port: 7999,
+            serial_supported: true,
+            cobs_trace: false,
+            host_transport: crate::config::HostTransport::Udp,
+            metrics_port: None,
+        };
+        ControlState::new(Arc::new(AtomicBool::new(false)), info).0
+    }
+
+    async fn get(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn test_render_metrics() {
+        let stats = Stats::new();
+        stats.add_rx(12);
+        stats.add_tx(5);
+        stats.add_tx_batch();
+        stats.record_message("Volume", Direction::In, 12);
+        stats.record_message("Set \"A\"", Direction::Out, 5);
+        stats.add_reconnection();
+        stats.add_channel_drop();
+        stats.add_oversized_drop();
+        stats.record_control_request(Duration::from_millis(2));
+
+        let text = render(&stats, true);
+        for line in [
+            "# TYPE oc_bridge_rx_bytes_total counter",
+            "oc_bridge_rx_bytes_total 12",
+            "oc_bridge_tx_bytes_total 5",
+            "oc_bridge_rx_messages_total{name=\"Volume\"} 1",
+            "oc_bridge_tx_messages_total{name=\"Set \\\"A\\\"\"} 1",
+            "oc_bridge_tx_batches_total 1",
+            "oc_bridge_reconnections_total 1",
+            "oc_bridge_channel_drops_total 1",
+            "oc_bridge_oversized_drops_total 1",
+            "oc_bridge_control_requests_total 1",
+            "# TYPE oc_bridge_control_latency_ms_avg gauge",
+            "oc_bridge_control_latency_ms_avg 2",
+            "oc_bridge_serial_connected 1",
+            "# TYPE oc_bridge_uptime_seconds gauge",
+        ] {
+            assert!(text.lines().any(|l| l == line), "{}\n{}", line, text);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_server_answers_scrapes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        tokio::spawn(run_server(
+            listener,
+            control_state(),
+            Arc::new(Stats::new()),
+            Vec::new(),
+            shutdown.clone(),
+        ));
+
+        let (first, second) = tokio::join!(
+            get(addr, "GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n"),
+            get(addr, "GET /metrics?x=1 HTTP/1.0\r\n\r\n"),
+        );
+        for response in [first, second] {
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+            assert!(response.contains("oc_bridge_serial_connected 0\n"));
+        }
+        let missing = get(addr, "GET / HTTP/1.1\r\n\r\n").await;
+        assert!(missing.starts_with("HTTP/1.1 404"));
+        let post = get(addr, "POST /metrics HTTP/1.1\r\n\r\n").await;
+        assert!(post.starts_with("HTTP/1.1 405"));
+        let garbage = get(addr, "hello\r\n\r\n").await;
+        assert!(garbage.starts_with("HTTP/1.1 400"));
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+}
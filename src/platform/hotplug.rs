@@ -0,0 +1,202 @@
+//! Serial device hotplug events from udev (Linux only)
+//!
+//! Listens on the `NETLINK_KOBJECT_UEVENT` multicast group that udev
+//! re-broadcasts to once it has processed a device (rules applied, device
+//! database updated), so a port is ready to be enumerated and opened when
+//! its `add` event arrives. Kernel-format messages are understood as well.
+//!
+//! Events are only wake-up hints: the serial runner still matches the new
+//! port against the device preset before opening it.
+
+use super::HotplugEvent;
+use crate::error::{BridgeError, Result};
+use std::io;
+use std::mem::size_of;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Multicast group of events re-broadcast by udev (1 = raw kernel events)
+const UDEV_GROUP: u32 = 2;
+
+/// Prefix of udev-format messages (`monitor_netlink_header`)
+const UDEV_PREFIX: &[u8] = b"libudev\0";
+
+/// Offset of `properties_off` in the udev header (prefix, magic, header_size)
+const UDEV_PROPERTIES_OFF: usize = 16;
+
+/// Receive timeout, so the thread notices shutdown
+const RECV_TIMEOUT_US: libc::suseconds_t = 100_000;
+
+/// Open the uevent socket and forward `tty` events until `shutdown` is set
+/// or the receiver is dropped
+pub fn spawn(shutdown: Arc<AtomicBool>) -> Result<mpsc::Receiver<HotplugEvent>> {
+    let socket = open_socket().map_err(|source| BridgeError::HotplugWatch { source })?;
+    let (tx, rx) = mpsc::channel(16);
+
+    std::thread::Builder::new()
+        .name("serial-hotplug".to_string())
+        .spawn(move || {
+            let mut buf = vec![0u8; 8192];
+            while !shutdown.load(Ordering::Relaxed) && !tx.is_closed() {
+                // SAFETY: `socket` is a valid descriptor and `buf` is valid
+                // for writes of its length.
+                let n = unsafe {
+                    libc::recv(socket.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0)
+                };
+                let Ok(n) = usize::try_from(n) else {
+                    continue; // Timeout or interrupted
+                };
+                if let Some(event) = parse_uevent(&buf[..n]) {
+                    // A full queue means nobody is waiting: dropping is fine
+                    let _ = tx.try_send(event);
+                }
+            }
+        })
+        .map_err(|source| BridgeError::HotplugWatch { source })?;
+
+    Ok(rx)
+}
+
+fn open_socket() -> io::Result<OwnedFd> {
+    // SAFETY: plain socket creation; the descriptor is owned right away.
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            libc::NETLINK_KOBJECT_UEVENT,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `fd` was just created and is not owned elsewhere.
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // SAFETY: zeroed `sockaddr_nl` is valid; only family and groups are set.
+    let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_groups = UDEV_GROUP;
+    // SAFETY: `addr` is a valid `sockaddr_nl` living for the call.
+    let rc = unsafe {
+        libc::bind(
+            socket.as_raw_fd(),
+            (&addr as *const libc::sockaddr_nl).cast(),
+            size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let timeout = libc::timeval {
+        tv_sec: 0,
+        tv_usec: RECV_TIMEOUT_US,
+    };
+    // SAFETY: the option value is a `timeval` living for the call.
+    let rc = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            (&timeout as *const libc::timeval).cast(),
+            size_of::<libc::timeval>() as libc::socklen_t,
+        )
+    };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+/// `add` / `remove` of a `tty` device, from a udev or kernel uevent message
+fn parse_uevent(msg: &[u8]) -> Option<HotplugEvent> {
+    let properties = if msg.starts_with(UDEV_PREFIX) {
+        let off = msg.get(UDEV_PROPERTIES_OFF..UDEV_PROPERTIES_OFF + 4)?;
+        let off = u32::from_ne_bytes(off.try_into().ok()?) as usize;
+        msg.get(off..)?
+    } else {
+        // Kernel format: "action@devpath\0" then the properties
+        let header_end = msg.iter().position(|&b| b == 0)?;
+        msg.get(header_end + 1..)?
+    };
+
+    let (mut action, mut subsystem, mut devname) = (None, None, None);
+    for field in properties.split(|&b| b == 0) {
+        let Ok(field) = std::str::from_utf8(field) else {
+            continue;
+        };
+        match field.split_once('=') {
+            Some(("ACTION", v)) => action = Some(v),
+            Some(("SUBSYSTEM", v)) => subsystem = Some(v),
+            Some(("DEVNAME", v)) => devname = Some(v),
+            _ => {}
+        }
+    }
+    if subsystem != Some("tty") {
+        return None;
+    }
+    let devname = devname?;
+    let port = if devname.starts_with('/') {
+        devname.to_string()
+    } else {
+        format!("/dev/{}", devname)
+    };
+    match action? {
+        "add" => Some(HotplugEvent::Added(port)),
+        "remove" => Some(HotplugEvent::Removed(port)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn udev_message(properties: &str) -> Vec<u8> {
+        let mut msg = UDEV_PREFIX.to_vec();
+        msg.extend_from_slice(&0xfeedcafe_u32.to_be_bytes());
+        msg.extend_from_slice(&40u32.to_ne_bytes()); // header_size
+        msg.extend_from_slice(&40u32.to_ne_bytes()); // properties_off
+        msg.extend_from_slice(&(properties.len() as u32).to_ne_bytes());
+        msg.resize(40, 0); // filter hashes
+        msg.extend_from_slice(properties.replace('\n', "\0").as_bytes());
+        msg
+    }
+
+    #[test]
+    fn test_parse_uevent() {
+        let added = udev_message(
+            "ACTION=add\nDEVPATH=/devices/pci0000:00/usb1/1-1/1-1:1.0/tty/ttyACM0\n\
+             SUBSYSTEM=tty\nDEVNAME=/dev/ttyACM0\nID_VENDOR_ID=16c0\n",
+        );
+        assert_eq!(
+            parse_uevent(&added),
+            Some(HotplugEvent::Added("/dev/ttyACM0".to_string()))
+        );
+
+        let kernel = b"remove@/devices/usb1/1-1/tty/ttyUSB1\0ACTION=remove\0\
+                       SUBSYSTEM=tty\0DEVNAME=ttyUSB1\0";
+        assert_eq!(
+            parse_uevent(kernel),
+            Some(HotplugEvent::Removed("/dev/ttyUSB1".to_string()))
+        );
+
+        // Other subsystems, actions and truncated messages are ignored
+        assert_eq!(
+            parse_uevent(&udev_message(
+                "ACTION=add\nSUBSYSTEM=usb\nDEVNAME=bus/usb/001/004\n"
+            )),
+            None
+        );
+        assert_eq!(
+            parse_uevent(&udev_message(
+                "ACTION=change\nSUBSYSTEM=tty\nDEVNAME=tty1\n"
+            )),
+            None
+        );
+        assert_eq!(parse_uevent(&UDEV_PREFIX[..6]), None);
+        assert_eq!(parse_uevent(&added[..18]), None);
+    }
+}
@@ -0,0 +1,52 @@
+//! macOS platform implementation
+//!
+//! Features:
+//! - Thread QoS (user-interactive) for the calling thread
+//! - Thread priority (round-robin real-time policy for the serial writer)
+//! - Serial port low-latency configuration (exclusive access, `IOSSIOSPEED`)
+
+use std::os::fd::AsRawFd;
+
+/// `_IOW('T', 2, speed_t)` from `IOKit/serial/ioss.h`: set the line speed
+/// directly, bypassing the termios speed table
+const IOSSIOSPEED: libc::c_ulong = 0x8008_5402;
+
+// =============================================================================
+// Performance: Thread QoS and priority
+// =============================================================================
+
+/// Run the calling thread at the user-interactive QoS class
+pub fn init_perf() {
+    unsafe {
+        let _ =
+            libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_USER_INTERACTIVE, 0);
+    }
+}
+
+/// Switch the current thread to `SCHED_RR` at the highest priority
+///
+/// Best effort: ignored when the system refuses it.
+pub fn set_thread_high_priority() {
+    unsafe {
+        let mut param: libc::sched_param = std::mem::zeroed();
+        param.sched_priority = libc::sched_get_priority_max(libc::SCHED_RR);
+        let _ = libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_RR, &param);
+    }
+}
+
+// =============================================================================
+// Serial: Low-latency configuration
+// =============================================================================
+
+/// Configure serial port for exclusive, exact-speed access
+///
+/// - `TIOCEXCL`: other processes cannot open the port while we hold it
+/// - `IOSSIOSPEED`: sets `baud_rate` even when termios has no constant for it
+pub fn configure_serial_low_latency(port: &serialport::TTYPort, baud_rate: u32) {
+    let fd = port.as_raw_fd();
+    let speed = baud_rate as libc::speed_t;
+    unsafe {
+        let _ = libc::ioctl(fd, libc::TIOCEXCL as libc::c_ulong);
+        let _ = libc::ioctl(fd, IOSSIOSPEED, &speed);
+    }
+}
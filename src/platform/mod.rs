@@ -17,11 +17,28 @@
 //! }
 //! ```
 
+#[cfg(target_os = "linux")]
+mod hotplug;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+pub mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "linux")]
+mod pmtu;
+#[cfg(unix)]
+mod pty;
+#[cfg(target_os = "linux")]
+mod systemd;
 #[cfg(windows)]
 mod windows;
 
+#[cfg(all(test, target_os = "linux"))]
+pub(crate) use systemd::inherit_for_test;
+
 use crate::error::{BridgeError, Result};
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 // =============================================================================
 // Platform functions (static dispatch)
@@ -30,21 +47,27 @@ use std::path::{Path, PathBuf};
 /// Initialize platform-specific performance optimizations
 ///
 /// - Windows: Sets 1ms timer resolution via timeBeginPeriod
+/// - macOS: user-interactive QoS class for the calling thread
 /// - Other platforms: No-op
 #[inline]
 pub fn init_perf() {
     #[cfg(windows)]
     windows::init_perf();
+    #[cfg(target_os = "macos")]
+    macos::init_perf();
 }
 
 /// Set current thread to high priority for time-critical operations
 ///
 /// - Windows: THREAD_PRIORITY_HIGHEST
+/// - macOS: SCHED_RR at its highest priority
 /// - Other platforms: No-op
 #[inline]
 pub fn set_thread_high_priority() {
     #[cfg(windows)]
     windows::set_thread_high_priority();
+    #[cfg(target_os = "macos")]
+    macos::set_thread_high_priority();
 }
 
 // =============================================================================
@@ -60,6 +83,15 @@ pub fn configure_serial_low_latency(port: &serialport::COMPort) {
     windows::configure_serial_low_latency(port);
 }
 
+/// Configure serial port for exclusive access at an exact speed (macOS only)
+///
+/// Sets `TIOCEXCL` and the line speed through `IOSSIOSPEED`.
+/// Call after opening the port with `open_native()`.
+#[cfg(target_os = "macos")]
+pub fn configure_serial_low_latency(port: &serialport::TTYPort, baud_rate: u32) {
+    macos::configure_serial_low_latency(port, baud_rate);
+}
+
 /// Hide the current console window only if we appear to own it (Windows only)
 #[cfg(windows)]
 #[inline]
@@ -67,6 +99,165 @@ pub fn hide_console_window_if_solo() {
     windows::hide_console_window_if_solo();
 }
 
+// =============================================================================
+// Sockets
+// =============================================================================
+
+/// Pre-bound UDP socket passed by the service manager for `port`
+///
+/// Returns a duplicate on every call, so each transport start can adopt it.
+///
+/// - Linux: systemd socket activation (`LISTEN_PID`/`LISTEN_FDS`)
+/// - Other platforms: always `None`
+pub fn inherited_udp_socket(port: u16) -> Option<std::net::UdpSocket> {
+    #[cfg(target_os = "linux")]
+    {
+        systemd::udp_socket(port)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = port;
+        None
+    }
+}
+
+/// Path MTU towards `target` as known to the kernel (nothing is sent)
+///
+/// - Linux: `IP_MTU` / `IPV6_MTU` of a connected Don't-Fragment socket
+/// - Other platforms: always `None`
+pub fn path_mtu(target: SocketAddr) -> Option<usize> {
+    #[cfg(target_os = "linux")]
+    {
+        pmtu::path_mtu(target)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = target;
+        None
+    }
+}
+
+/// Virtual serial port that echoes back everything written to it, for
+/// `test-loopback --software-loopback`; returns its device path
+///
+/// - Unix: pseudo-terminal echoed by a thread until `shutdown` is set
+/// - Windows: not supported (virtual COM pairs need a driver)
+pub fn spawn_serial_echo(
+    shutdown: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) -> Result<String> {
+    #[cfg(unix)]
+    {
+        pty::spawn_serial_echo(shutdown)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = shutdown;
+        Err(BridgeError::PlatformNotSupported {
+            feature: "Software serial loopback",
+        })
+    }
+}
+
+/// Extra kernel attributes for a serial port (diagnostics only)
+///
+/// - Linux: `KEY=value` pairs from `/sys/class/tty/<name>/device/uevent`
+/// - Other platforms: empty
+pub fn serial_port_attributes(port_name: &str) -> Vec<(String, String)> {
+    #[cfg(target_os = "linux")]
+    {
+        let Some(uevent) = tty_uevent_path(port_name) else {
+            return Vec::new();
+        };
+        std::fs::read_to_string(uevent)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| line.split_once('='))
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = port_name;
+        Vec::new()
+    }
+}
+
+/// When a USB serial device was plugged in (best effort)
+///
+/// - Linux: mtime of `/sys/class/tty/<name>/device/uevent`
+/// - Windows: last write time of its `Enum\USB\VID_xxxx&PID_xxxx\<serial>` key
+/// - Other platforms: always `None`
+pub fn serial_device_plugged_at(
+    port_name: &str,
+    vid: u16,
+    pid: u16,
+    serial_number: Option<&str>,
+) -> Option<SystemTime> {
+    #[cfg(target_os = "linux")]
+    {
+        let _ = (vid, pid, serial_number);
+        std::fs::metadata(tty_uevent_path(port_name)?)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+    #[cfg(windows)]
+    {
+        let _ = port_name;
+        windows::usb_device_arrival(vid, pid, serial_number?)
+    }
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = (port_name, vid, pid, serial_number);
+        None
+    }
+}
+
+/// Serial device plugged in or out, by device path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotplugEvent {
+    Added(String),
+    Removed(String),
+}
+
+/// Watch serial devices being plugged in and out, until `shutdown` is set
+///
+/// - Linux: udev `tty` events from a netlink socket
+/// - Windows: `WM_DEVICECHANGE` for COM port interfaces, on a hidden window
+/// - Other platforms: not supported (callers keep polling)
+pub fn watch_serial_hotplug(
+    shutdown: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) -> Result<tokio::sync::mpsc::Receiver<HotplugEvent>> {
+    #[cfg(target_os = "linux")]
+    {
+        hotplug::spawn(shutdown)
+    }
+    #[cfg(windows)]
+    {
+        windows::start_device_change_watcher(shutdown)
+    }
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = shutdown;
+        Err(BridgeError::PlatformNotSupported {
+            feature: "Serial hotplug events",
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn tty_uevent_path(port_name: &str) -> Option<PathBuf> {
+    let name = Path::new(port_name).file_name()?;
+    Some(
+        Path::new("/sys/class/tty")
+            .join(name)
+            .join("device")
+            .join("uevent"),
+    )
+}
+
 // =============================================================================
 // File operations
 // =============================================================================
@@ -117,6 +308,26 @@ pub fn open_file(path: &Path) -> Result<()> {
 // Terminal detection and relaunch
 // =============================================================================
 
+/// `TERM_PROGRAM` values of terminals known to support OSC 8 hyperlinks
+const HYPERLINK_TERMINALS: &[&str] = &["iTerm.app", "WezTerm", "vscode", "ghostty"];
+
+/// Whether the terminal renders OSC 8 hyperlinks
+///
+/// Checks `TERM_PROGRAM` against known terminals, and `VTE_VERSION`
+/// (GNOME Terminal, Tilix and other VTE-based terminals since 0.50).
+pub fn supports_hyperlinks() -> bool {
+    let program = std::env::var("TERM_PROGRAM").ok();
+    let vte = std::env::var("VTE_VERSION").ok();
+    hyperlinks_supported(program.as_deref(), vte.as_deref())
+}
+
+fn hyperlinks_supported(term_program: Option<&str>, vte_version: Option<&str>) -> bool {
+    term_program.is_some_and(|p| HYPERLINK_TERMINALS.contains(&p))
+        || vte_version
+            .and_then(|v| v.parse::<u32>().ok())
+            .is_some_and(|v| v >= 5000)
+}
+
 /// List of terminal emulators to try on Unix (in order of preference)
 #[cfg(unix)]
 const UNIX_TERMINAL_EMULATORS: &[&str] = &[
@@ -0,0 +1,76 @@
+//! Path MTU lookup (Linux only)
+//!
+//! A UDP socket connected with `IP_PMTUDISC_DO` (Don't Fragment) reports the
+//! kernel's path MTU towards its peer through `IP_MTU` / `IPV6_MTU`. Nothing
+//! is sent: a probe datagram would reach the host application as data.
+
+use std::mem::size_of;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::os::fd::AsRawFd;
+
+/// Path MTU towards `target`, or `None` if the kernel does not report one
+pub fn path_mtu(target: SocketAddr) -> Option<usize> {
+    let (bind, level, discover, dont_fragment, mtu_opt): (SocketAddr, _, _, _, _) =
+        if target.is_ipv4() {
+            (
+                (Ipv4Addr::UNSPECIFIED, 0).into(),
+                libc::IPPROTO_IP,
+                libc::IP_MTU_DISCOVER,
+                libc::IP_PMTUDISC_DO,
+                libc::IP_MTU,
+            )
+        } else {
+            (
+                (Ipv6Addr::UNSPECIFIED, 0).into(),
+                libc::IPPROTO_IPV6,
+                libc::IPV6_MTU_DISCOVER,
+                libc::IPV6_PMTUDISC_DO,
+                libc::IPV6_MTU,
+            )
+        };
+
+    let socket = UdpSocket::bind(bind).ok()?;
+    let fd = socket.as_raw_fd();
+    let len = size_of::<libc::c_int>() as libc::socklen_t;
+
+    // SAFETY: `fd` is a valid socket owned by `socket`; the option value is
+    // a c_int living for the duration of the call.
+    let rc = unsafe {
+        libc::setsockopt(
+            fd,
+            level,
+            discover,
+            (&dont_fragment as *const libc::c_int).cast(),
+            len,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    socket.connect(target).ok()?;
+
+    let mut mtu: libc::c_int = 0;
+    let mut mtu_len = len;
+    // SAFETY: as above; `mtu` and `mtu_len` are valid for writes.
+    let rc = unsafe {
+        libc::getsockopt(
+            fd,
+            level,
+            mtu_opt,
+            (&mut mtu as *mut libc::c_int).cast(),
+            &mut mtu_len,
+        )
+    };
+    (rc == 0 && mtu > 0).then_some(mtu as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loopback_path_mtu() {
+        let mtu = path_mtu((Ipv4Addr::LOCALHOST, 9).into()).expect("loopback MTU");
+        assert!(mtu >= 1500, "{}", mtu);
+    }
+}
@@ -0,0 +1,86 @@
+//! Echoing pseudo-terminal (Unix only)
+//!
+//! Stands in for a serial loopback connector: the slave side is opened like
+//! any serial port, and a thread writes every byte read on the master side
+//! straight back.
+
+use crate::error::{BridgeError, Result};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// How long one `poll` waits before re-checking `shutdown`
+const POLL_TIMEOUT_MS: libc::c_int = 50;
+
+/// Open a pty pair and echo its master side until `shutdown` is set
+///
+/// Returns the slave device path (e.g. `/dev/pts/3`).
+pub fn spawn_serial_echo(shutdown: Arc<AtomicBool>) -> Result<String> {
+    let mut master: libc::c_int = -1;
+    let mut slave: libc::c_int = -1;
+    // SAFETY: both out-pointers are valid; name, termios and winsize may be null.
+    let rc = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if rc != 0 {
+        return Err(BridgeError::OsCommand {
+            program: "openpty",
+            source: std::io::Error::last_os_error(),
+        });
+    }
+    // SAFETY: openpty returned two open descriptors that nothing else owns.
+    let (mut master, slave) = unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) };
+
+    let path = tty_name(&slave).ok_or_else(|| BridgeError::OsCommand {
+        program: "ttyname",
+        source: std::io::Error::last_os_error(),
+    })?;
+
+    std::thread::spawn(move || {
+        // Held open so the master does not see a hangup between clients
+        let _slave = slave;
+        let mut buf = [0u8; 4096];
+        while !shutdown.load(Ordering::Relaxed) {
+            let mut fds = libc::pollfd {
+                fd: master.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: one valid pollfd for the duration of the call.
+            if unsafe { libc::poll(&mut fds, 1, POLL_TIMEOUT_MS) } <= 0 {
+                continue;
+            }
+            match master.read(&mut buf) {
+                Ok(n) if n > 0 => {
+                    if master.write_all(&buf[..n]).is_err() {
+                        return;
+                    }
+                }
+                _ => return,
+            }
+        }
+    });
+
+    Ok(path)
+}
+
+/// Device path of a terminal descriptor
+fn tty_name(file: &File) -> Option<String> {
+    // SAFETY: `file` is an open descriptor; the returned string is copied
+    // before any other ttyname call.
+    let name = unsafe { libc::ttyname(file.as_raw_fd()) };
+    if name.is_null() {
+        return None;
+    }
+    // SAFETY: ttyname returned a valid C string.
+    let name = unsafe { std::ffi::CStr::from_ptr(name) };
+    Some(name.to_string_lossy().into_owned())
+}
@@ -0,0 +1,133 @@
+//! systemd socket activation (Linux only)
+//!
+//! When started from a `.socket` unit, systemd passes pre-bound sockets
+//! starting at fd 3 and announces them via `LISTEN_PID`/`LISTEN_FDS`.
+//! Adopting them instead of binding again removes the port-in-use window
+//! while the service restarts.
+//!
+//! The inherited descriptors stay in a pool for the life of the process;
+//! each transport start gets a duplicate, so a rebind (reconnect, host
+//! switch, failover) still uses the activated socket.
+//!
+//! Example socket unit (`oc-bridge.socket`):
+//!
+//! ```text
+//! [Socket]
+//! ListenDatagram=127.0.0.1:9000
+//! ReusePort=true
+//!
+//! [Install]
+//! WantedBy=sockets.target
+//! ```
+
+use parking_lot::Mutex;
+use socket2::{SockRef, Type};
+use std::net::UdpSocket;
+use std::os::fd::{AsFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::OnceLock;
+
+/// First file descriptor passed by systemd (`SD_LISTEN_FDS_START`)
+const LISTEN_FDS_START: RawFd = 3;
+
+/// Inherited descriptors, kept open for the life of the process
+static INHERITED_FDS: OnceLock<Mutex<Vec<OwnedFd>>> = OnceLock::new();
+
+/// Parse `LISTEN_PID`/`LISTEN_FDS` into the list of passed descriptors
+///
+/// Returns nothing unless `LISTEN_PID` names this process (the variables
+/// are inherited by children otherwise).
+fn parse_listen_fds(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> Vec<RawFd> {
+    let pid_matches = listen_pid
+        .and_then(|p| p.trim().parse::<u32>().ok())
+        .is_some_and(|p| p == pid);
+    if !pid_matches {
+        return Vec::new();
+    }
+
+    let count = listen_fds
+        .and_then(|n| n.trim().parse::<RawFd>().ok())
+        .unwrap_or(0);
+    (LISTEN_FDS_START..LISTEN_FDS_START + count.max(0)).collect()
+}
+
+fn inherited_fds() -> &'static Mutex<Vec<OwnedFd>> {
+    INHERITED_FDS.get_or_init(|| {
+        let pid = std::env::var("LISTEN_PID").ok();
+        let fds = std::env::var("LISTEN_FDS").ok();
+        let fds = parse_listen_fds(pid.as_deref(), fds.as_deref(), std::process::id());
+        Mutex::new(
+            fds.into_iter()
+                // SAFETY: announced by systemd via LISTEN_FDS for this process
+                // and adopted exactly once, here.
+                .map(|fd| unsafe { OwnedFd::from_raw_fd(fd) })
+                .collect(),
+        )
+    })
+}
+
+/// Duplicate of the inherited UDP socket bound to `port`, if systemd passed one
+///
+/// The inherited descriptor itself stays in the pool, so every call (one per
+/// transport start) gets a socket bound to the activated port.
+pub fn udp_socket(port: u16) -> Option<UdpSocket> {
+    duplicate_udp(&inherited_fds().lock(), port)
+}
+
+/// Add a socket to the pool as if systemd had passed it
+#[cfg(test)]
+pub(crate) fn inherit_for_test(socket: UdpSocket) {
+    inherited_fds().lock().push(OwnedFd::from(socket));
+}
+
+fn duplicate_udp(fds: &[OwnedFd], port: u16) -> Option<UdpSocket> {
+    let fd = fds.iter().find(|fd| {
+        let sock = SockRef::from(fd);
+        let is_udp = sock.r#type().is_ok_and(|t| t == Type::DGRAM);
+        let bound_port = sock
+            .local_addr()
+            .ok()
+            .and_then(|a| a.as_socket())
+            .map(|a| a.port());
+        is_udp && bound_port == Some(port)
+    })?;
+    fd.as_fd().try_clone_to_owned().ok().map(UdpSocket::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_fds_matching_pid() {
+        assert_eq!(parse_listen_fds(Some("42"), Some("2"), 42), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_duplicate_udp_serves_every_transport_start() {
+        let inherited = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = inherited.local_addr().unwrap();
+        let pool = vec![OwnedFd::from(inherited)];
+        assert!(duplicate_udp(&pool, addr.port().wrapping_add(1)).is_none());
+
+        // First transport start ends and closes its socket...
+        let first = duplicate_udp(&pool, addr.port()).unwrap();
+        assert_eq!(first.local_addr().unwrap(), addr);
+        drop(first);
+
+        // ...the second still gets the activated socket
+        let second = duplicate_udp(&pool, addr.port()).unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.send_to(b"again", addr).unwrap();
+        let mut buf = [0u8; 8];
+        let (len, _) = second.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"again");
+    }
+
+    #[test]
+    fn test_parse_listen_fds_other_pid_or_missing() {
+        assert!(parse_listen_fds(Some("41"), Some("2"), 42).is_empty());
+        assert!(parse_listen_fds(None, Some("2"), 42).is_empty());
+        assert!(parse_listen_fds(Some("42"), None, 42).is_empty());
+        assert!(parse_listen_fds(Some("42"), Some("-1"), 42).is_empty());
+    }
+}
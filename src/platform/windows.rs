@@ -6,19 +6,44 @@
 //! - Timer resolution (1ms for USB polling)
 //! - Thread priority (highest for serial reader)
 //! - Serial port low-latency configuration
+//! - USB device arrival time (registry key last write)
+//! - Serial hotplug events (`WM_DEVICECHANGE` on a hidden window)
 //!
 //! Note: oc-bridge background mode is user-scoped; we avoid UAC flows.
 
+use super::HotplugEvent;
+use crate::error::{BridgeError, Result};
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::mem::{offset_of, size_of};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use windows::core::{w, HSTRING, PCWSTR};
 use windows::Win32::Devices::Communication::{
     PurgeComm, SetCommTimeouts, SetupComm, COMMTIMEOUTS, PURGE_COMM_FLAGS,
 };
-use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::{
+    ERROR_SUCCESS, FILETIME, HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, WPARAM,
+};
 use windows::Win32::Media::timeBeginPeriod;
 use windows::Win32::System::Console::{GetConsoleProcessList, GetConsoleWindow};
+use windows::Win32::System::Ioctl::GUID_DEVINTERFACE_COMPORT;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegOpenKeyExW, RegQueryInfoKeyW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ,
+};
 use windows::Win32::System::Threading::{
     GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_HIGHEST,
 };
-use windows::Win32::UI::WindowsAndMessaging::{ShowWindow, SW_HIDE};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW, RegisterClassW,
+    RegisterDeviceNotificationW, SetTimer, ShowWindow, UnregisterDeviceNotification,
+    DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE, DBT_DEVTYP_DEVICEINTERFACE, DBT_DEVTYP_PORT,
+    DEVICE_NOTIFY_WINDOW_HANDLE, DEV_BROADCAST_DEVICEINTERFACE_W, DEV_BROADCAST_HDR,
+    DEV_BROADCAST_PORT_W, HDEVNOTIFY, HWND_MESSAGE, MSG, SW_HIDE, WINDOW_EX_STYLE, WINDOW_STYLE,
+    WM_DEVICECHANGE, WNDCLASSW,
+};
 
 // =============================================================================
 // Performance: Timer resolution
@@ -78,6 +103,59 @@ pub fn configure_serial_low_latency(port: &serialport::COMPort) {
     }
 }
 
+// =============================================================================
+// Serial: device arrival time
+// =============================================================================
+
+/// Seconds between 1601-01-01 (FILETIME epoch) and 1970-01-01
+const FILETIME_UNIX_OFFSET_SECS: u64 = 11_644_473_600;
+
+/// Last write time of the device's key under `Enum\USB`, updated when the
+/// device arrives
+pub fn usb_device_arrival(vid: u16, pid: u16, serial: &str) -> Option<std::time::SystemTime> {
+    let path = HSTRING::from(format!(
+        r"SYSTEM\CurrentControlSet\Enum\USB\VID_{:04X}&PID_{:04X}\{}",
+        vid, pid, serial
+    ));
+    let mut key = HKEY::default();
+    let mut written = FILETIME::default();
+    unsafe {
+        if RegOpenKeyExW(
+            HKEY_LOCAL_MACHINE,
+            &path,
+            Default::default(),
+            KEY_READ,
+            &mut key,
+        ) != ERROR_SUCCESS
+        {
+            return None;
+        }
+        let status = RegQueryInfoKeyW(
+            key,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(&mut written as *mut FILETIME),
+        );
+        let _ = RegCloseKey(key);
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+    }
+
+    let ticks = (u64::from(written.dwHighDateTime) << 32) | u64::from(written.dwLowDateTime);
+    let since_1601 = std::time::Duration::from_nanos(ticks.saturating_mul(100));
+    let offset = std::time::Duration::from_secs(FILETIME_UNIX_OFFSET_SECS);
+    std::time::UNIX_EPOCH.checked_add(since_1601.checked_sub(offset)?)
+}
+
 /// Hide the current console window (best-effort)
 ///
 /// Used by `--daemon` to avoid flashing a terminal window when launched on login.
@@ -107,3 +185,181 @@ pub fn hide_console_window_if_solo() {
         }
     }
 }
+
+// =============================================================================
+// Serial: hotplug notifications
+// =============================================================================
+
+/// Class of the hidden window receiving `WM_DEVICECHANGE`
+const HOTPLUG_WINDOW_CLASS: PCWSTR = w!("oc-bridge-hotplug");
+
+/// `WM_TIMER` period, so the message loop notices shutdown
+const HOTPLUG_TIMER_MS: u32 = 100;
+
+thread_local! {
+    /// Where the window procedure of the watcher thread sends its events
+    static HOTPLUG_TX: RefCell<Option<mpsc::Sender<HotplugEvent>>> = const { RefCell::new(None) };
+}
+
+/// Forward COM port arrivals and removals until `shutdown` is set or the
+/// receiver is dropped
+///
+/// A message-only window registered for `GUID_DEVINTERFACE_COMPORT`
+/// notifications, pumped on its own thread. Fails when the window cannot
+/// be created (e.g. a service without a desktop); callers then keep polling.
+pub fn start_device_change_watcher(
+    shutdown: Arc<AtomicBool>,
+) -> Result<mpsc::Receiver<HotplugEvent>> {
+    let (tx, rx) = mpsc::channel(16);
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+    std::thread::Builder::new()
+        .name("serial-hotplug".to_string())
+        .spawn(move || {
+            let window = match DeviceChangeWindow::create() {
+                Ok(window) => window,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            HOTPLUG_TX.with(|slot| *slot.borrow_mut() = Some(tx.clone()));
+            let _ = ready_tx.send(Ok(()));
+
+            let mut msg = MSG::default();
+            while !shutdown.load(Ordering::Relaxed) && !tx.is_closed() {
+                unsafe {
+                    // 0 = WM_QUIT, -1 = error (`msg` is not filled in)
+                    if GetMessageW(&mut msg, None, 0, 0).0 <= 0 {
+                        break;
+                    }
+                    DispatchMessageW(&msg);
+                }
+            }
+            drop(window);
+        })
+        .map_err(|source| BridgeError::HotplugWatch { source })?;
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => Ok(rx),
+        Ok(Err(e)) => Err(BridgeError::HotplugWatch {
+            source: std::io::Error::other(e.to_string()),
+        }),
+        Err(_) => Err(BridgeError::HotplugWatch {
+            source: std::io::Error::other("hotplug thread exited"),
+        }),
+    }
+}
+
+/// Hidden window registered for device notifications (destroyed on drop)
+struct DeviceChangeWindow {
+    hwnd: HWND,
+    notify: HDEVNOTIFY,
+}
+
+impl DeviceChangeWindow {
+    fn create() -> windows::core::Result<Self> {
+        unsafe {
+            let instance: HINSTANCE = GetModuleHandleW(None)?.into();
+            let class = WNDCLASSW {
+                lpfnWndProc: Some(device_change_proc),
+                hInstance: instance,
+                lpszClassName: HOTPLUG_WINDOW_CLASS,
+                ..Default::default()
+            };
+            // Fails harmlessly when an earlier watcher registered it
+            RegisterClassW(&class);
+
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                HOTPLUG_WINDOW_CLASS,
+                PCWSTR::null(),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                Some(HWND_MESSAGE),
+                None,
+                Some(instance),
+                None,
+            )?;
+
+            let filter = DEV_BROADCAST_DEVICEINTERFACE_W {
+                dbcc_size: size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as u32,
+                dbcc_devicetype: DBT_DEVTYP_DEVICEINTERFACE.0,
+                dbcc_classguid: GUID_DEVINTERFACE_COMPORT,
+                ..Default::default()
+            };
+            let notify = match RegisterDeviceNotificationW(
+                HANDLE(hwnd.0),
+                &filter as *const _ as *const c_void,
+                DEVICE_NOTIFY_WINDOW_HANDLE,
+            ) {
+                Ok(notify) => notify,
+                Err(e) => {
+                    let _ = DestroyWindow(hwnd);
+                    return Err(e);
+                }
+            };
+            SetTimer(Some(hwnd), 1, HOTPLUG_TIMER_MS, None);
+
+            Ok(Self { hwnd, notify })
+        }
+    }
+}
+
+impl Drop for DeviceChangeWindow {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = UnregisterDeviceNotification(self.notify);
+            let _ = DestroyWindow(self.hwnd);
+        }
+    }
+}
+
+unsafe extern "system" fn device_change_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_DEVICECHANGE && lparam.0 != 0 {
+        // For arrival and removal, `lparam` points to a DEV_BROADCAST_* struct
+        let name = || unsafe { broadcast_name(lparam.0 as *const DEV_BROADCAST_HDR) };
+        let event = match wparam.0 as u32 {
+            DBT_DEVICEARRIVAL => name().map(HotplugEvent::Added),
+            DBT_DEVICEREMOVECOMPLETE => name().map(HotplugEvent::Removed),
+            _ => None,
+        };
+        if let Some(event) = event {
+            HOTPLUG_TX.with(|slot| {
+                if let Some(tx) = slot.borrow().as_ref() {
+                    // A full queue means nobody is waiting: dropping is fine
+                    let _ = tx.try_send(event);
+                }
+            });
+        }
+        return LRESULT(1);
+    }
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+/// Device interface path (`\\?\USB#VID_...`) or port name (`COM3`)
+unsafe fn broadcast_name(header: *const DEV_BROADCAST_HDR) -> Option<String> {
+    let (size, kind) = unsafe { ((*header).dbch_size as usize, (*header).dbch_devicetype) };
+    let offset = if kind == DBT_DEVTYP_DEVICEINTERFACE {
+        offset_of!(DEV_BROADCAST_DEVICEINTERFACE_W, dbcc_name)
+    } else if kind == DBT_DEVTYP_PORT {
+        offset_of!(DEV_BROADCAST_PORT_W, dbcp_name)
+    } else {
+        return None;
+    };
+
+    // The name runs to the end of the struct (`dbch_size` bytes), NUL-terminated
+    let len = size.saturating_sub(offset) / 2;
+    let chars =
+        unsafe { std::slice::from_raw_parts(header.cast::<u8>().add(offset).cast::<u16>(), len) };
+    let end = chars.iter().position(|&c| c == 0).unwrap_or(len);
+    Some(String::from_utf16_lossy(&chars[..end]))
+}
@@ -0,0 +1,128 @@
+//! Shell integration (`oc-bridge shell-init`)
+//!
+//! Prints shell functions to source from `.bashrc` / `.zshrc` / fish config:
+//!
+//! ```text
+//! eval "$(oc-bridge shell-init --shell bash)"
+//! ocbridge-port    # exports OC_BRIDGE_PORT=<first controller port>
+//! ```
+//!
+//! followed by completions for `oc-bridge` itself. `OC_BRIDGE_PORT` is used
+//! as the serial port when none is configured (see `config::env`).
+//!
+//! The completions alone, for any shell clap supports, come from the hidden
+//! `oc-bridge completions <SHELL>` subcommand.
+
+use crate::cli::{Cli, ShellArg};
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+/// Port of the first device matching the preset, else the first port listed
+const PORT_QUERY: &str = "first(.[] | select(.matches_preset)) // .[0] | .port // empty";
+
+/// Full `shell-init` output for `shell`
+pub fn init_script(shell: ShellArg) -> String {
+    let mut script = match shell {
+        ShellArg::Bash | ShellArg::Zsh => posix_functions(shell),
+        ShellArg::Fish => fish_functions(),
+    };
+
+    script.push_str("\n# Completions\n");
+    script.push_str(&completions(Shell::from(shell)));
+    script
+}
+
+/// Completion script for `oc-bridge` in `shell`
+pub fn completions(shell: Shell) -> String {
+    let mut out = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "oc-bridge", &mut out);
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn posix_functions(shell: ShellArg) -> String {
+    let rc = if shell == ShellArg::Zsh {
+        "~/.zshrc"
+    } else {
+        "~/.bashrc"
+    };
+    format!(
+        r#"# oc-bridge shell integration
+# Add to {rc}:  eval "$(oc-bridge shell-init --shell {shell})"
+
+# Export OC_BRIDGE_PORT with the first available controller port (needs jq)
+ocbridge-port() {{
+    local port
+    port="$(oc-bridge list-ports --json | jq -r '{query}')"
+    if [ -n "$port" ]; then
+        export OC_BRIDGE_PORT="$port"
+        echo "OC_BRIDGE_PORT=$port"
+    else
+        unset OC_BRIDGE_PORT
+        echo "No serial port found" >&2
+        return 1
+    fi
+}}
+"#,
+        rc = rc,
+        shell = shell.name(),
+        query = PORT_QUERY,
+    )
+}
+
+fn fish_functions() -> String {
+    format!(
+        r#"# oc-bridge shell integration
+# Add to ~/.config/fish/config.fish:  oc-bridge shell-init --shell fish | source
+
+# Export OC_BRIDGE_PORT with the first available controller port (needs jq)
+function ocbridge-port
+    set -l port (oc-bridge list-ports --json | jq -r '{query}')
+    if test -n "$port"
+        set -gx OC_BRIDGE_PORT $port
+        echo "OC_BRIDGE_PORT=$port"
+    else
+        set -e OC_BRIDGE_PORT
+        echo "No serial port found" >&2
+        return 1
+    end
+end
+"#,
+        query = PORT_QUERY,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn test_init_script_per_shell() {
+        let bash = init_script(ShellArg::Bash);
+        assert!(bash.contains("ocbridge-port() {"));
+        assert!(bash.contains("export OC_BRIDGE_PORT=\"$port\""));
+        assert!(bash.contains("complete -F"));
+
+        let zsh = init_script(ShellArg::Zsh);
+        assert!(zsh.contains("~/.zshrc"));
+        assert!(zsh.contains("#compdef oc-bridge"));
+
+        let fish = init_script(ShellArg::Fish);
+        assert!(fish.contains("function ocbridge-port"));
+        assert!(fish.contains("set -gx OC_BRIDGE_PORT $port"));
+        assert!(fish.contains("complete -c oc-bridge"));
+    }
+
+    #[test]
+    fn test_completions_per_shell() {
+        for shell in Shell::value_variants() {
+            let script = completions(*shell);
+            assert!(!script.is_empty(), "{}", shell);
+            assert!(script.contains("list-ports"), "{}", shell);
+            // PowerShell and Elvish completions do not list option values
+            if matches!(shell, Shell::Bash | Shell::Zsh | Shell::Fish) {
+                assert!(script.contains("websocket"), "{}", shell);
+            }
+        }
+    }
+}
@@ -6,7 +6,7 @@
 //!
 //! Each transport manages its own execution model internally:
 //! - Serial: blocking threads for low latency
-//! - UDP/TCP/WebSocket: async tokio tasks
+//! - UDP/TCP/WebSocket/named pipe/UNIX socket: async tokio tasks
 //!
 //! # Adding a new transport
 //!
@@ -15,17 +15,31 @@
 //! 3. Add `pub mod my_transport;` here
 //! 4. No other changes needed
 
+#[cfg(windows)]
+pub mod named_pipe;
+pub mod ports;
 pub mod serial;
+pub mod tcp;
 pub mod udp;
+#[cfg(unix)]
+pub mod unix_socket;
 pub mod websocket;
 
-pub use serial::{SerialMatchRequest, SerialTransport};
+#[cfg(windows)]
+pub use named_pipe::NamedPipeTransport;
+pub use serial::{SerialDeviceCandidate, SerialMatchRequest, SerialTransport};
+pub use tcp::TcpTransport;
 pub use udp::UdpTransport;
+#[cfg(unix)]
+pub use unix_socket::UnixTransport;
 pub use websocket::WebSocketTransport;
 
 use bytes::Bytes;
-use std::sync::atomic::AtomicBool;
+use parking_lot::RwLock;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 use crate::error::Result;
@@ -47,6 +61,76 @@ pub struct TransportChannels {
     pub tx: mpsc::Sender<Bytes>,
 }
 
+impl TransportChannels {
+    /// Wait up to `timeout` for the transport to take everything queued on `tx`
+    ///
+    /// Returns `false` if data was still queued at the deadline or the
+    /// transport stopped first.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if self.tx.capacity() == self.tx.max_capacity() {
+                return true;
+            }
+            if self.tx.is_closed() || tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+}
+
+/// Transport channels plus a sender that jumps the write queue
+///
+/// Data sent on `priority_tx` is written before anything still queued on
+/// `channels.tx` (e.g. emergency stop, reset).
+pub struct PriorityTransportChannels {
+    pub channels: TransportChannels,
+    pub priority_tx: mpsc::Sender<Bytes>,
+}
+
+/// Clients seen by the host transports (reported in `status` and `watch`)
+#[derive(Debug, Default)]
+pub struct HostClients {
+    ws_clients: AtomicUsize,
+    /// Messages a WebSocket client was too slow to take
+    ws_dropped: AtomicU64,
+    udp_peer: RwLock<Option<SocketAddr>>,
+}
+
+impl HostClients {
+    /// Open WebSocket connections
+    pub fn ws_clients(&self) -> usize {
+        self.ws_clients.load(Ordering::Relaxed)
+    }
+
+    /// Messages dropped for lagging WebSocket clients (one per client)
+    pub fn ws_dropped(&self) -> u64 {
+        self.ws_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Last UDP sender (the reply target)
+    pub fn udp_peer(&self) -> Option<SocketAddr> {
+        *self.udp_peer.read()
+    }
+
+    fn ws_connected(&self) {
+        self.ws_clients.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn ws_disconnected(&self) {
+        self.ws_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn ws_drop(&self) {
+        self.ws_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn set_udp_peer(&self, addr: SocketAddr) {
+        *self.udp_peer.write() = Some(addr);
+    }
+}
+
 /// Trait for spawnable transports
 ///
 /// A transport abstracts byte-level I/O operations. It handles:
@@ -95,4 +179,21 @@ pub trait Transport: Send + 'static {
     /// Returns an error if the transport cannot be initialized
     /// (e.g., port not found, bind failed).
     fn spawn(self, shutdown: Arc<AtomicBool>) -> Result<TransportChannels>;
+
+    /// Spawn, flushing queued outgoing data on shutdown
+    ///
+    /// On shutdown the transport stops accepting data on `tx`, writes what
+    /// is already queued for up to `drain_timeout`, then closes. Transports
+    /// without a drain of their own just `spawn`.
+    fn spawn_with_drain(
+        self,
+        shutdown: Arc<AtomicBool>,
+        drain_timeout: Duration,
+    ) -> Result<TransportChannels>
+    where
+        Self: Sized,
+    {
+        let _ = drain_timeout;
+        self.spawn(shutdown)
+    }
 }
@@ -0,0 +1,240 @@
+//! Named pipe transport for local IPC on Windows
+//!
+//! Creates a server pipe (default `\\.\pipe\oc-bridge-host`) and accepts one
+//! client at a time. Named pipes avoid the loopback network stack and the
+//! firewall prompts that UDP sockets can trigger on Windows.
+//!
+//! The pipe runs in message mode so each write from either side is delivered
+//! as one message, matching the datagram semantics of the UDP transport.
+//!
+//! Uses async tokio tasks for I/O:
+//! - Server task: creates a pipe instance, waits for a client, then relays
+//! - TX task: forwards outgoing messages to the connected client (if any)
+
+use super::{Transport, TransportChannels};
+use crate::constants::{CHANNEL_CAPACITY, UDP_BUFFER_SIZE};
+use crate::error::{BridgeError, Result};
+use bytes::Bytes;
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::windows::named_pipe::{NamedPipeServer, PipeMode, ServerOptions};
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+/// Named pipe transport for local host applications (Windows only)
+///
+/// # Example
+///
+/// ```ignore
+/// let transport = NamedPipeTransport::new(HOST_PIPE_NAME);
+/// let channels = transport.spawn(shutdown)?;
+///
+/// // Messages written by the pipe client come through channels.rx
+/// // Data sent to channels.tx goes to the connected client
+/// ```
+pub struct NamedPipeTransport {
+    name: String,
+}
+
+impl NamedPipeTransport {
+    /// Create a new named pipe transport for the given pipe path
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl Transport for NamedPipeTransport {
+    fn spawn(self, shutdown: Arc<AtomicBool>) -> Result<TransportChannels> {
+        let (in_tx, in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+        let (out_tx, mut out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+
+        // Create the first instance synchronously so name conflicts surface
+        // as a spawn error instead of a silent background failure.
+        let first = create_pipe(&self.name, true)?;
+
+        // Sender for the currently connected client
+        let client_tx: Arc<RwLock<Option<mpsc::Sender<Bytes>>>> = Arc::new(RwLock::new(None));
+
+        // TX forwarder task
+        let client_tx_fwd = client_tx.clone();
+        let shutdown_tx = shutdown.clone();
+        tokio::spawn(async move {
+            while !shutdown_tx.load(Ordering::Relaxed) {
+                match tokio::time::timeout(Duration::from_millis(100), out_rx.recv()).await {
+                    Ok(Some(data)) => {
+                        let sender = client_tx_fwd.read().clone();
+                        if let Some(tx) = sender {
+                            if tx.send(data).await.is_err() {
+                                *client_tx_fwd.write() = None;
+                            }
+                        }
+                        // No client connected yet: drop the message
+                    }
+                    Ok(None) => break,
+                    Err(_) => {}
+                }
+            }
+        });
+
+        // Server task: one client at a time
+        let name = self.name;
+        tokio::spawn(async move {
+            let mut server = first;
+            while !shutdown.load(Ordering::Relaxed) {
+                match tokio::time::timeout(Duration::from_millis(100), server.connect()).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        debug!("Named pipe connect failed: {}", e);
+                        continue;
+                    }
+                    Err(_) => continue, // Timeout, check shutdown flag
+                }
+
+                info!("Named pipe client connected: {}", name);
+                let (pipe_out_tx, pipe_out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+                *client_tx.write() = Some(pipe_out_tx);
+
+                handle_pipe_client(server, in_tx.clone(), pipe_out_rx, shutdown.clone()).await;
+
+                *client_tx.write() = None;
+                info!("Named pipe client disconnected: {}", name);
+
+                if shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                // Create the next instance for the following client
+                server = match create_pipe(&name, false) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        debug!("{}", e);
+                        break;
+                    }
+                };
+            }
+        });
+
+        Ok(TransportChannels {
+            rx: in_rx,
+            tx: out_tx,
+        })
+    }
+}
+
+/// Create a message-mode pipe instance
+fn create_pipe(name: &str, first: bool) -> Result<NamedPipeServer> {
+    ServerOptions::new()
+        .first_pipe_instance(first)
+        .pipe_mode(PipeMode::Message)
+        .in_buffer_size(UDP_BUFFER_SIZE as u32)
+        .out_buffer_size(UDP_BUFFER_SIZE as u32)
+        .create(name)
+        .map_err(|e| BridgeError::NamedPipeCreate {
+            name: name.to_string(),
+            source: e,
+        })
+}
+
+/// Relay data with a single connected client until it disconnects
+async fn handle_pipe_client(
+    server: NamedPipeServer,
+    in_tx: mpsc::Sender<Bytes>,
+    mut out_rx: mpsc::Receiver<Bytes>,
+    shutdown: Arc<AtomicBool>,
+) {
+    let (mut reader, mut writer) = tokio::io::split(server);
+
+    // RX task: pipe → channel
+    let shutdown_rx = shutdown.clone();
+    let rx_handle = tokio::spawn(async move {
+        let mut buf = [0u8; UDP_BUFFER_SIZE];
+        while !shutdown_rx.load(Ordering::Relaxed) {
+            match tokio::time::timeout(Duration::from_millis(100), reader.read(&mut buf)).await {
+                Ok(Ok(0)) => break, // Client closed the pipe
+                Ok(Ok(len)) => {
+                    if in_tx
+                        .send(Bytes::copy_from_slice(&buf[..len]))
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Ok(Err(_)) => break, // Broken pipe
+                Err(_) => {}         // Timeout
+            }
+        }
+    });
+
+    // TX task: channel → pipe
+    let tx_handle = tokio::spawn(async move {
+        while !shutdown.load(Ordering::Relaxed) {
+            match tokio::time::timeout(Duration::from_millis(100), out_rx.recv()).await {
+                Ok(Some(data)) => {
+                    if writer.write_all(&data).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => {}
+            }
+        }
+    });
+
+    tokio::select! {
+        _ = rx_handle => {}
+        _ = tx_handle => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::windows::named_pipe::ClientOptions;
+
+    #[test]
+    fn test_named_pipe_transport_new() {
+        let transport = NamedPipeTransport::new(r"\\.\pipe\oc-bridge-test");
+        assert_eq!(transport.name, r"\\.\pipe\oc-bridge-test");
+    }
+
+    #[tokio::test]
+    async fn test_named_pipe_roundtrip() {
+        let name = format!(r"\\.\pipe\oc-bridge-test-{}", std::process::id());
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = NamedPipeTransport::new(name.clone())
+            .spawn(shutdown.clone())
+            .unwrap();
+
+        let mut client = ClientOptions::new()
+            .pipe_mode(PipeMode::Message)
+            .open(&name)
+            .unwrap();
+
+        // Client → bridge
+        client.write_all(b"hello").await.unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(2), channels.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&received[..], b"hello");
+
+        // Bridge → client
+        channels
+            .tx
+            .send(Bytes::from_static(b"world"))
+            .await
+            .unwrap();
+        let mut buf = [0u8; 16];
+        let n = tokio::time::timeout(Duration::from_secs(2), client.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&buf[..n], b"world");
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+}
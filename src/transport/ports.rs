@@ -0,0 +1,174 @@
+//! Serial port listing (`oc-bridge list-ports`, TUI port picker)
+//!
+//! Every enumerated port with its USB metadata, marked when it matches the
+//! device preset the same way auto-detection matches it.
+
+use super::SerialDeviceCandidate;
+use crate::config::DeviceConfig;
+use crate::platform;
+use serde::Serialize;
+use serialport::{SerialPortInfo, SerialPortType};
+use std::collections::BTreeMap;
+
+/// One serial port, as listed by `list-ports --json`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PortListing {
+    pub port: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub serial_number: Option<String>,
+    pub matches_preset: bool,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Enumerate the system's serial ports
+pub fn list_ports(preset: Option<&DeviceConfig>) -> Result<Vec<PortListing>, String> {
+    serialport::available_ports()
+        .map(|ports| listings(&ports, preset))
+        .map_err(|e| format!("Failed to enumerate serial ports: {}", e))
+}
+
+/// `ports` with their metadata, marked when they match `preset`
+pub fn listings(ports: &[SerialPortInfo], preset: Option<&DeviceConfig>) -> Vec<PortListing> {
+    ports
+        .iter()
+        .map(|info| {
+            let kind = match info.port_type {
+                SerialPortType::UsbPort(_) => "USB",
+                SerialPortType::PciPort => "PCI",
+                SerialPortType::BluetoothPort => "BT",
+                SerialPortType::Unknown => "Unknown",
+            };
+            let usb = SerialDeviceCandidate::from_port_info(info);
+            PortListing {
+                port: info.port_name.clone(),
+                kind,
+                vid: usb.as_ref().map(|c| c.vid),
+                pid: usb.as_ref().map(|c| c.pid),
+                manufacturer: usb.as_ref().and_then(|c| c.manufacturer.clone()),
+                product: usb.as_ref().and_then(|c| c.product.clone()),
+                serial_number: usb.as_ref().and_then(|c| c.serial_number.clone()),
+                matches_preset: match (&usb, preset) {
+                    (Some(c), Some(p)) => c.matches_device(p),
+                    _ => false,
+                },
+                attributes: platform::serial_port_attributes(&info.port_name)
+                    .into_iter()
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Aligned table rows, header first; `*` marks the ports matching the preset
+///
+/// ```text
+///   PORT          TYPE  VID   PID   MANUFACTURER       PRODUCT
+/// * /dev/ttyACM0  USB   16C0  0489  petitechose.audio  MIDI Studio [hw]
+/// ```
+pub fn table(listings: &[PortListing]) -> Vec<String> {
+    let hex = |id: Option<u16>| id.map_or_else(|| "-".to_string(), |id| format!("{:04X}", id));
+    let text = |s: &Option<String>| s.clone().unwrap_or_else(|| "-".to_string());
+
+    let header = ["PORT", "TYPE", "VID", "PID", "MANUFACTURER", "PRODUCT"].map(String::from);
+    let rows: Vec<(bool, [String; 6])> = std::iter::once((false, header))
+        .chain(listings.iter().map(|p| {
+            (
+                p.matches_preset,
+                [
+                    p.port.clone(),
+                    p.kind.to_string(),
+                    hex(p.vid),
+                    hex(p.pid),
+                    text(&p.manufacturer),
+                    text(&p.product),
+                ],
+            )
+        }))
+        .collect();
+
+    let mut widths = [0; 6];
+    for (_, cells) in &rows {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    rows.iter()
+        .map(|(matches, cells)| {
+            let mut line = if *matches { "* " } else { "  " }.to_string();
+            for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+                if i + 1 == cells.len() {
+                    line.push_str(cell);
+                } else {
+                    line.push_str(&format!("{:<width$}  ", cell, width = width));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{PlatformNameHint, SerialSignals};
+    use serialport::UsbPortInfo;
+
+    fn usb_port(name: &str, pid: u16) -> SerialPortInfo {
+        SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: SerialPortType::UsbPort(UsbPortInfo {
+                vid: 0x16C0,
+                pid,
+                serial_number: Some("17081760".to_string()),
+                manufacturer: Some("petitechose.audio".to_string()),
+                product: Some("MIDI Studio [hw]".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_table_marks_preset_matches() {
+        let preset = DeviceConfig {
+            name: "Teensy".to_string(),
+            vid: 0x16C0,
+            pid_list: vec![0x0489],
+            name_hint: PlatformNameHint::default(),
+            udev_rules: None,
+            udev_rules_filename: None,
+            baud_rate: None,
+            serial_on_connect: SerialSignals::default(),
+            serial_on_disconnect: SerialSignals::default(),
+            post_connect_delay_ms: 0,
+        };
+        let ports = [
+            usb_port("/dev/ttyACM0", 0x0489),
+            usb_port("/dev/ttyACM1", 0x0001),
+            SerialPortInfo {
+                port_name: "/dev/ttyS0".to_string(),
+                port_type: SerialPortType::Unknown,
+            },
+        ];
+
+        let listed = listings(&ports, Some(&preset));
+        assert!(listed[0].matches_preset);
+        assert!(!listed[1].matches_preset);
+        assert_eq!(listed[2].vid, None);
+
+        assert_eq!(
+            table(&listed),
+            [
+                "  PORT          TYPE     VID   PID   MANUFACTURER       PRODUCT",
+                "* /dev/ttyACM0  USB      16C0  0489  petitechose.audio  MIDI Studio [hw]",
+                "  /dev/ttyACM1  USB      16C0  0001  petitechose.audio  MIDI Studio [hw]",
+                "  /dev/ttyS0    Unknown  -     -     -                  -",
+            ]
+        );
+    }
+}
@@ -1,17 +1,21 @@
-//! Serial transport for USB CDC communication
+//! Serial transport for USB CDC and UART devices
 //!
 //! Uses blocking threads for low-latency I/O:
-//! - Reader thread: reads from serial port, sends to channel
-//! - Writer thread: receives from channel, writes to serial port (high priority)
+//! - Reader thread: reads from serial port, sends to channel (through
+//!   io_uring on Linux with the `io_uring` feature, when available)
+//! - Writer thread: receives from channel, writes to serial port (high priority);
+//!   with `spawn_with_priority`, a second queue is always written first
 //!
 //! The transport stops when:
 //! - `shutdown` flag is set
 //! - Serial port disconnects (detected via consecutive read errors)
 //! - Write error occurs
 
-use super::{Transport, TransportChannels};
-use crate::config::DeviceConfig;
-use crate::constants::{CHANNEL_CAPACITY, SERIAL_DISCONNECT_THRESHOLD, UDP_BUFFER_SIZE};
+use super::{PriorityTransportChannels, Transport, TransportChannels};
+use crate::config::{DeviceConfig, SerialSignals};
+use crate::constants::{
+    CHANNEL_CAPACITY, DEFAULT_BAUD_RATE, SERIAL_DISCONNECT_THRESHOLD, UDP_BUFFER_SIZE,
+};
 use crate::error::{BridgeError, Result};
 use crate::platform;
 use bytes::Bytes;
@@ -19,8 +23,13 @@ use serialport::{SerialPortInfo, SerialPortType};
 use std::io::{Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc;
 
+/// Longest wait of one io_uring read before re-checking shutdown/pause
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+const IO_URING_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(10);
+
 /// Serial transport for USB CDC communication
 ///
 /// Optimized for low-latency bidirectional communication:
@@ -32,7 +41,7 @@ use tokio::sync::mpsc;
 /// ```ignore
 /// // Auto-detect device using preset config
 /// let config = config::load_device_preset("teensy")?;
-/// let port = SerialTransport::detect(&config)?;
+/// let port = SerialTransport::detect_with_request(&config, &SerialMatchRequest::default())?;
 /// let transport = SerialTransport::new(&port);
 /// let channels = transport.spawn(shutdown)?;
 ///
@@ -42,6 +51,15 @@ use tokio::sync::mpsc;
 /// ```
 pub struct SerialTransport {
     port_name: String,
+    /// Ignored by USB CDC devices (native USB speed)
+    baud_rate: u32,
+    /// While set, the reader thread stops reading (backpressure)
+    pause_reader: Arc<AtomicBool>,
+    /// Modem lines set after opening, and before releasing the port
+    on_connect: SerialSignals,
+    on_disconnect: SerialSignals,
+    /// How long `on_connect` is held before the lines are flipped back
+    post_connect_delay: Duration,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -64,9 +82,34 @@ impl SerialTransport {
     pub fn new(port_name: impl Into<String>) -> Self {
         Self {
             port_name: port_name.into(),
+            baud_rate: DEFAULT_BAUD_RATE,
+            pause_reader: Arc::new(AtomicBool::new(false)),
+            on_connect: SerialSignals::default(),
+            on_disconnect: SerialSignals::default(),
+            post_connect_delay: Duration::ZERO,
         }
     }
 
+    /// Line speed for UART devices (see `BridgeConfig::serial_baud_rate`)
+    pub fn with_baud_rate(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    /// Drive DTR/RTS as `device` asks (see `DeviceConfig::serial_on_connect`)
+    pub fn with_signals(mut self, device: &DeviceConfig) -> Self {
+        self.on_connect = device.serial_on_connect;
+        self.on_disconnect = device.serial_on_disconnect;
+        self.post_connect_delay = Duration::from_millis(device.post_connect_delay_ms);
+        self
+    }
+
+    /// Share a pause flag with the session (see `BridgeSession::with_backpressure`)
+    pub fn with_pause_reader(mut self, pause_reader: Arc<AtomicBool>) -> Self {
+        self.pause_reader = pause_reader;
+        self
+    }
+
     /// Detect a USB device matching the given configuration
     ///
     /// Searches available USB serial ports for a device matching the VID/PID
@@ -76,15 +119,20 @@ impl SerialTransport {
     ///
     /// - `NoDeviceFound` - No matching device found
     /// - `MultipleDevicesFound` - More than one matching device found
-    pub fn detect(config: &DeviceConfig) -> Result<String> {
-        Self::detect_with_request(config, &SerialMatchRequest::default())
-    }
-
     pub fn detect_with_request(
         config: &DeviceConfig,
         request: &SerialMatchRequest,
     ) -> Result<String> {
         let ports = serialport::available_ports().unwrap_or_default();
+        Self::detect_among(&ports, config, request)
+    }
+
+    /// `detect_with_request` over an already enumerated port list
+    pub fn detect_among(
+        ports: &[SerialPortInfo],
+        config: &DeviceConfig,
+        request: &SerialMatchRequest,
+    ) -> Result<String> {
         let candidates = ports
             .iter()
             .filter_map(candidate_from_port)
@@ -93,22 +141,47 @@ impl SerialTransport {
         select_candidate(&candidates, config, request).map(|candidate| candidate.port_name.clone())
     }
 
-    /// Open a serial port for USB CDC communication
+    /// All listed USB devices matching the preset and request, most recently
+    /// plugged in first
     ///
-    /// Baud rate is ignored for USB CDC devices (native USB speed).
-    /// Configures low-latency settings on Windows.
-    pub fn open(port_name: &str) -> Result<Box<dyn serialport::SerialPort>> {
-        // Baud rate is ignored for USB CDC - uses native USB speed
-        const USB_CDC_BAUD: u32 = 115200;
-
-        let map_err = |e: serialport::Error| BridgeError::SerialOpen {
-            port: port_name.to_string(),
-            source: std::io::Error::other(e.to_string()),
-        };
+    /// Devices whose plug-in time is unknown (see
+    /// `platform::serial_device_plugged_at`) come last, by port name.
+    pub fn detect_all_among(
+        ports: &[SerialPortInfo],
+        config: &DeviceConfig,
+        request: &SerialMatchRequest,
+    ) -> Vec<String> {
+        let matching = ports
+            .iter()
+            .filter_map(candidate_from_port)
+            .filter(|candidate| matches_device_config(candidate, config))
+            .filter(|candidate| matches_request(candidate, request))
+            .map(|candidate| {
+                let plugged_at = platform::serial_device_plugged_at(
+                    &candidate.port_name,
+                    candidate.vid,
+                    candidate.pid,
+                    candidate.serial_number.as_deref(),
+                );
+                (candidate.port_name, plugged_at)
+            })
+            .collect();
+        sort_by_recency(matching)
+    }
 
+    /// Open a serial port at `baud_rate`
+    ///
+    /// USB CDC devices ignore the baud rate (native USB speed).
+    /// Configures low-latency settings on Windows and macOS.
+    #[cfg_attr(all(target_os = "linux", feature = "io_uring"), allow(dead_code))]
+    pub fn open(port_name: &str, baud_rate: u32) -> Result<Box<dyn serialport::SerialPort>> {
         #[cfg(windows)]
         {
-            let port = serialport::new(port_name, USB_CDC_BAUD)
+            let map_err = |e: serialport::Error| BridgeError::SerialOpen {
+                port: port_name.to_string(),
+                source: std::io::Error::other(e.to_string()),
+            };
+            let port = serialport::new(port_name, baud_rate)
                 .timeout(std::time::Duration::from_millis(1))
                 .open_native()
                 .map_err(map_err)?;
@@ -118,12 +191,38 @@ impl SerialTransport {
 
         #[cfg(not(windows))]
         {
-            serialport::new(port_name, USB_CDC_BAUD)
-                .timeout(std::time::Duration::from_millis(1))
-                .open()
-                .map_err(map_err)
+            let port = Self::open_tty(port_name, baud_rate)?;
+            #[cfg(target_os = "macos")]
+            platform::configure_serial_low_latency(&port, baud_rate);
+            Ok(Box::new(port))
         }
     }
+
+    /// Open a serial port as a native TTY (exposes its file descriptor)
+    #[cfg(not(windows))]
+    fn open_tty(port_name: &str, baud_rate: u32) -> Result<serialport::TTYPort> {
+        serialport::new(port_name, baud_rate)
+            .timeout(std::time::Duration::from_millis(1))
+            .open_native()
+            .map_err(|e| BridgeError::SerialOpen {
+                port: port_name.to_string(),
+                source: std::io::Error::other(e.to_string()),
+            })
+    }
+}
+
+/// Set the DTR/RTS lines listed in `signals`
+fn set_signals(
+    port: &mut dyn serialport::SerialPort,
+    signals: SerialSignals,
+) -> serialport::Result<()> {
+    if let Some(level) = signals.dtr {
+        port.write_data_terminal_ready(level)?;
+    }
+    if let Some(level) = signals.rts {
+        port.write_request_to_send(level)?;
+    }
+    Ok(())
 }
 
 fn candidate_from_port(port: &SerialPortInfo) -> Option<SerialDeviceCandidate> {
@@ -140,8 +239,20 @@ fn candidate_from_port(port: &SerialPortInfo) -> Option<SerialDeviceCandidate> {
     }
 }
 
+impl SerialDeviceCandidate {
+    /// Build a candidate from a USB serial port (other port types yield `None`)
+    pub fn from_port_info(port: &SerialPortInfo) -> Option<Self> {
+        candidate_from_port(port)
+    }
+
+    /// Whether the VID/PID match the device preset
+    pub fn matches_device(&self, config: &DeviceConfig) -> bool {
+        self.vid == config.vid && config.pid_list.contains(&self.pid)
+    }
+}
+
 fn matches_device_config(candidate: &SerialDeviceCandidate, config: &DeviceConfig) -> bool {
-    candidate.vid == config.vid && config.pid_list.contains(&candidate.pid)
+    candidate.matches_device(config)
 }
 
 fn matches_request(candidate: &SerialDeviceCandidate, request: &SerialMatchRequest) -> bool {
@@ -169,27 +280,112 @@ fn select_candidate<'a>(
     }
 }
 
-impl Transport for SerialTransport {
-    fn spawn(self, shutdown: Arc<AtomicBool>) -> Result<TransportChannels> {
+/// Port names, newest plug-in time first; unknown times last, by name
+fn sort_by_recency(mut ports: Vec<(String, Option<SystemTime>)>) -> Vec<String> {
+    // `None` orders before `Some`, so comparing b to a puts it last
+    ports.sort_by(|(a_name, a_time), (b_name, b_time)| {
+        b_time.cmp(a_time).then_with(|| a_name.cmp(b_name))
+    });
+    ports.into_iter().map(|(name, _)| name).collect()
+}
+
+/// Serial write queues: high-priority data goes out before normal data
+pub struct PriorityWriteChannel {
+    high_priority: mpsc::Receiver<Bytes>,
+    normal: mpsc::Receiver<Bytes>,
+}
+
+impl PriorityWriteChannel {
+    pub fn new(high_priority: mpsc::Receiver<Bytes>, normal: mpsc::Receiver<Bytes>) -> Self {
+        Self {
+            high_priority,
+            normal,
+        }
+    }
+
+    /// Next data to write, high priority first
+    ///
+    /// Returns `None` once the normal sender is gone and nothing is queued.
+    pub async fn recv(&mut self) -> Option<Bytes> {
+        tokio::select! {
+            biased;
+            // A closed high-priority queue just disables this branch
+            Some(data) = self.high_priority.recv() => Some(data),
+            data = self.normal.recv() => data,
+        }
+    }
+}
+
+impl SerialTransport {
+    /// Spawn with a second write queue that is always drained first
+    pub fn spawn_with_priority(
+        self,
+        shutdown: Arc<AtomicBool>,
+    ) -> Result<PriorityTransportChannels> {
         let (in_tx, in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
-        let (out_tx, mut out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+        let (out_tx, out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+        let (priority_tx, priority_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+
+        // The writer thread waits on both queues through the runtime
+        let runtime = tokio::runtime::Handle::try_current().map_err(|e| BridgeError::Runtime {
+            source: std::io::Error::other(e),
+        })?;
 
         // Open serial port
-        let port_read = Self::open(&self.port_name)?;
-        let port_write = port_read.try_clone().map_err(|e| BridgeError::SerialOpen {
+        #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
+        let mut port_read = Self::open(&self.port_name, self.baud_rate)?;
+
+        // Read through io_uring when the kernel allows it, else plain read()
+        #[cfg(all(target_os = "linux", feature = "io_uring"))]
+        let (mut port_read, mut uring) = {
+            use std::os::fd::AsRawFd;
+
+            let tty = Self::open_tty(&self.port_name, self.baud_rate)?;
+            let uring =
+                platform::linux::IoUringSerialReader::new(tty.as_raw_fd(), IO_URING_READ_TIMEOUT)
+                    .ok();
+            let port: Box<dyn serialport::SerialPort> = Box::new(tty);
+            (port, uring)
+        };
+        let open_err = |e: serialport::Error| BridgeError::SerialOpen {
             port: self.port_name.clone(),
             source: std::io::Error::other(e.to_string()),
-        })?;
+        };
+        set_signals(port_read.as_mut(), self.on_connect).map_err(open_err)?;
+        let port_write = port_read.try_clone().map_err(open_err)?;
 
         // Reader thread (blocking)
         let shutdown_reader = shutdown.clone();
+        let pause_reader = self.pause_reader.clone();
+        let (on_connect, on_disconnect) = (self.on_connect, self.on_disconnect);
+        let post_connect_delay = self.post_connect_delay;
         std::thread::spawn(move || {
             let mut port = port_read;
             let mut buf = [0u8; UDP_BUFFER_SIZE];
             let mut consecutive_errors = 0u32;
 
+            // End of a reset pulse: the device reboots while nothing is read
+            if !post_connect_delay.is_zero() && !on_connect.is_empty() {
+                std::thread::sleep(post_connect_delay);
+                let _ = set_signals(port.as_mut(), on_connect.inverted());
+            }
+
             while !shutdown_reader.load(Ordering::Relaxed) {
-                match port.read(&mut buf) {
+                if pause_reader.load(Ordering::Relaxed) {
+                    // Leave data in the OS buffer until the host catches up
+                    std::thread::sleep(std::time::Duration::from_millis(1));
+                    continue;
+                }
+
+                #[cfg(all(target_os = "linux", feature = "io_uring"))]
+                let result = match uring.as_mut() {
+                    Some(uring) => uring.read(&mut buf),
+                    None => port.read(&mut buf),
+                };
+                #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
+                let result = port.read(&mut buf);
+
+                match result {
                     Ok(n) if n > 0 => {
                         consecutive_errors = 0;
                         // Send to channel (blocking)
@@ -219,6 +415,8 @@ impl Transport for SerialTransport {
                     }
                 }
             }
+            // Before the port is released (disconnect, pause, shutdown)
+            let _ = set_signals(port.as_mut(), on_disconnect);
             // Channel will be closed when in_tx is dropped
         });
 
@@ -227,14 +425,14 @@ impl Transport for SerialTransport {
         std::thread::spawn(move || {
             platform::set_thread_high_priority();
             let mut port = port_write;
+            let mut queue = PriorityWriteChannel::new(priority_rx, out_rx);
 
             loop {
                 if shutdown_writer.load(Ordering::Relaxed) {
                     break;
                 }
 
-                // Use blocking_recv with a short poll to check shutdown
-                match out_rx.blocking_recv() {
+                match runtime.block_on(queue.recv()) {
                     Some(data) => {
                         if port.write_all(&data).is_err() {
                             // Write error - port disconnected
@@ -247,16 +445,26 @@ impl Transport for SerialTransport {
                     }
                 }
             }
-            // Channel will be closed when out_rx is dropped
+            // Channels will be closed when the queue is dropped
         });
 
-        Ok(TransportChannels {
-            rx: in_rx,
-            tx: out_tx,
+        Ok(PriorityTransportChannels {
+            channels: TransportChannels {
+                rx: in_rx,
+                tx: out_tx,
+            },
+            priority_tx,
         })
     }
 }
 
+impl Transport for SerialTransport {
+    fn spawn(self, shutdown: Arc<AtomicBool>) -> Result<TransportChannels> {
+        self.spawn_with_priority(shutdown)
+            .map(|priority| priority.channels)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,6 +476,25 @@ mod tests {
         assert_eq!(transport.port_name, "COM3");
     }
 
+    #[tokio::test]
+    async fn test_priority_write_channel_sends_high_priority_first() {
+        let (high_tx, high_rx) = mpsc::channel(4);
+        let (normal_tx, normal_rx) = mpsc::channel(4);
+        let mut queue = PriorityWriteChannel::new(high_rx, normal_rx);
+
+        normal_tx.send(Bytes::from_static(b"1")).await.unwrap();
+        normal_tx.send(Bytes::from_static(b"2")).await.unwrap();
+        high_tx.send(Bytes::from_static(b"!")).await.unwrap();
+        assert_eq!(queue.recv().await.unwrap(), "!");
+        assert_eq!(queue.recv().await.unwrap(), "1");
+
+        // Closing the high-priority queue leaves the normal one working
+        drop(high_tx);
+        assert_eq!(queue.recv().await.unwrap(), "2");
+        drop(normal_tx);
+        assert_eq!(queue.recv().await, None);
+    }
+
     #[test]
     fn test_serial_transport_from_string() {
         let transport = SerialTransport::new(String::from("/dev/ttyACM0"));
@@ -282,6 +509,10 @@ mod tests {
             name_hint: PlatformNameHint::default(),
             udev_rules: None,
             udev_rules_filename: None,
+            baud_rate: None,
+            serial_on_connect: SerialSignals::default(),
+            serial_on_disconnect: SerialSignals::default(),
+            post_connect_delay_ms: 0,
         }
     }
 
@@ -340,6 +571,42 @@ mod tests {
         assert!(matches!(err, BridgeError::NoDeviceFound));
     }
 
+    #[test]
+    fn test_sort_by_recency_puts_latest_plug_in_first() {
+        let plugged = |secs| Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs));
+        let ports = vec![
+            ("/dev/ttyACM0".to_string(), plugged(100)),
+            ("/dev/ttyACM1".to_string(), plugged(200)),
+        ];
+        assert_eq!(sort_by_recency(ports), ["/dev/ttyACM1", "/dev/ttyACM0"]);
+
+        let ports = vec![
+            ("COM9".to_string(), None),
+            ("COM4".to_string(), None),
+            ("COM6".to_string(), plugged(50)),
+        ];
+        assert_eq!(sort_by_recency(ports), ["COM6", "COM4", "COM9"]);
+    }
+
+    /// Needs a loopback cable wired DTR->DSR and RTS->CTS:
+    /// `OC_BRIDGE_LOOPBACK_PORT=/dev/ttyUSB0 cargo test -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_signals_on_loopback_cable() {
+        let port_name = std::env::var("OC_BRIDGE_LOOPBACK_PORT").expect("OC_BRIDGE_LOOPBACK_PORT");
+        let mut port = SerialTransport::open(&port_name, DEFAULT_BAUD_RATE).unwrap();
+        for level in [true, false] {
+            let signals = SerialSignals {
+                dtr: Some(level),
+                rts: Some(!level),
+            };
+            set_signals(port.as_mut(), signals).unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+            assert_eq!(port.read_data_set_ready().unwrap(), level);
+            assert_eq!(port.read_clear_to_send().unwrap(), !level);
+        }
+    }
+
     #[test]
     fn test_matches_request_rejects_wrong_serial() {
         let request = SerialMatchRequest {
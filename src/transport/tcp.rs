@@ -0,0 +1,352 @@
+//! TCP transport for reliable network links
+//!
+//! For controllers on lossy links (e.g. Wi-Fi) where UDP datagrams may be
+//! lost. TCP is a byte stream, like serial: one `read()` may return part of a
+//! message or several of them. The transport passes bytes through unchanged;
+//! the bridge pairs it with `LengthPrefixCodec` (2-byte big-endian), which
+//! buffers incomplete frames across reads and prefixes outgoing messages
+//! with their length.
+//!
+//! Two modes:
+//! - `TcpTransport::listen`: bind `addr` and serve the first peer that connects
+//! - `TcpTransport::connect`: connect to a peer listening on `addr`
+//!
+//! One connection per spawn: when the peer disconnects (or cannot be
+//! reached), the channels close and the bridge spawns a new transport to
+//! retry, as for an unplugged serial device. In listen mode the bridge binds
+//! once (`bind_listener`) and hands the listener to each transport
+//! (`with_listener`), so the port stays open between connections.
+
+use super::{Transport, TransportChannels};
+use crate::constants::{CHANNEL_CAPACITY, UDP_BUFFER_SIZE};
+use crate::error::{BridgeError, Result};
+use crate::logging::{self, LogEntry};
+use bytes::Bytes;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// How long `connect` mode waits for the peer before giving up
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether the transport accepts or opens the connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TcpMode {
+    /// Bind and wait for a peer (server)
+    Listen,
+    /// Connect to a listening peer (client)
+    Connect,
+}
+
+/// TCP transport carrying a byte stream over one connection
+///
+/// # Example
+///
+/// ```ignore
+/// let transport = TcpTransport::listen("0.0.0.0:8200".parse()?);
+/// let channels = transport.spawn(shutdown)?;
+/// // channels.rx yields raw chunks; frame them with LengthPrefixCodec
+/// ```
+pub struct TcpTransport {
+    addr: SocketAddr,
+    mode: TcpMode,
+    /// Receives connect / disconnect notices
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    /// Already-bound listener to accept on instead of binding `addr`
+    listener: Option<Arc<TcpListener>>,
+}
+
+impl TcpTransport {
+    /// Serve the first peer connecting to `addr`
+    pub fn listen(addr: SocketAddr) -> Self {
+        Self::new(addr, TcpMode::Listen)
+    }
+
+    /// Connect to a peer listening on `addr`
+    pub fn connect(addr: SocketAddr) -> Self {
+        Self::new(addr, TcpMode::Connect)
+    }
+
+    fn new(addr: SocketAddr, mode: TcpMode) -> Self {
+        Self {
+            addr,
+            mode,
+            log_tx: None,
+            listener: None,
+        }
+    }
+
+    /// Log connection events
+    pub fn with_log(mut self, log_tx: Option<mpsc::Sender<LogEntry>>) -> Self {
+        self.log_tx = log_tx;
+        self
+    }
+
+    /// Accept on `listener` (from `bind_listener`) instead of binding `addr`
+    pub fn with_listener(mut self, listener: Arc<TcpListener>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+}
+
+impl Transport for TcpTransport {
+    fn spawn(self, shutdown: Arc<AtomicBool>) -> Result<TransportChannels> {
+        let (in_tx, in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+        let (out_tx, out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+
+        // Bind now so an address in use is reported to the caller
+        let listener = match (self.mode, self.listener) {
+            (TcpMode::Listen, Some(listener)) => Some(listener),
+            (TcpMode::Listen, None) => Some(Arc::new(bind_listener(self.addr)?)),
+            (TcpMode::Connect, _) => None,
+        };
+        let addr = self.addr;
+        let log_tx = self.log_tx;
+
+        tokio::spawn(async move {
+            let stream = match listener {
+                Some(listener) => accept_one(&listener, &shutdown).await,
+                None => match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await
+                {
+                    Ok(Ok(stream)) => Some(stream),
+                    Ok(Err(e)) => {
+                        logging::try_log(
+                            &log_tx,
+                            LogEntry::system(format!("TCP connect to {} failed: {}", addr, e)),
+                            "tcp_connect_failed",
+                        );
+                        None
+                    }
+                    Err(_) => {
+                        logging::try_log(
+                            &log_tx,
+                            LogEntry::system(format!("TCP connect to {} timed out", addr)),
+                            "tcp_connect_failed",
+                        );
+                        None
+                    }
+                },
+            };
+            // Dropping `in_tx` closes `rx`: the bridge sees a disconnect
+            let Some(stream) = stream else {
+                return;
+            };
+
+            let peer = stream
+                .peer_addr()
+                .map_or_else(|_| "?".to_string(), |p| p.to_string());
+            logging::try_log(
+                &log_tx,
+                LogEntry::system(format!("TCP {}: connected to {}", addr, peer)),
+                "tcp_connected",
+            );
+            // Small control messages: do not wait to coalesce them
+            let _ = stream.set_nodelay(true);
+            run_connection(stream, in_tx, out_rx, &shutdown).await;
+            if !shutdown.load(Ordering::Relaxed) {
+                logging::try_log(
+                    &log_tx,
+                    LogEntry::system(format!("TCP {}: {} disconnected", addr, peer)),
+                    "tcp_disconnected",
+                );
+            }
+        });
+
+        Ok(TransportChannels {
+            rx: in_rx,
+            tx: out_tx,
+        })
+    }
+}
+
+/// Bind the listen-mode socket
+pub fn bind_listener(addr: SocketAddr) -> Result<TcpListener> {
+    let map_err = |source| BridgeError::TcpBind { addr, source };
+    let listener = std::net::TcpListener::bind(addr).map_err(map_err)?;
+    listener.set_nonblocking(true).map_err(map_err)?;
+    TcpListener::from_std(listener).map_err(map_err)
+}
+
+/// Wait for the first peer, checking `shutdown` every 100ms
+async fn accept_one(listener: &TcpListener, shutdown: &AtomicBool) -> Option<TcpStream> {
+    while !shutdown.load(Ordering::Relaxed) {
+        if let Ok(Ok((stream, _))) =
+            tokio::time::timeout(Duration::from_millis(100), listener.accept()).await
+        {
+            return Some(stream);
+        }
+    }
+    None
+}
+
+/// Relay between a byte stream and the channels until either side closes
+///
+/// Reads are passed on as they arrive (partial frames included); writes use
+/// `write_all` so a message is never cut short. Also used by the UNIX socket
+/// transport.
+pub(super) async fn run_connection<S>(
+    stream: S,
+    in_tx: mpsc::Sender<Bytes>,
+    mut out_rx: mpsc::Receiver<Bytes>,
+    shutdown: &AtomicBool,
+) where
+    S: AsyncRead + AsyncWrite,
+{
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    let mut buf = vec![0u8; UDP_BUFFER_SIZE];
+
+    while !shutdown.load(Ordering::Relaxed) {
+        tokio::select! {
+            read = reader.read(&mut buf) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if in_tx.send(Bytes::copy_from_slice(&buf[..n])).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            data = out_rx.recv() => match data {
+                Some(data) => {
+                    if writer.write_all(&data).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+            // Periodic wakeup to check the shutdown flag
+            _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{Codec, Frame, LengthPrefixCodec, PrefixSize};
+
+    fn decode_all(codec: &mut LengthPrefixCodec, data: &[u8]) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        codec.decode(data, &mut |frame| {
+            if let Frame::Message { payload, .. } = frame {
+                frames.push(payload.to_vec());
+            }
+        });
+        frames
+    }
+
+    #[tokio::test]
+    async fn test_tcp_connect_relays_partial_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = TcpTransport::connect(addr).spawn(shutdown.clone()).unwrap();
+        let (mut peer, _) = listener.accept().await.unwrap();
+
+        // One frame split across two writes, then a second frame
+        let mut codec = LengthPrefixCodec::new(PrefixSize::U16Be, 1024);
+        let (mut wire, mut second) = (Vec::new(), Vec::new());
+        codec.encode(b"\x02\x01ab", &mut wire);
+        codec.encode(b"\x02\x01cd", &mut second);
+        wire.extend_from_slice(&second);
+        peer.write_all(&wire[..3]).await.unwrap();
+        peer.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        peer.write_all(&wire[3..]).await.unwrap();
+
+        let mut frames = Vec::new();
+        while frames.len() < 2 {
+            let chunk = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            frames.extend(decode_all(&mut codec, &chunk));
+        }
+        assert_eq!(frames, [b"\x02\x01ab".to_vec(), b"\x02\x01cd".to_vec()]);
+
+        // Outgoing data is written in full
+        let mut out = Vec::new();
+        codec.encode(&[7u8; 3000], &mut out);
+        channels.tx.send(Bytes::from(out.clone())).await.unwrap();
+        let mut received = vec![0u8; out.len()];
+        peer.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, out);
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_peer_disconnect_closes_channel() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = TcpTransport::connect(addr).spawn(shutdown).unwrap();
+
+        let (peer, _) = listener.accept().await.unwrap();
+        drop(peer);
+
+        let end = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv()).await;
+        assert_eq!(end.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_listen_serves_first_peer() {
+        // Find a free port, then let the transport bind it
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = TcpTransport::listen(addr).spawn(shutdown.clone()).unwrap();
+
+        // The port is taken until the transport stops
+        assert!(matches!(
+            TcpTransport::listen(addr).spawn(shutdown.clone()),
+            Err(BridgeError::TcpBind { .. })
+        ));
+
+        let mut peer = TcpStream::connect(addr).await.unwrap();
+        peer.write_all(b"hello").await.unwrap();
+        let chunk = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&chunk[..], b"hello");
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_shared_listener_accepts_between_sessions() {
+        let listener = Arc::new(bind_listener("127.0.0.1:0".parse().unwrap()).unwrap());
+        let addr = listener.local_addr().unwrap();
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut first = TcpTransport::listen(addr)
+            .with_listener(listener.clone())
+            .spawn(shutdown.clone())
+            .unwrap();
+        let peer = TcpStream::connect(addr).await.unwrap();
+        drop(peer);
+        let end = tokio::time::timeout(Duration::from_secs(1), first.rx.recv()).await;
+        assert_eq!(end.unwrap(), None);
+
+        // Reconnecting before the next session starts is not refused
+        let mut peer = TcpStream::connect(addr).await.unwrap();
+        peer.write_all(b"again").await.unwrap();
+        let mut second = TcpTransport::listen(addr)
+            .with_listener(listener)
+            .spawn(shutdown.clone())
+            .unwrap();
+        let chunk = tokio::time::timeout(Duration::from_secs(1), second.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&chunk[..], b"again");
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+}
@@ -3,24 +3,50 @@
 //! Operates in "server" mode: listens on a port and tracks the address
 //! of clients that send data. Replies are sent to the last known client.
 //!
+//! Binds to loopback by default. When bound to another interface, datagrams
+//! from senders outside the allowed networks are dropped before they can
+//! become the reply target.
+//!
 //! Uses async tokio tasks for I/O:
 //! - RX task: receives datagrams, tracks client address, sends to channel
-//! - TX task: receives from channel, sends to last known client address
+//! - TX task: receives from channel, sends to last known client address;
+//!   with `spawn_with_drain`, flushes the queue on shutdown
+//!
+//! The RX buffer adapts to the traffic (`AdaptiveBuffer`): sized from the
+//! 95th percentile of recent datagram sizes, and grown at once when a
+//! datagram is truncated. Truncated datagrams are dropped and counted, never
+//! forwarded.
+//!
+//! With `with_payload_warning`, the path MTU to each new client is looked up
+//! and the first datagram too large to cross it unfragmented is logged.
+//!
+//! With `with_keepalive`, the TX task sends a small datagram to the client
+//! after a quiet period, so NAT routers keep the port mapping open.
+//!
+//! With `with_clients`, the last sender is also published in `HostClients`.
 
-use super::{Transport, TransportChannels};
+use super::{HostClients, Transport, TransportChannels};
 use crate::constants::{
     CHANNEL_CAPACITY, MAX_SOCKET_RETRY_ATTEMPTS, RETRY_BASE_DELAY_MS, UDP_BUFFER_SIZE,
 };
 use crate::error::{BridgeError, Result};
+use crate::logging::{self, LogEntry, LogLevel};
+use crate::platform;
 use bytes::Bytes;
+use ipnet::IpNet;
 use parking_lot::RwLock;
-use socket2::{Domain, Protocol, Socket, Type};
-use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use socket2::{Domain, MaybeUninitSlice, Protocol, SockRef, Socket, Type};
+use std::collections::VecDeque;
+use std::io;
+use std::mem::MaybeUninit;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::io::Interest;
 use tokio::net::UdpSocket;
 use tokio::sync::mpsc;
+use tracing::{debug, trace, warn};
 
 /// UDP transport for network communication
 ///
@@ -38,22 +64,330 @@ use tokio::sync::mpsc;
 /// ```
 pub struct UdpTransport {
     port: u16,
+    bind: IpAddr,
+    allowed_remotes: Vec<IpNet>,
+    /// Initial RX buffer size (adapted at runtime)
+    recv_buf_hint: usize,
+    /// Receives RX buffer resize notices
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    /// Warn about datagrams larger than the client path MTU allows
+    payload_warning: bool,
+    /// NAT keepalive interval and payload (zero interval = off)
+    keepalive: (Duration, Bytes),
+    /// Where the last sender is reported
+    clients: Option<Arc<HostClients>>,
 }
 
 impl UdpTransport {
-    /// Create a new UDP transport listening on the specified port
+    /// Create a new UDP transport listening on the specified port (loopback)
     pub fn new(port: u16) -> Self {
-        Self { port }
+        Self {
+            port,
+            bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            allowed_remotes: Vec::new(),
+            recv_buf_hint: UDP_BUFFER_SIZE,
+            log_tx: None,
+            payload_warning: false,
+            keepalive: (Duration::ZERO, Bytes::new()),
+            clients: None,
+        }
+    }
+
+    /// Bind to `ip` instead of loopback
+    pub fn with_bind(mut self, ip: IpAddr) -> Self {
+        self.bind = ip;
+        self
+    }
+
+    /// Accept datagrams from these networks in addition to loopback
+    pub fn with_allowed_remotes(mut self, cidrs: Vec<IpNet>) -> Self {
+        self.allowed_remotes = cidrs;
+        self
+    }
+
+    /// Log RX buffer resizes (as debug entries)
+    pub fn with_log(mut self, log_tx: Option<mpsc::Sender<LogEntry>>) -> Self {
+        self.log_tx = log_tx;
+        self
+    }
+
+    /// Log a warning when a datagram to the client would be IP-fragmented
+    pub fn with_payload_warning(mut self, enabled: bool) -> Self {
+        self.payload_warning = enabled;
+        self
+    }
+
+    /// Send `payload` to the client after `interval` without outgoing data
+    pub fn with_keepalive(mut self, interval: Duration, payload: Bytes) -> Self {
+        self.keepalive = (interval, payload);
+        self
+    }
+
+    /// Report the last sender in `clients`
+    pub fn with_clients(mut self, clients: Arc<HostClients>) -> Self {
+        self.clients = Some(clients);
+        self
+    }
+
+    /// Initial RX buffer size, clamped to `RECV_BUF_MIN..=RECV_BUF_MAX`
+    pub fn set_recv_buf_hint(&mut self, bytes: usize) {
+        self.recv_buf_hint = bytes.clamp(RECV_BUF_MIN, RECV_BUF_MAX);
+    }
+}
+
+// =============================================================================
+// Adaptive RX buffer
+// =============================================================================
+
+/// Smallest RX buffer hint (the adaptive buffer never shrinks below `UDP_BUFFER_SIZE`)
+pub const RECV_BUF_MIN: usize = 512;
+/// Largest adaptive RX buffer (largest UDP payload)
+pub const RECV_BUF_MAX: usize = 65535;
+/// Datagram sizes kept for the percentile
+const RECV_SIZE_WINDOW: usize = 1000;
+/// Minimum time between percentile-based resizes
+const RECV_RESIZE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// RX buffer sized from the 95th percentile of recent datagram sizes
+///
+/// Keeps the last `RECV_SIZE_WINDOW` sizes in arrival order plus a sorted
+/// copy, so the percentile is an index lookup. The buffer gets twice the
+/// percentile as headroom, at least the largest size in the window (rounded
+/// up to a power of two) and never less than `UDP_BUFFER_SIZE`.
+struct AdaptiveBuffer {
+    buf: Vec<u8>,
+    /// Sizes in arrival order (oldest first)
+    recent: VecDeque<usize>,
+    /// Same sizes, sorted
+    sorted: Vec<usize>,
+    last_resize: Instant,
+    /// Datagrams dropped because they did not fit the buffer
+    truncated: u64,
+}
+
+impl AdaptiveBuffer {
+    fn new(size: usize) -> Self {
+        Self {
+            buf: vec![0; size],
+            recent: VecDeque::with_capacity(RECV_SIZE_WINDOW),
+            sorted: Vec::with_capacity(RECV_SIZE_WINDOW),
+            last_resize: Instant::now(),
+            truncated: 0,
+        }
+    }
+
+    fn record(&mut self, len: usize) {
+        if self.recent.len() == RECV_SIZE_WINDOW {
+            if let Some(old) = self.recent.pop_front() {
+                if let Ok(i) = self.sorted.binary_search(&old) {
+                    self.sorted.remove(i);
+                }
+            }
+        }
+        self.recent.push_back(len);
+        let i = self.sorted.partition_point(|&s| s < len);
+        self.sorted.insert(i, len);
+    }
+
+    fn percentile_95(&self) -> Option<usize> {
+        let n = self.sorted.len();
+        (n > 0).then(|| self.sorted[(n * 95).div_ceil(100) - 1])
+    }
+
+    fn target_size(&self) -> usize {
+        let (Some(p95), Some(&max)) = (self.percentile_95(), self.sorted.last()) else {
+            return self.buf.len();
+        };
+        (p95 * 2)
+            .max(max)
+            .next_power_of_two()
+            .clamp(UDP_BUFFER_SIZE, RECV_BUF_MAX)
+    }
+
+    /// Record a received datagram; returns `(old, new)` sizes on a resize
+    fn on_datagram(&mut self, len: usize, now: Instant) -> Option<(usize, usize)> {
+        self.record(len);
+        if now.duration_since(self.last_resize) < RECV_RESIZE_INTERVAL {
+            return None;
+        }
+        self.last_resize = now;
+        self.resize(self.target_size())
+    }
+
+    /// Count a datagram that did not fit and grow to the largest UDP payload
+    ///
+    /// Its real size is unknown, so it is recorded as the largest one: the
+    /// buffer stays grown while it is in the window. Returns `(old, new)`
+    /// sizes on a resize.
+    fn on_truncated(&mut self, now: Instant) -> Option<(usize, usize)> {
+        self.truncated += 1;
+        self.record(RECV_BUF_MAX);
+        self.last_resize = now;
+        self.resize(RECV_BUF_MAX)
+    }
+
+    fn resize(&mut self, new: usize) -> Option<(usize, usize)> {
+        let old = self.buf.len();
+        if new == old {
+            return None;
+        }
+        self.buf = vec![0; new];
+        Some((old, new))
+    }
+}
+
+/// Receive one datagram into `buf`; the flag is set when it was truncated
+///
+/// Uses `recvmsg` so truncation is reported on every platform (`MSG_TRUNC`
+/// on Unix, `WSAEMSGSIZE` on Windows) instead of passing as a full buffer.
+async fn recv_datagram(
+    socket: &UdpSocket,
+    buf: &mut [u8],
+) -> io::Result<(usize, SocketAddr, bool)> {
+    socket
+        .async_io(Interest::READABLE, || {
+            // SAFETY: initialized bytes are valid `MaybeUninit<u8>`, and
+            // `recvmsg` only writes initialized bytes into the buffer
+            let uninit = unsafe { &mut *(&mut *buf as *mut [u8] as *mut [MaybeUninit<u8>]) };
+            let (len, flags, addr) =
+                SockRef::from(socket).recv_from_vectored(&mut [MaybeUninitSlice::new(uninit)])?;
+            let addr = addr
+                .as_socket()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "non-IP sender"))?;
+            Ok((len, addr, flags.is_truncated()))
+        })
+        .await
+}
+
+/// Loopback senders are always accepted; others must match an allowed network
+pub(crate) fn is_allowed(addr: IpAddr, allowed: &[IpNet]) -> bool {
+    let addr = addr.to_canonical();
+    addr.is_loopback() || allowed.iter().any(|net| net.contains(&addr))
+}
+
+// =============================================================================
+// Path MTU check
+// =============================================================================
+
+/// IPv4 + UDP header bytes
+const UDP_IPV4_OVERHEAD: usize = 28;
+/// IPv6 + UDP header bytes
+const UDP_IPV6_OVERHEAD: usize = 48;
+
+/// Flags the first oversized datagram to each client
+struct MtuCheck {
+    enabled: bool,
+    client: Option<SocketAddr>,
+    /// Largest unfragmented payload to `client` (None = unknown)
+    max_payload: Option<usize>,
+    warned: bool,
+}
+
+impl MtuCheck {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            client: None,
+            max_payload: None,
+            warned: false,
+        }
+    }
+
+    /// Warning for a `len`-byte datagram to `client`, once per client
+    fn check(&mut self, client: Option<SocketAddr>, len: usize) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let client = client?;
+        if self.client != Some(client) {
+            let overhead = if client.is_ipv4() {
+                UDP_IPV4_OVERHEAD
+            } else {
+                UDP_IPV6_OVERHEAD
+            };
+            self.client = Some(client);
+            self.max_payload = platform::path_mtu(client).map(|mtu| mtu.saturating_sub(overhead));
+            self.warned = false;
+        }
+
+        let max = self.max_payload?;
+        if len <= max || self.warned {
+            return None;
+        }
+        self.warned = true;
+        Some(format!(
+            "{} byte datagram to {} exceeds the path MTU payload ({} bytes) and will be fragmented",
+            len, client, max
+        ))
+    }
+}
+
+// =============================================================================
+// NAT keepalive
+// =============================================================================
+
+/// Tracks outgoing silence to decide when a keepalive is due
+struct Keepalive {
+    interval: Duration,
+    payload: Bytes,
+    last_sent: Instant,
+}
+
+impl Keepalive {
+    fn new((interval, payload): (Duration, Bytes)) -> Self {
+        Self {
+            interval,
+            payload,
+            last_sent: Instant::now(),
+        }
+    }
+
+    fn on_sent(&mut self, now: Instant) {
+        self.last_sent = now;
+    }
+
+    /// Payload to send now, if enabled and the link was quiet long enough
+    fn due(&mut self, now: Instant) -> Option<Bytes> {
+        if self.interval.is_zero() || now.duration_since(self.last_sent) < self.interval {
+            return None;
+        }
+        self.last_sent = now;
+        Some(self.payload.clone())
+    }
+}
+
+/// Send to the last known client (dropped if none yet)
+async fn send_to_client(socket: &UdpSocket, client: &RwLock<Option<SocketAddr>>, data: &[u8]) {
+    // Copy the address out: the lock must not be held across the await
+    let addr_opt = *client.read();
+    if let Some(addr) = addr_opt {
+        let _ = socket.send_to(data, addr).await;
     }
 }
 
 impl Transport for UdpTransport {
     fn spawn(self, shutdown: Arc<AtomicBool>) -> Result<TransportChannels> {
+        self.spawn_with_drain(shutdown, Duration::ZERO)
+    }
+
+    fn spawn_with_drain(
+        self,
+        shutdown: Arc<AtomicBool>,
+        drain_timeout: Duration,
+    ) -> Result<TransportChannels> {
         let (in_tx, in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
         let (out_tx, mut out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
 
         // Create socket with SO_REUSEADDR for quick rebind
-        let socket = create_reusable_udp_socket(self.port)?;
+        let socket = create_reusable_udp_socket(self.bind, self.port)?;
+        let allowed = self.allowed_remotes;
+        let log_tx = self.log_tx;
+        let port = self.port;
+        let mut buffer = AdaptiveBuffer::new(self.recv_buf_hint);
+        let mut mtu_check = MtuCheck::new(self.payload_warning);
+        let mut keepalive = Keepalive::new(self.keepalive);
+        let log_tx_mtu = log_tx.clone();
+        let clients = self.clients;
 
         // Track client address (last sender)
         let client_addr: Arc<RwLock<Option<SocketAddr>>> = Arc::new(RwLock::new(None));
@@ -63,25 +397,45 @@ impl Transport for UdpTransport {
         let addr_store = client_addr.clone();
         let shutdown_rx = shutdown.clone();
         tokio::spawn(async move {
-            let mut buf = [0u8; UDP_BUFFER_SIZE];
-
             while !shutdown_rx.load(Ordering::Relaxed) {
                 match tokio::time::timeout(
                     Duration::from_millis(100),
-                    socket_rx.recv_from(&mut buf),
+                    recv_datagram(&socket_rx, &mut buffer.buf),
                 )
                 .await
                 {
-                    Ok(Ok((len, addr))) => {
+                    Ok(Ok((_, addr, _))) if !is_allowed(addr.ip(), &allowed) => {
+                        debug!("UDP: dropped datagram from {} (not allowed)", addr);
+                    }
+                    Ok(Ok((_, addr, true))) => {
+                        // Never forward a partial frame: drop it and make room
+                        let resized = buffer.on_truncated(Instant::now());
+                        warn!("UDP {}: dropped truncated datagram from {}", port, addr);
+                        logging::try_log(
+                            &log_tx,
+                            LogEntry::system(format!(
+                                "UDP {}: dropped truncated datagram ({} so far)",
+                                port, buffer.truncated
+                            )),
+                            "udp_truncated_drop",
+                        );
+                        if let Some((old, new)) = resized {
+                            log_resize(&log_tx, port, old, new);
+                        }
+                    }
+                    Ok(Ok((len, addr, false))) => {
                         // Track client address
                         *addr_store.write() = Some(addr);
+                        if let Some(clients) = &clients {
+                            clients.set_udp_peer(addr);
+                        }
 
                         // Send to channel
-                        if in_tx
-                            .send(Bytes::copy_from_slice(&buf[..len]))
-                            .await
-                            .is_err()
-                        {
+                        let data = Bytes::copy_from_slice(&buffer.buf[..len]);
+                        if let Some((old, new)) = buffer.on_datagram(len, Instant::now()) {
+                            log_resize(&log_tx, port, old, new);
+                        }
+                        if in_tx.send(data).await.is_err() {
                             // Channel closed
                             break;
                         }
@@ -104,22 +458,44 @@ impl Transport for UdpTransport {
             while !shutdown_tx.load(Ordering::Relaxed) {
                 match tokio::time::timeout(Duration::from_millis(100), out_rx.recv()).await {
                     Ok(Some(data)) => {
-                        // Read client address (drop lock before await)
-                        let addr_opt = *addr_read.read();
-                        if let Some(addr) = addr_opt {
-                            let _ = socket_tx.send_to(&data, addr).await;
+                        let client = *addr_read.read();
+                        if let Some(warning) = mtu_check.check(client, data.len()) {
+                            logging::try_log(
+                                &log_tx_mtu,
+                                LogEntry::system(format!("UDP {}: {}", port, warning)),
+                                "udp_payload_over_mtu",
+                            );
                         }
-                        // If no client address yet, drop the packet
+                        send_to_client(&socket_tx, &addr_read, &data).await;
+                        keepalive.on_sent(Instant::now());
                     }
                     Ok(None) => {
                         // Channel closed
                         break;
                     }
                     Err(_) => {
-                        // Timeout - check shutdown flag
+                        // Timeout - check shutdown flag and keepalive
+                        let client = *addr_read.read();
+                        let Some(addr) = client else {
+                            continue;
+                        };
+                        if let Some(payload) = keepalive.due(Instant::now()) {
+                            trace!("UDP {}: NAT keepalive to {}", port, addr);
+                            let _ = socket_tx.send_to(&payload, addr).await;
+                        }
                     }
                 }
             }
+
+            // Refuse new data, then flush what was queued before shutdown
+            out_rx.close();
+            let deadline = tokio::time::Instant::now() + drain_timeout;
+            while tokio::time::Instant::now() < deadline {
+                match tokio::time::timeout_at(deadline, out_rx.recv()).await {
+                    Ok(Some(data)) => send_to_client(&socket_tx, &addr_read, &data).await,
+                    Ok(None) | Err(_) => break,
+                }
+            }
         });
 
         Ok(TransportChannels {
@@ -129,17 +505,37 @@ impl Transport for UdpTransport {
     }
 }
 
+/// Log an RX buffer resize (as a debug entry)
+fn log_resize(log_tx: &Option<mpsc::Sender<LogEntry>>, port: u16, old: usize, new: usize) {
+    logging::try_log(
+        log_tx,
+        LogEntry::debug_log(
+            Some(LogLevel::Debug),
+            format!("UDP {}: RX buffer {} -> {} bytes", port, old, new),
+        ),
+        "udp_recv_buf_resize",
+    );
+}
+
 /// Create a UDP socket with SO_REUSEADDR for quick rebind after disconnect
 ///
-/// Retries a few times if the socket is still in use (e.g., from previous run).
-fn create_reusable_udp_socket(port: u16) -> Result<Arc<UdpSocket>> {
-    let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port));
+/// Adopts a socket passed by the service manager (systemd socket activation)
+/// when one is bound to `port`. Otherwise binds a new one, retrying a few
+/// times if the socket is still in use (e.g., from previous run).
+fn create_reusable_udp_socket(ip: IpAddr, port: u16) -> Result<Arc<UdpSocket>> {
+    let addr = SocketAddr::new(ip, port);
     let map_err = |e| BridgeError::UdpBind { port, source: e };
 
+    if let Some(std_socket) = platform::inherited_udp_socket(port) {
+        std_socket.set_nonblocking(true).map_err(map_err)?;
+        let tokio_socket = UdpSocket::from_std(std_socket).map_err(map_err)?;
+        return Ok(Arc::new(tokio_socket));
+    }
+
     // Try up to MAX_SOCKET_RETRY_ATTEMPTS times with increasing delay
     for attempt in 0..MAX_SOCKET_RETRY_ATTEMPTS {
-        let socket =
-            Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).map_err(map_err)?;
+        let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))
+            .map_err(map_err)?;
         socket.set_reuse_address(true).map_err(map_err)?;
         socket.set_nonblocking(true).map_err(map_err)?;
 
@@ -171,5 +567,234 @@ mod tests {
     fn test_udp_transport_new() {
         let transport = UdpTransport::new(9000);
         assert_eq!(transport.port, 9000);
+        assert_eq!(transport.bind, IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn test_adaptive_buffer_follows_p95_and_grows_when_truncated() {
+        let mut buffer = AdaptiveBuffer::new(RECV_BUF_MAX);
+        let start = buffer.last_resize;
+        for _ in 0..RECV_SIZE_WINDOW {
+            assert_eq!(buffer.on_datagram(40, start), None);
+        }
+        assert_eq!(buffer.percentile_95(), Some(40));
+
+        // Percentile resizes wait for the interval; the default size is the floor
+        let later = start + RECV_RESIZE_INTERVAL;
+        assert_eq!(
+            buffer.on_datagram(40, later),
+            Some((RECV_BUF_MAX, UDP_BUFFER_SIZE))
+        );
+
+        // A datagram that exactly fills the buffer is not a truncation
+        assert_eq!(buffer.on_datagram(UDP_BUFFER_SIZE, later), None);
+
+        // A truncated one grows the buffer immediately
+        assert_eq!(
+            buffer.on_truncated(later),
+            Some((UDP_BUFFER_SIZE, RECV_BUF_MAX))
+        );
+        assert_eq!(buffer.truncated, 1);
+        assert_eq!(buffer.recent.len(), RECV_SIZE_WINDOW);
+        assert_eq!(buffer.sorted.len(), RECV_SIZE_WINDOW);
+
+        // ...and stays grown while the truncation is in the window
+        let later = later + RECV_RESIZE_INTERVAL;
+        assert_eq!(buffer.on_datagram(40, later), None);
+    }
+
+    #[test]
+    fn test_adaptive_buffer_keeps_room_for_the_top_five_percent() {
+        let mut buffer = AdaptiveBuffer::new(RECV_BUF_MAX);
+        let mut now = buffer.last_resize;
+        for _ in 0..RECV_SIZE_WINDOW {
+            buffer.on_datagram(40, now);
+        }
+        now += RECV_RESIZE_INTERVAL;
+        assert_eq!(
+            buffer.on_datagram(40, now),
+            Some((RECV_BUF_MAX, UDP_BUFFER_SIZE))
+        );
+
+        // After the shrink, the largest 5% of datagrams still fit
+        for i in 0..RECV_SIZE_WINDOW {
+            let len = if i % 100 < 95 { 40 } else { 3000 };
+            assert!(len <= buffer.buf.len());
+            buffer.on_datagram(len, now);
+        }
+
+        // Larger ones above p95: only the first is lost, then the window max
+        // keeps the buffer big enough for them
+        for i in 0..2 * RECV_SIZE_WINDOW {
+            let len = if i % 100 < 95 { 40 } else { 6000 };
+            if len > buffer.buf.len() {
+                buffer.on_truncated(now);
+            } else {
+                buffer.on_datagram(len, now);
+            }
+            if i == RECV_SIZE_WINDOW {
+                now += RECV_RESIZE_INTERVAL;
+            }
+        }
+        assert_eq!(buffer.truncated, 1);
+        assert_eq!(buffer.percentile_95(), Some(40));
+        now += RECV_RESIZE_INTERVAL;
+        assert_eq!(buffer.on_datagram(40, now), Some((RECV_BUF_MAX, 8192)));
+    }
+
+    #[test]
+    fn test_set_recv_buf_hint_clamps() {
+        let mut transport = UdpTransport::new(9000);
+        transport.set_recv_buf_hint(16);
+        assert_eq!(transport.recv_buf_hint, RECV_BUF_MIN);
+        transport.set_recv_buf_hint(1 << 20);
+        assert_eq!(transport.recv_buf_hint, RECV_BUF_MAX);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_with_drain_flushes_queue_on_shutdown() {
+        let port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = UdpTransport::new(port)
+            .spawn_with_drain(shutdown.clone(), Duration::from_secs(1))
+            .unwrap();
+
+        // Register the client address
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.send_to(b"hello", ("127.0.0.1", port)).await.unwrap();
+        channels.rx.recv().await.unwrap();
+
+        // Queue 10 datagrams without yielding, then shut down
+        shutdown.store(true, Ordering::SeqCst);
+        for i in 0..10u8 {
+            channels.tx.try_send(Bytes::from(vec![i])).unwrap();
+        }
+
+        let mut buf = [0u8; 16];
+        for i in 0..10u8 {
+            let (len, _) = tokio::time::timeout(Duration::from_secs(1), client.recv_from(&mut buf))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(&buf[..len], &[i]);
+        }
+
+        // The transport closes once drained
+        tokio::time::timeout(Duration::from_secs(1), channels.tx.closed())
+            .await
+            .unwrap();
+        assert!(channels.tx.try_send(Bytes::from_static(b"late")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_datagram_is_dropped_not_forwarded() {
+        let port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        // Start below the datagram size
+        let mut transport = UdpTransport::new(port);
+        transport.set_recv_buf_hint(RECV_BUF_MIN);
+        let mut channels = transport.spawn(shutdown.clone()).unwrap();
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let large: Vec<u8> = (0..2048).map(|i| i as u8).collect();
+        client.send_to(&large, ("127.0.0.1", port)).await.unwrap();
+        client.send_to(&large, ("127.0.0.1", port)).await.unwrap();
+
+        // The first one did not fit and is dropped; the second arrives whole
+        let data = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&data[..], &large[..]);
+        assert!(channels.rx.try_recv().is_err());
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_inherited_socket_survives_transport_restart() {
+        let inherited = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let port = inherited.local_addr().unwrap().port();
+        platform::inherit_for_test(inherited);
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        for round in 0..2u8 {
+            let shutdown = Arc::new(AtomicBool::new(false));
+            let mut channels = UdpTransport::new(port).spawn(shutdown.clone()).unwrap();
+            client.send_to(&[round], ("127.0.0.1", port)).await.unwrap();
+            let data = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(&data[..], &[round]);
+
+            // Let the transport close its socket before the next start
+            shutdown.store(true, Ordering::SeqCst);
+            tokio::time::timeout(Duration::from_secs(1), channels.tx.closed())
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_keepalive_due_after_quiet_interval() {
+        let payload = Bytes::from_static(&[0xFF, 0xFF]);
+        let mut keepalive = Keepalive::new((Duration::from_secs(25), payload.clone()));
+        let start = keepalive.last_sent;
+
+        assert_eq!(keepalive.due(start + Duration::from_secs(10)), None);
+        // Outgoing data restarts the quiet period
+        keepalive.on_sent(start + Duration::from_secs(10));
+        assert_eq!(keepalive.due(start + Duration::from_secs(30)), None);
+        assert_eq!(
+            keepalive.due(start + Duration::from_secs(35)),
+            Some(payload)
+        );
+        // Sent: the next one waits a full interval again
+        assert_eq!(keepalive.due(start + Duration::from_secs(40)), None);
+
+        let mut disabled = Keepalive::new((Duration::ZERO, Bytes::new()));
+        assert_eq!(disabled.due(start + Duration::from_secs(3600)), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_mtu_check_warns_once_per_client() {
+        let client: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+        let mut check = MtuCheck::new(true);
+        assert!(check.check(None, 100_000).is_none());
+        assert!(check.check(Some(client), 1000).is_none());
+
+        // Loopback MTU is 64 KiB: only jumbo datagrams are flagged
+        assert!(check.check(Some(client), 100_000).is_some());
+        assert!(check.check(Some(client), 100_000).is_none());
+
+        // A new client is looked up and warned about again
+        let other: SocketAddr = "127.0.0.1:9001".parse().unwrap();
+        assert!(check.check(Some(other), 100_000).is_some());
+
+        assert!(MtuCheck::new(false).check(Some(client), 100_000).is_none());
+    }
+
+    #[test]
+    fn test_is_allowed_loopback_and_cidrs() {
+        let lan: IpNet = "192.168.1.0/24".parse().unwrap();
+
+        assert!(is_allowed("127.0.0.1".parse().unwrap(), &[]));
+        assert!(is_allowed("::1".parse().unwrap(), &[]));
+        assert!(!is_allowed("192.168.1.20".parse().unwrap(), &[]));
+
+        assert!(is_allowed("192.168.1.20".parse().unwrap(), &[lan]));
+        assert!(is_allowed("::ffff:192.168.1.20".parse().unwrap(), &[lan]));
+        assert!(!is_allowed("10.0.0.5".parse().unwrap(), &[lan]));
     }
 }
@@ -0,0 +1,303 @@
+//! UNIX domain socket transport for local controllers (Linux/macOS)
+//!
+//! For a controller running as a process on the same machine (e.g. a
+//! firmware simulator): no TCP/IP stack, no port to allocate. Like TCP, a
+//! stream socket carries bytes, not messages; the bridge pairs it with
+//! `LengthPrefixCodec`.
+//!
+//! Two roles:
+//! - `Role::Server`: bind `path` and serve the first peer that connects.
+//!   A stale socket file left by a previous run is removed before binding,
+//!   and the file is removed again when the connection ends.
+//! - `Role::Client`: connect to a peer listening on `path`
+//!
+//! One connection per spawn, as for `TcpTransport`: when the peer goes
+//! away the channels close and the bridge spawns a new transport. As a
+//! server, the bridge binds once (`bind_listener`) and hands the listener to
+//! each transport (`with_listener`); the socket file then lives as long as
+//! the returned `SocketFile`.
+
+use super::tcp::run_connection;
+use super::{Transport, TransportChannels};
+use crate::constants::CHANNEL_CAPACITY;
+use crate::error::{BridgeError, Result};
+use crate::logging::{self, LogEntry};
+use bytes::Bytes;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc;
+
+/// Whether the transport binds the socket or connects to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Bind `path` and wait for a peer
+    Server,
+    /// Connect to a peer bound to `path`
+    Client,
+}
+
+/// UNIX stream socket transport carrying a byte stream over one connection
+///
+/// # Example
+///
+/// ```ignore
+/// let transport = UnixTransport::new("/tmp/oc-bridge-controller.sock", Role::Server);
+/// let channels = transport.spawn(shutdown)?;
+/// // channels.rx yields raw chunks; frame them with LengthPrefixCodec
+/// ```
+pub struct UnixTransport {
+    path: PathBuf,
+    role: Role,
+    /// Receives connect / disconnect notices
+    log_tx: Option<mpsc::Sender<LogEntry>>,
+    /// Already-bound listener to accept on instead of binding `path`
+    listener: Option<Arc<UnixListener>>,
+}
+
+impl UnixTransport {
+    /// Create a transport for the socket at `path`
+    pub fn new(path: impl Into<PathBuf>, role: Role) -> Self {
+        Self {
+            path: path.into(),
+            role,
+            log_tx: None,
+            listener: None,
+        }
+    }
+
+    /// Log connection events
+    pub fn with_log(mut self, log_tx: Option<mpsc::Sender<LogEntry>>) -> Self {
+        self.log_tx = log_tx;
+        self
+    }
+
+    /// Accept on `listener` (from `bind_listener`) instead of binding `path`
+    pub fn with_listener(mut self, listener: Arc<UnixListener>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+}
+
+impl Transport for UnixTransport {
+    fn spawn(self, shutdown: Arc<AtomicBool>) -> Result<TransportChannels> {
+        let (in_tx, in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+        let (out_tx, out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+
+        // Bind now so a bad path is reported to the caller
+        let server = match (self.role, self.listener) {
+            (Role::Server, Some(listener)) => Some((listener, None)),
+            (Role::Server, None) => {
+                let (listener, socket_file) = bind_listener(&self.path)?;
+                Some((Arc::new(listener), Some(socket_file)))
+            }
+            (Role::Client, _) => None,
+        };
+        let path = self.path;
+        let log_tx = self.log_tx;
+
+        tokio::spawn(async move {
+            // Held until the task ends, whatever the exit path
+            let (listener, _socket_file) = server.unzip();
+            let stream = match listener {
+                Some(listener) => accept_one(&listener, &shutdown).await,
+                None => match UnixStream::connect(&path).await {
+                    Ok(stream) => Some(stream),
+                    Err(e) => {
+                        logging::try_log(
+                            &log_tx,
+                            LogEntry::system(format!(
+                                "UNIX socket connect to {} failed: {}",
+                                path.display(),
+                                e
+                            )),
+                            "unix_connect_failed",
+                        );
+                        None
+                    }
+                },
+            };
+            // Dropping `in_tx` closes `rx`: the bridge sees a disconnect
+            let Some(stream) = stream else {
+                return;
+            };
+
+            logging::try_log(
+                &log_tx,
+                LogEntry::system(format!("UNIX socket {}: connected", path.display())),
+                "unix_connected",
+            );
+            run_connection(stream, in_tx, out_rx, &shutdown).await;
+            if !shutdown.load(Ordering::Relaxed) {
+                logging::try_log(
+                    &log_tx,
+                    LogEntry::system(format!("UNIX socket {}: peer disconnected", path.display())),
+                    "unix_disconnected",
+                );
+            }
+        });
+
+        Ok(TransportChannels {
+            rx: in_rx,
+            tx: out_tx,
+        })
+    }
+}
+
+/// Removes the socket file when dropped
+pub struct SocketFile(PathBuf);
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Bind `path`, replacing a stale socket file (never a regular file)
+pub fn bind_listener(path: &Path) -> Result<(UnixListener, SocketFile)> {
+    let map_err = |source| BridgeError::UnixBind {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if meta.file_type().is_socket() {
+            std::fs::remove_file(path).map_err(map_err)?;
+        }
+    }
+    let listener = UnixListener::bind(path).map_err(map_err)?;
+    Ok((listener, SocketFile(path.to_path_buf())))
+}
+
+/// Wait for the first peer, checking `shutdown` every 100ms
+async fn accept_one(listener: &UnixListener, shutdown: &AtomicBool) -> Option<UnixStream> {
+    while !shutdown.load(Ordering::Relaxed) {
+        if let Ok(Ok((stream, _))) =
+            tokio::time::timeout(Duration::from_millis(100), listener.accept()).await
+        {
+            return Some(stream);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn socket_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("oc-bridge-{}-{}.sock", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_unix_server_replaces_stale_socket_and_cleans_up() {
+        let path = socket_path("server");
+        // Stale socket file from a previous run
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = UnixTransport::new(&path, Role::Server)
+            .spawn(shutdown.clone())
+            .unwrap();
+
+        let mut peer = UnixStream::connect(&path).await.unwrap();
+        peer.write_all(b"hello").await.unwrap();
+        let chunk = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&chunk[..], b"hello");
+
+        channels
+            .tx
+            .send(Bytes::from_static(b"world"))
+            .await
+            .unwrap();
+        let mut received = [0u8; 5];
+        peer.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"world");
+        assert!(path.exists());
+
+        // Peer leaves: the channel closes and the socket file is removed
+        drop(peer);
+        let end = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv()).await;
+        assert_eq!(end.unwrap(), None);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_unix_server_keeps_regular_file() {
+        let path = socket_path("regular");
+        std::fs::write(&path, b"not a socket").unwrap();
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let result = UnixTransport::new(&path, Role::Server).spawn(shutdown);
+        assert!(matches!(result, Err(BridgeError::UnixBind { .. })));
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unix_client_connects() {
+        let path = socket_path("client");
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = UnixTransport::new(&path, Role::Client)
+            .spawn(shutdown.clone())
+            .unwrap();
+        let (mut peer, _) = listener.accept().await.unwrap();
+
+        peer.write_all(b"ping").await.unwrap();
+        let chunk = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&chunk[..], b"ping");
+
+        shutdown.store(true, Ordering::SeqCst);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unix_shared_listener_keeps_socket_between_sessions() {
+        let path = socket_path("shared");
+        let _ = std::fs::remove_file(&path);
+        let (listener, socket_file) = bind_listener(&path).unwrap();
+        let listener = Arc::new(listener);
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut first = UnixTransport::new(&path, Role::Server)
+            .with_listener(listener.clone())
+            .spawn(shutdown.clone())
+            .unwrap();
+        drop(UnixStream::connect(&path).await.unwrap());
+        let end = tokio::time::timeout(Duration::from_secs(1), first.rx.recv()).await;
+        assert_eq!(end.unwrap(), None);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(path.exists());
+
+        // A peer reconnecting before the next session is not refused
+        let mut peer = UnixStream::connect(&path).await.unwrap();
+        peer.write_all(b"again").await.unwrap();
+        let mut second = UnixTransport::new(&path, Role::Server)
+            .with_listener(listener)
+            .spawn(shutdown.clone())
+            .unwrap();
+        let chunk = tokio::time::timeout(Duration::from_secs(1), second.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&chunk[..], b"again");
+
+        shutdown.store(true, Ordering::SeqCst);
+        drop(socket_file);
+        assert!(!path.exists());
+    }
+}
@@ -8,26 +8,54 @@
 //! ```text
 //! Browser (WASM) ──WebSocket:810x──► oc-bridge ──UDP:900x──► Host (e.g., Bitwig)
 //! ```
+//!
+//! Subprotocols: a client may request `oc-bridge-v1` via
+//! `Sec-WebSocket-Protocol`. Clients that request no subprotocol are
+//! accepted for backward compatibility; unknown subprotocols get HTTP 400.
+//!
+//! Several clients may be connected at once (e.g. two browser tabs): each
+//! outgoing message is sent to all of them, and messages from any client are
+//! relayed. `with_max_clients` caps the count; extra clients are closed with
+//! code 1013 (try again later) right after the handshake.
+//!
+//! Open connections are counted in `HostClients` (see `with_clients`). A
+//! client whose queue is full loses the message rather than holding up the
+//! others; the drop is counted there too and logged (at most every 10 s).
+//!
+//! TLS: with `with_tls` (and the `tls` feature), connections are wrapped in
+//! TLS before the handshake and only `wss://` clients are served. A client
+//! gets 10 s to complete the TLS handshake.
+//!
+//! With `with_listener`, the transport serves on a listener that is already
+//! bound (`bind_listener`), so a replacement transport can take over the port
+//! while the previous one is still running.
 
-use super::{Transport, TransportChannels};
+use super::{HostClients, Transport, TransportChannels};
+use crate::config::TlsConfig;
 use crate::constants::CHANNEL_CAPACITY;
 use crate::error::{BridgeError, Result};
 use bytes::Bytes;
 use futures_util::{SinkExt, StreamExt};
-use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::mpsc;
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{self, HeaderValue, StatusCode};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{accept_hdr_async, WebSocketStream};
 use tracing::{debug, error, info, warn};
 
 /// WebSocket transport for browser clients
 ///
 /// Listens on a specified port and accepts WebSocket connections.
-/// Currently supports a single client at a time (last connection wins).
+/// Outgoing data is broadcast to every connected client.
 ///
 /// # Example
 ///
@@ -36,16 +64,187 @@ use tracing::{debug, error, info, warn};
 /// let channels = transport.spawn(shutdown)?;
 ///
 /// // Data from WebSocket clients comes through channels.rx
-/// // Data sent to channels.tx goes to the connected client
+/// // Data sent to channels.tx goes to all connected clients
 /// ```
 pub struct WebSocketTransport {
     port: u16,
+    subprotocol: String,
+    clients: Arc<HostClients>,
+    /// Most clients connected at once (0 = no limit)
+    max_clients: usize,
+    /// Serve `wss://` with this certificate
+    tls: Option<TlsConfig>,
+    /// Already-bound listener to serve on instead of binding `port`
+    listener: Option<Arc<TcpListener>>,
 }
 
+/// Minimum time between two warnings about lagging clients
+const LAGGING_WARN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Identifies one accepted connection (assigned in accept order)
+type ConnectionId = u64;
+
+/// Outgoing queue of each connected client
+type ClientSenders = Arc<Mutex<HashMap<ConnectionId, mpsc::Sender<Bytes>>>>;
+
+/// Default (and currently only) protocol version
+pub const SUBPROTOCOL_V1: &str = "oc-bridge-v1";
+
 impl WebSocketTransport {
     /// Create a new WebSocket transport listening on the specified port
     pub fn new(port: u16) -> Self {
-        Self { port }
+        Self {
+            port,
+            subprotocol: SUBPROTOCOL_V1.to_string(),
+            clients: Arc::default(),
+            max_clients: 0,
+            tls: None,
+            listener: None,
+        }
+    }
+
+    /// Override the subprotocol accepted in the handshake (`None` = default)
+    pub fn with_subprotocol(mut self, subprotocol: Option<String>) -> Self {
+        if let Some(name) = subprotocol {
+            self.subprotocol = name;
+        }
+        self
+    }
+
+    /// Count open connections in `clients`
+    pub fn with_clients(mut self, clients: Arc<HostClients>) -> Self {
+        self.clients = clients;
+        self
+    }
+
+    /// Close connections beyond `max` clients (0 = no limit)
+    pub fn with_max_clients(mut self, max: usize) -> Self {
+        self.max_clients = max;
+        self
+    }
+
+    /// Serve `wss://` with the given certificate (`None` = plain `ws://`)
+    pub fn with_tls(mut self, tls: Option<TlsConfig>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Serve on `listener` (from `bind_listener`) instead of binding the port
+    pub fn with_listener(mut self, listener: Arc<TcpListener>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+}
+
+// =============================================================================
+// TLS
+// =============================================================================
+
+#[cfg(feature = "tls")]
+type TlsAcceptor = tokio_rustls::TlsAcceptor;
+
+/// Time a client has to complete the TLS handshake
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Without the `tls` feature no acceptor can be built
+#[cfg(not(feature = "tls"))]
+#[derive(Clone)]
+enum TlsAcceptor {}
+
+/// Load the certificate chain and private key
+#[cfg(feature = "tls")]
+fn tls_acceptor(config: &TlsConfig) -> Result<TlsAcceptor> {
+    use tokio_rustls::rustls::pki_types::pem::PemObject;
+    use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+    use tokio_rustls::rustls::{crypto, ServerConfig};
+
+    let invalid = |path: &std::path::Path, reason: String| BridgeError::TlsConfig {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let certs = CertificateDer::pem_file_iter(&config.cert_pem)
+        .and_then(|certs| certs.collect::<std::result::Result<Vec<_>, _>>())
+        .map_err(|e| invalid(&config.cert_pem, e.to_string()))?;
+    if certs.is_empty() {
+        return Err(invalid(&config.cert_pem, "no certificate found".into()));
+    }
+    let key = PrivateKeyDer::from_pem_file(&config.key_pem)
+        .map_err(|e| invalid(&config.key_pem, e.to_string()))?;
+
+    let server_config =
+        ServerConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| invalid(&config.cert_pem, e.to_string()))?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| invalid(&config.key_pem, e.to_string()))?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+#[cfg(not(feature = "tls"))]
+fn tls_acceptor(config: &TlsConfig) -> Result<TlsAcceptor> {
+    Err(BridgeError::TlsConfig {
+        path: config.cert_pem.clone(),
+        reason: "oc-bridge was built without the `tls` feature".into(),
+    })
+}
+
+/// TLS handshake with a new connection, given up after `timeout`
+#[cfg(feature = "tls")]
+async fn tls_accept(
+    acceptor: &TlsAcceptor,
+    stream: TcpStream,
+    timeout: Duration,
+) -> Result<tokio_rustls::server::TlsStream<TcpStream>> {
+    let accepted = tokio::time::timeout(timeout, acceptor.accept(stream))
+        .await
+        .unwrap_or_else(|_| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "TLS handshake timed out",
+            ))
+        });
+    accepted.map_err(|e| BridgeError::WebSocketAccept {
+        source: Box::new(e.into()),
+    })
+}
+
+#[cfg(not(feature = "tls"))]
+async fn tls_accept(
+    acceptor: &TlsAcceptor,
+    _stream: TcpStream,
+    _timeout: Duration,
+) -> Result<TcpStream> {
+    match *acceptor {}
+}
+
+/// Outcome of subprotocol negotiation for one handshake
+#[derive(Debug, PartialEq, Eq)]
+enum Negotiation {
+    /// Client requested nothing: accept without a subprotocol
+    Legacy,
+    /// Client offered the supported subprotocol
+    Accept,
+    /// Client only offered unknown subprotocols
+    Reject,
+}
+
+/// Match the client's comma-separated `Sec-WebSocket-Protocol` offer
+fn negotiate(requested: Option<&str>, supported: &str) -> Negotiation {
+    let Some(requested) = requested else {
+        return Negotiation::Legacy;
+    };
+    let offers: Vec<&str> = requested
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    if offers.is_empty() {
+        Negotiation::Legacy
+    } else if offers.contains(&supported) {
+        Negotiation::Accept
+    } else {
+        Negotiation::Reject
     }
 }
 
@@ -54,11 +253,33 @@ impl Transport for WebSocketTransport {
         let (in_tx, in_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
         let (out_tx, out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
 
-        let port = self.port;
+        // Check the certificate before binding: a bad one fails the spawn
+        let tls = self.tls.as_ref().map(tls_acceptor).transpose()?;
+
+        // Bind synchronously so a port in use is reported to the caller
+        // (used by host transport failover).
+        let listener = match self.listener {
+            Some(listener) => listener,
+            None => Arc::new(bind_listener(self.port)?),
+        };
+        let subprotocol: Arc<str> = self.subprotocol.into();
+        let clients = self.clients;
+        let max_clients = self.max_clients;
 
         // Spawn the WebSocket server task
         tokio::spawn(async move {
-            if let Err(e) = run_websocket_server(port, in_tx, out_rx, shutdown).await {
+            if let Err(e) = run_websocket_server(
+                listener,
+                subprotocol,
+                clients,
+                max_clients,
+                tls,
+                in_tx,
+                out_rx,
+                shutdown,
+            )
+            .await
+            {
                 error!("WebSocket server error: {}", e);
             }
         });
@@ -70,39 +291,66 @@ impl Transport for WebSocketTransport {
     }
 }
 
+/// Bind the WebSocket listener on all interfaces
+pub fn bind_listener(port: u16) -> Result<TcpListener> {
+    let map_err = |e| BridgeError::WebSocketBind { port, source: e };
+    let std_listener = std::net::TcpListener::bind(("0.0.0.0", port)).map_err(map_err)?;
+    std_listener.set_nonblocking(true).map_err(map_err)?;
+    TcpListener::from_std(std_listener).map_err(map_err)
+}
+
 /// Run the WebSocket server
+#[allow(clippy::too_many_arguments)]
 async fn run_websocket_server(
-    port: u16,
+    listener: Arc<TcpListener>,
+    subprotocol: Arc<str>,
+    clients: Arc<HostClients>,
+    max_clients: usize,
+    tls: Option<TlsAcceptor>,
     in_tx: mpsc::Sender<Bytes>,
     out_rx: mpsc::Receiver<Bytes>,
     shutdown: Arc<AtomicBool>,
 ) -> Result<()> {
-    let addr = format!("0.0.0.0:{}", port);
-    let listener = TcpListener::bind(&addr)
-        .await
-        .map_err(|e| BridgeError::WebSocketBind { port, source: e })?;
-
-    info!("WebSocket server listening on ws://{}", addr);
+    if let Ok(addr) = listener.local_addr() {
+        let scheme = if tls.is_some() { "wss" } else { "ws" };
+        info!("WebSocket server listening on {}://{}", scheme, addr);
+    }
 
-    // Shared sender for the currently connected client
-    let client_tx: Arc<RwLock<Option<mpsc::Sender<Bytes>>>> = Arc::new(RwLock::new(None));
+    let senders: ClientSenders = Arc::default();
 
-    // TX forwarder task: forwards outgoing messages to the connected client
-    let client_tx_clone = client_tx.clone();
+    // TX forwarder task: broadcasts outgoing messages to every client
+    let senders_tx = senders.clone();
     let shutdown_clone = shutdown.clone();
+    let clients_tx = clients.clone();
     let mut out_rx = out_rx;
     tokio::spawn(async move {
+        // Drops since the last warning, and when it was logged
+        let mut dropped: u64 = 0;
+        let mut warned_at: Option<Instant> = None;
         while !shutdown_clone.load(Ordering::Relaxed) {
             match tokio::time::timeout(Duration::from_millis(100), out_rx.recv()).await {
                 Ok(Some(data)) => {
-                    // Get current client sender
-                    let sender = client_tx_clone.read().clone();
-                    if let Some(tx) = sender {
-                        if tx.send(data).await.is_err() {
-                            // Client disconnected, clear sender
-                            *client_tx_clone.write() = None;
-                        }
-                    }
+                    // A full client queue loses this message; a closed one is pruned
+                    senders_tx
+                        .lock()
+                        .await
+                        .retain(|id, tx| match tx.try_send(data.clone()) {
+                            Ok(()) => true,
+                            Err(mpsc::error::TrySendError::Full(_)) => {
+                                clients_tx.ws_drop();
+                                dropped += 1;
+                                if warned_at.is_none_or(|t| t.elapsed() >= LAGGING_WARN_INTERVAL) {
+                                    warn!(
+                                        "WebSocket client #{} lagging: {} message(s) dropped",
+                                        id, dropped
+                                    );
+                                    dropped = 0;
+                                    warned_at = Some(Instant::now());
+                                }
+                                true
+                            }
+                            Err(mpsc::error::TrySendError::Closed(_)) => false,
+                        });
                 }
                 Ok(None) => break, // Channel closed
                 Err(_) => {}       // Timeout, continue
@@ -111,35 +359,62 @@ async fn run_websocket_server(
     });
 
     // Accept connections
+    let mut next_id: ConnectionId = 0;
     while !shutdown.load(Ordering::Relaxed) {
         match tokio::time::timeout(Duration::from_millis(100), listener.accept()).await {
             Ok(Ok((stream, addr))) => {
-                info!("WebSocket client connected: {}", addr);
+                let id = next_id;
+                next_id += 1;
 
                 // Create channel for this client's outgoing messages
                 let (ws_out_tx, ws_out_rx) = mpsc::channel::<Bytes>(CHANNEL_CAPACITY);
+                let admitted = {
+                    let mut senders = senders.lock().await;
+                    let admitted = max_clients == 0 || senders.len() < max_clients;
+                    if admitted {
+                        senders.insert(id, ws_out_tx);
+                    }
+                    admitted
+                };
+
+                let subprotocol = subprotocol.clone();
+                let tls = tls.clone();
+
+                if !admitted {
+                    warn!(
+                        "WebSocket client {} rejected: {} clients connected",
+                        addr, max_clients
+                    );
+                    tokio::spawn(async move {
+                        if let Err(e) =
+                            serve_client(stream, addr, &subprotocol, tls.as_ref(), None).await
+                        {
+                            debug!("WebSocket client {} error: {}", addr, e);
+                        }
+                    });
+                    continue;
+                }
 
-                // Update the shared client sender
-                *client_tx.write() = Some(ws_out_tx);
+                info!("WebSocket client #{} connected: {}", id, addr);
+                let senders = senders.clone();
+                let clients = clients.clone();
+                clients.ws_connected();
 
-                // Spawn handler for this client
-                let in_tx = in_tx.clone();
-                let shutdown = shutdown.clone();
-                let client_tx_ref = client_tx.clone();
+                let relay = Relay {
+                    in_tx: in_tx.clone(),
+                    out_rx: ws_out_rx,
+                    shutdown: shutdown.clone(),
+                };
 
                 tokio::spawn(async move {
                     if let Err(e) =
-                        handle_websocket_client(stream, addr, in_tx, ws_out_rx, shutdown).await
+                        serve_client(stream, addr, &subprotocol, tls.as_ref(), Some(relay)).await
                     {
                         debug!("WebSocket client {} error: {}", addr, e);
                     }
-                    info!("WebSocket client disconnected: {}", addr);
-
-                    // Clear client sender if this was the active client
-                    let mut guard = client_tx_ref.write();
-                    // Note: We can't easily check if this is "our" sender, so we just clear it
-                    // A more robust implementation would use client IDs
-                    *guard = None;
+                    info!("WebSocket client #{} disconnected: {}", id, addr);
+                    clients.ws_disconnected();
+                    senders.lock().await.remove(&id);
                 });
             }
             Ok(Err(e)) => {
@@ -152,19 +427,132 @@ async fn run_websocket_server(
     Ok(())
 }
 
-/// Handle a single WebSocket client connection
-async fn handle_websocket_client(
-    stream: TcpStream,
-    _addr: SocketAddr,
+/// Channels of an admitted client
+struct Relay {
     in_tx: mpsc::Sender<Bytes>,
-    mut out_rx: mpsc::Receiver<Bytes>,
+    out_rx: mpsc::Receiver<Bytes>,
     shutdown: Arc<AtomicBool>,
+}
+
+/// TLS (if enabled) and WebSocket handshakes, then relay or reject
+///
+/// `relay` is `None` for a client over the `max_clients` limit.
+async fn serve_client(
+    stream: TcpStream,
+    addr: SocketAddr,
+    subprotocol: &str,
+    tls: Option<&TlsAcceptor>,
+    relay: Option<Relay>,
 ) -> Result<()> {
-    let ws_stream = accept_async(stream)
+    match tls {
+        Some(acceptor) => {
+            let stream = tls_accept(acceptor, stream, TLS_HANDSHAKE_TIMEOUT).await?;
+            serve_websocket(stream, addr, subprotocol, relay).await
+        }
+        None => serve_websocket(stream, addr, subprotocol, relay).await,
+    }
+}
+
+async fn serve_websocket<S>(
+    stream: S,
+    addr: SocketAddr,
+    subprotocol: &str,
+    relay: Option<Relay>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    match relay {
+        Some(relay) => {
+            handle_websocket_client(
+                stream,
+                addr,
+                subprotocol,
+                relay.in_tx,
+                relay.out_rx,
+                relay.shutdown,
+            )
+            .await
+        }
+        None => reject_websocket_client(stream, addr, subprotocol).await,
+    }
+}
+
+/// Complete the handshake, then close with 1013 (too many clients)
+async fn reject_websocket_client<S>(stream: S, addr: SocketAddr, subprotocol: &str) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut ws_stream = accept_websocket(stream, addr, subprotocol).await?;
+    let frame = CloseFrame {
+        code: CloseCode::Again,
+        reason: "too many clients".into(),
+    };
+    let _ = ws_stream.close(Some(frame)).await;
+    Ok(())
+}
+
+/// WebSocket handshake, negotiating the subprotocol
+async fn accept_websocket<S>(
+    stream: S,
+    addr: SocketAddr,
+    subprotocol: &str,
+) -> Result<WebSocketStream<S>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // Signature (and error size) is fixed by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let check_subprotocol = |req: &Request, mut resp: Response| {
+        let requested = req
+            .headers()
+            .get(http::header::SEC_WEBSOCKET_PROTOCOL)
+            .and_then(|v| v.to_str().ok());
+        match negotiate(requested, subprotocol) {
+            Negotiation::Legacy => Ok(resp),
+            Negotiation::Accept => {
+                if let Ok(value) = HeaderValue::from_str(subprotocol) {
+                    resp.headers_mut()
+                        .insert(http::header::SEC_WEBSOCKET_PROTOCOL, value);
+                }
+                Ok(resp)
+            }
+            Negotiation::Reject => {
+                warn!(
+                    "WebSocket client {} requested unsupported subprotocol {:?}",
+                    addr,
+                    requested.unwrap_or_default()
+                );
+                let mut err = ErrorResponse::new(Some(format!(
+                    "unsupported subprotocol (expected {})",
+                    subprotocol
+                )));
+                *err.status_mut() = StatusCode::BAD_REQUEST;
+                Err(err)
+            }
+        }
+    };
+
+    accept_hdr_async(stream, check_subprotocol)
         .await
         .map_err(|e| BridgeError::WebSocketAccept {
             source: Box::new(e),
-        })?;
+        })
+}
+
+/// Handle a single WebSocket client connection
+async fn handle_websocket_client<S>(
+    stream: S,
+    addr: SocketAddr,
+    subprotocol: &str,
+    in_tx: mpsc::Sender<Bytes>,
+    mut out_rx: mpsc::Receiver<Bytes>,
+    shutdown: Arc<AtomicBool>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let ws_stream = accept_websocket(stream, addr, subprotocol).await?;
 
     let (mut ws_sink, mut ws_stream) = ws_stream.split();
 
@@ -229,4 +617,247 @@ mod tests {
         let transport = WebSocketTransport::new(8100);
         assert_eq!(transport.port, 8100);
     }
+
+    #[test]
+    fn test_negotiate_subprotocol() {
+        assert_eq!(negotiate(None, SUBPROTOCOL_V1), Negotiation::Legacy);
+        assert_eq!(negotiate(Some(" "), SUBPROTOCOL_V1), Negotiation::Legacy);
+        assert_eq!(
+            negotiate(Some("oc-bridge-v2, oc-bridge-v1"), SUBPROTOCOL_V1),
+            Negotiation::Accept
+        );
+        assert_eq!(
+            negotiate(Some("oc-bridge-v2"), SUBPROTOCOL_V1),
+            Negotiation::Reject
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handshake_subprotocol() {
+        use tokio_tungstenite::connect_async;
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+        let port = {
+            let probe = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+            probe.local_addr().unwrap().port()
+        };
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let _channels = WebSocketTransport::new(port)
+            .spawn(shutdown.clone())
+            .unwrap();
+        let url = format!("ws://127.0.0.1:{}", port);
+
+        let request = |protocol: Option<&str>| {
+            let mut req = url.as_str().into_client_request().unwrap();
+            if let Some(p) = protocol {
+                req.headers_mut().insert(
+                    http::header::SEC_WEBSOCKET_PROTOCOL,
+                    HeaderValue::from_str(p).unwrap(),
+                );
+            }
+            req
+        };
+
+        let (_ws, resp) = connect_async(request(Some(SUBPROTOCOL_V1))).await.unwrap();
+        assert_eq!(
+            resp.headers().get(http::header::SEC_WEBSOCKET_PROTOCOL),
+            Some(&HeaderValue::from_static(SUBPROTOCOL_V1))
+        );
+
+        let (_ws, resp) = connect_async(request(None)).await.unwrap();
+        assert!(resp
+            .headers()
+            .get(http::header::SEC_WEBSOCKET_PROTOCOL)
+            .is_none());
+
+        match connect_async(request(Some("oc-bridge-v9"))).await {
+            Err(tokio_tungstenite::tungstenite::Error::Http(resp)) => {
+                assert_eq!(resp.status(), StatusCode::BAD_REQUEST)
+            }
+            other => panic!("expected HTTP 400, got {:?}", other.map(|_| ())),
+        }
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    fn free_port() -> u16 {
+        let probe = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        probe.local_addr().unwrap().port()
+    }
+
+    /// Self-signed `localhost` / 127.0.0.1 certificate and its key
+    #[cfg(feature = "tls")]
+    fn test_tls() -> TlsConfig {
+        let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        TlsConfig {
+            cert_pem: fixtures.join("tls-cert.pem"),
+            key_pem: fixtures.join("tls-key.pem"),
+        }
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_wss_handshake_and_relay() {
+        use tokio_rustls::rustls::pki_types::pem::PemObject;
+        use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+        use tokio_rustls::rustls::{crypto, ClientConfig, RootCertStore};
+
+        let tls = test_tls();
+        let port = free_port();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = WebSocketTransport::new(port)
+            .with_tls(Some(tls.clone()))
+            .spawn(shutdown.clone())
+            .unwrap();
+
+        let mut roots = RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(&tls.cert_pem).unwrap() {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let client_config =
+            ClientConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(client_config));
+        let tcp = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let stream = connector
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap();
+        let url = format!("wss://localhost:{}", port);
+        let (mut ws, _) = tokio_tungstenite::client_async(url.as_str(), stream)
+            .await
+            .unwrap();
+
+        ws.send(Message::Binary(Bytes::from_static(b"secure")))
+            .await
+            .unwrap();
+        let data = tokio::time::timeout(Duration::from_secs(2), channels.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&data[..], b"secure");
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_tls_accept_times_out_on_silent_client() {
+        let acceptor = tls_acceptor(&test_tls()).unwrap();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Connects but never sends a ClientHello
+        let _silent = TcpStream::connect(addr).await.unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(2),
+            tls_accept(&acceptor, stream, Duration::from_millis(100)),
+        )
+        .await
+        .expect("tls_accept should give up on its own");
+        assert!(matches!(result, Err(BridgeError::WebSocketAccept { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_broadcasts_to_all_clients() {
+        use tokio_tungstenite::connect_async;
+
+        let port = free_port();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let mut channels = WebSocketTransport::new(port)
+            .spawn(shutdown.clone())
+            .unwrap();
+        let url = format!("ws://127.0.0.1:{}", port);
+        let (mut first, _) = connect_async(url.as_str()).await.unwrap();
+        let (mut second, _) = connect_async(url.as_str()).await.unwrap();
+        // Let the server register both connections
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        channels.tx.send(Bytes::from_static(b"all")).await.unwrap();
+        for ws in [&mut first, &mut second] {
+            let msg = tokio::time::timeout(Duration::from_secs(1), ws.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(msg, Message::Binary(Bytes::from_static(b"all")));
+        }
+
+        // Input from any client is relayed
+        second
+            .send(Message::Binary(Bytes::from_static(b"from 2")))
+            .await
+            .unwrap();
+        let data = tokio::time::timeout(Duration::from_secs(1), channels.rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&data[..], b"from 2");
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_max_clients_closes_extra_connections() {
+        use tokio_tungstenite::connect_async;
+
+        let port = free_port();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let _channels = WebSocketTransport::new(port)
+            .with_max_clients(1)
+            .spawn(shutdown.clone())
+            .unwrap();
+        let url = format!("ws://127.0.0.1:{}", port);
+        let (_first, _) = connect_async(url.as_str()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let (mut extra, _) = connect_async(url.as_str()).await.unwrap();
+        let msg = tokio::time::timeout(Duration::from_secs(1), extra.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        match msg {
+            Message::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Again),
+            other => panic!("expected close frame, got {:?}", other),
+        }
+
+        shutdown.store(true, Ordering::SeqCst);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_reports_port_in_use() {
+        let taken = std::net::TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let result = WebSocketTransport::new(port).spawn(shutdown);
+        assert!(matches!(result, Err(BridgeError::WebSocketBind { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_reports_unusable_certificate() {
+        let port = free_port();
+        let tls = TlsConfig {
+            cert_pem: "missing-cert.pem".into(),
+            key_pem: "missing-key.pem".into(),
+        };
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let result = WebSocketTransport::new(port)
+            .with_tls(Some(tls))
+            .spawn(shutdown);
+        match result {
+            Err(BridgeError::TlsConfig { path, .. }) => {
+                assert_eq!(path, std::path::Path::new("missing-cert.pem"))
+            }
+            _ => panic!("expected TlsConfig error"),
+        }
+        // Nothing was bound
+        assert!(std::net::TcpListener::bind(("0.0.0.0", port)).is_ok());
+    }
 }
@@ -0,0 +1,138 @@
+//! Clickable URLs in the log view (OSC 8 terminal hyperlinks)
+//!
+//! Ratatui measures every cell symbol, so escape sequences cannot go
+//! through the frame buffer. Instead, after each draw the URLs visible in
+//! the log area are found in the rendered buffer and written again on top,
+//! wrapped in OSC 8 sequences, with the same style. The link areas are kept
+//! so a mouse click can open the URL under the pointer.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::Color,
+};
+use std::io::{self, Write};
+use std::ops::Range;
+
+/// URL schemes recognized in log messages
+const URL_SCHEMES: [&str; 2] = ["https://", "http://"];
+
+/// A URL drawn on one screen row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkArea {
+    pub area: Rect,
+    pub url: String,
+    fg: Color,
+}
+
+/// Byte ranges of the URLs in `text`
+///
+/// A URL runs to the next whitespace or quote; trailing punctuation is
+/// left out (`see http://host/path.` links `http://host/path`).
+pub fn find_urls(text: &str) -> Vec<Range<usize>> {
+    let mut urls = Vec::new();
+    let mut from = 0;
+    while let Some((start, scheme)) = URL_SCHEMES
+        .iter()
+        .filter_map(|s| text[from..].find(s).map(|i| (from + i, s.len())))
+        .min()
+    {
+        let rest = &text[start..];
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>'))
+            .unwrap_or(rest.len());
+        let url = rest[..len].trim_end_matches(['.', ',', ';', ':', ')', ']', '}', '!', '?']);
+        let glued = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric());
+        if url.len() > scheme && !glued {
+            urls.push(start..start + url.len());
+        }
+        from = start + len.max(scheme);
+    }
+    urls
+}
+
+/// URLs drawn inside `area` of the rendered frame
+pub fn find_links(buf: &Buffer, area: Rect) -> Vec<LinkArea> {
+    let area = area.intersection(buf.area);
+    let mut links = Vec::new();
+    for y in area.top()..area.bottom() {
+        // One char per cell, so char counts are columns
+        let row: String = (area.left()..area.right())
+            .map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' '))
+            .collect();
+        for range in find_urls(&row) {
+            let x = area.left() + row[..range.start].chars().count() as u16;
+            let url = &row[range];
+            links.push(LinkArea {
+                area: Rect::new(x, y, url.chars().count() as u16, 1),
+                url: url.to_string(),
+                fg: buf[(x, y)].fg,
+            });
+        }
+    }
+    links
+}
+
+/// URL of the link under (`column`, `row`), if any
+pub fn link_at(links: &[LinkArea], column: u16, row: u16) -> Option<&str> {
+    links
+        .iter()
+        .find(|link| link.area.contains(Position::new(column, row)))
+        .map(|link| link.url.as_str())
+}
+
+/// Redraw `links` wrapped in OSC 8 hyperlink sequences
+pub fn write_links(out: &mut impl Write, links: &[LinkArea]) -> io::Result<()> {
+    use crossterm::{
+        cursor::MoveTo,
+        queue,
+        style::{Attribute, Print, ResetColor, SetAttribute, SetForegroundColor},
+    };
+
+    for link in links {
+        queue!(
+            out,
+            MoveTo(link.area.x, link.area.y),
+            SetForegroundColor(link.fg.into()),
+            SetAttribute(Attribute::Underlined),
+            Print(format!(
+                "\x1b]8;;{url}\x1b\\{url}\x1b]8;;\x1b\\",
+                url = link.url
+            )),
+            SetAttribute(Attribute::Reset),
+            ResetColor,
+        )?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::widgets::{Paragraph, Widget};
+
+    #[test]
+    fn test_find_urls() {
+        let text = "see https://example.com/a?b=1. and (http://host:80/x), xhttp://no";
+        let urls: Vec<&str> = find_urls(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(urls, ["https://example.com/a?b=1", "http://host:80/x"]);
+        assert!(find_urls("http:// nothing").is_empty());
+    }
+
+    #[test]
+    fn test_find_links_in_buffer() {
+        let area = Rect::new(0, 0, 40, 2);
+        let mut buf = Buffer::empty(area);
+        Paragraph::new("ok\n  docs: http://h/x").render(area, &mut buf);
+
+        let links = find_links(&buf, area);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "http://h/x");
+        assert_eq!(links[0].area, Rect::new(8, 1, 10, 1));
+        assert_eq!(link_at(&links, 12, 1), Some("http://h/x"));
+        assert_eq!(link_at(&links, 12, 0), None);
+    }
+}
@@ -3,58 +3,151 @@
 //! Thin layer responsible only for terminal I/O. All business logic
 //! is delegated to App via handle_key() and handle_scroll().
 
+pub mod hyperlink;
+pub mod monitor;
+pub mod recording;
 pub mod theme;
 pub mod widgets;
 
-use crate::app::App;
+use crate::app::{App, AppState};
 use crate::constants::FRAME_DURATION_MS;
 use crate::error::{BridgeError, Result};
+use crate::platform;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind, MouseEventKind},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind, MouseButton, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Layout},
+    layout::{Alignment, Constraint, Layout, Rect},
+    widgets::Paragraph,
     Frame, Terminal,
 };
+use recording::EventSource;
+use std::future::Future;
 use std::io;
-use widgets::{actions::ActionsWidget, log::LogWidget, status::StatusWidget};
+use widgets::{
+    actions::ActionsWidget, clipboard::ClipboardPopup, log::LogWidget, note::NoteInput,
+    palette::CommandPalette, peers::PeersPopup, ports::PortsPopup, statistics::StatisticsPopup,
+    stats::StatsWidget, status::StatusWidget,
+};
 
 /// Map io::Error to BridgeError::Runtime
 fn map_io_err(e: io::Error) -> BridgeError {
     BridgeError::Runtime { source: e }
 }
 
+type Term = Terminal<CrosstermBackend<io::Stdout>>;
+
 /// Run the TUI event loop
-pub async fn run(app: &mut App) -> Result<()> {
-    // Setup terminal
+///
+/// Input comes from `events`: the live terminal, or a recorder/player
+/// wrapping it (`--record` / `--playback`).
+pub async fn run(app: &mut App, events: Box<dyn EventSource>) -> Result<()> {
+    let mut terminal = setup_terminal()?;
+    let result = event_loop(&mut terminal, app, events);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+/// Show a splash screen while `init` builds the app, then run the TUI
+pub async fn run_starting(
+    init: impl Future<Output = App>,
+    events: Box<dyn EventSource>,
+) -> Result<()> {
+    let mut terminal = setup_terminal()?;
+    terminal.draw(draw_splash).map_err(map_io_err)?;
+    let mut app = init.await;
+    let result = event_loop(&mut terminal, &mut app, events);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+fn setup_terminal() -> Result<Term> {
     enable_raw_mode().map_err(map_io_err)?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture).map_err(map_io_err)?;
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).map_err(map_io_err)?;
+    Terminal::new(backend).map_err(map_io_err)
+}
+
+fn restore_terminal(terminal: &mut Term) -> Result<()> {
+    disable_raw_mode().map_err(map_io_err)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )
+    .map_err(map_io_err)?;
+    terminal.show_cursor().map_err(map_io_err)?;
+
+    Ok(())
+}
+
+fn event_loop(terminal: &mut Term, app: &mut App, mut events: Box<dyn EventSource>) -> Result<()> {
+    // Last rendered snapshot; None forces a full redraw
+    let mut last_state: Option<AppState<'static>> = None;
+    let mut last_size = terminal.size().map_err(map_io_err)?;
+    // Clickable URLs in the log view (empty when unsupported)
+    let hyperlinks = platform::supports_hyperlinks();
+    let mut links = Vec::new();
 
-    // Main loop
     loop {
         // Poll for bridge logs and update state
         app.poll();
 
-        // Draw UI
-        terminal.draw(|f| draw(f, app)).map_err(map_io_err)?;
+        // Ctrl+L, or a size change without a Resize event (another process
+        // wrote to the terminal): clear the screen and redraw everything
+        let size = terminal.size().map_err(map_io_err)?;
+        if app.take_force_redraw() || size != last_size {
+            terminal.clear().map_err(map_io_err)?;
+            last_size = size;
+            last_state = None;
+        }
+
+        // Draw UI only when something visible changed. Ratatui renders whole
+        // frames, so an idle TUI skips the draw entirely.
+        let state = app.state();
+        let changed = last_state
+            .as_ref()
+            .is_none_or(|prev| state.diff(prev).any());
+        if changed {
+            let mut log_area = Rect::default();
+            let frame = terminal
+                .draw(|f| log_area = draw(f, app, &state, hyperlinks))
+                .map_err(map_io_err)?;
+            if hyperlinks {
+                links = hyperlink::find_links(frame.buffer, log_area);
+                hyperlink::write_links(terminal.backend_mut(), &links).map_err(map_io_err)?;
+            }
+            let state = state.into_owned();
+            #[cfg(feature = "http-api")]
+            app.publish_state(&state);
+            last_state = Some(state);
+        }
 
         // Handle input with timeout
-        if event::poll(std::time::Duration::from_millis(FRAME_DURATION_MS)).map_err(map_io_err)? {
-            match event::read().map_err(map_io_err)? {
+        let timeout = std::time::Duration::from_millis(FRAME_DURATION_MS);
+        if let Some(event) = events.next_event(timeout)? {
+            match event {
                 Event::Key(key) if key.kind == KeyEventKind::Press && app.handle_key(key) => {
                     break;
                 }
                 Event::Mouse(mouse) => match mouse.kind {
                     MouseEventKind::ScrollUp => app.handle_scroll(true),
                     MouseEventKind::ScrollDown => app.handle_scroll(false),
+                    MouseEventKind::Moved => app.handle_mouse_move(mouse.column, mouse.row),
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(url) = hyperlink::link_at(&links, mouse.column, mouse.row) {
+                            app.handle_link_click(url);
+                        }
+                    }
                     _ => {}
                 },
+                Event::Resize(..) => last_state = None,
                 _ => {}
             }
         }
@@ -64,27 +157,32 @@ pub async fn run(app: &mut App) -> Result<()> {
         }
     }
 
-    // Restore terminal
-    disable_raw_mode().map_err(map_io_err)?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )
-    .map_err(map_io_err)?;
-    terminal.show_cursor().map_err(map_io_err)?;
-
     Ok(())
 }
 
-fn draw(frame: &mut Frame, app: &App) {
+fn draw_splash(frame: &mut Frame) {
+    let area = frame.area();
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let splash = Paragraph::new("Starting...")
+        .style(theme::STYLE_MUTED)
+        .alignment(Alignment::Center);
+    frame.render_widget(splash, middle);
+}
+
+/// Draw a frame; returns the log widget area
+fn draw(frame: &mut Frame, app: &App, state: &AppState, hyperlinks: bool) -> Rect {
     let area = frame.area();
     let is_wide = area.width > 80;
 
     // Status widget height depends on layout:
-    // - Wide: border(2) + header(1) + boxes side-by-side(4) = 7
-    // - Narrow: border(2) + header(1) + 2 stacked boxes(3+3) = 9
-    let status_height = if is_wide { 7 } else { 9 };
+    // - Wide: border(2) + header(1) + details(1) + boxes side-by-side(4) = 8
+    // - Narrow: border(2) + header(1) + details(1) + 2 stacked boxes(3+3) = 10
+    let status_height = if is_wide { 8 } else { 10 };
 
     let chunks = Layout::vertical([
         Constraint::Length(status_height), // Status widget (responsive)
@@ -93,26 +191,48 @@ fn draw(frame: &mut Frame, app: &App) {
     ])
     .split(area);
 
-    let state = app.state();
     let filter_mode = app.filter_mode();
 
     // Status widget
-    let status = StatusWidget::new(&state);
+    let status = StatusWidget::new(state);
     frame.render_widget(status, chunks[0]);
 
     // Log widget
     let log = LogWidget::new(
-        app.logs(),
-        app.filter(),
+        app.log_store(),
         filter_mode,
         app.scroll_position(),
         state.paused,
-    );
+    )
+    .with_word_wrap(state.word_wrap)
+    .with_hyperlinks(hyperlinks)
+    .with_search_input(state.search_input.as_deref());
     frame.render_widget(log, chunks[1]);
+    if let Some(messages) = &state.top_messages {
+        frame.render_widget(StatsWidget::new(messages), chunks[1]);
+    }
 
     // Actions widget
-    let actions = ActionsWidget::new(&state);
+    let actions = ActionsWidget::new(state);
     frame.render_widget(actions, chunks[2]);
 
-    // No popups.
+    if let Some(popup) = &state.clipboard_popup {
+        frame.render_widget(ClipboardPopup::new(popup), chunks[1]);
+    }
+    if let Some(peers) = &state.peers_popup {
+        frame.render_widget(PeersPopup::new(peers), chunks[1]);
+    }
+    if let Some(ports) = &state.ports_popup {
+        frame.render_widget(PortsPopup::new(ports), chunks[1]);
+    }
+    if let Some(lines) = &state.stats_popup {
+        frame.render_widget(StatisticsPopup::new(lines), chunks[1]);
+    }
+    if let Some(input) = &state.note_input {
+        frame.render_widget(NoteInput::new(input), chunks[1]);
+    }
+    if let Some(input) = &state.command_palette {
+        frame.render_widget(CommandPalette::new(input), chunks[1]);
+    }
+    chunks[1]
 }
@@ -0,0 +1,287 @@
+//! Live daemon stats (`oc-bridge ctl monitor`)
+//!
+//! A compact view of a running daemon for when the full TUI is too much:
+//! serial status, host port, traffic rates and totals, reconnections,
+//! dropped messages and the last log line. Fed by a control plane `subscribe` stream, read on
+//! its own thread; the screen is redrawn every `--interval`.
+
+use super::{map_io_err, restore_terminal, setup_terminal, Term};
+use crate::control::{self, ControlEvent};
+use crate::error::Result;
+use crate::logging::search_text;
+use crate::ui::theme::{
+    style_title, COLOR_LOG_RX, COLOR_LOG_TX, COLOR_RUNNING, COLOR_STOPPED, STYLE_ACTION,
+    STYLE_BORDER, STYLE_KEY, STYLE_LABEL, STYLE_MUTED, STYLE_TEXT, STYLE_VALUE,
+};
+use crate::ui::widgets::stats::format_bytes;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Row, Table, Widget},
+};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Monitor the daemon on `port` until `q`, Esc or Ctrl+C
+pub fn run(port: u16, interval: Duration) -> Result<()> {
+    let timeout = Duration::from_secs(2);
+    let info = control::send_command_blocking(port, "info", timeout)?;
+    let events = control::subscribe_blocking(port, timeout)?;
+
+    let mut state = MonitorState {
+        serial_port: info
+            .serial_open
+            .then_some(info.resolved_serial_port)
+            .flatten(),
+        host_udp_port: info.host_udp_port,
+        ..MonitorState::default()
+    };
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("ctl-monitor".to_string())
+        .spawn(move || {
+            for event in events {
+                let end = event.is_err();
+                if tx.send(event).is_err() || end {
+                    break;
+                }
+            }
+        })
+        .map_err(map_io_err)?;
+
+    let mut terminal = setup_terminal()?;
+    let result = monitor_loop(&mut terminal, &mut state, &rx, interval);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+fn monitor_loop(
+    terminal: &mut Term,
+    state: &mut MonitorState,
+    events: &mpsc::Receiver<Result<ControlEvent>>,
+    interval: Duration,
+) -> Result<()> {
+    loop {
+        loop {
+            match events.try_recv() {
+                Ok(Ok(event)) => state.apply(event, Instant::now()),
+                Ok(Err(e)) => state.ended = Some(e.to_string()),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    state
+                        .ended
+                        .get_or_insert_with(|| "daemon closed the stream".to_string());
+                    break;
+                }
+            }
+        }
+
+        terminal
+            .draw(|f| f.render_widget(MonitorWidget { state }, f.area()))
+            .map_err(map_io_err)?;
+
+        // Wait out the interval, leaving early on a quit key
+        let deadline = Instant::now() + interval;
+        while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+            if !event::poll(left).map_err(map_io_err)? {
+                break;
+            }
+            if let Event::Key(key) = event::read().map_err(map_io_err)? {
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press
+                    && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) || ctrl_c)
+                {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// What the monitor shows, folded from the event stream
+#[derive(Debug, Default)]
+struct MonitorState {
+    /// Open serial port, `None` while disconnected
+    serial_port: Option<String>,
+    host_udp_port: Option<u16>,
+    rx_bytes: u64,
+    tx_bytes: u64,
+    /// Bytes per second between the last two stats events
+    rx_rate: f64,
+    tx_rate: f64,
+    reconnections: u64,
+    channel_drops: u64,
+    last_log: Option<String>,
+    /// Time and byte counters of the previous stats event
+    last_stats: Option<(Instant, u64, u64)>,
+    /// Why the stream ended, if it did
+    ended: Option<String>,
+}
+
+impl MonitorState {
+    fn apply(&mut self, event: ControlEvent, now: Instant) {
+        match event {
+            ControlEvent::SerialConnected { port } => self.serial_port = Some(port),
+            ControlEvent::SerialDisconnected => self.serial_port = None,
+            ControlEvent::Stats {
+                rx_bytes,
+                tx_bytes,
+                reconnections,
+                channel_drops,
+                ..
+            } => {
+                if let Some((at, rx, tx)) = self.last_stats {
+                    let secs = now.duration_since(at).as_secs_f64();
+                    if secs > 0.0 {
+                        self.rx_rate = rx_bytes.saturating_sub(rx) as f64 / secs;
+                        self.tx_rate = tx_bytes.saturating_sub(tx) as f64 / secs;
+                    }
+                }
+                self.last_stats = Some((now, rx_bytes, tx_bytes));
+                self.rx_bytes = rx_bytes;
+                self.tx_bytes = tx_bytes;
+                self.reconnections = reconnections;
+                self.channel_drops = channel_drops;
+            }
+            ControlEvent::Log { entry } => {
+                self.last_log = Some(format!(
+                    "{} {}",
+                    entry.timestamp,
+                    search_text(&entry).trim_end()
+                ));
+            }
+            ControlEvent::Ping => {}
+        }
+    }
+}
+
+struct MonitorWidget<'a> {
+    state: &'a MonitorState,
+}
+
+impl Widget for MonitorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let state = self.state;
+        let serial = match &state.serial_port {
+            Some(port) => {
+                Cell::from(format!("connected ({})", port)).style(Style::new().fg(COLOR_RUNNING))
+            }
+            None => Cell::from("disconnected").style(Style::new().fg(COLOR_STOPPED)),
+        };
+        let host = state
+            .host_udp_port
+            .map_or_else(|| "-".to_string(), |p| p.to_string());
+        let rate = |bytes_per_sec: f64| format!("{:.1} KB/s", bytes_per_sec / 1024.0);
+
+        let rows = vec![
+            Row::new([Cell::from("Serial").style(STYLE_LABEL), serial]),
+            Row::new([
+                Cell::from("UDP port").style(STYLE_LABEL),
+                Cell::from(host).style(STYLE_VALUE),
+            ]),
+            Row::new([
+                Cell::from("RX").style(STYLE_LABEL),
+                Cell::from(format!(
+                    "{}  ({} total)",
+                    rate(state.rx_rate),
+                    format_bytes(state.rx_bytes)
+                ))
+                .style(Style::new().fg(COLOR_LOG_RX)),
+            ]),
+            Row::new([
+                Cell::from("TX").style(STYLE_LABEL),
+                Cell::from(format!(
+                    "{}  ({} total)",
+                    rate(state.tx_rate),
+                    format_bytes(state.tx_bytes)
+                ))
+                .style(Style::new().fg(COLOR_LOG_TX)),
+            ]),
+            Row::new([
+                Cell::from("Reconnects").style(STYLE_LABEL),
+                Cell::from(state.reconnections.to_string()).style(STYLE_VALUE),
+            ]),
+            Row::new([
+                Cell::from("Drops").style(STYLE_LABEL),
+                Cell::from(state.channel_drops.to_string()).style(STYLE_VALUE),
+            ]),
+            Row::new([
+                Cell::from("Last log").style(STYLE_LABEL),
+                Cell::from(state.last_log.as_deref().unwrap_or("-")).style(STYLE_TEXT),
+            ]),
+        ];
+
+        let title = Line::from(vec![
+            Span::styled(" Monitor ", style_title()),
+            Span::styled("q", STYLE_KEY),
+            Span::styled(" quit ", STYLE_ACTION),
+        ]);
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER)
+            .title(title);
+        if let Some(reason) = &state.ended {
+            block = block.title_bottom(Span::styled(format!(" {} ", reason), STYLE_MUTED));
+        }
+
+        Table::new(rows, [Constraint::Length(12), Constraint::Fill(1)])
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::LogEntry;
+
+    fn stats(rx_bytes: u64, tx_bytes: u64) -> ControlEvent {
+        ControlEvent::Stats {
+            rx_bytes,
+            tx_bytes,
+            rx_messages: 0,
+            tx_messages: 0,
+            tx_batches: 0,
+            reconnections: 2,
+            channel_drops: 3,
+        }
+    }
+
+    #[test]
+    fn test_monitor_state_applies_events() {
+        let start = Instant::now();
+        let mut state = MonitorState::default();
+
+        state.apply(
+            ControlEvent::SerialConnected {
+                port: "/dev/ttyACM0".to_string(),
+            },
+            start,
+        );
+        state.apply(stats(1000, 100), start);
+        assert_eq!(state.rx_rate, 0.0);
+        state.apply(stats(3048, 612), start + Duration::from_secs(2));
+        assert_eq!(state.rx_rate, 1024.0);
+        assert_eq!(state.tx_rate, 256.0);
+        assert_eq!(state.rx_bytes, 3048);
+        assert_eq!(state.reconnections, 2);
+        assert_eq!(state.channel_drops, 3);
+        assert_eq!(state.serial_port.as_deref(), Some("/dev/ttyACM0"));
+
+        state.apply(
+            ControlEvent::Log {
+                entry: LogEntry::system("Hello"),
+            },
+            start,
+        );
+        assert!(state.last_log.as_deref().unwrap().ends_with("Hello"));
+
+        state.apply(ControlEvent::SerialDisconnected, start);
+        assert_eq!(state.serial_port, None);
+    }
+}
@@ -0,0 +1,254 @@
+//! TUI session recording and playback
+//!
+//! Captures terminal input events with timestamps so UI bugs can be
+//! reproduced exactly (`--record`), and replays them (`--playback`).
+//!
+//! Format: JSON Lines, one `RecordedEvent` per line:
+//!
+//! ```text
+//! {"t_ms":0,"event":{"Resize":[120,40]}}
+//! {"t_ms":1520,"event":{"Key":{"code":{"Char":"q"},...}}}
+//! ```
+
+use crate::error::{BridgeError, Result};
+use crossterm::event::{self, Event};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Playback speed multiplier for `--fast`
+pub const FAST_PLAYBACK_SPEED: f64 = 10.0;
+
+/// Source of terminal input events for the TUI loop
+pub trait EventSource {
+    /// Wait up to `timeout` for the next event
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>>;
+}
+
+/// One recorded event with its offset from the start of the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub t_ms: u64,
+    pub event: Event,
+}
+
+fn map_io_err(path: &Path) -> impl Fn(std::io::Error) -> BridgeError + '_ {
+    move |e| BridgeError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    }
+}
+
+// =============================================================================
+// Live terminal
+// =============================================================================
+
+/// Real terminal input via crossterm
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        let map_err = |e| BridgeError::Runtime { source: e };
+        if event::poll(timeout).map_err(map_err)? {
+            Ok(Some(event::read().map_err(map_err)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+// =============================================================================
+// Recorder
+// =============================================================================
+
+/// Wraps an event source and writes every event it yields to a file
+pub struct Recorder<S: EventSource> {
+    inner: S,
+    writer: BufWriter<File>,
+    path: PathBuf,
+    start: Instant,
+}
+
+impl<S: EventSource> Recorder<S> {
+    /// Create the recording file (truncating any existing one)
+    pub fn create(inner: S, path: &Path) -> Result<Self> {
+        let file = File::create(path).map_err(map_io_err(path))?;
+        let mut recorder = Self {
+            inner,
+            writer: BufWriter::new(file),
+            path: path.to_path_buf(),
+            start: Instant::now(),
+        };
+
+        // Capture the initial terminal size so playback starts from the same layout
+        if let Ok((cols, rows)) = crossterm::terminal::size() {
+            recorder.write(&Event::Resize(cols, rows))?;
+        }
+        Ok(recorder)
+    }
+
+    fn write(&mut self, event: &Event) -> Result<()> {
+        let record = RecordedEvent {
+            t_ms: self.start.elapsed().as_millis() as u64,
+            event: event.clone(),
+        };
+        let line = serde_json::to_string(&record).map_err(|e| BridgeError::Io {
+            path: self.path.clone(),
+            source: e.into(),
+        })?;
+        writeln!(self.writer, "{}", line).map_err(map_io_err(&self.path))?;
+        // Flush per event: a crashing session is exactly what we want to keep
+        self.writer.flush().map_err(map_io_err(&self.path))
+    }
+}
+
+impl<S: EventSource> EventSource for Recorder<S> {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        let event = self.inner.next_event(timeout)?;
+        if let Some(ref e) = event {
+            self.write(e)?;
+        }
+        Ok(event)
+    }
+}
+
+// =============================================================================
+// Player
+// =============================================================================
+
+/// Replays a recording with its original timing (scaled by `speed`)
+///
+/// Once the recording is exhausted, input falls back to the live terminal
+/// so the final state can be inspected.
+pub struct Player {
+    events: VecDeque<RecordedEvent>,
+    start: Instant,
+    speed: f64,
+    fallback: TerminalEvents,
+}
+
+impl Player {
+    /// Load a recording file
+    pub fn open(path: &Path, speed: f64) -> Result<Self> {
+        let file = File::open(path).map_err(map_io_err(path))?;
+        let mut events = VecDeque::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(map_io_err(path))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: RecordedEvent =
+                serde_json::from_str(&line).map_err(|e| BridgeError::RecordingParse {
+                    path: path.to_path_buf(),
+                    line: i + 1,
+                    reason: e.to_string(),
+                })?;
+            events.push_back(record);
+        }
+        Ok(Self::from_events(events, speed))
+    }
+
+    fn from_events(events: VecDeque<RecordedEvent>, speed: f64) -> Self {
+        Self {
+            events,
+            start: Instant::now(),
+            speed: speed.max(f64::MIN_POSITIVE),
+            fallback: TerminalEvents,
+        }
+    }
+
+    /// Playback time at which `record` is due
+    fn due_at(&self, record: &RecordedEvent) -> Duration {
+        Duration::from_secs_f64(record.t_ms as f64 / 1000.0 / self.speed)
+    }
+}
+
+impl EventSource for Player {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        let Some(next) = self.events.front() else {
+            return self.fallback.next_event(timeout);
+        };
+
+        let due = self.due_at(next);
+        let elapsed = self.start.elapsed();
+        if elapsed < due {
+            let wait = (due - elapsed).min(timeout);
+            std::thread::sleep(wait);
+            if self.start.elapsed() < due {
+                return Ok(None);
+            }
+        }
+
+        Ok(self.events.pop_front().map(|r| r.event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_recorded_event_json_roundtrip() {
+        let record = RecordedEvent {
+            t_ms: 42,
+            event: Event::Resize(80, 24),
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        let restored: RecordedEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.t_ms, 42);
+        assert_eq!(restored.event, Event::Resize(80, 24));
+    }
+
+    #[test]
+    fn test_player_respects_timing_and_speed() {
+        let events = VecDeque::from(vec![
+            RecordedEvent {
+                t_ms: 0,
+                event: key('a'),
+            },
+            RecordedEvent {
+                t_ms: 1000,
+                event: key('b'),
+            },
+        ]);
+        let mut player = Player::from_events(events, FAST_PLAYBACK_SPEED);
+
+        assert_eq!(player.next_event(Duration::ZERO).unwrap(), Some(key('a')));
+        // 'b' is due after 100ms at 10x: not yet
+        assert_eq!(player.next_event(Duration::ZERO).unwrap(), None);
+        assert_eq!(
+            player.next_event(Duration::from_millis(500)).unwrap(),
+            Some(key('b'))
+        );
+    }
+
+    #[test]
+    fn test_player_open_reports_file_and_line() {
+        let path =
+            std::env::temp_dir().join(format!("oc-bridge-recording-{}.jsonl", std::process::id()));
+        let valid = serde_json::to_string(&RecordedEvent {
+            t_ms: 0,
+            event: key('a'),
+        })
+        .unwrap();
+        std::fs::write(&path, format!("{}\n\nnot json\n", valid)).unwrap();
+
+        let result = Player::open(&path, 1.0);
+        let _ = std::fs::remove_file(&path);
+        match result {
+            Err(BridgeError::RecordingParse { path: p, line, .. }) => {
+                assert_eq!(p, path);
+                assert_eq!(line, 3);
+            }
+            _ => panic!("expected a recording parse error"),
+        }
+    }
+}
@@ -28,6 +28,7 @@ pub const COLOR_STOPPED: Color = COLOR_MUTED;
 pub const COLOR_LOG_TX: Color = COLOR_SUCCESS; // Outgoing (TX) - green
 pub const COLOR_LOG_RX: Color = COLOR_ACCENT; // Incoming (RX) - cyan
 pub const COLOR_LOG_SYSTEM: Color = COLOR_MUTED;
+pub const COLOR_LOG_EVENT: Color = COLOR_TEXT; // Controller connect/disconnect
 
 // Action bar
 pub const COLOR_KEY: Color = COLOR_ACCENT;
@@ -50,6 +51,7 @@ pub const STYLE_VALUE: Style = Style::new().fg(COLOR_VALUE);
 pub const STYLE_BORDER: Style = Style::new().fg(COLOR_BORDER);
 pub const STYLE_KEY: Style = Style::new().fg(COLOR_KEY);
 pub const STYLE_ACTION: Style = Style::new().fg(COLOR_ACTION);
+pub const STYLE_SEARCH_MATCH: Style = Style::new().fg(Color::Black).bg(COLOR_WARNING);
 
 #[inline]
 pub fn style_title() -> Style {
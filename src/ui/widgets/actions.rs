@@ -1,9 +1,12 @@
 //! Actions widget - displays keyboard shortcuts bar
 //!
-//! Shows available commands based on current state.
+//! Shows only the commands that apply to the current state: bridge controls
+//! are hidden while the daemon is offline, the serial toggle only appears
+//! for a serial controller. The session log file, when one is written,
+//! follows the utilities.
 
 use crate::app::AppState;
-use crate::config::ControllerTransport;
+use crate::config::ExportFormat;
 use crate::ui::theme::{STYLE_ACTION, STYLE_DIM, STYLE_KEY};
 use ratatui::{
     buffer::Buffer,
@@ -12,6 +15,9 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
+/// One key hint: key label and action text
+type Hint = (String, String);
+
 pub struct ActionsWidget<'a> {
     state: &'a AppState<'a>,
 }
@@ -22,71 +28,255 @@ impl<'a> ActionsWidget<'a> {
     }
 }
 
-impl Widget for ActionsWidget<'_> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let serial_action = if !self.state.daemon_running
-            || self.state.controller_transport_config != ControllerTransport::Serial
-        {
-            ("–", true)
-        } else if self.state.bridge_paused {
-            ("Attach", false)
-        } else {
-            ("Release", false)
-        };
-
-        // Build first line: main commands
-        let mut line1_spans = vec![Span::raw("  "), Span::styled("B", STYLE_KEY)];
+/// Key hints applicable in `state`, as (main commands, utilities)
+///
+/// Character keys are shown as currently bound (`[keybindings]`).
+fn hints(state: &AppState) -> (Vec<Hint>, Vec<Hint>) {
+    let keys = &state.keys;
+    let hint = |key: &str, label: &str| (key.to_uppercase(), label.to_string());
+    let mut main = Vec::new();
 
-        if serial_action.1 {
-            line1_spans.push(Span::styled(" Serial:–  ", STYLE_DIM));
-        } else {
-            line1_spans.push(Span::styled(
-                format!(" Serial:{}  ", serial_action.0),
-                STYLE_ACTION,
-            ));
+    if state.daemon_running {
+        if state.controller_transport_config.is_serial() {
+            let serial = if state.bridge_paused {
+                "Serial:Attach"
+            } else {
+                "Serial:Release"
+            };
+            main.push(hint(&keys.toggle_bridge, serial));
         }
+        main.push(hint("^H", "Host"));
+    }
+
+    if state.config_changed {
+        main.push(hint("⇧R", "Apply"));
+    }
+
+    main.extend([
+        hint(&keys.filter_protocol, "Protocol"),
+        hint(&keys.filter_debug, "Debug"),
+        hint(&keys.filter_all, "All"),
+        hint(&keys.filter_events, "Events"),
+        hint(&keys.quit, "Quit"),
+    ]);
 
-        line1_spans.extend(vec![
-            Span::styled("1", STYLE_KEY),
-            Span::styled(" Protocol  ", STYLE_ACTION),
-            Span::styled("2", STYLE_KEY),
-            Span::styled(" Debug  ", STYLE_ACTION),
-            Span::styled("3", STYLE_KEY),
-            Span::styled(" All  ", STYLE_ACTION),
-            Span::styled("Q", STYLE_KEY),
-            Span::styled(" Quit", STYLE_ACTION),
-        ]);
-
-        // Pause state
-        let logs_label = if self.state.paused {
-            "Follow"
+    // Freeze/Follow only matters for a live stream, but stay reachable
+    // while frozen so the view can be released
+    let mut utilities = Vec::new();
+    if (state.log_available && state.log_connected) || state.paused {
+        let logs = if state.paused {
+            "Logs:Follow"
         } else {
-            "Freeze"
+            "Logs:Freeze"
         };
+        utilities.push(hint(&keys.toggle_pause, logs));
+    }
+    let export = match state.export_format {
+        ExportFormat::Text => "Export",
+        ExportFormat::JsonLines => "Export:JSON",
+    };
+    utilities.extend([
+        hint(&keys.copy, "Copy"),
+        hint(&keys.cut, "Cut"),
+        hint(&keys.export, export),
+        hint(&keys.open_config, "Config"),
+        hint(&keys.scan, "Scan"),
+        hint("⌫", "Clear"),
+    ]);
 
-        // Build second line: utilities
-        let line2_spans = vec![
-            Span::raw("  "),
-            Span::styled("P", STYLE_KEY),
-            Span::styled(format!(" Logs:{} ", logs_label), STYLE_ACTION),
-            Span::styled("C", STYLE_KEY),
-            Span::styled(" Copy ", STYLE_ACTION),
-            Span::styled("X", STYLE_KEY),
-            Span::styled(" Cut ", STYLE_ACTION),
-            Span::styled("E", STYLE_KEY),
-            Span::styled(" Export ", STYLE_ACTION),
-            Span::styled("F", STYLE_KEY),
-            Span::styled(" Config ", STYLE_ACTION),
-            Span::styled("⌫", STYLE_KEY),
-            Span::styled(" Clear", STYLE_ACTION),
-        ];
+    (main, utilities)
+}
+
+/// Render hints as `KEY label` pairs separated by `gap`
+fn hint_line(hints: Vec<Hint>, gap: &str) -> Line<'static> {
+    let last = hints.len().saturating_sub(1);
+    let mut spans = vec![Span::raw("  ")];
+    for (i, (key, label)) in hints.into_iter().enumerate() {
+        let sep = if i == last { "" } else { gap };
+        spans.push(Span::styled(key, STYLE_KEY));
+        spans.push(Span::styled(format!(" {}{}", label, sep), STYLE_ACTION));
+    }
+    Line::from(spans)
+}
+
+impl Widget for ActionsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (main, utilities) = hints(self.state);
 
         let block = Block::default()
             .borders(Borders::TOP)
             .border_style(STYLE_DIM);
 
-        let paragraph =
-            Paragraph::new(vec![Line::from(line1_spans), Line::from(line2_spans)]).block(block);
+        let mut utilities = hint_line(utilities, " ");
+        if let Some(path) = &self.state.log_file {
+            utilities
+                .spans
+                .push(Span::styled(format!("  LOG {}", path), STYLE_DIM));
+        }
+
+        let paragraph = Paragraph::new(vec![hint_line(main, "  "), utilities]).block(block);
         paragraph.render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{ControllerTransportState, HostTransportState};
+    use crate::config::{ControllerTransport, HostTransport, KeyBindings};
+    use std::borrow::Cow;
+
+    fn state() -> AppState<'static> {
+        AppState {
+            daemon_running: true,
+            profile: None,
+            keys: Cow::Owned(KeyBindings::default()),
+            controller_transport_config: ControllerTransport::Serial,
+            host_transport_config: HostTransport::Udp,
+            controller_state: Cow::Owned(ControllerTransportState::Waiting),
+            config_changed: false,
+            serial_port_from_env: false,
+            host_state: HostTransportState::Udp { port: 9000 },
+            active_codec: "COBS+Debug".to_string(),
+            active_host_info: "UDP:9000".to_string(),
+            jitter_ms: None,
+            write_fill_ratio: None,
+            control_requests_total: None,
+            control_latency_ms_avg: None,
+            bridge_paused: false,
+            cobs_trace: false,
+            control_port: 7999,
+            log_port: 9999,
+            log_available: true,
+            log_connected: true,
+            log_signal: 4,
+            log_reconnects: 0,
+            rx_rate: 0.0,
+            tx_rate: 0.0,
+            rx_rate_avg: 0.0,
+            tx_rate_avg: 0.0,
+            spike_flash: false,
+            recording_bytes: None,
+            log_file: None,
+            export_format: ExportFormat::Text,
+            paused: false,
+            status_message: None,
+            log_revision: 0,
+            word_wrap: false,
+            clipboard_popup: None,
+            peers_popup: None,
+            ports_popup: None,
+            command_palette: None,
+            stats_popup: None,
+            note_input: None,
+            search_input: None,
+            top_messages: None,
+            mouse: None,
+        }
+    }
+
+    /// Rendered text of the two hint rows (below the top border)
+    fn rendered(state: &AppState) -> (String, String) {
+        let area = Rect::new(0, 0, 100, 3);
+        let mut buf = Buffer::empty(area);
+        ActionsWidget::new(state).render(area, &mut buf);
+        let row = |y| {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        (row(1), row(2))
+    }
+
+    #[test]
+    fn test_hints_daemon_offline_hide_bridge_controls() {
+        let mut s = state();
+        s.daemon_running = false;
+        s.log_connected = false;
+
+        let (main, utilities) = rendered(&s);
+        assert_eq!(main, "  1 Protocol  2 Debug  3 All  4 Events  Q Quit");
+        assert_eq!(utilities, "  C Copy X Cut E Export F Config N Scan ⌫ Clear");
+    }
+
+    #[test]
+    fn test_hints_serial_running_and_released() {
+        let mut s = state();
+        let (main, utilities) = rendered(&s);
+        assert_eq!(
+            main,
+            "  B Serial:Release  ^H Host  1 Protocol  2 Debug  3 All  4 Events  Q Quit"
+        );
+        assert_eq!(
+            utilities,
+            "  P Logs:Freeze C Copy X Cut E Export F Config N Scan ⌫ Clear"
+        );
+
+        s.bridge_paused = true;
+        s.paused = true;
+        let (main, utilities) = rendered(&s);
+        assert!(main.starts_with("  B Serial:Attach  ^H Host"));
+        assert!(utilities.starts_with("  P Logs:Follow "));
+    }
+
+    #[test]
+    fn test_hints_network_controller_has_no_serial_toggle() {
+        let mut s = state();
+        s.controller_transport_config = ControllerTransport::Udp;
+        let (main, _) = rendered(&s);
+        assert_eq!(
+            main,
+            "  ^H Host  1 Protocol  2 Debug  3 All  4 Events  Q Quit"
+        );
+    }
+
+    #[test]
+    fn test_hints_config_changed_offers_apply() {
+        let mut s = state();
+        s.controller_transport_config = ControllerTransport::Udp;
+        s.config_changed = true;
+        let (main, _) = rendered(&s);
+        assert_eq!(
+            main,
+            "  ^H Host  ⇧R Apply  1 Protocol  2 Debug  3 All  4 Events  Q Quit"
+        );
+    }
+
+    #[test]
+    fn test_hints_show_session_log_file() {
+        let mut s = state();
+        s.daemon_running = false;
+        s.log_connected = false;
+        s.log_file = Some(Cow::Borrowed("/tmp/logs/tui-1.log"));
+        let (_, utilities) = rendered(&s);
+        assert_eq!(
+            utilities,
+            "  C Copy X Cut E Export F Config N Scan ⌫ Clear  LOG /tmp/logs/tui-1.log"
+        );
+    }
+
+    #[test]
+    fn test_hints_mention_json_export() {
+        let mut s = state();
+        s.export_format = ExportFormat::JsonLines;
+        let (_, utilities) = rendered(&s);
+        assert!(utilities.contains(" E Export:JSON "));
+    }
+
+    #[test]
+    fn test_hints_show_remapped_keys() {
+        let mut s = state();
+        s.keys = Cow::Owned(KeyBindings {
+            toggle_bridge: "s".into(),
+            quit: "z".into(),
+            ..KeyBindings::default()
+        });
+        let (main, _) = rendered(&s);
+        assert_eq!(
+            main,
+            "  S Serial:Release  ^H Host  1 Protocol  2 Debug  3 All  4 Events  Z Quit"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Clipboard history popup
+//!
+//! Centered over the log view; lists recent copies, most recent first.
+
+use crate::app::ClipboardPopupState;
+use crate::ui::theme::{
+    style_title, STYLE_ACTION, STYLE_BORDER, STYLE_BRIGHT, STYLE_KEY, STYLE_TEXT,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+pub struct ClipboardPopup<'a> {
+    state: &'a ClipboardPopupState,
+}
+
+impl<'a> ClipboardPopup<'a> {
+    pub fn new(state: &'a ClipboardPopupState) -> Self {
+        Self { state }
+    }
+}
+
+impl Widget for ClipboardPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Border(2) + entries + blank + help line
+        let height = self.state.entries.len() as u16 + 4;
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Max(80)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let mut lines: Vec<Line> = self
+            .state
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                if i == self.state.selected {
+                    Line::styled(
+                        format!("> {}", entry),
+                        STYLE_BRIGHT.add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Line::styled(format!("  {}", entry), STYLE_TEXT)
+                }
+            })
+            .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled("  ↑↓", STYLE_KEY),
+            Span::styled(" Select  ", STYLE_ACTION),
+            Span::styled("Enter", STYLE_KEY),
+            Span::styled(" Copy  ", STYLE_ACTION),
+            Span::styled("Esc", STYLE_KEY),
+            Span::styled(" Close", STYLE_ACTION),
+        ]));
+
+        let block = Block::default()
+            .title(" CLIPBOARD HISTORY ")
+            .title_style(style_title())
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
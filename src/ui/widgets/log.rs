@@ -2,51 +2,94 @@
 //!
 //! Wide mode (>80 cols): logs on left, filter sidebar on right
 //! Narrow mode (<=80 cols): filter bar above logs
+//!
+//! With word wrap on, long debug/system/event messages continue on extra
+//! rows (prefixed with `WRAP_INDENT`) instead of being truncated. Scrolling
+//! stays entry-based: the entry at the scroll position is kept at the bottom.
+//!
+//! Bookmarked entries show `BOOKMARK_MARKER` in the left margin, annotated
+//! entries `NOTE_MARKER` next to it.
+//!
+//! While a search is active, matches in messages are highlighted and the
+//! bottom border shows the query and match count.
+//!
+//! With hyperlinks on, URLs in messages are underlined; `ui::hyperlink`
+//! makes them clickable after the frame is drawn.
 
 use crate::constants::{SIDEBAR_WIDTH, WIDE_THRESHOLD};
-use crate::logging::{Direction, FilterMode, LogEntry, LogFilter, LogKind, LogLevel};
+use crate::logging::{
+    match_ranges, search_text, Direction, FilterMode, LogEntry, LogKind, LogLevel, LogStore,
+    EVENT_TAG,
+};
+use crate::ui::hyperlink::find_urls;
 use crate::ui::theme::{
-    style_bold, COLOR_BRIGHT, COLOR_ERROR, COLOR_LOG_RX, COLOR_LOG_SYSTEM, COLOR_LOG_TX,
-    COLOR_MUTED, COLOR_WARNING, STYLE_BORDER, STYLE_BRIGHT, STYLE_DIM, STYLE_KEY, STYLE_LABEL,
-    STYLE_MUTED, STYLE_TEXT, SYMBOL_IN, SYMBOL_OUT,
+    style_bold, COLOR_BRIGHT, COLOR_ERROR, COLOR_LOG_EVENT, COLOR_LOG_RX, COLOR_LOG_SYSTEM,
+    COLOR_LOG_TX, COLOR_MUTED, COLOR_WARNING, STYLE_BORDER, STYLE_BRIGHT, STYLE_DIM, STYLE_KEY,
+    STYLE_LABEL, STYLE_MUTED, STYLE_SEARCH_MATCH, STYLE_TEXT, SYMBOL_IN, SYMBOL_OUT,
 };
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::Style,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
         Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget,
         Widget,
     },
 };
-use std::collections::VecDeque;
+use std::borrow::Cow;
 
 pub struct LogWidget<'a> {
-    entries: &'a VecDeque<LogEntry>,
-    filter: &'a LogFilter,
+    logs: &'a LogStore,
     filter_mode: FilterMode,
     scroll: usize,
     paused: bool,
+    word_wrap: bool,
+    hyperlinks: bool,
+    /// Query being typed, if the search input is open
+    search_input: Option<&'a str>,
 }
 
+/// Prefix of wrapped continuation rows
+const WRAP_INDENT: &str = "    ";
+
+/// Left-margin marker of bookmarked entries
+const BOOKMARK_MARKER: &str = "◆";
+
+/// Second-column marker of entries with a user note
+const NOTE_MARKER: &str = "✎";
+
 impl<'a> LogWidget<'a> {
-    pub fn new(
-        entries: &'a VecDeque<LogEntry>,
-        filter: &'a LogFilter,
-        filter_mode: FilterMode,
-        scroll: usize,
-        paused: bool,
-    ) -> Self {
+    pub fn new(logs: &'a LogStore, filter_mode: FilterMode, scroll: usize, paused: bool) -> Self {
         Self {
-            entries,
-            filter,
+            logs,
             filter_mode,
             scroll,
             paused,
+            word_wrap: false,
+            hyperlinks: false,
+            search_input: None,
         }
     }
 
+    /// Wrap long messages onto continuation rows
+    pub fn with_word_wrap(mut self, word_wrap: bool) -> Self {
+        self.word_wrap = word_wrap;
+        self
+    }
+
+    /// Underline URLs in messages (the terminal supports hyperlinks)
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    /// Show the search input with the query being typed
+    pub fn with_search_input(mut self, search_input: Option<&'a str>) -> Self {
+        self.search_input = search_input;
+        self
+    }
+
     fn is_wide(&self, width: u16) -> bool {
         width > WIDE_THRESHOLD
     }
@@ -86,6 +129,7 @@ impl LogWidget<'_> {
     fn render_filter_bar(&self, area: Rect, buf: &mut Buffer) {
         let is_protocol = self.filter_mode == FilterMode::Protocol;
         let is_debug = self.filter_mode == FilterMode::Debug;
+        let is_events = self.filter_mode == FilterMode::Events;
         let is_all = self.filter_mode == FilterMode::All;
 
         let line = Line::from(vec![
@@ -95,6 +139,8 @@ impl LogWidget<'_> {
             self.filter_button("2", "Debug", is_debug),
             Span::raw("  "),
             self.filter_button("3", "All", is_all),
+            Span::raw("  "),
+            self.filter_button("4", "Events", is_events),
         ]);
 
         Paragraph::new(line).style(STYLE_DIM).render(area, buf);
@@ -104,6 +150,7 @@ impl LogWidget<'_> {
     fn render_sidebar(&self, area: Rect, buf: &mut Buffer) {
         let is_protocol = self.filter_mode == FilterMode::Protocol;
         let is_debug = self.filter_mode == FilterMode::Debug;
+        let is_events = self.filter_mode == FilterMode::Events;
         let is_all = self.filter_mode == FilterMode::All;
 
         let block = Block::default()
@@ -118,6 +165,7 @@ impl LogWidget<'_> {
             self.sidebar_item("1", "Protocol", is_protocol),
             self.sidebar_item("2", "Debug", is_debug),
             self.sidebar_item("3", "All", is_all),
+            self.sidebar_item("4", "Events", is_events),
         ];
 
         Paragraph::new(lines).render(inner, buf);
@@ -152,42 +200,78 @@ impl LogWidget<'_> {
         let inner_height = area.height.saturating_sub(2) as usize;
         let inner_width = area.width.saturating_sub(3) as usize; // -2 for borders, -1 for scrollbar
 
-        // Count filtered entries
-        let total_lines = self
-            .entries
-            .iter()
-            .filter(|e| self.filter.matches(e))
-            .count();
-
         let start = self.scroll.saturating_sub(inner_height.saturating_sub(1));
-        let end = (start + inner_height).min(total_lines);
+        let window = self.logs.visible_window(start, inner_height);
+
+        let bookmarked = |index: usize, mut lines: Vec<Line<'static>>| {
+            if let Some(query) = self.logs.search_query() {
+                for (row, line) in lines.iter_mut().enumerate() {
+                    // Message span: after timestamp and symbol, or the indent
+                    highlight_matches(line, if row == 0 { 2 } else { 1 }, query);
+                }
+            }
+            if let Some(first) = lines.first_mut() {
+                let annotated = self.logs.get(index).is_some_and(|e| e.user_note.is_some());
+                if annotated {
+                    mark_note(first);
+                }
+                if self.logs.is_bookmarked(index) {
+                    mark_bookmark(first);
+                }
+            }
+            if self.hyperlinks {
+                lines.iter_mut().for_each(underline_urls);
+            }
+            lines
+        };
 
-        // Format visible lines
-        let lines: Vec<Line> = self
-            .entries
-            .iter()
-            .filter(|e| self.filter.matches(e))
-            .skip(start)
-            .take(end - start)
-            .map(|entry| format_log_entry(entry, inner_width))
-            .collect();
+        // Format visible lines only (virtual scrolling)
+        let (lines, total_lines, position) = if self.word_wrap {
+            // Keep the rows ending with the scrolled-to entry
+            let mut lines: Vec<Line> = window
+                .into_iter()
+                .flat_map(|(i, entry)| bookmarked(i, format_log_entry_wrapped(entry, inner_width)))
+                .collect();
+            lines.drain(..lines.len().saturating_sub(inner_height));
+
+            let rows = |entry: &LogEntry| entry_rows(entry, inner_width);
+            let total = self
+                .logs
+                .visual_line_count(self.logs.filtered_count(), rows);
+            let position = self
+                .logs
+                .visual_line_count(self.scroll + 1, rows)
+                .saturating_sub(1);
+            (lines, total, position)
+        } else {
+            let lines = window
+                .into_iter()
+                .flat_map(|(i, entry)| bookmarked(i, vec![format_log_entry(entry, inner_width)]))
+                .collect();
+            // Filtered count is cached by the store (O(1))
+            (lines, self.logs.filtered_count(), self.scroll)
+        };
 
-        // Title with freeze/follow hint on the right
-        let title_left = " Logs ";
-        let title_right = if self.paused {
-            Line::from(vec![
+        // Title with bookmark count, freeze/follow hint on the right
+        let title_left = match self.logs.bookmark_count() {
+            0 => " Logs ".to_string(),
+            n => format!(" Logs {}{} ", BOOKMARK_MARKER, n),
+        };
+        let mut title_right = self.search_line();
+        if self.paused {
+            title_right.extend([
                 Span::styled("FROZEN ", Style::new().fg(COLOR_WARNING)),
                 Span::styled("P Follow ", STYLE_MUTED),
-            ])
+            ]);
         } else {
-            Line::from(Span::styled("P Freeze ", STYLE_DIM))
-        };
+            title_right.push(Span::styled("P Freeze ", STYLE_DIM));
+        }
 
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(STYLE_BORDER)
             .title(Span::styled(title_left, STYLE_LABEL))
-            .title_bottom(title_right);
+            .title_bottom(Line::from(title_right));
 
         let paragraph = Paragraph::new(lines).block(block);
         paragraph.render(area, buf);
@@ -198,7 +282,7 @@ impl LogWidget<'_> {
                 .begin_symbol(Some("▲"))
                 .end_symbol(Some("▼"));
 
-            let mut scrollbar_state = ScrollbarState::new(total_lines).position(self.scroll);
+            let mut scrollbar_state = ScrollbarState::new(total_lines).position(position);
 
             let scrollbar_area = Rect {
                 x: area.x + area.width - 1,
@@ -212,29 +296,59 @@ impl LogWidget<'_> {
     }
 }
 
-/// Format a log entry into a styled Line
-fn format_log_entry(entry: &LogEntry, max_width: usize) -> Line<'static> {
+impl LogWidget<'_> {
+    /// Bottom-border search spans: the query being typed, or match position
+    fn search_line(&self) -> Vec<Span<'static>> {
+        if let Some(input) = self.search_input {
+            return vec![
+                Span::styled(format!(" /{}", input), STYLE_BRIGHT),
+                Span::styled("█ ", STYLE_KEY),
+            ];
+        }
+        let Some(query) = self.logs.search_query() else {
+            return Vec::new();
+        };
+        let matches = self.logs.search_matches();
+        let position = matches.iter().position(|&p| p == self.scroll);
+        let count = match position {
+            Some(i) => format!("{}/{}", i + 1, matches.len()),
+            None => format!("{} matches", matches.len()),
+        };
+        vec![
+            Span::styled(format!(" /{} ", query), STYLE_TEXT),
+            Span::styled(format!("{} ", count), STYLE_MUTED),
+        ]
+    }
+}
+
+/// Width left for the message after the fixed columns
+fn message_width(max_width: usize) -> usize {
     // Fixed widths: "  " + timestamp(12) + "  " + symbol(2) + "  " + size(8) = ~26 chars
     // Message gets the rest
-    let msg_width = max_width.saturating_sub(30);
+    max_width.saturating_sub(30)
+}
 
+/// Leading columns, message text and message style of an entry
+fn entry_parts(entry: &LogEntry) -> (Vec<Span<'static>>, Cow<'_, str>, Style) {
+    let timestamp = Span::styled(format!("  {} ", entry.timestamp), STYLE_MUTED);
     match &entry.kind {
         LogKind::Protocol {
             direction,
             message_name,
-            size,
+            ..
         } => {
             let (symbol, color) = match direction {
                 Direction::In => (SYMBOL_IN, COLOR_LOG_RX),
                 Direction::Out => (SYMBOL_OUT, COLOR_LOG_TX),
             };
-
-            Line::from(vec![
-                Span::styled(format!("  {} ", entry.timestamp), STYLE_MUTED),
-                Span::styled(format!(" {} ", symbol), Style::new().fg(color)),
-                Span::styled(pad_or_truncate(message_name, msg_width), STYLE_TEXT),
-                Span::styled(format!("{:>6} B", size), STYLE_MUTED),
-            ])
+            (
+                vec![
+                    timestamp,
+                    Span::styled(format!(" {} ", symbol), Style::new().fg(color)),
+                ],
+                Cow::Borrowed(message_name),
+                STYLE_TEXT,
+            )
         }
         LogKind::Debug { level, message } => {
             let (level_str, color) = match level {
@@ -244,22 +358,197 @@ fn format_log_entry(entry: &LogEntry, max_width: usize) -> Line<'static> {
                 Some(LogLevel::Error) => ("[ERR]", COLOR_ERROR),
                 None => ("     ", COLOR_MUTED),
             };
+            (
+                vec![
+                    timestamp,
+                    Span::styled(format!("{} ", level_str), Style::new().fg(color)),
+                ],
+                Cow::Borrowed(message),
+                STYLE_TEXT,
+            )
+        }
+        LogKind::System { message } => (
+            vec![timestamp, Span::raw("      ")],
+            Cow::Borrowed(message),
+            Style::new().fg(COLOR_LOG_SYSTEM),
+        ),
+        LogKind::ControllerEvent { .. } => (
+            vec![
+                timestamp,
+                Span::styled(format!("{} ", EVENT_TAG), STYLE_MUTED),
+            ],
+            search_text(entry),
+            Style::new().fg(COLOR_LOG_EVENT),
+        ),
+    }
+}
 
-            Line::from(vec![
-                Span::styled(format!("  {} ", entry.timestamp), STYLE_MUTED),
-                Span::styled(format!("{} ", level_str), Style::new().fg(color)),
-                Span::styled(pad_or_truncate(message, msg_width), STYLE_TEXT),
-            ])
+/// Put the bookmark marker in the first column of an entry's first row
+fn mark_bookmark(line: &mut Line<'static>) {
+    if let Some(first) = line.spans.first_mut() {
+        // Every entry starts with the two-space margin of its timestamp
+        first.content = Cow::Owned(first.content.chars().skip(1).collect());
+    }
+    line.spans.insert(
+        0,
+        Span::styled(BOOKMARK_MARKER, Style::new().fg(COLOR_WARNING)),
+    );
+}
+
+/// Put the note marker in the second column of an entry's first row
+fn mark_note(line: &mut Line<'static>) {
+    if let Some(first) = line.spans.first_mut() {
+        first.content = Cow::Owned(first.content.chars().skip(2).collect());
+    }
+    line.spans.splice(
+        0..0,
+        [
+            Span::raw(" "),
+            Span::styled(NOTE_MARKER, Style::new().fg(COLOR_LOG_SYSTEM)),
+        ],
+    );
+}
+
+/// Split the span at `index` so the matches of `query` are highlighted
+fn highlight_matches(line: &mut Line<'static>, index: usize, query: &str) {
+    let Some(span) = line.spans.get(index) else {
+        return;
+    };
+    let ranges = match_ranges(&span.content, query);
+    if ranges.is_empty() {
+        return;
+    }
+    let span = line.spans.remove(index);
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for range in ranges {
+        if range.start > last {
+            parts.push(Span::styled(
+                span.content[last..range.start].to_string(),
+                span.style,
+            ));
         }
-        LogKind::System { message } => Line::from(vec![
-            Span::styled(format!("  {} ", entry.timestamp), STYLE_MUTED),
-            Span::raw("      "),
-            Span::styled(
-                pad_or_truncate(message, msg_width),
-                Style::new().fg(COLOR_LOG_SYSTEM),
-            ),
-        ]),
+        parts.push(Span::styled(
+            span.content[range.clone()].to_string(),
+            STYLE_SEARCH_MATCH,
+        ));
+        last = range.end;
     }
+    if last < span.content.len() {
+        parts.push(Span::styled(span.content[last..].to_string(), span.style));
+    }
+    line.spans.splice(index..index, parts);
+}
+
+/// Split spans so the URLs they contain are underlined
+fn underline_urls(line: &mut Line<'static>) {
+    if !line.spans.iter().any(|s| !find_urls(&s.content).is_empty()) {
+        return;
+    }
+    let spans = std::mem::take(&mut line.spans);
+    for span in spans {
+        let urls = find_urls(&span.content);
+        if urls.is_empty() {
+            line.spans.push(span);
+            continue;
+        }
+        let mut last = 0;
+        for url in urls {
+            if url.start > last {
+                line.spans.push(Span::styled(
+                    span.content[last..url.start].to_string(),
+                    span.style,
+                ));
+            }
+            line.spans.push(Span::styled(
+                span.content[url.clone()].to_string(),
+                span.style.add_modifier(Modifier::UNDERLINED),
+            ));
+            last = url.end;
+        }
+        if last < span.content.len() {
+            line.spans
+                .push(Span::styled(span.content[last..].to_string(), span.style));
+        }
+    }
+}
+
+/// Trailing size column (protocol entries only; these are never wrapped)
+fn size_column(entry: &LogEntry) -> Option<Span<'static>> {
+    match &entry.kind {
+        LogKind::Protocol { size, .. } => Some(Span::styled(format!("{:>6} B", size), STYLE_MUTED)),
+        _ => None,
+    }
+}
+
+/// Format a log entry into a styled Line
+fn format_log_entry(entry: &LogEntry, max_width: usize) -> Line<'static> {
+    let (mut spans, message, style) = entry_parts(entry);
+    spans.push(Span::styled(
+        pad_or_truncate(&message, message_width(max_width)),
+        style,
+    ));
+    spans.extend(size_column(entry));
+    Line::from(spans)
+}
+
+/// Format a log entry, wrapping a long message onto continuation rows
+fn format_log_entry_wrapped(entry: &LogEntry, max_width: usize) -> Vec<Line<'static>> {
+    if size_column(entry).is_some() {
+        return vec![format_log_entry(entry, max_width)];
+    }
+    let (mut spans, message, style) = entry_parts(entry);
+
+    let rows = wrap_text(&message, message_width(max_width), wrap_width(max_width));
+    let mut rows = rows.into_iter();
+    spans.push(Span::styled(rows.next().unwrap_or("").to_string(), style));
+
+    let mut lines = vec![Line::from(spans)];
+    lines.extend(rows.map(|row| {
+        Line::from(vec![
+            Span::raw(WRAP_INDENT),
+            Span::styled(row.to_string(), style),
+        ])
+    }));
+    lines
+}
+
+/// Visual rows of an entry when word wrap is on
+fn entry_rows(entry: &LogEntry, max_width: usize) -> usize {
+    if size_column(entry).is_some() {
+        return 1;
+    }
+    let (_, message, _) = entry_parts(entry);
+    wrap_text(&message, message_width(max_width), wrap_width(max_width)).len()
+}
+
+/// Width of a continuation row's text
+fn wrap_width(max_width: usize) -> usize {
+    max_width.saturating_sub(WRAP_INDENT.len())
+}
+
+/// Split `text` into rows of at most `first` chars, then `rest` chars
+///
+/// Breaks at the last space that fits, or mid-word when a word is too long.
+fn wrap_text(text: &str, first: usize, rest: usize) -> Vec<&str> {
+    let mut rows = Vec::new();
+    let mut remaining = text;
+    let mut width = first;
+
+    while width > 0 {
+        let Some((split, _)) = remaining.char_indices().nth(width) else {
+            break;
+        };
+        let (row, next) = match remaining[..split].rfind(' ') {
+            Some(space) if space > 0 => (&remaining[..space], &remaining[space + 1..]),
+            _ => (&remaining[..split], &remaining[split..]),
+        };
+        rows.push(row);
+        remaining = next;
+        width = rest;
+    }
+    rows.push(remaining);
+    rows
 }
 
 /// Pad or truncate a string to exactly the given width
@@ -272,3 +561,102 @@ fn pad_or_truncate(s: &str, width: usize) -> String {
         s[..width].to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text_breaks_at_spaces_then_mid_word() {
+        assert_eq!(wrap_text("short", 10, 20), vec!["short"]);
+        assert_eq!(
+            wrap_text("alpha beta gamma delta", 11, 6),
+            vec!["alpha beta", "gamma", "delta"]
+        );
+        assert_eq!(wrap_text("abcdefghij", 4, 4), vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn test_wrapped_entry_uses_continuation_rows() {
+        let entry = LogEntry::debug_log(Some(LogLevel::Info), "word ".repeat(30));
+        let lines = format_log_entry_wrapped(&entry, 80);
+        assert_eq!(lines.len(), entry_rows(&entry, 80));
+        assert!(lines.len() > 1);
+        assert!(lines[1].spans[0].content == WRAP_INDENT);
+
+        // Protocol entries keep a single row
+        let entry = LogEntry::protocol_in("x".repeat(200), 12);
+        assert_eq!(format_log_entry_wrapped(&entry, 80).len(), 1);
+    }
+
+    #[test]
+    fn test_visual_line_count_sums_wrapped_rows() {
+        let mut store = LogStore::new(10);
+        store.add(LogEntry::system("short"));
+        store.add(LogEntry::system("long ".repeat(40)));
+
+        let rows = |e: &LogEntry| entry_rows(e, 80);
+        let long_rows = entry_rows(store.entries().back().unwrap(), 80);
+        assert_eq!(store.visual_line_count(1, rows), 1);
+        assert_eq!(store.visual_line_count(2, rows), 1 + long_rows);
+    }
+
+    #[test]
+    fn test_underline_urls_splits_spans() {
+        let entry = LogEntry::system("Docs at https://example.com/doc, see there");
+        let mut line = format_log_entry(&entry, 120);
+        let before: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        underline_urls(&mut line);
+
+        let after: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(after, before);
+        let underlined: Vec<&str> = line
+            .spans
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::UNDERLINED))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(underlined, ["https://example.com/doc"]);
+    }
+
+    #[test]
+    fn test_highlight_matches_only_in_message() {
+        let entry = LogEntry::system("Ping sent, ping lost");
+        let mut line = format_log_entry(&entry, 120);
+        let before: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        highlight_matches(&mut line, 2, "PING");
+
+        let after: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(after, before);
+        let highlighted: Vec<&str> = line
+            .spans
+            .iter()
+            .filter(|s| s.style == STYLE_SEARCH_MATCH)
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(highlighted, ["Ping", "ping"]);
+    }
+
+    #[test]
+    fn test_bookmark_marker_replaces_margin() {
+        let entry = LogEntry::system("hello");
+        let mut line = format_log_entry(&entry, 80);
+        let width = line.width();
+        mark_bookmark(&mut line);
+
+        assert_eq!(line.width(), width);
+        assert_eq!(line.spans[0].content, BOOKMARK_MARKER);
+        assert!(line.spans[1].content.starts_with(' '));
+
+        let mut line = format_log_entry(&entry, 80);
+        mark_note(&mut line);
+        mark_bookmark(&mut line);
+        assert_eq!(line.width(), width);
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert!(
+            text.starts_with(&format!("◆✎{}", entry.timestamp)),
+            "{}",
+            text
+        );
+    }
+}
@@ -1,5 +1,12 @@
 //! UI widgets
 
 pub mod actions;
+pub mod clipboard;
 pub mod log;
+pub mod note;
+pub mod palette;
+pub mod peers;
+pub mod ports;
+pub mod statistics;
+pub mod stats;
 pub mod status;
@@ -0,0 +1,54 @@
+//! Note input popup
+//!
+//! Single-line input centered over the log view; Enter saves the text as
+//! the note of the entry at the scroll position (empty removes it).
+
+use crate::ui::theme::{style_title, STYLE_ACTION, STYLE_BORDER, STYLE_BRIGHT, STYLE_KEY};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+pub struct NoteInput<'a> {
+    input: &'a str,
+}
+
+impl<'a> NoteInput<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+}
+
+impl Widget for NoteInput<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Border(2) + input + blank + help line
+        let [area] = Layout::vertical([Constraint::Length(5)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Max(80)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let lines = vec![
+            Line::styled(format!("✎ {}_", self.input), STYLE_BRIGHT),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled("  Enter", STYLE_KEY),
+                Span::styled(" Save (empty = remove)  ", STYLE_ACTION),
+                Span::styled("Esc", STYLE_KEY),
+                Span::styled(" Cancel", STYLE_ACTION),
+            ]),
+        ];
+
+        let block = Block::default()
+            .title(" NOTE ")
+            .title_style(style_title())
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -0,0 +1,58 @@
+//! Command palette popup
+//!
+//! Single-line input centered over the log view; the typed text is run as
+//! a pipeline of space-separated command names on Enter.
+
+use crate::ui::theme::{style_title, STYLE_ACTION, STYLE_BORDER, STYLE_BRIGHT, STYLE_KEY};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+pub struct CommandPalette<'a> {
+    input: &'a str,
+}
+
+impl<'a> CommandPalette<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+}
+
+impl Widget for CommandPalette<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Border(2) + input + blank + help line
+        let [area] = Layout::vertical([Constraint::Length(5)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Max(80)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let lines = vec![
+            Line::styled(format!("> {}_", self.input), STYLE_BRIGHT),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled("  Enter", STYLE_KEY),
+                Span::styled(" Run  ", STYLE_ACTION),
+                Span::styled("Esc", STYLE_KEY),
+                Span::styled(" Close  ", STYLE_ACTION),
+                Span::styled(
+                    "e.g. FilterAll ClearLogs Sleep(500) ExportLogs",
+                    STYLE_ACTION,
+                ),
+            ]),
+        ];
+
+        let block = Block::default()
+            .title(" COMMAND ")
+            .title_style(style_title())
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -0,0 +1,84 @@
+//! Discovered bridges popup
+//!
+//! Centered over the log view; lists the bridges found by a network scan,
+//! scrolling to keep the selection visible.
+
+use crate::app::PeersPopupState;
+use crate::ui::theme::{
+    style_title, STYLE_ACTION, STYLE_BORDER, STYLE_BRIGHT, STYLE_KEY, STYLE_TEXT,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+/// Rows of bridges shown at once
+const MAX_VISIBLE: usize = 10;
+
+pub struct PeersPopup<'a> {
+    state: &'a PeersPopupState,
+}
+
+impl<'a> PeersPopup<'a> {
+    pub fn new(state: &'a PeersPopupState) -> Self {
+        Self { state }
+    }
+}
+
+impl Widget for PeersPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Never taller than the log view: border(2) + blank + help line
+        let visible = MAX_VISIBLE
+            .min(self.state.entries.len())
+            .min((area.height as usize).saturating_sub(4).max(1));
+        let first = (self.state.selected + 1).saturating_sub(visible);
+
+        let [area] = Layout::vertical([Constraint::Length(visible as u16 + 4)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Max(90)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let mut lines: Vec<Line> = self
+            .state
+            .entries
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(visible)
+            .map(|(i, entry)| {
+                if i == self.state.selected {
+                    Line::styled(
+                        format!("> {}", entry),
+                        STYLE_BRIGHT.add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Line::styled(format!("  {}", entry), STYLE_TEXT)
+                }
+            })
+            .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled("  ↑↓", STYLE_KEY),
+            Span::styled(" Scroll  ", STYLE_ACTION),
+            Span::styled("Esc", STYLE_KEY),
+            Span::styled(" Close", STYLE_ACTION),
+        ]));
+
+        let block = Block::default()
+            .title(format!(
+                " BRIDGES ON THE NETWORK ({}) ",
+                self.state.entries.len()
+            ))
+            .title_style(style_title())
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
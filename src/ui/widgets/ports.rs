@@ -0,0 +1,90 @@
+//! Serial port picker popup
+//!
+//! Centered over the log view; the `list-ports` table under its column
+//! titles, scrolling to keep the selection visible.
+
+use crate::app::PortsPopupState;
+use crate::ui::theme::{
+    style_title, STYLE_ACTION, STYLE_BORDER, STYLE_BRIGHT, STYLE_KEY, STYLE_LABEL, STYLE_TEXT,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+/// Rows of ports shown at once
+const MAX_VISIBLE: usize = 10;
+
+pub struct PortsPopup<'a> {
+    state: &'a PortsPopupState,
+}
+
+impl<'a> PortsPopup<'a> {
+    pub fn new(state: &'a PortsPopupState) -> Self {
+        Self { state }
+    }
+}
+
+impl Widget for PortsPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Never taller than the log view: border(2) + header + blank + help line
+        let visible = MAX_VISIBLE
+            .min(self.state.entries.len())
+            .min((area.height as usize).saturating_sub(5).max(1));
+        let first = (self.state.selected + 1).saturating_sub(visible);
+
+        let [area] = Layout::vertical([Constraint::Length(visible as u16 + 5)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Max(100)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let mut lines = vec![Line::styled(
+            format!("  {}", self.state.header),
+            STYLE_LABEL,
+        )];
+        lines.extend(
+            self.state
+                .entries
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(visible)
+                .map(|(i, entry)| {
+                    if i == self.state.selected {
+                        Line::styled(
+                            format!("> {}", entry),
+                            STYLE_BRIGHT.add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        Line::styled(format!("  {}", entry), STYLE_TEXT)
+                    }
+                }),
+        );
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled("  ↑↓", STYLE_KEY),
+            Span::styled(" Select  ", STYLE_ACTION),
+            Span::styled("Enter", STYLE_KEY),
+            Span::styled(" Use port  ", STYLE_ACTION),
+            Span::styled("c", STYLE_KEY),
+            Span::styled(" Copy  ", STYLE_ACTION),
+            Span::styled("Esc", STYLE_KEY),
+            Span::styled(" Close  ", STYLE_ACTION),
+            Span::styled("* = device preset", STYLE_LABEL),
+        ]));
+
+        let block = Block::default()
+            .title(format!(" SERIAL PORTS ({}) ", self.state.entries.len()))
+            .title_style(style_title())
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -0,0 +1,55 @@
+//! Log statistics popup
+//!
+//! Centered over the log view; shows `LogStatistics::table_lines` for the
+//! logs in memory when the popup was opened.
+
+use crate::ui::theme::{style_title, STYLE_ACTION, STYLE_BORDER, STYLE_KEY, STYLE_TEXT};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+pub struct StatisticsPopup<'a> {
+    lines: &'a [String],
+}
+
+impl<'a> StatisticsPopup<'a> {
+    pub fn new(lines: &'a [String]) -> Self {
+        Self { lines }
+    }
+}
+
+impl Widget for StatisticsPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // border(2) + rows + blank + help line, cut to the log view
+        let height = (self.lines.len() as u16 + 4).min(area.height);
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Max(70)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let mut lines: Vec<Line> = self
+            .lines
+            .iter()
+            .map(|line| Line::styled(format!("  {}", line), STYLE_TEXT))
+            .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Esc", STYLE_KEY),
+            Span::styled(" Close", STYLE_ACTION),
+        ]));
+
+        let block = Block::default()
+            .title(" LOG STATISTICS ")
+            .title_style(style_title())
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
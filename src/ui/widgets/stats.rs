@@ -0,0 +1,113 @@
+//! Message stats panel - per-message-type traffic (Tab)
+//!
+//! Covers the log view with the message types that moved the most bytes
+//! since the TUI started, as counted from the received protocol logs.
+
+use crate::bridge::stats::MessageStats;
+use crate::ui::theme::{
+    style_title, COLOR_LOG_RX, COLOR_LOG_TX, STYLE_ACTION, STYLE_BORDER, STYLE_KEY, STYLE_LABEL,
+    STYLE_MUTED, STYLE_TEXT,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Row, Table, Widget},
+};
+
+pub struct StatsWidget<'a> {
+    messages: &'a [(String, MessageStats)],
+}
+
+impl<'a> StatsWidget<'a> {
+    pub fn new(messages: &'a [(String, MessageStats)]) -> Self {
+        Self { messages }
+    }
+}
+
+impl Widget for StatsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let header =
+            Row::new(["Message", "RX", "RX bytes", "TX", "TX bytes", "Last"]).style(STYLE_LABEL);
+        let rows = self.messages.iter().map(|(name, stats)| {
+            Row::new([
+                Cell::from(name.as_str()).style(STYLE_TEXT),
+                Cell::from(stats.rx_count.to_string()).style(Style::new().fg(COLOR_LOG_RX)),
+                Cell::from(format_bytes(stats.rx_bytes)).style(Style::new().fg(COLOR_LOG_RX)),
+                Cell::from(stats.tx_count.to_string()).style(Style::new().fg(COLOR_LOG_TX)),
+                Cell::from(format_bytes(stats.tx_bytes)).style(Style::new().fg(COLOR_LOG_TX)),
+                Cell::from(format_idle(stats.idle_ms)).style(STYLE_MUTED),
+            ])
+        });
+        let widths = [
+            Constraint::Min(16),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(8),
+        ];
+
+        let block = Block::default()
+            .title(" MESSAGE STATS ")
+            .title_style(style_title())
+            .title_bottom(Line::from(vec![
+                Span::styled(" Tab", STYLE_KEY),
+                Span::styled(" Logs ", STYLE_ACTION),
+            ]))
+            .borders(Borders::ALL)
+            .border_style(STYLE_BORDER);
+
+        Clear.render(area, buf);
+        if self.messages.is_empty() {
+            let empty = Line::styled("  No protocol messages yet", STYLE_MUTED);
+            let inner = block.inner(area);
+            block.render(area, buf);
+            empty.render(inner, buf);
+            return;
+        }
+        Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+/// `512 B`, `12.3 KB`, `4.5 MB`
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let value = bytes as f64;
+    if value < KB {
+        format!("{} B", bytes)
+    } else if value < KB * KB {
+        format!("{:.1} KB", value / KB)
+    } else {
+        format!("{:.1} MB", value / KB / KB)
+    }
+}
+
+/// Time since the last message: `now`, `42s`, `5m`, `2h`
+fn format_idle(idle_ms: u64) -> String {
+    match idle_ms / 1000 {
+        0 => "now".to_string(),
+        secs @ 1..=59 => format!("{}s", secs),
+        secs @ 60..=3599 => format!("{}m", secs / 60),
+        secs => format!("{}h", secs / 3600),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes_and_idle() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(12_600), "12.3 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_idle(400), "now");
+        assert_eq!(format_idle(42_000), "42s");
+        assert_eq!(format_idle(7_200_000), "2h");
+    }
+}
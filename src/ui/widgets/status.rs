@@ -1,19 +1,24 @@
 //! Status widget - displays daemon/client status with responsive layout
 //!
-//! Shows daemon state, transport config, and connection state.
+//! Shows daemon state, transport config, and connection state. A details
+//! row under the header shows the controller codec and the host ports with
+//! their clients. The log link signal bars show the daemon restart count
+//! while hovered.
 
+use crate::app::operations::format_size;
 use crate::app::state::{ControllerTransportState, HostTransportState};
 use crate::app::AppState;
 use crate::config::{ControllerTransport, HostTransport};
 use crate::constants::WIDE_THRESHOLD;
 use crate::ui::theme::{
-    style_title, COLOR_LOG_RX, COLOR_LOG_TX, COLOR_MUTED, COLOR_RUNNING, COLOR_STOPPED,
-    STYLE_BORDER, STYLE_DIM, STYLE_LABEL, STYLE_VALUE, SYMBOL_IN, SYMBOL_OUT,
+    style_title, COLOR_ERROR, COLOR_LOG_RX, COLOR_LOG_TX, COLOR_MUTED, COLOR_RUNNING,
+    COLOR_STOPPED, COLOR_WARNING, STYLE_BORDER, STYLE_DIM, STYLE_LABEL, STYLE_VALUE, SYMBOL_IN,
+    SYMBOL_OUT,
 };
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Constraint, Layout, Rect},
-    style::Style,
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
@@ -24,6 +29,18 @@ const SYMBOL_DISCONNECTED: &str = "○";
 const SYMBOL_STOPPED_SQUARE: &str = "■";
 const SYMBOL_UNAVAILABLE: &str = "×";
 
+/// Log link signal bars, weakest first
+const SIGNAL_BARS: [&str; 4] = ["▂", "▄", "▆", "█"];
+
+/// Width of the header's right side (ports and log link)
+const HEADER_RIGHT_WIDTH: u16 = 32;
+/// Blank columns after the signal bars
+const HEADER_RIGHT_PADDING: u16 = 2;
+/// Below this width the details row drops its labels
+const DETAILS_COMPACT_WIDTH: u16 = 100;
+/// Jitter above this is shown as a warning (ms)
+const JITTER_WARN_MS: f64 = 5.0;
+
 pub struct StatusWidget<'a> {
     state: &'a AppState<'a>,
 }
@@ -43,18 +60,41 @@ impl Widget for StatusWidget<'_> {
         let is_wide = self.is_wide(area.width);
 
         // Title with optional status message
-        let title = if let Some(msg) = &self.state.status_message {
+        let mut title = if let Some(msg) = &self.state.status_message {
             format!(" OC BRIDGE │ {} ", msg)
         } else if self.state.bridge_paused {
             " OC BRIDGE │ SERIAL RELEASED ".to_string()
         } else {
             " OC BRIDGE ".to_string()
         };
+        if let Some(profile) = self.state.profile {
+            title.push_str(&format!("│ PROFILE {} ", profile));
+        }
+        if self.state.cobs_trace {
+            title.push_str("│ COBS TRACE ");
+        }
+
+        // Flash red during a traffic spike
+        let (border_style, title_style) = if self.state.spike_flash {
+            title.push_str("│ TRAFFIC SPIKE ");
+            let red = Style::new().fg(COLOR_ERROR);
+            (red, red.add_modifier(Modifier::BOLD))
+        } else {
+            (STYLE_BORDER, style_title())
+        };
+
+        let mut title = vec![Span::styled(title, title_style)];
+        if let Some(bytes) = self.state.recording_bytes {
+            title.push(Span::styled(
+                format!("[REC] {} ", format_size(bytes)),
+                Style::new().fg(COLOR_ERROR).add_modifier(Modifier::BOLD),
+            ));
+        }
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_style(STYLE_BORDER)
-            .title(Span::styled(title, style_title()));
+            .border_style(border_style)
+            .title(Line::from(title));
 
         // Render block and get inner area
         let inner = block.inner(area);
@@ -65,22 +105,28 @@ impl Widget for StatusWidget<'_> {
         } else {
             self.render_narrow(inner, buf);
         }
+        self.render_signal_tooltip(inner, buf);
     }
 }
 
 impl StatusWidget<'_> {
     /// Render wide layout: header line + two boxes side by side
     fn render_wide(&self, area: Rect, buf: &mut Buffer) {
-        // Split into header (1 line) and boxes area (remaining)
-        let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(3)]).split(area);
+        // Split into header, details (1 line each) and boxes area (remaining)
+        let chunks = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(3),
+        ])
+        .split(area);
 
-        // Header line
         self.render_header(chunks[0], buf);
+        self.render_details(chunks[1], buf);
 
         // Two boxes side by side
         let box_chunks =
             Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .split(chunks[1]);
+                .split(chunks[2]);
 
         self.render_controller_box(box_chunks[0], buf);
         self.render_host_box(box_chunks[1], buf);
@@ -89,6 +135,7 @@ impl StatusWidget<'_> {
     /// Render narrow layout: header + stacked boxes
     fn render_narrow(&self, area: Rect, buf: &mut Buffer) {
         let chunks = Layout::vertical([
+            Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(3),
             Constraint::Length(3),
@@ -96,8 +143,25 @@ impl StatusWidget<'_> {
         .split(area);
 
         self.render_header(chunks[0], buf);
-        self.render_controller_box(chunks[1], buf);
-        self.render_host_box(chunks[2], buf);
+        self.render_details(chunks[1], buf);
+        self.render_controller_box(chunks[2], buf);
+        self.render_host_box(chunks[3], buf);
+    }
+
+    /// Render details line: codec and host ports with their clients
+    fn render_details(&self, area: Rect, buf: &mut Buffer) {
+        let compact = area.width < DETAILS_COMPACT_WIDTH;
+        let label = |text: &'static str| Span::styled(if compact { "" } else { text }, STYLE_LABEL);
+
+        let line = Line::from(vec![
+            Span::raw("  "),
+            label("Codec: "),
+            Span::styled(self.state.active_codec.as_str(), STYLE_VALUE),
+            Span::styled(" │ ", STYLE_DIM),
+            label("Host: "),
+            Span::styled(self.state.active_host_info.as_str(), STYLE_VALUE),
+        ]);
+        Paragraph::new(line).render(area, buf);
     }
 
     /// Render header line
@@ -108,39 +172,46 @@ impl StatusWidget<'_> {
             (SYMBOL_DISCONNECTED, COLOR_STOPPED, "stopped")
         };
 
-        let (session_label, session_text) =
-            if self.state.controller_transport_config == ControllerTransport::Serial {
-                let text = if !self.state.daemon_running {
-                    "stopped"
-                } else if self.state.bridge_paused {
-                    "released"
-                } else {
-                    match self.state.controller_state {
-                        ControllerTransportState::Serial { .. } => "attached",
-                        ControllerTransportState::Waiting => "waiting",
-                        _ => "running",
-                    }
-                };
-                ("Serial: ", text)
+        let (session_label, session_text) = if self.state.controller_transport_config.is_serial() {
+            let text = if !self.state.daemon_running {
+                "stopped"
+            } else if self.state.bridge_paused {
+                "released"
             } else {
-                let text = if !self.state.daemon_running {
-                    "stopped"
-                } else {
-                    "running"
-                };
-                ("Bridge: ", text)
+                match *self.state.controller_state {
+                    ControllerTransportState::Serial { .. } => "attached",
+                    ControllerTransportState::Waiting => "waiting",
+                    _ => "running",
+                }
+            };
+            ("Serial: ", text)
+        } else {
+            let text = if !self.state.daemon_running {
+                "stopped"
+            } else {
+                "running"
             };
+            ("Bridge: ", text)
+        };
 
         let controller_text = match self.state.controller_transport_config {
             ControllerTransport::Serial => "Serial",
             ControllerTransport::Udp => "UDP",
             ControllerTransport::WebSocket => "WebSocket",
+            ControllerTransport::FramedLength2 => "Serial (len16)",
+            ControllerTransport::FramedLength4 => "Serial (len32)",
+            ControllerTransport::HexSerial => "Serial (hex)",
+            ControllerTransport::Tcp => "TCP",
+            #[cfg(unix)]
+            ControllerTransport::Unix => "UNIX socket",
         };
 
         let host_text = match self.state.host_transport_config {
             HostTransport::Udp => "UDP",
             HostTransport::WebSocket => "WebSocket",
             HostTransport::Both => "UDP+WebSocket",
+            #[cfg(windows)]
+            HostTransport::NamedPipe => "Named pipe",
         };
 
         let left = Line::from(vec![
@@ -166,7 +237,7 @@ impl StatusWidget<'_> {
             Span::styled(SYMBOL_DISCONNECTED, Style::new().fg(COLOR_MUTED))
         };
 
-        let right = Line::from(vec![
+        let mut right = vec![
             Span::styled("Control ", STYLE_LABEL),
             Span::styled(format!("{}", self.state.control_port), STYLE_VALUE),
             Span::raw("  "),
@@ -174,54 +245,117 @@ impl StatusWidget<'_> {
             Span::styled(format!("{}", self.state.log_port), STYLE_VALUE),
             Span::raw(" "),
             log_indicator,
-            Span::raw("  "),
-        ]);
+            Span::raw(" "),
+        ];
+        right.extend(self.signal_spans());
+        right.push(Span::raw(" ".repeat(HEADER_RIGHT_PADDING as usize)));
 
-        let chunks = Layout::horizontal([Constraint::Min(1), Constraint::Length(26)]).split(area);
+        let chunks =
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(HEADER_RIGHT_WIDTH)])
+                .split(area);
         Paragraph::new(left).render(chunks[0], buf);
-        Paragraph::new(right)
+        Paragraph::new(Line::from(right))
             .alignment(Alignment::Right)
             .render(chunks[1], buf);
     }
 
+    /// Signal bars, missing ones dimmed
+    fn signal_spans(&self) -> impl Iterator<Item = Span<'static>> {
+        let signal = self.state.log_signal;
+        let color = match signal {
+            0 | 1 => COLOR_ERROR,
+            2 => COLOR_WARNING,
+            _ => COLOR_RUNNING,
+        };
+        SIGNAL_BARS.iter().enumerate().map(move |(i, bar)| {
+            let style = if (i as u8) < signal {
+                Style::new().fg(color)
+            } else {
+                STYLE_DIM
+            };
+            Span::styled(*bar, style)
+        })
+    }
+
+    /// Reconnect count under the signal bars while the mouse is over them
+    fn render_signal_tooltip(&self, inner: Rect, buf: &mut Buffer) {
+        let Some((column, row)) = self.state.mouse else {
+            return;
+        };
+        let bars = SIGNAL_BARS.len() as u16;
+        let bars_end = inner.right().saturating_sub(HEADER_RIGHT_PADDING);
+        let hovered = Rect::new(bars_end.saturating_sub(bars), inner.y, bars, 1)
+            .contains(Position::new(column, row));
+        if !hovered || inner.height < 2 {
+            return;
+        }
+
+        let text = format!(" reconnects: {} ", self.state.log_reconnects);
+        let width = (text.chars().count() as u16).min(inner.width);
+        let area = Rect::new(bars_end.saturating_sub(width), inner.y + 1, width, 1);
+        Paragraph::new(text)
+            .style(
+                Style::new()
+                    .fg(COLOR_WARNING)
+                    .add_modifier(Modifier::REVERSED),
+            )
+            .render(area, buf);
+    }
+
     /// Render Controller (IN) box
     fn render_controller_box(&self, area: Rect, buf: &mut Buffer) {
         let rx_rate = self.state.rx_rate;
 
         // Transport info with indicator
-        let (indicator, indicator_color, transport_text) = if self.state.bridge_paused
-            && matches!(
-                self.state.controller_transport_config,
-                ControllerTransport::Serial
-            ) {
-            (
-                SYMBOL_STOPPED_SQUARE,
-                COLOR_MUTED,
-                "Serial:released".to_string(),
-            )
-        } else {
-            match &self.state.controller_state {
-                ControllerTransportState::Serial { port } => {
-                    (SYMBOL_CONNECTED, COLOR_RUNNING, format!("Serial:{}", port))
-                }
-                ControllerTransportState::Udp { port } => {
-                    (SYMBOL_CONNECTED, COLOR_RUNNING, format!("UDP:{}", port))
-                }
-                ControllerTransportState::WebSocket { port } => (
-                    SYMBOL_CONNECTED,
-                    COLOR_RUNNING,
-                    format!("WebSocket:{}", port),
-                ),
-                ControllerTransportState::Waiting => {
-                    (SYMBOL_DISCONNECTED, COLOR_MUTED, "Waiting...".to_string())
+        let (indicator, indicator_color, transport_text) =
+            if self.state.bridge_paused && self.state.controller_transport_config.is_serial() {
+                (
+                    SYMBOL_STOPPED_SQUARE,
+                    COLOR_MUTED,
+                    "Serial:released".to_string(),
+                )
+            } else {
+                match &*self.state.controller_state {
+                    ControllerTransportState::Serial { port } => {
+                        let source = if self.state.serial_port_from_env {
+                            " (from env)"
+                        } else {
+                            ""
+                        };
+                        (
+                            SYMBOL_CONNECTED,
+                            COLOR_RUNNING,
+                            format!("Serial:{}{}", port, source),
+                        )
+                    }
+                    ControllerTransportState::Udp { port } => {
+                        (SYMBOL_CONNECTED, COLOR_RUNNING, format!("UDP:{}", port))
+                    }
+                    ControllerTransportState::WebSocket { port } => (
+                        SYMBOL_CONNECTED,
+                        COLOR_RUNNING,
+                        format!("WebSocket:{}", port),
+                    ),
+                    ControllerTransportState::Tcp { addr } => {
+                        (SYMBOL_CONNECTED, COLOR_RUNNING, format!("TCP:{}", addr))
+                    }
+                    #[cfg(unix)]
+                    ControllerTransportState::Unix { path } => {
+                        (SYMBOL_CONNECTED, COLOR_RUNNING, format!("UNIX:{}", path))
+                    }
+                    // Serial only: the device is not plugged in (yet)
+                    ControllerTransportState::Waiting => (
+                        SYMBOL_DISCONNECTED,
+                        COLOR_MUTED,
+                        "Waiting for device (⇧L list ports)".to_string(),
+                    ),
+                    ControllerTransportState::Disconnected => (
+                        SYMBOL_DISCONNECTED,
+                        COLOR_STOPPED,
+                        "Disconnected".to_string(),
+                    ),
                 }
-                ControllerTransportState::Disconnected => (
-                    SYMBOL_DISCONNECTED,
-                    COLOR_STOPPED,
-                    "Disconnected".to_string(),
-                ),
-            }
-        };
+            };
 
         let block = Block::default()
             .borders(Borders::ALL)
@@ -231,7 +365,7 @@ impl StatusWidget<'_> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let line = Line::from(vec![
+        let mut line = Line::from(vec![
             Span::raw(" "),
             Span::styled(indicator, Style::new().fg(indicator_color)),
             Span::raw(" "),
@@ -239,7 +373,16 @@ impl StatusWidget<'_> {
             Span::styled("  ", STYLE_LABEL),
             Span::styled(format!("{} ", SYMBOL_IN), Style::new().fg(COLOR_LOG_RX)),
             Span::styled(format!("{:.1} KB/s", rx_rate), STYLE_VALUE),
+            Span::styled(format!(" (1m {:.1})", self.state.rx_rate_avg), STYLE_LABEL),
         ]);
+        if let Some(jitter) = self.state.jitter_ms {
+            let style = if jitter > JITTER_WARN_MS {
+                Style::new().fg(COLOR_WARNING)
+            } else {
+                STYLE_VALUE
+            };
+            line.push_span(Span::styled(format!("  J:{:.1}ms", jitter), style));
+        }
 
         Paragraph::new(line).render(inner, buf);
     }
@@ -255,6 +398,8 @@ impl StatusWidget<'_> {
             HostTransportState::Both { udp_port, ws_port } => {
                 format!("UDP:{} + WebSocket:{}", udp_port, ws_port)
             }
+            #[cfg(windows)]
+            HostTransportState::NamedPipe { name } => format!("Pipe:{}", name),
         };
 
         let (indicator, indicator_color) = if self.state.daemon_running {
@@ -279,6 +424,7 @@ impl StatusWidget<'_> {
             Span::styled("  ", STYLE_LABEL),
             Span::styled(format!("{} ", SYMBOL_OUT), Style::new().fg(COLOR_LOG_TX)),
             Span::styled(format!("{:.1} KB/s", tx_rate), STYLE_VALUE),
+            Span::styled(format!(" (1m {:.1})", self.state.tx_rate_avg), STYLE_LABEL),
         ]);
 
         Paragraph::new(line).render(inner, buf);